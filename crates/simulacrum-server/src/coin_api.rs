@@ -0,0 +1,187 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use move_core_types::language_storage::TypeTag;
+use sui_json_rpc::api::{cap_page_limit, CoinReadApiServer};
+use sui_json_rpc::coin_api::parse_to_type_tag;
+use sui_json_rpc::error::Error;
+use sui_json_rpc::{with_tracing, SuiRpcModule};
+use sui_json_rpc_types::{Balance, Coin, CoinPage, SuiCoinMetadata};
+use sui_open_rpc::Module;
+use sui_types::balance::Supply;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use tracing::instrument;
+
+use crate::SharedSimulacrum;
+
+pub struct CoinReadApi<R> {
+    simulacrum: SharedSimulacrum<R>,
+}
+
+impl<R> CoinReadApi<R> {
+    pub fn new(simulacrum: SharedSimulacrum<R>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+impl<R: Send + Sync> CoinReadApi<R> {
+    /// All the coins owned by `owner` (optionally only those of type `coin_type`), in ascending
+    /// order of object ID.
+    fn coins(&self, owner: SuiAddress, coin_type: Option<&TypeTag>) -> Vec<Coin> {
+        let simulacrum = self.simulacrum.read().unwrap();
+        let mut coins: Vec<_> = simulacrum
+            .store()
+            .owned_objects(owner)
+            .filter_map(|object| {
+                let type_ = object.coin_type_maybe()?;
+                if coin_type.is_some_and(|t| t != &type_) {
+                    return None;
+                }
+
+                let (coin_object_id, version, digest) = object.compute_object_reference();
+                Some(Coin {
+                    coin_type: type_.to_string(),
+                    coin_object_id,
+                    version,
+                    digest,
+                    balance: object.get_coin_value_unsafe(),
+                    previous_transaction: object.previous_transaction,
+                })
+            })
+            .collect();
+
+        coins.sort_by_key(|coin| coin.coin_object_id);
+        coins
+    }
+
+    fn coin_page(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<&TypeTag>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> CoinPage {
+        let limit = cap_page_limit(limit);
+
+        let mut data: Vec<_> = self
+            .coins(owner, coin_type)
+            .into_iter()
+            .filter(|coin| cursor.map_or(true, |c| coin.coin_object_id > c))
+            .take(limit + 1)
+            .collect();
+
+        let has_next_page = data.len() > limit;
+        data.truncate(limit);
+
+        let next_cursor = if has_next_page {
+            data.last().map(|coin| coin.coin_object_id)
+        } else {
+            None
+        };
+
+        CoinPage {
+            data,
+            next_cursor,
+            has_next_page,
+        }
+    }
+
+    fn balances(&self, owner: SuiAddress, coin_type: Option<&TypeTag>) -> Vec<Balance> {
+        let mut balances = BTreeMap::new();
+        for coin in self.coins(owner, coin_type) {
+            let balance = balances
+                .entry(coin.coin_type.clone())
+                .or_insert_with(|| Balance::zero(coin.coin_type));
+            balance.coin_object_count += 1;
+            balance.total_balance += coin.balance as u128;
+        }
+
+        balances.into_values().collect()
+    }
+}
+
+#[async_trait]
+impl<R> CoinReadApiServer for CoinReadApi<R>
+where
+    R: Send + Sync + 'static,
+{
+    #[instrument(skip(self))]
+    async fn get_coins(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<CoinPage> {
+        with_tracing!(async move {
+            let coin_type = parse_to_type_tag(coin_type)?;
+            Ok(self.coin_page(owner, Some(&coin_type), cursor, limit))
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_all_coins(
+        &self,
+        owner: SuiAddress,
+        cursor: Option<ObjectID>,
+        limit: Option<usize>,
+    ) -> RpcResult<CoinPage> {
+        with_tracing!(async move { Ok(self.coin_page(owner, None, cursor, limit)) })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_balance(
+        &self,
+        owner: SuiAddress,
+        coin_type: Option<String>,
+    ) -> RpcResult<Balance> {
+        with_tracing!(async move {
+            let coin_type = parse_to_type_tag(coin_type)?;
+            Ok(self
+                .balances(owner, Some(&coin_type))
+                .pop()
+                .unwrap_or_else(|| Balance::zero(coin_type.to_string())))
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_all_balances(&self, owner: SuiAddress) -> RpcResult<Vec<Balance>> {
+        with_tracing!(async move { Ok(self.balances(owner, None)) })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_coin_metadata(&self, _coin_type: String) -> RpcResult<Option<SuiCoinMetadata>> {
+        with_tracing!(async move {
+            Err(Error::UnsupportedFeature(
+                "getCoinMetadata is not supported by the simulator".to_string(),
+            ))
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_total_supply(&self, _coin_type: String) -> RpcResult<Supply> {
+        with_tracing!(async move {
+            Err(Error::UnsupportedFeature(
+                "getTotalSupply is not supported by the simulator".to_string(),
+            ))
+        })
+    }
+}
+
+impl<R> SuiRpcModule for CoinReadApi<R>
+where
+    R: Send + Sync + 'static,
+{
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        sui_json_rpc::api::CoinReadApiOpenRpc::module_doc()
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use sui_json_rpc::api::GovernanceReadApiServer;
+use sui_json_rpc::error::{Error, SuiRpcInputError};
+use sui_json_rpc::{with_tracing, SuiRpcModule};
+use sui_json_rpc_types::{DelegatedStake, SuiCommittee, ValidatorApys};
+use sui_open_rpc::Module;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::sui_serde::BigInt;
+use sui_types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
+use sui_types::sui_system_state::SuiSystemStateTrait;
+use tracing::instrument;
+
+use crate::SharedSimulacrum;
+
+pub struct GovernanceReadApi<R> {
+    simulacrum: SharedSimulacrum<R>,
+}
+
+impl<R> GovernanceReadApi<R> {
+    pub fn new(simulacrum: SharedSimulacrum<R>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+#[async_trait]
+impl<R> GovernanceReadApiServer for GovernanceReadApi<R>
+where
+    R: Send + Sync + 'static,
+{
+    #[instrument(skip(self))]
+    async fn get_stakes_by_ids(
+        &self,
+        _staked_sui_ids: Vec<ObjectID>,
+    ) -> RpcResult<Vec<DelegatedStake>> {
+        with_tracing!(async move {
+            Err(Error::UnsupportedFeature(
+                "getStakesByIds is not supported by the simulator".to_string(),
+            ))
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_stakes(&self, _owner: SuiAddress) -> RpcResult<Vec<DelegatedStake>> {
+        with_tracing!(async move {
+            Err(Error::UnsupportedFeature(
+                "getStakes is not supported by the simulator".to_string(),
+            ))
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_committee_info(&self, epoch: Option<BigInt<u64>>) -> RpcResult<SuiCommittee> {
+        with_tracing!(async move {
+            let simulacrum = self.simulacrum.read().unwrap();
+            let store = simulacrum.store();
+
+            let epoch = epoch
+                .map(|e| *e)
+                .unwrap_or_else(|| store.get_system_state().epoch());
+
+            let committee = store.get_committee_by_epoch(epoch).ok_or_else(|| {
+                SuiRpcInputError::GenericNotFound(format!("Committee for epoch {epoch} not found"))
+            })?;
+
+            Ok(committee.clone().into())
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_latest_sui_system_state(&self) -> RpcResult<SuiSystemStateSummary> {
+        with_tracing!(async move {
+            let simulacrum = self.simulacrum.read().unwrap();
+            Ok(simulacrum
+                .store()
+                .get_system_state()
+                .into_sui_system_state_summary())
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_reference_gas_price(&self) -> RpcResult<BigInt<u64>> {
+        with_tracing!(async move {
+            let simulacrum = self.simulacrum.read().unwrap();
+            Ok(simulacrum.reference_gas_price().into())
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_validators_apy(&self) -> RpcResult<ValidatorApys> {
+        with_tracing!(async move {
+            Err(Error::UnsupportedFeature(
+                "getValidatorsApy is not supported by the simulator".to_string(),
+            ))
+        })
+    }
+}
+
+impl<R> SuiRpcModule for GovernanceReadApi<R>
+where
+    R: Send + Sync + 'static,
+{
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        sui_json_rpc::api::GovernanceReadApiOpenRpc::module_doc()
+    }
+}
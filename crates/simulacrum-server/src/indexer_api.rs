@@ -0,0 +1,289 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::types::SubscriptionResult;
+use jsonrpsee::{RpcModule, SubscriptionSink};
+use simulacrum::InMemoryStore;
+use sui_json_rpc::api::{validate_limit, IndexerApiServer, QUERY_MAX_RESULT_LIMIT};
+use sui_json_rpc::error::{Error, SuiRpcInputError};
+use sui_json_rpc::{with_tracing, SuiRpcModule};
+use sui_json_rpc_types::{
+    DynamicFieldPage, EventFilter, EventPage, ObjectsPage, Page, SuiObjectResponse,
+    SuiObjectResponseQuery, SuiTransactionBlockResponseQuery, TransactionBlocksPage,
+    TransactionFilter,
+};
+use sui_open_rpc::Module;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::digests::TransactionDigest;
+use sui_types::dynamic_field::DynamicFieldName;
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::error::{SuiError, UserInputError};
+use sui_types::event::EventID;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::transaction::TransactionDataAPI;
+use tracing::instrument;
+
+use crate::read_api::transaction_block_response;
+use crate::SharedSimulacrum;
+
+/// Serves the transaction queries of the extended API. Only transactions that have been included
+/// in a checkpoint are indexed, as on a fullnode.
+pub struct IndexerApi<R> {
+    simulacrum: SharedSimulacrum<R>,
+}
+
+impl<R> IndexerApi<R> {
+    pub fn new(simulacrum: SharedSimulacrum<R>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+impl<R: Send + Sync> IndexerApi<R> {
+    /// The digests of checkpointed transactions matching `filter`, in the order they were
+    /// executed (or the reverse), starting after `cursor`. At most `limit + 1` digests are
+    /// returned, to tell whether there is another page.
+    fn transaction_digests(
+        &self,
+        filter: Option<&TransactionFilter>,
+        cursor: Option<TransactionDigest>,
+        limit: usize,
+        descending_order: bool,
+    ) -> Result<Vec<TransactionDigest>, Error> {
+        let simulacrum = self.simulacrum.read().unwrap();
+        let store = simulacrum.store();
+
+        let latest = store
+            .get_highest_checkpint()
+            .map_or(0, |checkpoint| *checkpoint.sequence_number());
+
+        let mut digests = Vec::new();
+        for sequence_number in 0..=latest {
+            let Some(checkpoint) = store.get_checkpoint_by_sequence_number(sequence_number) else {
+                continue;
+            };
+
+            let contents = store
+                .get_checkpoint_contents(&checkpoint.content_digest)
+                .ok_or(UserInputError::CheckpointContentsNotFound(
+                    checkpoint.content_digest,
+                ))?;
+
+            for execution in contents.iter() {
+                let digest = execution.transaction;
+                if let Some(filter) = filter {
+                    if !matches(store, filter, &digest, sequence_number)? {
+                        continue;
+                    }
+                }
+                digests.push(digest);
+            }
+        }
+
+        if descending_order {
+            digests.reverse();
+        }
+
+        if let Some(cursor) = cursor {
+            let position = digests
+                .iter()
+                .position(|digest| *digest == cursor)
+                .ok_or(SuiError::TransactionNotFound { digest: cursor })?;
+            digests.drain(..=position);
+        }
+
+        digests.truncate(limit + 1);
+        Ok(digests)
+    }
+}
+
+#[async_trait]
+impl<R> IndexerApiServer for IndexerApi<R>
+where
+    R: Send + Sync + 'static,
+{
+    #[instrument(skip(self))]
+    async fn get_owned_objects(
+        &self,
+        _address: SuiAddress,
+        _query: Option<SuiObjectResponseQuery>,
+        _cursor: Option<ObjectID>,
+        _limit: Option<usize>,
+    ) -> RpcResult<ObjectsPage> {
+        with_tracing!(async move {
+            Err(Error::UnsupportedFeature(
+                "getOwnedObjects is not supported by the simulator".to_string(),
+            ))
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn query_transaction_blocks(
+        &self,
+        query: SuiTransactionBlockResponseQuery,
+        cursor: Option<TransactionDigest>,
+        limit: Option<usize>,
+        descending_order: Option<bool>,
+    ) -> RpcResult<TransactionBlocksPage> {
+        with_tracing!(async move {
+            let limit =
+                validate_limit(limit, *QUERY_MAX_RESULT_LIMIT).map_err(SuiRpcInputError::from)?;
+
+            let mut digests = self.transaction_digests(
+                query.filter.as_ref(),
+                cursor,
+                limit,
+                descending_order.unwrap_or(false),
+            )?;
+
+            let has_next_page = digests.len() > limit;
+            digests.truncate(limit);
+            let next_cursor = if has_next_page {
+                digests.last().copied()
+            } else {
+                None
+            };
+
+            let options = query.options.unwrap_or_default();
+            let mut data = Vec::with_capacity(digests.len());
+            for digest in digests {
+                data.push(
+                    transaction_block_response(&self.simulacrum, digest, options.clone()).await?,
+                );
+            }
+
+            Ok(Page {
+                data,
+                next_cursor,
+                has_next_page,
+            })
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn query_events(
+        &self,
+        _query: EventFilter,
+        _cursor: Option<EventID>,
+        _limit: Option<usize>,
+        _descending_order: Option<bool>,
+    ) -> RpcResult<EventPage> {
+        with_tracing!(async move {
+            Err(Error::UnsupportedFeature(
+                "queryEvents is not supported by the simulator".to_string(),
+            ))
+        })
+    }
+
+    fn subscribe_event(&self, _sink: SubscriptionSink, _filter: EventFilter) -> SubscriptionResult {
+        Err(anyhow!("subscribeEvent is not supported by the simulator").into())
+    }
+
+    fn subscribe_transaction(
+        &self,
+        _sink: SubscriptionSink,
+        _filter: TransactionFilter,
+    ) -> SubscriptionResult {
+        Err(anyhow!("subscribeTransaction is not supported by the simulator").into())
+    }
+
+    #[instrument(skip(self))]
+    async fn get_dynamic_fields(
+        &self,
+        _parent_object_id: ObjectID,
+        _cursor: Option<ObjectID>,
+        _limit: Option<usize>,
+    ) -> RpcResult<DynamicFieldPage> {
+        with_tracing!(async move {
+            Err(Error::UnsupportedFeature(
+                "getDynamicFields is not supported by the simulator".to_string(),
+            ))
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_dynamic_field_object(
+        &self,
+        _parent_object_id: ObjectID,
+        _name: DynamicFieldName,
+    ) -> RpcResult<SuiObjectResponse> {
+        with_tracing!(async move {
+            Err(Error::UnsupportedFeature(
+                "getDynamicFieldObject is not supported by the simulator".to_string(),
+            ))
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn resolve_name_service_address(&self, _name: String) -> RpcResult<Option<SuiAddress>> {
+        with_tracing!(async move {
+            Err(Error::UnsupportedFeature(
+                "resolveNameServiceAddress is not supported by the simulator".to_string(),
+            ))
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn resolve_name_service_names(
+        &self,
+        _address: SuiAddress,
+        _cursor: Option<ObjectID>,
+        _limit: Option<usize>,
+    ) -> RpcResult<Page<String, ObjectID>> {
+        with_tracing!(async move {
+            Err(Error::UnsupportedFeature(
+                "resolveNameServiceNames is not supported by the simulator".to_string(),
+            ))
+        })
+    }
+}
+
+impl<R> SuiRpcModule for IndexerApi<R>
+where
+    R: Send + Sync + 'static,
+{
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        sui_json_rpc::api::IndexerApiOpenRpc::module_doc()
+    }
+}
+
+/// Whether the checkpointed transaction with `digest` matches `filter`, following the semantics of
+/// a fullnode's transaction indices: changed objects include created, mutated and unwrapped
+/// objects, and input objects include every object the transaction takes as input.
+fn matches(
+    store: &InMemoryStore,
+    filter: &TransactionFilter,
+    digest: &TransactionDigest,
+    checkpoint: CheckpointSequenceNumber,
+) -> Result<bool, Error> {
+    let transaction = store
+        .get_transaction(digest)
+        .ok_or(SuiError::TransactionNotFound { digest: *digest })?;
+    let data = transaction.data().transaction_data();
+
+    Ok(match filter {
+        TransactionFilter::Checkpoint(sequence_number) => checkpoint == *sequence_number,
+        TransactionFilter::FromAddress(address) => data.sender() == *address,
+        TransactionFilter::InputObject(id) => data
+            .input_objects()?
+            .iter()
+            .any(|object| object.object_id() == *id),
+        TransactionFilter::ChangedObject(id) => store
+            .get_transaction_effects(digest)
+            .ok_or(SuiError::TransactionNotFound { digest: *digest })?
+            .all_changed_objects()
+            .iter()
+            .any(|((object_id, _, _), _, _)| object_id == id),
+        _ => {
+            return Err(Error::UnsupportedFeature(format!(
+                "Querying transactions by {filter:?} is not supported by the simulator"
+            )))
+        }
+    })
+}
@@ -0,0 +1,393 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Serves a [`Simulacrum`] over the network.
+//!
+//! This crate exposes a subset of the Sui JSON-RPC read, write, coin, governance and extended
+//! (transaction query) APIs, and the checkpoint, object and transaction routes of the REST API, on
+//! top of a [`Simulacrum`], so that clients written in other languages can test against a
+//! deterministic, in-process chain. Because the chain doesn't do anything unless acted upon, an
+//! additional `simulacrum_*` JSON-RPC namespace is served, to advance the clock, advance the
+//! epoch, create checkpoints and request gas.
+//!
+//! JSON-RPC is served from `/`, and the REST API is served from `/rest`.
+//!
+//! [`Simulacrum`]: simulacrum::Simulacrum
+
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use axum::Router;
+use prometheus::Registry;
+use rand::rngs::OsRng;
+use simulacrum::Simulacrum;
+use sui_json_rpc::error::Error;
+use sui_json_rpc::{JsonRpcServerBuilder, ObjectProvider};
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::error::UserInputError;
+use sui_types::object::Object;
+use tokio::task::JoinHandle;
+use tracing::info;
+
+pub use crate::coin_api::CoinReadApi;
+pub use crate::governance_api::GovernanceReadApi;
+pub use crate::indexer_api::IndexerApi;
+pub use crate::read_api::ReadApi;
+pub use crate::simulacrum_api::{SimulacrumApi, SimulacrumApiClient, SimulacrumApiServer};
+pub use crate::write_api::WriteApi;
+
+mod coin_api;
+mod governance_api;
+mod indexer_api;
+mod read_api;
+mod simulacrum_api;
+mod write_api;
+
+/// The path that the REST API is nested under.
+pub const REST_API_PATH: &str = "/rest";
+
+/// A `Simulacrum` that can be shared between the handlers of concurrent requests.
+pub type SharedSimulacrum<R = OsRng> = Arc<RwLock<Simulacrum<R>>>;
+
+pub struct ServerHandle {
+    local_addr: SocketAddr,
+    handle: JoinHandle<()>,
+}
+
+impl ServerHandle {
+    /// The address that the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub async fn stopped(self) {
+        self.handle.await.unwrap()
+    }
+}
+
+/// Build a router serving the JSON-RPC and REST APIs on top of `simulacrum`.
+pub fn router<R>(simulacrum: SharedSimulacrum<R>) -> Result<Router>
+where
+    R: rand::RngCore + rand::CryptoRng + Send + Sync + 'static,
+{
+    let mut builder = JsonRpcServerBuilder::new(env!("CARGO_PKG_VERSION"), &Registry::new());
+    builder.register_module(ReadApi::new(simulacrum.clone()))?;
+    builder.register_module(WriteApi::new(simulacrum.clone()))?;
+    builder.register_module(CoinReadApi::new(simulacrum.clone()))?;
+    builder.register_module(GovernanceReadApi::new(simulacrum.clone()))?;
+    builder.register_module(IndexerApi::new(simulacrum.clone()))?;
+    builder.register_module(SimulacrumApi::new(simulacrum.clone()))?;
+
+    Ok(builder.to_router(None)?.nest(
+        REST_API_PATH,
+        sui_rest_api::rest_router(simulacrum.clone(), Some(simulacrum)),
+    ))
+}
+
+/// Start serving the JSON-RPC and REST APIs on top of `simulacrum`, at `listen_address`.
+pub async fn start<R>(
+    simulacrum: SharedSimulacrum<R>,
+    listen_address: SocketAddr,
+) -> Result<ServerHandle>
+where
+    R: rand::RngCore + rand::CryptoRng + Send + Sync + 'static,
+{
+    let app = router(simulacrum)?;
+    let server = axum::Server::try_bind(&listen_address)?.serve(app.into_make_service());
+
+    let local_addr = server.local_addr();
+    let handle = tokio::spawn(async move { server.await.unwrap() });
+
+    info!(?local_addr, "Simulacrum server listening on {local_addr}");
+    Ok(ServerHandle { local_addr, handle })
+}
+
+/// Provides past versions of objects from the simulacrum's store, to calculate the object and
+/// balance changes of transactions.
+///
+/// Acquires the lock on the simulacrum for each object it fetches, rather than holding it for the
+/// duration of the calculation, which spans await points.
+struct StoreObjectProvider<R>(SharedSimulacrum<R>);
+
+#[async_trait]
+impl<R: Send + Sync> ObjectProvider for StoreObjectProvider<R> {
+    type Error = Error;
+
+    async fn get_object(
+        &self,
+        id: &ObjectID,
+        version: &SequenceNumber,
+    ) -> Result<Object, Self::Error> {
+        let simulacrum = self.0.read().unwrap();
+        simulacrum
+            .store()
+            .get_object_at_version(id, *version)
+            .cloned()
+            .ok_or_else(|| {
+                UserInputError::ObjectNotFound {
+                    object_id: *id,
+                    version: Some(*version),
+                }
+                .into()
+            })
+    }
+
+    async fn find_object_lt_or_eq_version(
+        &self,
+        id: &ObjectID,
+        version: &SequenceNumber,
+    ) -> Result<Option<Object>, Self::Error> {
+        let simulacrum = self.0.read().unwrap();
+        Ok(simulacrum
+            .store()
+            .get_object_lt_or_eq_version(id, *version)
+            .cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use jsonrpsee::http_client::HttpClientBuilder;
+    use rand::{rngs::StdRng, SeedableRng};
+    use shared_crypto::intent::Intent;
+    use sui_json_rpc::api::{
+        CoinReadApiClient, GovernanceReadApiClient, IndexerApiClient, ReadApiClient,
+    };
+    use sui_json_rpc_types::{
+        SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseQuery, TransactionFilter,
+    };
+    use sui_rest_api::RestError;
+    use sui_types::base_types::SuiAddress;
+    use sui_types::digests::TransactionDigest;
+    use sui_types::effects::TransactionEffectsAPI;
+    use sui_types::gas_coin::MIST_PER_SUI;
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+    use sui_types::transaction::{Transaction, TransactionData};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn drive_simulacrum_over_rpc() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let address = SuiAddress::generate(&mut rng);
+        let simulacrum = Arc::new(RwLock::new(Simulacrum::new_with_rng(rng)));
+
+        let server = start(simulacrum, "127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let client = HttpClientBuilder::default()
+            .build(format!("http://{}", server.local_addr()))
+            .unwrap();
+
+        let effects = client.request_gas(address, MIST_PER_SUI).await.unwrap();
+        client.advance_clock(1000).await.unwrap();
+        let checkpoint = client.create_checkpoint().await.unwrap();
+
+        assert_eq!(checkpoint.sequence_number, 1);
+        assert!(checkpoint
+            .transactions
+            .contains(effects.transaction_digest()));
+        let latest = client
+            .get_latest_checkpoint_sequence_number()
+            .await
+            .unwrap();
+        assert_eq!(*latest, 1);
+
+        let balance = client.get_balance(address, None).await.unwrap();
+        assert_eq!(balance.total_balance, MIST_PER_SUI as u128);
+
+        let response = client
+            .get_transaction_block(*effects.transaction_digest(), None)
+            .await
+            .unwrap();
+        assert_eq!(response.digest, *effects.transaction_digest());
+
+        client.advance_epoch().await.unwrap();
+        let state = client.get_latest_sui_system_state().await.unwrap();
+        assert_eq!(state.epoch, 1);
+
+        // The REST API is served alongside JSON-RPC.
+        let rest =
+            sui_rest_api::Client::new(format!("http://{}{REST_API_PATH}", server.local_addr()));
+        let latest = rest.get_latest_checkpoint().await.unwrap();
+        assert_eq!(*latest.sequence_number(), 2);
+    }
+
+    #[tokio::test]
+    async fn query_transactions_over_rpc() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let address = SuiAddress::generate(&mut rng);
+        let simulacrum = Arc::new(RwLock::new(Simulacrum::new_with_rng(rng)));
+
+        let (first, second) = {
+            let mut simulacrum = simulacrum.write().unwrap();
+            let first = simulacrum.request_gas(address, MIST_PER_SUI).unwrap();
+            let second = simulacrum.request_gas(address, MIST_PER_SUI).unwrap();
+            simulacrum.create_checkpoint();
+            (first, second)
+        };
+
+        let server = start(simulacrum, "127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let client = HttpClientBuilder::default()
+            .build(format!("http://{}", server.local_addr()))
+            .unwrap();
+
+        // Transactions are found by the objects they change.
+        let (coin, _) = first.created()[0];
+        let query = SuiTransactionBlockResponseQuery::new_with_filter(
+            TransactionFilter::ChangedObject(coin.0),
+        );
+        let page = client
+            .query_transaction_blocks(query, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(page.data.len(), 1);
+        assert_eq!(page.data[0].digest, *first.transaction_digest());
+        assert!(!page.has_next_page);
+
+        // Queries are paginated, in either order.
+        let query =
+            SuiTransactionBlockResponseQuery::new_with_filter(TransactionFilter::Checkpoint(1));
+        let page = client
+            .query_transaction_blocks(query.clone(), None, Some(1), Some(true))
+            .await
+            .unwrap();
+        assert_eq!(page.data[0].digest, *second.transaction_digest());
+        assert!(page.has_next_page);
+
+        let page = client
+            .query_transaction_blocks(query, page.next_cursor, Some(1), Some(true))
+            .await
+            .unwrap();
+        assert_eq!(page.data[0].digest, *first.transaction_digest());
+        assert!(!page.has_next_page);
+    }
+
+    #[tokio::test]
+    async fn execute_over_rest() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let recipient = SuiAddress::generate(&mut rng);
+        let simulacrum = Simulacrum::new_with_rng(rng);
+
+        let (sender, key) = simulacrum.keystore().accounts().next().unwrap();
+        let gas = simulacrum
+            .store()
+            .owned_objects(*sender)
+            .find(|object| object.is_gas_coin())
+            .unwrap()
+            .compute_object_reference();
+
+        let pt = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            builder.transfer_sui(recipient, Some(MIST_PER_SUI));
+            builder.finish()
+        };
+
+        let tx_data = TransactionData::new_programmable(
+            *sender,
+            vec![gas],
+            pt,
+            1_000_000_000,
+            simulacrum.reference_gas_price(),
+        );
+        let tx = Transaction::from_data_and_signer(tx_data, Intent::sui_transaction(), vec![key]);
+
+        let server = start(
+            Arc::new(RwLock::new(simulacrum)),
+            "127.0.0.1:0".parse().unwrap(),
+        )
+        .await
+        .unwrap();
+        let rest =
+            sui_rest_api::Client::new(format!("http://{}{REST_API_PATH}", server.local_addr()));
+
+        let executed = rest.execute_transaction(&tx).await.unwrap();
+        assert!(executed.effects.status().is_ok());
+        assert_eq!(executed.effects.transaction_digest(), tx.digest());
+
+        let fetched = rest.get_transaction(*tx.digest()).await.unwrap();
+        assert_eq!(fetched.transaction.digest(), tx.digest());
+        assert_eq!(fetched.effects, executed.effects);
+        assert_eq!(fetched.events, executed.events);
+    }
+
+    #[tokio::test]
+    async fn stream_checkpoints_over_rest() {
+        let simulacrum = Arc::new(RwLock::new(Simulacrum::new_with_rng(
+            StdRng::seed_from_u64(3),
+        )));
+        simulacrum.write().unwrap().create_checkpoint();
+
+        let server = start(simulacrum.clone(), "127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let rest =
+            sui_rest_api::Client::new(format!("http://{}{REST_API_PATH}", server.local_addr()));
+
+        // Existing checkpoints are streamed straight away.
+        let mut stream = Box::pin(rest.stream_checkpoints(0).await.unwrap());
+        for expected in 0..=1 {
+            let checkpoint = stream.next().await.unwrap().unwrap();
+            assert_eq!(checkpoint.checkpoint_summary.sequence_number, expected);
+        }
+
+        // New checkpoints are streamed as they are created.
+        let address = SuiAddress::generate(&mut StdRng::seed_from_u64(4));
+        let effects = {
+            let mut simulacrum = simulacrum.write().unwrap();
+            let effects = simulacrum.request_gas(address, MIST_PER_SUI).unwrap();
+            simulacrum.create_checkpoint();
+            effects
+        };
+
+        let checkpoint = stream.next().await.unwrap().unwrap();
+        assert_eq!(checkpoint.checkpoint_summary.sequence_number, 2);
+        assert_eq!(
+            checkpoint.transactions[0].transaction.digest(),
+            effects.transaction_digest()
+        );
+
+        // Streams can be resumed from any checkpoint.
+        let mut resumed = Box::pin(rest.stream_checkpoints(2).await.unwrap());
+        let checkpoint = resumed.next().await.unwrap().unwrap();
+        assert_eq!(checkpoint.checkpoint_summary.sequence_number, 2);
+    }
+
+    #[tokio::test]
+    async fn rest_errors() {
+        let simulacrum = Arc::new(RwLock::new(Simulacrum::new_with_rng(
+            StdRng::seed_from_u64(5),
+        )));
+        let server = start(simulacrum, "127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let rest =
+            sui_rest_api::Client::new(format!("http://{}{REST_API_PATH}", server.local_addr()));
+
+        let err = rest.get_object(ObjectID::ZERO).await.unwrap_err();
+        assert!(
+            matches!(err.rest_error(), Some(RestError::NotFound(_))),
+            "{err}"
+        );
+
+        let err = rest.get_full_checkpoint(100).await.unwrap_err();
+        assert!(
+            matches!(err.rest_error(), Some(RestError::NotFound(_))),
+            "{err}"
+        );
+
+        let err = rest
+            .get_transaction(TransactionDigest::ZERO)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err.rest_error(), Some(RestError::NotFound(_))),
+            "{err}"
+        );
+    }
+}
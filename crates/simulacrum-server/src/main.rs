@@ -0,0 +1,49 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, RwLock};
+
+use clap::Parser;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use simulacrum::Simulacrum;
+
+#[derive(Parser)]
+#[clap(
+    name = "simulacrum-server",
+    about = "Serve the JSON-RPC and REST APIs on top of a simulated Sui chain"
+)]
+struct Args {
+    #[clap(long, default_value = "127.0.0.1")]
+    host: IpAddr,
+
+    #[clap(long, default_value_t = 9000)]
+    port: u16,
+
+    /// Seed for the simulator's source of randomness. Chains created with the same seed, that are
+    /// sent the same requests, evolve identically. A random seed is used if none is provided.
+    #[clap(long)]
+    seed: Option<u64>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let _guard = telemetry_subscribers::TelemetryConfig::new()
+        .with_env()
+        .init();
+
+    let args = Args::parse();
+
+    let rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let simulacrum = Arc::new(RwLock::new(Simulacrum::new_with_rng(rng)));
+    let server =
+        simulacrum_server::start(simulacrum, SocketAddr::new(args.host, args.port)).await?;
+
+    server.stopped().await;
+    Ok(())
+}
@@ -0,0 +1,530 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use simulacrum::InMemoryStore;
+use sui_json_rpc::api::{
+    validate_limit, ReadApiServer, QUERY_MAX_RESULT_LIMIT, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS,
+};
+use sui_json_rpc::error::{Error, SuiRpcInputError};
+use sui_json_rpc::{
+    get_balance_changes_from_effect, get_object_changes, with_tracing, SuiRpcModule,
+};
+use sui_json_rpc_types::{
+    Checkpoint, CheckpointId, CheckpointPage, ProtocolConfigResponse, SuiEvent,
+    SuiGetPastObjectRequest, SuiLoadedChildObjectsResponse, SuiObjectDataOptions,
+    SuiObjectResponse, SuiPastObjectResponse, SuiTransactionBlock, SuiTransactionBlockEvents,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_open_rpc::Module;
+use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
+use sui_types::base_types::{ObjectID, SequenceNumber, TransactionDigest};
+use sui_types::digests::ChainIdentifier;
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents};
+use sui_types::error::{SuiError, UserInputError};
+use sui_types::messages_checkpoint::{CheckpointSequenceNumber, VerifiedCheckpoint};
+use sui_types::object::{ObjectFormatOptions, ObjectRead};
+use sui_types::sui_serde::BigInt;
+use sui_types::sui_system_state::SuiSystemStateTrait;
+use sui_types::transaction::TransactionDataAPI;
+use tracing::instrument;
+
+use crate::{SharedSimulacrum, StoreObjectProvider};
+
+pub struct ReadApi<R> {
+    simulacrum: SharedSimulacrum<R>,
+}
+
+impl<R> ReadApi<R> {
+    pub fn new(simulacrum: SharedSimulacrum<R>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+impl<R: Send + Sync> ReadApi<R> {
+    fn get_object(
+        &self,
+        object_id: ObjectID,
+        options: SuiObjectDataOptions,
+    ) -> Result<SuiObjectResponse, Error> {
+        let simulacrum = self.simulacrum.read().unwrap();
+        let store = simulacrum.store();
+
+        let object_read = match store.get_object(&object_id) {
+            None => ObjectRead::NotExists(object_id),
+            Some(object) => {
+                let layout = object.get_layout(ObjectFormatOptions::default(), store)?;
+                ObjectRead::Exists(object.compute_object_reference(), object.clone(), layout)
+            }
+        };
+
+        Ok((object_read, options).try_into()?)
+    }
+
+    fn try_get_past_object(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+        options: SuiObjectDataOptions,
+    ) -> Result<SuiPastObjectResponse, Error> {
+        let simulacrum = self.simulacrum.read().unwrap();
+        let store = simulacrum.store();
+
+        if let Some(object) = store.get_object_at_version(&object_id, version) {
+            let layout = object.get_layout(ObjectFormatOptions::default(), store)?;
+            let data = (
+                object.compute_object_reference(),
+                object.clone(),
+                layout,
+                options,
+            )
+                .try_into()?;
+            return Ok(SuiPastObjectResponse::VersionFound(data));
+        }
+
+        Ok(
+            match store.get_object_lt_or_eq_version(&object_id, SequenceNumber::MAX) {
+                None => SuiPastObjectResponse::ObjectNotExists(object_id),
+                Some(latest) if latest.version() < version => {
+                    SuiPastObjectResponse::VersionTooHigh {
+                        object_id,
+                        asked_version: version,
+                        latest_version: latest.version(),
+                    }
+                }
+                Some(_) => SuiPastObjectResponse::VersionNotFound(object_id, version),
+            },
+        )
+    }
+
+    fn get_checkpoint(&self, id: CheckpointId) -> Result<Checkpoint, Error> {
+        let simulacrum = self.simulacrum.read().unwrap();
+        let store = simulacrum.store();
+
+        let summary = match id {
+            CheckpointId::SequenceNumber(seq) => store
+                .get_checkpoint_by_sequence_number(seq)
+                .ok_or(UserInputError::VerifiedCheckpointNotFound(seq))?,
+            CheckpointId::Digest(digest) => {
+                store.get_checkpoint_by_digest(&digest).ok_or_else(|| {
+                    UserInputError::VerifiedCheckpointDigestNotFound(digest.to_string())
+                })?
+            }
+        };
+
+        checkpoint(store, summary.clone())
+    }
+
+    fn get_checkpoints(
+        &self,
+        cursor: Option<CheckpointSequenceNumber>,
+        limit: Option<usize>,
+        descending_order: bool,
+    ) -> Result<CheckpointPage, Error> {
+        let limit = validate_limit(limit, QUERY_MAX_RESULT_LIMIT_CHECKPOINTS)
+            .map_err(SuiRpcInputError::from)?;
+
+        let simulacrum = self.simulacrum.read().unwrap();
+        let store = simulacrum.store();
+        let latest = latest_checkpoint_sequence_number(store);
+
+        let sequence_numbers: Box<dyn Iterator<Item = CheckpointSequenceNumber>> =
+            match (cursor, descending_order) {
+                (None, false) => Box::new(0..=latest),
+                (Some(cursor), false) => Box::new(cursor.saturating_add(1)..=latest),
+                (None, true) => Box::new((0..=latest).rev()),
+                (Some(cursor), true) => Box::new((0..cursor.min(latest + 1)).rev()),
+            };
+
+        let mut data = sequence_numbers
+            .take(limit + 1)
+            .map(|seq| {
+                let summary = store
+                    .get_checkpoint_by_sequence_number(seq)
+                    .ok_or(UserInputError::VerifiedCheckpointNotFound(seq))?;
+                checkpoint(store, summary.clone())
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let has_next_page = data.len() > limit;
+        data.truncate(limit);
+
+        let next_cursor = if has_next_page {
+            data.last().map(|c| c.sequence_number.into())
+        } else {
+            None
+        };
+
+        Ok(CheckpointPage {
+            data,
+            next_cursor,
+            has_next_page,
+        })
+    }
+
+    fn get_events(&self, digest: TransactionDigest) -> Result<Vec<SuiEvent>, Error> {
+        let simulacrum = self.simulacrum.read().unwrap();
+        let store = simulacrum.store();
+
+        let effects = store
+            .get_transaction_effects(&digest)
+            .ok_or(SuiError::TransactionNotFound { digest })?;
+
+        let events = transaction_events(store, effects)?;
+        Ok(SuiTransactionBlockEvents::try_from(events, digest, None, store)?.data)
+    }
+
+    fn get_protocol_config(
+        &self,
+        version: Option<BigInt<u64>>,
+    ) -> Result<ProtocolConfigResponse, Error> {
+        let simulacrum = self.simulacrum.read().unwrap();
+        let store = simulacrum.store();
+
+        let version = version
+            .map(|v| *v)
+            .unwrap_or_else(|| store.get_system_state().protocol_version());
+
+        let config = ProtocolConfig::get_for_version_if_supported(
+            version.into(),
+            chain_identifier(store).chain(),
+        )
+        .ok_or(SuiRpcInputError::ProtocolVersionUnsupported(
+            ProtocolVersion::MIN.as_u64(),
+            ProtocolVersion::MAX.as_u64(),
+        ))?;
+
+        Ok(ProtocolConfigResponse::from(config))
+    }
+}
+
+#[async_trait]
+impl<R> ReadApiServer for ReadApi<R>
+where
+    R: Send + Sync + 'static,
+{
+    #[instrument(skip(self))]
+    async fn get_transaction_block(
+        &self,
+        digest: TransactionDigest,
+        options: Option<SuiTransactionBlockResponseOptions>,
+    ) -> RpcResult<SuiTransactionBlockResponse> {
+        with_tracing!(async move {
+            transaction_block_response(&self.simulacrum, digest, options.unwrap_or_default()).await
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn multi_get_transaction_blocks(
+        &self,
+        digests: Vec<TransactionDigest>,
+        options: Option<SuiTransactionBlockResponseOptions>,
+    ) -> RpcResult<Vec<SuiTransactionBlockResponse>> {
+        with_tracing!(async move {
+            if digests.len() > *QUERY_MAX_RESULT_LIMIT {
+                Err(SuiRpcInputError::SizeLimitExceeded(
+                    QUERY_MAX_RESULT_LIMIT.to_string(),
+                ))?
+            }
+
+            let options = options.unwrap_or_default();
+            let mut responses = Vec::with_capacity(digests.len());
+            for digest in digests {
+                responses.push(
+                    transaction_block_response(&self.simulacrum, digest, options.clone()).await?,
+                );
+            }
+
+            Ok(responses)
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_object(
+        &self,
+        object_id: ObjectID,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiObjectResponse> {
+        with_tracing!(async move { self.get_object(object_id, options.unwrap_or_default()) })
+    }
+
+    #[instrument(skip(self))]
+    async fn multi_get_objects(
+        &self,
+        object_ids: Vec<ObjectID>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiObjectResponse>> {
+        with_tracing!(async move {
+            if object_ids.len() > *QUERY_MAX_RESULT_LIMIT {
+                Err(SuiRpcInputError::SizeLimitExceeded(
+                    QUERY_MAX_RESULT_LIMIT.to_string(),
+                ))?
+            }
+
+            let options = options.unwrap_or_default();
+            object_ids
+                .into_iter()
+                .map(|id| self.get_object(id, options.clone()))
+                .collect()
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn try_get_past_object(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<SuiPastObjectResponse> {
+        with_tracing!(async move {
+            self.try_get_past_object(object_id, version, options.unwrap_or_default())
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn try_multi_get_past_objects(
+        &self,
+        past_objects: Vec<SuiGetPastObjectRequest>,
+        options: Option<SuiObjectDataOptions>,
+    ) -> RpcResult<Vec<SuiPastObjectResponse>> {
+        with_tracing!(async move {
+            if past_objects.len() > *QUERY_MAX_RESULT_LIMIT {
+                Err(SuiRpcInputError::SizeLimitExceeded(
+                    QUERY_MAX_RESULT_LIMIT.to_string(),
+                ))?
+            }
+
+            let options = options.unwrap_or_default();
+            past_objects
+                .into_iter()
+                .map(|req| self.try_get_past_object(req.object_id, req.version, options.clone()))
+                .collect()
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_loaded_child_objects(
+        &self,
+        _digest: TransactionDigest,
+    ) -> RpcResult<SuiLoadedChildObjectsResponse> {
+        with_tracing!(async move {
+            Err(Error::UnsupportedFeature(
+                "getLoadedChildObjects is not supported by the simulator".to_string(),
+            ))
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_checkpoint(&self, id: CheckpointId) -> RpcResult<Checkpoint> {
+        with_tracing!(async move { self.get_checkpoint(id) })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_checkpoints(
+        &self,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<usize>,
+        descending_order: bool,
+    ) -> RpcResult<CheckpointPage> {
+        with_tracing!(
+            async move { self.get_checkpoints(cursor.map(|c| *c), limit, descending_order) }
+        )
+    }
+
+    #[instrument(skip(self))]
+    async fn get_checkpoints_deprecated_limit(
+        &self,
+        cursor: Option<BigInt<u64>>,
+        limit: Option<BigInt<u64>>,
+        descending_order: bool,
+    ) -> RpcResult<CheckpointPage> {
+        with_tracing!(async move {
+            self.get_checkpoints(
+                cursor.map(|c| *c),
+                limit.map(|l| *l as usize),
+                descending_order,
+            )
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_events(&self, transaction_digest: TransactionDigest) -> RpcResult<Vec<SuiEvent>> {
+        with_tracing!(async move { self.get_events(transaction_digest) })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_total_transaction_blocks(&self) -> RpcResult<BigInt<u64>> {
+        with_tracing!(async move {
+            let simulacrum = self.simulacrum.read().unwrap();
+            Ok(simulacrum
+                .store()
+                .get_highest_checkpint()
+                .map_or(0, |c| c.network_total_transactions)
+                .into())
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_latest_checkpoint_sequence_number(&self) -> RpcResult<BigInt<u64>> {
+        with_tracing!(async move {
+            let simulacrum = self.simulacrum.read().unwrap();
+            Ok(latest_checkpoint_sequence_number(simulacrum.store()).into())
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_protocol_config(
+        &self,
+        version: Option<BigInt<u64>>,
+    ) -> RpcResult<ProtocolConfigResponse> {
+        with_tracing!(async move { self.get_protocol_config(version) })
+    }
+
+    #[instrument(skip(self))]
+    async fn get_chain_identifier(&self) -> RpcResult<String> {
+        with_tracing!(async move {
+            let simulacrum = self.simulacrum.read().unwrap();
+            Ok(chain_identifier(simulacrum.store()).to_string())
+        })
+    }
+}
+
+impl<R> SuiRpcModule for ReadApi<R>
+where
+    R: Send + Sync + 'static,
+{
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        sui_json_rpc::api::ReadApiOpenRpc::module_doc()
+    }
+}
+
+/// Build the response for the executed transaction with `digest`, containing the fields requested
+/// in `options`.
+pub(crate) async fn transaction_block_response<R: Send + Sync>(
+    simulacrum: &SharedSimulacrum<R>,
+    digest: TransactionDigest,
+    options: SuiTransactionBlockResponseOptions,
+) -> Result<SuiTransactionBlockResponse, Error> {
+    let mut response = SuiTransactionBlockResponse::new(digest);
+
+    let (transaction, effects) = {
+        let simulacrum = simulacrum.read().unwrap();
+        let store = simulacrum.store();
+
+        let transaction = store
+            .get_transaction(&digest)
+            .ok_or(SuiError::TransactionNotFound { digest })?
+            .clone();
+
+        let effects = store
+            .get_transaction_effects(&digest)
+            .ok_or(SuiError::TransactionNotFound { digest })?
+            .clone();
+
+        if let Some(sequence_number) = store.get_transaction_checkpoint(&digest) {
+            response.checkpoint = Some(sequence_number);
+            response.timestamp_ms = store
+                .get_checkpoint_by_sequence_number(sequence_number)
+                .map(|checkpoint| checkpoint.timestamp_ms);
+        }
+
+        if options.show_input {
+            response.transaction = Some(SuiTransactionBlock::try_from(
+                transaction.data().clone(),
+                store,
+            )?);
+        }
+
+        if options.show_raw_input {
+            response.raw_transaction = bcs::to_bytes(transaction.data())?;
+        }
+
+        if options.show_events {
+            let events = transaction_events(store, &effects)?;
+            response.events = Some(SuiTransactionBlockEvents::try_from(
+                events, digest, None, store,
+            )?);
+        }
+
+        (transaction, effects)
+    };
+
+    let object_provider = StoreObjectProvider(simulacrum.clone());
+    let tx_data = transaction.data().transaction_data();
+
+    if options.show_balance_changes {
+        let input_objs = tx_data.input_objects().unwrap_or_default();
+        response.balance_changes = Some(
+            get_balance_changes_from_effect(&object_provider, &effects, input_objs, None).await?,
+        );
+    }
+
+    if options.show_object_changes {
+        response.object_changes = Some(
+            get_object_changes(
+                &object_provider,
+                tx_data.sender(),
+                effects.modified_at_versions(),
+                effects.all_changed_objects(),
+                effects.all_removed_objects(),
+            )
+            .await?,
+        );
+    }
+
+    if options.show_effects {
+        response.effects = Some(effects.try_into()?);
+    }
+
+    Ok(response)
+}
+
+/// Convert a checkpoint from the store into its JSON-RPC representation.
+pub(crate) fn checkpoint(
+    store: &InMemoryStore,
+    summary: VerifiedCheckpoint,
+) -> Result<Checkpoint, Error> {
+    let contents = store
+        .get_checkpoint_contents(&summary.content_digest)
+        .ok_or(UserInputError::CheckpointContentsNotFound(
+            summary.content_digest,
+        ))?
+        .clone();
+
+    let signature = summary.auth_sig().signature.clone();
+    Ok((summary.into_inner().into_data(), contents, signature).into())
+}
+
+fn transaction_events(
+    store: &InMemoryStore,
+    effects: &TransactionEffects,
+) -> Result<TransactionEvents, Error> {
+    let Some(digest) = effects.events_digest() else {
+        return Ok(TransactionEvents::default());
+    };
+
+    Ok(store
+        .get_transaction_events(digest)
+        .ok_or(SuiError::TransactionEventsNotFound { digest: *digest })?
+        .clone())
+}
+
+fn latest_checkpoint_sequence_number(store: &InMemoryStore) -> CheckpointSequenceNumber {
+    store
+        .get_highest_checkpint()
+        .map_or(0, |checkpoint| *checkpoint.sequence_number())
+}
+
+/// The simulated chain is identified by its genesis checkpoint, like a real network.
+fn chain_identifier(store: &InMemoryStore) -> ChainIdentifier {
+    let genesis = store
+        .get_checkpoint_by_sequence_number(0)
+        .expect("genesis checkpoint must exist");
+    ChainIdentifier::from(*genesis.digest())
+}
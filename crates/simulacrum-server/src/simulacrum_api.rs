@@ -0,0 +1,125 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use jsonrpsee::RpcModule;
+use sui_json_rpc::error::Error;
+use sui_json_rpc::{with_tracing, SuiRpcModule};
+use sui_json_rpc_types::{Checkpoint, SuiTransactionBlockEffects};
+use sui_open_rpc::Module;
+use sui_open_rpc_macros::open_rpc;
+use sui_types::base_types::SuiAddress;
+use sui_types::sui_serde::BigInt;
+use tracing::instrument;
+
+use crate::read_api::checkpoint;
+use crate::SharedSimulacrum;
+
+#[open_rpc(namespace = "simulacrum", tag = "Simulacrum API")]
+#[rpc(server, client, namespace = "simulacrum")]
+pub trait SimulacrumApi {
+    /// Advance the clock by `duration_ms` milliseconds, by executing a consensus commit prologue
+    /// transaction. Return the effects of that transaction.
+    #[method(name = "advanceClock")]
+    async fn advance_clock(
+        &self,
+        /// the number of milliseconds to advance the clock by
+        duration_ms: BigInt<u64>,
+    ) -> RpcResult<SuiTransactionBlockEffects>;
+
+    /// Advance to the next epoch, by executing a change epoch transaction, and creating the last
+    /// checkpoint of the current epoch.
+    #[method(name = "advanceEpoch")]
+    async fn advance_epoch(&self) -> RpcResult<()>;
+
+    /// Create a checkpoint containing all the transactions executed since the last checkpoint was
+    /// created, and return it.
+    #[method(name = "createCheckpoint")]
+    async fn create_checkpoint(&self) -> RpcResult<Checkpoint>;
+
+    /// Send `amount` MIST to `address` from the faucet account, and return the effects of the
+    /// transfer.
+    #[method(name = "requestGas")]
+    async fn request_gas(
+        &self,
+        /// the address to send gas to
+        address: SuiAddress,
+        /// the amount of MIST to send
+        amount: BigInt<u64>,
+    ) -> RpcResult<SuiTransactionBlockEffects>;
+}
+
+pub struct SimulacrumApi<R> {
+    simulacrum: SharedSimulacrum<R>,
+}
+
+impl<R> SimulacrumApi<R> {
+    pub fn new(simulacrum: SharedSimulacrum<R>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+#[async_trait]
+impl<R> SimulacrumApiServer for SimulacrumApi<R>
+where
+    R: rand::RngCore + rand::CryptoRng + Send + Sync + 'static,
+{
+    #[instrument(skip(self))]
+    async fn advance_clock(
+        &self,
+        duration_ms: BigInt<u64>,
+    ) -> RpcResult<SuiTransactionBlockEffects> {
+        with_tracing!(async move {
+            let mut simulacrum = self.simulacrum.write().unwrap();
+            let effects = simulacrum.advance_clock(Duration::from_millis(*duration_ms));
+            Ok(effects.try_into()?)
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn advance_epoch(&self) -> RpcResult<()> {
+        with_tracing!(async move {
+            self.simulacrum.write().unwrap().advance_epoch();
+            Ok(())
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn create_checkpoint(&self) -> RpcResult<Checkpoint> {
+        with_tracing!(async move {
+            let mut simulacrum = self.simulacrum.write().unwrap();
+            let summary = simulacrum.create_checkpoint();
+            checkpoint(simulacrum.store(), summary)
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn request_gas(
+        &self,
+        address: SuiAddress,
+        amount: BigInt<u64>,
+    ) -> RpcResult<SuiTransactionBlockEffects> {
+        with_tracing!(async move {
+            let mut simulacrum = self.simulacrum.write().unwrap();
+            let effects = simulacrum.request_gas(address, *amount)?;
+            Ok(effects.try_into()?)
+        })
+    }
+}
+
+impl<R> SuiRpcModule for SimulacrumApi<R>
+where
+    R: rand::RngCore + rand::CryptoRng + Send + Sync + 'static,
+{
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        SimulacrumApiOpenRpc::module_doc()
+    }
+}
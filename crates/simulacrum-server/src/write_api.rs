@@ -0,0 +1,168 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use fastcrypto::encoding::Base64;
+use fastcrypto::traits::ToFromBytes;
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::RpcModule;
+use shared_crypto::intent::Intent;
+use sui_json_rpc::api::WriteApiServer;
+use sui_json_rpc::error::{Error, SuiRpcInputError};
+use sui_json_rpc::{with_tracing, SuiRpcModule};
+use sui_json_rpc_types::{
+    DevInspectResults, DryRunTransactionBlockResponse, SuiTransactionBlockData,
+    SuiTransactionBlockEvents, SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
+};
+use sui_open_rpc::Module;
+use sui_types::base_types::SuiAddress;
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
+use sui_types::signature::GenericSignature;
+use sui_types::sui_serde::BigInt;
+use sui_types::transaction::{Transaction, TransactionData, TransactionKind};
+use tracing::instrument;
+
+use crate::read_api::transaction_block_response;
+use crate::SharedSimulacrum;
+
+pub struct WriteApi<R> {
+    simulacrum: SharedSimulacrum<R>,
+}
+
+impl<R> WriteApi<R> {
+    pub fn new(simulacrum: SharedSimulacrum<R>) -> Self {
+        Self { simulacrum }
+    }
+}
+
+impl<R: Send + Sync> WriteApi<R> {
+    fn convert_bytes<T: serde::de::DeserializeOwned>(
+        tx_bytes: Base64,
+    ) -> Result<T, SuiRpcInputError> {
+        let data: T = bcs::from_bytes(&tx_bytes.to_vec()?)?;
+        Ok(data)
+    }
+
+    /// Transactions are executed as soon as they are received, and their effects are written to
+    /// the store before responding, so every request type behaves like `WaitForLocalExecution`.
+    async fn execute_transaction_block(
+        &self,
+        tx_bytes: Base64,
+        signatures: Vec<Base64>,
+        opts: Option<SuiTransactionBlockResponseOptions>,
+    ) -> Result<SuiTransactionBlockResponse, Error> {
+        let tx_data: TransactionData = Self::convert_bytes(tx_bytes)?;
+
+        let mut sigs = Vec::new();
+        for sig in signatures {
+            sigs.push(GenericSignature::from_bytes(&sig.to_vec()?)?);
+        }
+
+        let txn = Transaction::from_generic_sig_data(tx_data, Intent::sui_transaction(), sigs);
+        let digest = *txn.digest();
+
+        self.simulacrum.write().unwrap().execute_transaction(txn)?;
+
+        let mut response =
+            transaction_block_response(&self.simulacrum, digest, opts.unwrap_or_default()).await?;
+        response.confirmed_local_execution = Some(true);
+        Ok(response)
+    }
+
+    /// Object and balance changes are not calculated for dry runs, because the objects written by
+    /// the transaction are not kept.
+    fn dry_run_transaction_block(
+        &self,
+        tx_bytes: Base64,
+    ) -> Result<DryRunTransactionBlockResponse, Error> {
+        let tx_data: TransactionData = Self::convert_bytes(tx_bytes)?;
+
+        let simulacrum = self.simulacrum.read().unwrap();
+        let store = simulacrum.store();
+        let (effects, events, _) = simulacrum.dry_run_transaction(&tx_data)?;
+
+        let digest = *effects.transaction_digest();
+        Ok(DryRunTransactionBlockResponse {
+            effects: effects.try_into()?,
+            events: SuiTransactionBlockEvents::try_from(events, digest, None, store)?,
+            object_changes: vec![],
+            balance_changes: vec![],
+            input: SuiTransactionBlockData::try_from(tx_data, store)?,
+        })
+    }
+
+    fn dev_inspect_transaction_block(
+        &self,
+        sender_address: SuiAddress,
+        tx_bytes: Base64,
+        gas_price: Option<BigInt<u64>>,
+    ) -> Result<DevInspectResults, Error> {
+        let tx_kind: TransactionKind = Self::convert_bytes(tx_bytes)?;
+
+        let simulacrum = self.simulacrum.read().unwrap();
+        let (effects, events, results) =
+            simulacrum.dev_inspect_transaction(sender_address, tx_kind, gas_price.map(|p| *p))?;
+
+        Ok(DevInspectResults::new(
+            effects,
+            events,
+            results,
+            simulacrum.store(),
+        )?)
+    }
+}
+
+#[async_trait]
+impl<R> WriteApiServer for WriteApi<R>
+where
+    R: Send + Sync + 'static,
+{
+    #[instrument(skip(self))]
+    async fn execute_transaction_block(
+        &self,
+        tx_bytes: Base64,
+        signatures: Vec<Base64>,
+        opts: Option<SuiTransactionBlockResponseOptions>,
+        _request_type: Option<ExecuteTransactionRequestType>,
+    ) -> RpcResult<SuiTransactionBlockResponse> {
+        with_tracing!(async move {
+            self.execute_transaction_block(tx_bytes, signatures, opts)
+                .await
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn dev_inspect_transaction_block(
+        &self,
+        sender_address: SuiAddress,
+        tx_bytes: Base64,
+        gas_price: Option<BigInt<u64>>,
+        _epoch: Option<BigInt<u64>>,
+    ) -> RpcResult<DevInspectResults> {
+        with_tracing!(async move {
+            self.dev_inspect_transaction_block(sender_address, tx_bytes, gas_price)
+        })
+    }
+
+    #[instrument(skip(self))]
+    async fn dry_run_transaction_block(
+        &self,
+        tx_bytes: Base64,
+    ) -> RpcResult<DryRunTransactionBlockResponse> {
+        with_tracing!(async move { self.dry_run_transaction_block(tx_bytes) })
+    }
+}
+
+impl<R> SuiRpcModule for WriteApi<R>
+where
+    R: Send + Sync + 'static,
+{
+    fn rpc(self) -> RpcModule<Self> {
+        self.into_rpc()
+    }
+
+    fn rpc_doc_module() -> Module {
+        sui_json_rpc::api::WriteApiOpenRpc::module_doc()
+    }
+}
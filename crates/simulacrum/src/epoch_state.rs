@@ -8,19 +8,34 @@ use sui_config::transaction_deny_config::TransactionDenyConfig;
 use sui_execution::Executor;
 use sui_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
 use sui_types::{
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
     committee::{Committee, EpochId},
+    crypto::default_hash,
+    digests::TransactionDigest,
     effects::TransactionEffects,
+    error::ExecutionError,
+    execution_mode::ExecutionResult,
+    gas::SuiGasStatus,
     inner_temporary_store::InnerTemporaryStore,
     metrics::BytecodeVerifierMetrics,
     metrics::LimitsMetrics,
+    object::{MoveObject, Object, Owner, OBJECT_START_VERSION},
     sui_system_state::{
         epoch_start_sui_system_state::{EpochStartSystemState, EpochStartSystemStateTrait},
         SuiSystemState, SuiSystemStateTrait,
     },
-    transaction::VerifiedTransaction,
+    transaction::{
+        TransactionData, TransactionDataAPI, TransactionKind, VerifiedTransaction,
+        VersionedProtocolMessage,
+    },
 };
 
-use crate::store::InMemoryStore;
+use crate::store::{KeyStore, SimulatorStore};
+
+/// ID given to the mock gas coin used to pay for dry runs and dev inspects. Nothing these
+/// transactions write is kept, so every run can reuse the same ID, which keeps their effects
+/// deterministic.
+const MOCK_GAS_COIN_ID: ObjectID = ObjectID::ZERO;
 
 pub struct EpochState {
     epoch_start_state: EpochStartSystemState,
@@ -43,7 +58,7 @@ impl EpochState {
         let registry = prometheus::Registry::new();
         let limits_metrics = Arc::new(LimitsMetrics::new(&registry));
         let bytecode_verifier_metrics = Arc::new(BytecodeVerifierMetrics::new(&registry));
-        let executor = sui_execution::executor(&protocol_config, false, true).unwrap();
+        let executor = sui_execution::executor(&protocol_config, false, true, None).unwrap();
 
         Self {
             epoch_start_state,
@@ -56,6 +71,26 @@ impl EpochState {
         }
     }
 
+    /// Like `new`, but if the epoch's committee includes validators whose keys are not in
+    /// `keystore` (e.g. because the chain was forked from a live network), a stand-in committee
+    /// made up of the validators in `keystore` is used to certify its checkpoints instead.
+    pub fn new_with_keys(system_state: SuiSystemState, keystore: &KeyStore) -> Self {
+        let epoch_state = Self::new(system_state);
+        if epoch_state
+            .committee
+            .names()
+            .all(|name| keystore.validator(name).is_some())
+        {
+            return epoch_state;
+        }
+
+        let committee = keystore.committee(epoch_state.epoch());
+        Self {
+            committee,
+            ..epoch_state
+        }
+    }
+
     pub fn epoch(&self) -> EpochId {
         self.epoch_start_state.epoch()
     }
@@ -64,6 +99,17 @@ impl EpochState {
         self.epoch_start_state.reference_gas_price()
     }
 
+    /// Resume counting consensus rounds from `round`, rather than from the start of the epoch.
+    pub fn with_next_consensus_round(mut self, round: u64) -> Self {
+        self.next_consensus_round = round;
+        self
+    }
+
+    /// The round that the next call to `next_consensus_round` will return, without advancing it.
+    pub fn peek_next_consensus_round(&self) -> u64 {
+        self.next_consensus_round
+    }
+
     pub fn next_consensus_round(&mut self) -> u64 {
         let round = self.next_consensus_round;
         self.next_consensus_round += 1;
@@ -82,9 +128,9 @@ impl EpochState {
         &self.protocol_config
     }
 
-    pub fn execute_transaction(
+    pub fn execute_transaction<S: SimulatorStore>(
         &self,
-        store: &InMemoryStore,
+        store: &S,
         deny_config: &TransactionDenyConfig,
         transaction: &VerifiedTransaction,
     ) -> Result<(
@@ -124,4 +170,137 @@ impl EpochState {
             tx_digest,
         ))
     }
+
+    /// Execute `transaction_data` against `store` without requiring it to be signed, returning the
+    /// objects it would write alongside its effects. Nothing is written back to `store`.
+    ///
+    /// If the transaction does not specify any gas coins, it is run with a mock gas coin owned by
+    /// its sender.
+    pub fn dry_run_transaction<S: SimulatorStore>(
+        &self,
+        store: &S,
+        deny_config: &TransactionDenyConfig,
+        transaction_data: &TransactionData,
+    ) -> Result<(
+        InnerTemporaryStore,
+        TransactionEffects,
+        Result<(), ExecutionError>,
+    )> {
+        let mut gas = transaction_data.gas().to_vec();
+        let (gas_status, input_objects) = if gas.is_empty() {
+            // Use a coin with the maximum amount of SUI that could ever be in circulation.
+            let gas_object = Object::new_move(
+                MoveObject::new_gas_coin(
+                    OBJECT_START_VERSION,
+                    MOCK_GAS_COIN_ID,
+                    sui_types::gas_coin::TOTAL_SUPPLY_MIST,
+                ),
+                Owner::AddressOwner(transaction_data.sender()),
+                TransactionDigest::genesis(),
+            );
+            gas = vec![gas_object.compute_object_reference()];
+            sui_transaction_checks::check_transaction_input_with_given_gas(
+                store,
+                &self.protocol_config,
+                self.epoch_start_state.reference_gas_price(),
+                self.epoch(),
+                transaction_data,
+                gas_object,
+                &self.bytecode_verifier_metrics,
+            )?
+        } else {
+            sui_transaction_checks::check_transaction_input(
+                store,
+                &self.protocol_config,
+                self.epoch_start_state.reference_gas_price(),
+                self.epoch(),
+                transaction_data,
+                &[],
+                deny_config,
+                &self.bytecode_verifier_metrics,
+            )?
+        };
+
+        let tx_digest = TransactionDigest::new(default_hash(transaction_data));
+        let (kind, signer, _) = transaction_data.execution_parts();
+        Ok(self.executor.execute_transaction_to_effects(
+            store,
+            &self.protocol_config,
+            self.limits_metrics.clone(),
+            false,           // enable_expensive_checks
+            &HashSet::new(), // certificate_deny_set
+            &self.epoch_start_state.epoch(),
+            self.epoch_start_state.epoch_start_timestamp_ms(),
+            input_objects,
+            gas,
+            gas_status,
+            kind,
+            signer,
+            tx_digest,
+        ))
+    }
+
+    /// Execute `kind` against `store` on behalf of `sender`, using a mock gas coin and skipping
+    /// the checks that usually guard transaction execution. Returns the objects it would write,
+    /// its effects, and the values returned by, and references mutated by each of its commands.
+    /// Nothing is written back to `store`.
+    pub fn dev_inspect_transaction<S: SimulatorStore>(
+        &self,
+        store: &S,
+        sender: SuiAddress,
+        kind: TransactionKind,
+        gas_price: Option<u64>,
+    ) -> Result<(
+        InnerTemporaryStore,
+        TransactionEffects,
+        Result<Vec<ExecutionResult>, ExecutionError>,
+    )> {
+        kind.check_version_supported(&self.protocol_config)?;
+
+        let max_tx_gas = self.protocol_config.max_tx_gas();
+        let reference_gas_price = self.epoch_start_state.reference_gas_price();
+        let gas_price = match gas_price {
+            None | Some(0) => reference_gas_price,
+            Some(gas_price) => gas_price,
+        };
+        let gas_status = SuiGasStatus::new(
+            max_tx_gas,
+            gas_price,
+            reference_gas_price,
+            &self.protocol_config,
+        )?;
+
+        // Give the gas coin twice the max gas, so there is a balance to play with during
+        // execution.
+        let gas_object = Object::new_move(
+            MoveObject::new_gas_coin(SequenceNumber::new(), MOCK_GAS_COIN_ID, max_tx_gas * 2),
+            Owner::AddressOwner(sender),
+            TransactionDigest::genesis(),
+        );
+        let (gas_object_ref, input_objects) = sui_transaction_checks::check_dev_inspect_input(
+            store,
+            &self.protocol_config,
+            &kind,
+            gas_object,
+        )?;
+
+        let transaction_data =
+            TransactionData::new(kind, sender, gas_object_ref, gas_price, max_tx_gas);
+        let tx_digest = TransactionDigest::new(default_hash(&transaction_data));
+        Ok(self.executor.dev_inspect_transaction(
+            store,
+            &self.protocol_config,
+            self.limits_metrics.clone(),
+            false,           // enable_expensive_checks
+            &HashSet::new(), // certificate_deny_set
+            &self.epoch_start_state.epoch(),
+            self.epoch_start_state.epoch_start_timestamp_ms(),
+            input_objects,
+            vec![gas_object_ref],
+            gas_status,
+            transaction_data.into_kind(),
+            sender,
+            tx_digest,
+        ))
+    }
 }
@@ -21,25 +21,27 @@ use sui_swarm_config::genesis_config::AccountConfig;
 use sui_swarm_config::network_config::NetworkConfig;
 use sui_swarm_config::network_config_builder::ConfigBuilder;
 use sui_types::base_types::AuthorityName;
-use sui_types::crypto::AuthoritySignature;
+use sui_types::crypto::{get_key_pair_from_rng, AuthorityKeyPair, AuthoritySignature};
 use sui_types::{
     base_types::SuiAddress,
     committee::Committee,
-    effects::TransactionEffects,
+    effects::{TransactionEffects, TransactionEvents},
     error::ExecutionError,
+    execution_mode::ExecutionResult,
     gas_coin::MIST_PER_SUI,
     inner_temporary_store::InnerTemporaryStore,
-    messages_checkpoint::{EndOfEpochData, VerifiedCheckpoint},
+    messages_checkpoint::{CheckpointSummary, EndOfEpochData, VerifiedCheckpoint},
     signature::VerifyParams,
-    transaction::{Transaction, VerifiedTransaction},
+    transaction::{Transaction, TransactionData, TransactionKind, VerifiedTransaction},
 };
 
 use self::epoch_state::EpochState;
 use self::store::KeyStore;
-pub use self::store::{InMemoryStore, SimulatorStore};
+pub use self::store::{InMemoryStore, PersistedStore, SimulatorStore};
 use sui_types::mock_checkpoint_builder::{MockCheckpointBuilder, ValidatorKeypairProvider};
 
 mod epoch_state;
+mod snapshot;
 mod store;
 
 /// A `Simulacrum` of Sui.
@@ -54,8 +56,8 @@ mod store;
 pub struct Simulacrum<R = OsRng, Store: SimulatorStore = InMemoryStore> {
     rng: R,
     keystore: KeyStore,
-    #[allow(unused)]
-    genesis: genesis::Genesis,
+    /// Forked chains don't have a genesis.
+    genesis: Option<genesis::Genesis>,
     store: Store,
     checkpoint_builder: MockCheckpointBuilder,
 
@@ -92,13 +94,8 @@ where
     /// let simulacrum = Simulacrum::new_with_rng(rng);
     /// # }
     /// ```
-    pub fn new_with_rng(mut rng: R) -> Self {
-        let config = ConfigBuilder::new_with_temp_dir()
-            .rng(&mut rng)
-            .with_chain_start_timestamp_ms(1)
-            .deterministic_committee_size(NonZeroUsize::new(1).unwrap())
-            .build();
-        Self::new_with_network_config(&config, rng)
+    pub fn new_with_rng(rng: R) -> Self {
+        Self::new_with_rng_and_store(rng, InMemoryStore::default())
     }
 
     pub fn new_with_protocol_version_and_accounts(
@@ -114,12 +111,81 @@ where
             .with_protocol_version(protocol_version)
             .with_accounts(account_configs)
             .build();
-        Self::new_with_network_config(&config, rng)
+        Self::new_with_network_config(&config, rng, InMemoryStore::default())
+    }
+}
+
+impl<R, S> Simulacrum<R, S>
+where
+    R: rand::RngCore + rand::CryptoRng,
+    S: SimulatorStore,
+{
+    /// Create a new Simulacrum instance using the provided `rng`, that keeps its state in `store`.
+    ///
+    /// `store` is expected to be empty, and is populated with the chain's genesis state.
+    ///
+    /// ```
+    /// use simulacrum::{PersistedStore, Simulacrum};
+    /// use rand::{SeedableRng, rngs::StdRng};
+    ///
+    /// # fn main() {
+    /// let dir = tempfile::tempdir().unwrap();
+    /// let store = PersistedStore::open(dir.path().join("store"));
+    /// let simulacrum = Simulacrum::new_with_rng_and_store(StdRng::seed_from_u64(1), store);
+    /// # }
+    /// ```
+    pub fn new_with_rng_and_store(mut rng: R, store: S) -> Self {
+        let config = ConfigBuilder::new_with_temp_dir()
+            .rng(&mut rng)
+            .with_chain_start_timestamp_ms(1)
+            .deterministic_committee_size(NonZeroUsize::new(1).unwrap())
+            .build();
+        Self::new_with_network_config(&config, rng, store)
+    }
+
+    /// Create a Simulacrum that continues a chain from `checkpoint`, rather than starting from a
+    /// new genesis, using the provided `rng`.
+    ///
+    /// `store` must provide the state of the chain as of `checkpoint`, for example by loading it on
+    /// demand from the network that the chain is being forked from. The keys of that network's
+    /// validators are not available, so `checkpoint` and the checkpoints that follow it are
+    /// certified by a stand-in committee with a single validator, and their digests will not match
+    /// the original network's. The contents of `checkpoint` are not added to `store`, and the
+    /// chain has no faucet accounts to request gas from.
+    pub fn new_forked(mut rng: R, mut store: S, mut checkpoint: CheckpointSummary) -> Self {
+        let (_, validator_key): (_, AuthorityKeyPair) = get_key_pair_from_rng(&mut rng);
+        let keystore = KeyStore::from_keys(vec![validator_key], vec![]);
+        let epoch_state = EpochState::new_with_keys(store.get_system_state(), &keystore);
+
+        // If `checkpoint` ends its epoch, the stand-in committee takes over in the next epoch too.
+        if let Some(end_of_epoch_data) = &mut checkpoint.end_of_epoch_data {
+            end_of_epoch_data.next_epoch_committee = epoch_state.committee().voting_rights.clone();
+        }
+
+        let committee = keystore.committee(checkpoint.epoch);
+        let checkpoint = MockCheckpointBuilder::create_certified_checkpoint(
+            &CommitteeWithKeys::new(&keystore, &committee),
+            checkpoint,
+        );
+
+        store.insert_committee(committee);
+        store.insert_checkpoint(checkpoint.clone());
+        let checkpoint_builder = MockCheckpointBuilder::new(checkpoint);
+
+        Self {
+            rng,
+            keystore,
+            genesis: None,
+            store,
+            checkpoint_builder,
+            epoch_state,
+            deny_config: TransactionDenyConfig::default(),
+        }
     }
 
-    fn new_with_network_config(config: &NetworkConfig, rng: R) -> Self {
+    fn new_with_network_config(config: &NetworkConfig, rng: R, mut store: S) -> Self {
         let keystore = KeyStore::from_network_config(config);
-        let store = InMemoryStore::new(&config.genesis);
+        store.init_with_genesis(&config.genesis);
         let checkpoint_builder = MockCheckpointBuilder::new(config.genesis.checkpoint());
 
         let genesis = &config.genesis;
@@ -128,7 +194,7 @@ where
         Self {
             rng,
             keystore,
-            genesis: genesis.clone(),
+            genesis: Some(genesis.clone()),
             store,
             checkpoint_builder,
             epoch_state,
@@ -137,7 +203,7 @@ where
     }
 }
 
-impl<R> Simulacrum<R> {
+impl<R, S: SimulatorStore> Simulacrum<R, S> {
     /// Attempts to execute the provided Transaction.
     ///
     /// The provided Transaction undergoes the same types of checks that a Validator does prior to
@@ -176,6 +242,63 @@ impl<R> Simulacrum<R> {
         Ok((effects, execution_error_opt.err()))
     }
 
+    /// Simulates executing the provided TransactionData against the current state, without
+    /// committing its results.
+    ///
+    /// The transaction is not required to be signed, and if it doesn't specify any gas coins, a
+    /// mock gas coin owned by its sender is used to pay for it. Otherwise, it undergoes the same
+    /// input checks as in `execute_transaction`.
+    ///
+    /// Returns the effects and events the transaction would have produced, but the objects it
+    /// writes are thrown away, and it is not enqueued to be included in the next checkpoint.
+    pub fn dry_run_transaction(
+        &self,
+        transaction_data: &TransactionData,
+    ) -> anyhow::Result<(
+        TransactionEffects,
+        TransactionEvents,
+        Option<ExecutionError>,
+    )> {
+        let (inner_temporary_store, effects, execution_error_opt) = self
+            .epoch_state
+            .dry_run_transaction(&self.store, &self.deny_config, transaction_data)?;
+
+        Ok((
+            effects,
+            inner_temporary_store.events,
+            execution_error_opt.err(),
+        ))
+    }
+
+    /// Runs the provided TransactionKind against the current state on behalf of `sender`, without
+    /// committing its results, in the same way as the `devInspectTransactionBlock` RPC.
+    ///
+    /// Execution uses a mock gas coin, and skips the checks usually performed on transactions,
+    /// so that the transaction can call any public or non-public Move function, and return
+    /// values that cannot usually be returned from transactions. `gas_price` defaults to the
+    /// reference gas price.
+    ///
+    /// Returns the effects and events the transaction would have produced, and the return values
+    /// and mutated references of each of its commands (or the error that stopped execution). The
+    /// objects it writes are thrown away, and it is not enqueued to be included in the next
+    /// checkpoint.
+    pub fn dev_inspect_transaction(
+        &self,
+        sender: SuiAddress,
+        kind: TransactionKind,
+        gas_price: Option<u64>,
+    ) -> anyhow::Result<(
+        TransactionEffects,
+        TransactionEvents,
+        Result<Vec<ExecutionResult>, ExecutionError>,
+    )> {
+        let (inner_temporary_store, effects, execution_result) = self
+            .epoch_state
+            .dev_inspect_transaction(&self.store, sender, kind, gas_price)?;
+
+        Ok((effects, inner_temporary_store.events, execution_result))
+    }
+
     /// Creates the next Checkpoint using the Transactions enqueued since the last checkpoint was
     /// created.
     pub fn create_checkpoint(&mut self) -> VerifiedCheckpoint {
@@ -232,7 +355,8 @@ impl<R> Simulacrum<R> {
         self.execute_transaction(tx.into())
             .expect("advancing the epoch cannot fail");
 
-        let new_epoch_state = EpochState::new(self.store.get_system_state());
+        let new_epoch_state =
+            EpochState::new_with_keys(self.store.get_system_state(), &self.keystore);
         let end_of_epoch_data = EndOfEpochData {
             next_epoch_committee: new_epoch_state.committee().voting_rights.clone(),
             next_epoch_protocol_version,
@@ -251,7 +375,7 @@ impl<R> Simulacrum<R> {
         self.epoch_state = new_epoch_state;
     }
 
-    pub fn store(&self) -> &InMemoryStore {
+    pub fn store(&self) -> &S {
         &self.store
     }
 
@@ -292,7 +416,11 @@ impl<R> Simulacrum<R> {
     pub fn request_gas(&mut self, address: SuiAddress, amount: u64) -> Result<TransactionEffects> {
         // For right now we'll just use the first account as the `faucet` account. We may want to
         // explicitly cordon off the faucet account from the rest of the accounts though.
-        let (sender, key) = self.keystore().accounts().next().unwrap();
+        let (sender, key) = self
+            .keystore()
+            .accounts()
+            .next()
+            .ok_or_else(|| anyhow!("no faucet account to request gas from"))?;
         let object = self
             .store()
             .owned_objects(*sender)
@@ -362,14 +490,16 @@ impl ValidatorKeypairProvider for CommitteeWithKeys<'_> {
 mod tests {
     use std::time::Duration;
 
+    use move_core_types::ident_str;
     use rand::{rngs::StdRng, SeedableRng};
     use shared_crypto::intent::Intent;
     use sui_types::{
         base_types::SuiAddress,
         effects::TransactionEffectsAPI,
-        gas_coin::GasCoin,
+        gas_coin::{GasCoin, GAS},
         programmable_transaction_builder::ProgrammableTransactionBuilder,
-        transaction::{GasData, TransactionData, TransactionKind},
+        transaction::{GasData, ObjectArg, TransactionData, TransactionKind},
+        SUI_FRAMEWORK_PACKAGE_ID,
     };
 
     use super::*;
@@ -501,4 +631,189 @@ mod tests {
         assert_eq!(&checkpoint.epoch_rolling_gas_cost_summary, gas_summary);
         assert_eq!(checkpoint.network_total_transactions, 2); // genesis + 1 txn
     }
+
+    #[test]
+    fn dry_run() {
+        let mut sim = Simulacrum::new();
+        let recipient = SuiAddress::generate(sim.rng());
+        let (sender, _) = sim.keystore().accounts().next().unwrap();
+        let sender = *sender;
+
+        let object = sim
+            .store()
+            .owned_objects(sender)
+            .find(|object| object.is_gas_coin())
+            .unwrap();
+        let gas_id = object.id();
+        let gas_ref = object.compute_object_reference();
+
+        let pt = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            builder.transfer_sui(recipient, Some(1000));
+            builder.finish()
+        };
+
+        let kind = TransactionKind::ProgrammableTransaction(pt);
+        let gas_data = GasData {
+            payment: vec![gas_ref],
+            owner: sender,
+            price: sim.reference_gas_price(),
+            budget: 1_000_000_000,
+        };
+        let tx_data = TransactionData::new_with_gas_data(kind, sender, gas_data);
+
+        let (effects, _, error) = sim.dry_run_transaction(&tx_data).unwrap();
+        assert!(error.is_none());
+        assert!(effects.status().is_ok());
+        assert_eq!(effects.created().len(), 1);
+
+        // Nothing was committed.
+        assert_eq!(
+            sim.store()
+                .get_object(&gas_id)
+                .unwrap()
+                .compute_object_reference(),
+            gas_ref,
+        );
+        assert!(sim.store().owned_objects(recipient).next().is_none());
+    }
+
+    #[test]
+    fn dev_inspect() {
+        let mut sim = Simulacrum::new();
+        let (sender, _) = sim.keystore().accounts().next().unwrap();
+        let sender = *sender;
+
+        let object = sim
+            .store()
+            .owned_objects(sender)
+            .find(|object| object.is_gas_coin())
+            .unwrap();
+        let value = GasCoin::try_from(object).unwrap().value();
+        let gas_ref = object.compute_object_reference();
+
+        let pt = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            let coin = builder.obj(ObjectArg::ImmOrOwnedObject(gas_ref)).unwrap();
+            builder.programmable_move_call(
+                SUI_FRAMEWORK_PACKAGE_ID,
+                ident_str!("coin").to_owned(),
+                ident_str!("value").to_owned(),
+                vec![GAS::type_tag()],
+                vec![coin],
+            );
+            builder.finish()
+        };
+
+        let kind = TransactionKind::ProgrammableTransaction(pt);
+        let (effects, _, results) = sim.dev_inspect_transaction(sender, kind, None).unwrap();
+        assert!(effects.status().is_ok());
+
+        let results = results.unwrap();
+        let (_, return_values) = &results[0];
+        let (bytes, _) = &return_values[0];
+        assert_eq!(bcs::from_bytes::<u64>(bytes).unwrap(), value);
+
+        // Nothing was committed.
+        assert_eq!(
+            sim.store()
+                .get_object(&gas_ref.0)
+                .unwrap()
+                .compute_object_reference(),
+            gas_ref,
+        );
+        sim.create_checkpoint();
+        assert_eq!(
+            sim.store()
+                .get_highest_checkpint()
+                .unwrap()
+                .network_total_transactions,
+            1
+        );
+    }
+
+    #[test]
+    fn deterministic_dev_inspect() {
+        let dev_inspect = || {
+            let sim = Simulacrum::new_with_rng(StdRng::from_seed([9; 32]));
+            let (sender, _) = sim.keystore().accounts().next().unwrap();
+
+            let pt = {
+                let mut builder = ProgrammableTransactionBuilder::new();
+                builder.transfer_sui(*sender, Some(1000));
+                builder.finish()
+            };
+
+            let kind = TransactionKind::ProgrammableTransaction(pt);
+            let (effects, _, results) = sim.dev_inspect_transaction(*sender, kind, None).unwrap();
+            assert!(results.is_ok());
+            effects
+        };
+
+        assert_eq!(dev_inspect(), dev_inspect());
+    }
+
+    #[test]
+    fn persisted_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PersistedStore::open(dir.path().join("store"));
+        let mut sim = Simulacrum::new_with_rng_and_store(StdRng::from_seed([9; 32]), store);
+        let recipient = SuiAddress::generate(sim.rng());
+
+        sim.request_gas(recipient, MIST_PER_SUI).unwrap();
+        sim.advance_clock(Duration::from_millis(1));
+        let checkpoint = sim.create_checkpoint();
+        assert_eq!(checkpoint.network_total_transactions, 3); // genesis + 2 txns
+
+        sim.advance_epoch();
+        let checkpoint = sim.store().get_highest_checkpint().unwrap();
+        assert_eq!(checkpoint.epoch, 0);
+        assert!(checkpoint.end_of_epoch_data.is_some());
+        assert!(sim.store().get_committee_by_epoch(1).is_some());
+
+        let coin = sim.store().owned_objects(recipient).next().unwrap();
+        assert_eq!(GasCoin::try_from(&coin).unwrap().value(), MIST_PER_SUI);
+    }
+
+    #[test]
+    fn snapshot_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PersistedStore::open(dir.path().join("store"));
+        let mut sim = Simulacrum::new_with_rng_and_store(StdRng::from_seed([9; 32]), store);
+        let recipient = SuiAddress::generate(sim.rng());
+
+        sim.request_gas(recipient, MIST_PER_SUI).unwrap();
+
+        // Snapshots can only be taken at checkpoint boundaries.
+        let snapshot = dir.path().join("snapshot");
+        sim.snapshot(&snapshot).unwrap_err();
+        sim.advance_epoch();
+        sim.snapshot(&snapshot).unwrap();
+
+        let expected = sim.store().get_highest_checkpint().unwrap();
+
+        // The same snapshot can be restored more than once, and the restored chains evolve
+        // independently.
+        for i in 0..2 {
+            let path = dir.path().join(format!("restored-{i}"));
+            let mut restored =
+                Simulacrum::restore(&snapshot, path, StdRng::from_seed([i; 32])).unwrap();
+
+            let highest = restored.store().get_highest_checkpint().unwrap();
+            assert_eq!(highest.digest(), expected.digest());
+
+            let coin = restored.store().owned_objects(recipient).next().unwrap();
+            assert_eq!(GasCoin::try_from(&coin).unwrap().value(), MIST_PER_SUI);
+
+            restored.request_gas(recipient, MIST_PER_SUI).unwrap();
+            restored.advance_clock(Duration::from_millis(1));
+            let checkpoint = restored.create_checkpoint();
+            assert_eq!(checkpoint.epoch, 1);
+            assert_eq!(checkpoint.sequence_number, expected.sequence_number + 1);
+            assert_eq!(restored.store().owned_objects(recipient).count(), 2);
+        }
+
+        // Restoring doesn't affect the original chain.
+        assert_eq!(sim.store().owned_objects(recipient).count(), 1);
+    }
 }
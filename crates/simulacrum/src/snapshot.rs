@@ -0,0 +1,138 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Saving a [`Simulacrum`] backed by a [`PersistedStore`] to disk, and restoring it later.
+//!
+//! A snapshot is a directory containing a RocksDB checkpoint of the store, the chain's genesis (if
+//! it has one), and the keys needed to keep driving the chain (signing checkpoints and requesting
+//! gas).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, ensure, Context, Result};
+use fastcrypto::traits::EncodeDecodeBase64;
+use serde::{Deserialize, Serialize};
+use sui_config::genesis::Genesis;
+use sui_config::transaction_deny_config::TransactionDenyConfig;
+use sui_types::crypto::{AccountKeyPair, AuthorityKeyPair};
+use sui_types::mock_checkpoint_builder::MockCheckpointBuilder;
+
+use crate::epoch_state::EpochState;
+use crate::store::{KeyStore, PersistedStore, SimulatorStore};
+use crate::Simulacrum;
+
+const STORE_DIR: &str = "store";
+const GENESIS_FILE: &str = "genesis.blob";
+const METADATA_FILE: &str = "metadata.bcs";
+
+/// State that is not captured by the store, but is needed to resume a `Simulacrum`.
+#[derive(Serialize, Deserialize)]
+struct SnapshotMetadata {
+    /// Base64 encoded validator protocol key pairs.
+    validator_keys: Vec<String>,
+    /// Base64 encoded account key pairs.
+    account_keys: Vec<String>,
+    next_consensus_round: u64,
+}
+
+impl<R> Simulacrum<R, PersistedStore> {
+    /// Save the state of this Simulacrum to a new directory at `path`.
+    ///
+    /// Snapshots can only be taken at checkpoint boundaries, so this fails if any transactions
+    /// have been executed since the last call to `create_checkpoint` (or `advance_epoch`).
+    pub fn snapshot(&self, path: &Path) -> Result<()> {
+        ensure!(
+            self.checkpoint_builder.size() == 0,
+            "Cannot snapshot a Simulacrum with transactions that are not in a checkpoint yet, \
+             call `create_checkpoint` first",
+        );
+
+        fs::create_dir_all(path)
+            .with_context(|| format!("Failed to create snapshot directory {}", path.display()))?;
+
+        self.store.checkpoint(&path.join(STORE_DIR))?;
+        if let Some(genesis) = &self.genesis {
+            genesis.save(path.join(GENESIS_FILE))?;
+        }
+
+        let metadata = SnapshotMetadata {
+            validator_keys: self
+                .keystore
+                .validators()
+                .map(|(_, key)| key.encode_base64())
+                .collect(),
+            account_keys: self
+                .keystore
+                .accounts()
+                .map(|(_, key)| key.encode_base64())
+                .collect(),
+            next_consensus_round: self.epoch_state.peek_next_consensus_round(),
+        };
+
+        fs::write(path.join(METADATA_FILE), bcs::to_bytes(&metadata)?)?;
+        Ok(())
+    }
+
+    /// Resume a Simulacrum from the snapshot at `snapshot`, using `rng` as its source of
+    /// randomness.
+    ///
+    /// The snapshot is copied into a new store at `path` (which must not already exist) before
+    /// being opened, and is left untouched, so that it can be restored from more than once.
+    pub fn restore(snapshot: &Path, path: PathBuf, rng: R) -> Result<Self> {
+        copy_dir(&snapshot.join(STORE_DIR), &path)?;
+
+        // Forked chains do not have a genesis.
+        let genesis_path = snapshot.join(GENESIS_FILE);
+        let genesis = genesis_path
+            .exists()
+            .then(|| Genesis::load(&genesis_path))
+            .transpose()?;
+        let metadata: SnapshotMetadata = bcs::from_bytes(&fs::read(snapshot.join(METADATA_FILE))?)?;
+
+        let validator_keys = metadata
+            .validator_keys
+            .iter()
+            .map(|key| AuthorityKeyPair::decode_base64(key).map_err(|e| anyhow!(e)))
+            .collect::<Result<_>>()?;
+
+        let account_keys = metadata
+            .account_keys
+            .iter()
+            .map(|key| AccountKeyPair::decode_base64(key).map_err(|e| anyhow!(e)))
+            .collect::<Result<_>>()?;
+
+        let store = PersistedStore::open(path);
+        let checkpoint = store
+            .get_highest_checkpint()
+            .ok_or_else(|| anyhow!("Snapshot does not contain any checkpoints"))?;
+
+        let keystore = KeyStore::from_keys(validator_keys, account_keys);
+        let epoch_state = EpochState::new_with_keys(store.get_system_state(), &keystore)
+            .with_next_consensus_round(metadata.next_consensus_round);
+
+        Ok(Self {
+            rng,
+            keystore,
+            genesis,
+            store,
+            checkpoint_builder: MockCheckpointBuilder::new(checkpoint),
+            epoch_state,
+            deny_config: TransactionDenyConfig::default(),
+        })
+    }
+}
+
+/// Copy the files in directory `from` into a new directory at `to`. RocksDB checkpoints do not
+/// contain nested directories, so neither does the copy.
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    ensure!(!to.exists(), "{} already exists", to.display());
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        fs::copy(entry.path(), to.join(entry.file_name()))?;
+    }
+
+    Ok(())
+}
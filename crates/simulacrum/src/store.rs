@@ -25,6 +25,10 @@ use sui_types::{
     transaction::VerifiedTransaction,
 };
 
+pub use self::persisted::PersistedStore;
+
+mod persisted;
+
 #[derive(Debug, Default)]
 pub struct InMemoryStore {
     // Checkpoint data
@@ -36,6 +40,7 @@ pub struct InMemoryStore {
     transactions: HashMap<TransactionDigest, VerifiedTransaction>,
     effects: HashMap<TransactionDigest, TransactionEffects>,
     events: HashMap<TransactionEventsDigest, TransactionEvents>,
+    transaction_to_checkpoint: HashMap<TransactionDigest, CheckpointSequenceNumber>,
 
     // Committee data
     epoch_to_committee: Vec<Committee>,
@@ -48,27 +53,7 @@ pub struct InMemoryStore {
 impl InMemoryStore {
     pub fn new(genesis: &genesis::Genesis) -> Self {
         let mut store = Self::default();
-
-        store.insert_checkpoint(genesis.checkpoint());
-        store.insert_checkpoint_contents(genesis.checkpoint_contents().clone());
-        store.insert_committee(genesis.committee().unwrap());
-        store.insert_transaction(VerifiedTransaction::new_unchecked(
-            genesis.transaction().clone(),
-        ));
-        store.insert_transaction_effects(genesis.effects().clone());
-        store.insert_events(genesis.events().clone());
-
-        for object in genesis.objects() {
-            let object_id = object.id();
-            let version = object.version();
-            store.live_objects.insert(object_id, version);
-            store
-                .objects
-                .entry(object_id)
-                .or_default()
-                .insert(version, object.clone());
-        }
-
+        store.init_with_genesis(genesis);
         store
     }
 
@@ -116,6 +101,15 @@ impl InMemoryStore {
         self.effects.get(digest)
     }
 
+    /// The sequence number of the checkpoint that the transaction with `digest` was included in,
+    /// if it has been included in one yet.
+    pub fn get_transaction_checkpoint(
+        &self,
+        digest: &TransactionDigest,
+    ) -> Option<CheckpointSequenceNumber> {
+        self.transaction_to_checkpoint.get(digest).copied()
+    }
+
     pub fn get_transaction_events(
         &self,
         digest: &TransactionEventsDigest,
@@ -134,6 +128,17 @@ impl InMemoryStore {
             .and_then(|versions| versions.get(&version))
     }
 
+    pub fn get_object_lt_or_eq_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Option<&Object> {
+        self.objects
+            .get(id)
+            .and_then(|versions| versions.range(..=version).next_back())
+            .map(|(_, object)| object)
+    }
+
     pub fn get_system_state(&self) -> sui_types::sui_system_state::SuiSystemState {
         sui_types::sui_system_state::get_sui_system_state(self).expect("system state must exist")
     }
@@ -174,6 +179,18 @@ impl InMemoryStore {
     }
 
     pub fn insert_checkpoint_contents(&mut self, contents: CheckpointContents) {
+        // Contents are inserted straight after the checkpoint they belong to.
+        if let Some(checkpoint) = self
+            .get_highest_checkpint()
+            .filter(|checkpoint| &checkpoint.content_digest == contents.digest())
+        {
+            let sequence_number = *checkpoint.sequence_number();
+            for digests in contents.iter() {
+                self.transaction_to_checkpoint
+                    .insert(digests.transaction, sequence_number);
+            }
+        }
+
         self.checkpoint_contents
             .insert(*contents.digest(), contents);
     }
@@ -369,7 +386,6 @@ impl ParentSync for InMemoryStore {
 #[derive(Debug)]
 pub struct KeyStore {
     validator_keys: BTreeMap<AuthorityName, AuthorityKeyPair>,
-    #[allow(unused)]
     account_keys: BTreeMap<SuiAddress, AccountKeyPair>,
 }
 
@@ -401,51 +417,102 @@ impl KeyStore {
         }
     }
 
+    /// Rebuild a `KeyStore` from the key pairs of its validators and accounts.
+    pub(crate) fn from_keys(
+        validator_keys: Vec<AuthorityKeyPair>,
+        account_keys: Vec<AccountKeyPair>,
+    ) -> Self {
+        use fastcrypto::traits::KeyPair;
+
+        let validator_keys = validator_keys
+            .into_iter()
+            .map(|key| (key.public().into(), key))
+            .collect();
+
+        let account_keys = account_keys
+            .into_iter()
+            .map(|key| (key.public().into(), key))
+            .collect();
+
+        Self {
+            validator_keys,
+            account_keys,
+        }
+    }
+
+    /// A committee for `epoch` made up of the validators in this key store, with equal voting
+    /// power.
+    pub fn committee(&self, epoch: EpochId) -> Committee {
+        Committee::new_for_testing_with_normalized_voting_power(
+            epoch,
+            self.validator_keys.keys().map(|name| (*name, 1)).collect(),
+        )
+    }
+
     pub fn validator(&self, name: &AuthorityName) -> Option<&AuthorityKeyPair> {
         self.validator_keys.get(name)
     }
 
+    pub fn validators(&self) -> impl Iterator<Item = (&AuthorityName, &AuthorityKeyPair)> {
+        self.validator_keys.iter()
+    }
+
     pub fn accounts(&self) -> impl Iterator<Item = (&SuiAddress, &AccountKeyPair)> {
         self.account_keys.iter()
     }
 }
 
 pub trait SimulatorStore:
-    sui_types::storage::BackingPackageStore
-    + sui_types::storage::ObjectStore
-    + sui_types::storage::ReceivedMarkerQuery
+    sui_types::storage::BackingStore + sui_types::storage::ReceivedMarkerQuery
 {
+    /// Populate an empty store with the checkpoint, transaction and objects created at `genesis`.
+    fn init_with_genesis(&mut self, genesis: &genesis::Genesis) {
+        self.insert_checkpoint(genesis.checkpoint());
+        self.insert_checkpoint_contents(genesis.checkpoint_contents().clone());
+        self.insert_committee(genesis.committee().unwrap());
+        self.insert_transaction(VerifiedTransaction::new_unchecked(
+            genesis.transaction().clone(),
+        ));
+        self.insert_transaction_effects(genesis.effects().clone());
+        self.insert_events(genesis.events().clone());
+
+        let written_objects = genesis
+            .objects()
+            .iter()
+            .map(|object| (object.id(), object.clone()))
+            .collect();
+        self.update_objects(written_objects, vec![]);
+    }
+
     fn get_checkpoint_by_sequence_number(
         &self,
         sequence_number: CheckpointSequenceNumber,
-    ) -> Option<&VerifiedCheckpoint>;
+    ) -> Option<VerifiedCheckpoint>;
 
-    fn get_checkpoint_by_digest(&self, digest: &CheckpointDigest) -> Option<&VerifiedCheckpoint>;
+    fn get_checkpoint_by_digest(&self, digest: &CheckpointDigest) -> Option<VerifiedCheckpoint>;
 
-    fn get_highest_checkpint(&self) -> Option<&VerifiedCheckpoint>;
+    fn get_highest_checkpint(&self) -> Option<VerifiedCheckpoint>;
     fn get_checkpoint_contents(
         &self,
         digest: &CheckpointContentsDigest,
-    ) -> Option<&CheckpointContents>;
+    ) -> Option<CheckpointContents>;
 
-    fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<&Committee>;
+    fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<Committee>;
 
-    fn get_transaction(&self, digest: &TransactionDigest) -> Option<&VerifiedTransaction>;
+    fn get_transaction(&self, digest: &TransactionDigest) -> Option<VerifiedTransaction>;
 
-    fn get_transaction_effects(&self, digest: &TransactionDigest) -> Option<&TransactionEffects>;
-    fn get_transaction_events(
-        &self,
-        digest: &TransactionEventsDigest,
-    ) -> Option<&TransactionEvents>;
+    fn get_transaction_effects(&self, digest: &TransactionDigest) -> Option<TransactionEffects>;
+    fn get_transaction_events(&self, digest: &TransactionEventsDigest)
+        -> Option<TransactionEvents>;
 
-    fn get_object(&self, id: &ObjectID) -> Option<&Object>;
-    fn get_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<&Object>;
+    fn get_object(&self, id: &ObjectID) -> Option<Object>;
+    fn get_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<Object>;
 
     fn get_system_state(&self) -> sui_types::sui_system_state::SuiSystemState;
 
     fn get_clock(&self) -> sui_types::clock::Clock;
 
-    fn owned_objects(&self, owner: SuiAddress) -> Box<dyn Iterator<Item = &Object> + '_>;
+    fn owned_objects(&self, owner: SuiAddress) -> Box<dyn Iterator<Item = Object> + '_>;
 
     fn insert_checkpoint(&mut self, checkpoint: VerifiedCheckpoint);
 
@@ -478,50 +545,51 @@ impl SimulatorStore for InMemoryStore {
     fn get_checkpoint_by_sequence_number(
         &self,
         sequence_number: CheckpointSequenceNumber,
-    ) -> Option<&VerifiedCheckpoint> {
+    ) -> Option<VerifiedCheckpoint> {
         self.get_checkpoint_by_sequence_number(sequence_number)
+            .cloned()
     }
 
-    fn get_checkpoint_by_digest(&self, digest: &CheckpointDigest) -> Option<&VerifiedCheckpoint> {
-        self.get_checkpoint_by_digest(digest)
+    fn get_checkpoint_by_digest(&self, digest: &CheckpointDigest) -> Option<VerifiedCheckpoint> {
+        self.get_checkpoint_by_digest(digest).cloned()
     }
 
-    fn get_highest_checkpint(&self) -> Option<&VerifiedCheckpoint> {
-        self.get_highest_checkpint()
+    fn get_highest_checkpint(&self) -> Option<VerifiedCheckpoint> {
+        self.get_highest_checkpint().cloned()
     }
 
     fn get_checkpoint_contents(
         &self,
         digest: &CheckpointContentsDigest,
-    ) -> Option<&CheckpointContents> {
-        self.get_checkpoint_contents(digest)
+    ) -> Option<CheckpointContents> {
+        self.get_checkpoint_contents(digest).cloned()
     }
 
-    fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<&Committee> {
-        self.get_committee_by_epoch(epoch)
+    fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<Committee> {
+        self.get_committee_by_epoch(epoch).cloned()
     }
 
-    fn get_transaction(&self, digest: &TransactionDigest) -> Option<&VerifiedTransaction> {
-        self.get_transaction(digest)
+    fn get_transaction(&self, digest: &TransactionDigest) -> Option<VerifiedTransaction> {
+        self.get_transaction(digest).cloned()
     }
 
-    fn get_transaction_effects(&self, digest: &TransactionDigest) -> Option<&TransactionEffects> {
-        self.get_transaction_effects(digest)
+    fn get_transaction_effects(&self, digest: &TransactionDigest) -> Option<TransactionEffects> {
+        self.get_transaction_effects(digest).cloned()
     }
 
     fn get_transaction_events(
         &self,
         digest: &TransactionEventsDigest,
-    ) -> Option<&TransactionEvents> {
-        self.get_transaction_events(digest)
+    ) -> Option<TransactionEvents> {
+        self.get_transaction_events(digest).cloned()
     }
 
-    fn get_object(&self, id: &ObjectID) -> Option<&Object> {
-        self.get_object(id)
+    fn get_object(&self, id: &ObjectID) -> Option<Object> {
+        self.get_object(id).cloned()
     }
 
-    fn get_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<&Object> {
-        self.get_object_at_version(id, version)
+    fn get_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<Object> {
+        self.get_object_at_version(id, version).cloned()
     }
 
     fn get_system_state(&self) -> sui_types::sui_system_state::SuiSystemState {
@@ -532,10 +600,9 @@ impl SimulatorStore for InMemoryStore {
         self.get_clock()
     }
 
-    fn owned_objects(&self, owner: SuiAddress) -> Box<dyn Iterator<Item = &Object> + '_> {
-        Box::new(self.owned_objects(owner))
+    fn owned_objects(&self, owner: SuiAddress) -> Box<dyn Iterator<Item = Object> + '_> {
+        Box::new(self.owned_objects(owner).cloned())
     }
-
     fn insert_checkpoint(&mut self, checkpoint: VerifiedCheckpoint) {
         self.insert_checkpoint(checkpoint)
     }
@@ -0,0 +1,454 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::{language_storage::ModuleId, resolver::ModuleResolver};
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
+    committee::{Committee, EpochId},
+    digests::{ObjectDigest, TransactionDigest, TransactionEventsDigest},
+    effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
+    error::{SuiError, SuiResult},
+    messages_checkpoint::{
+        CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
+        TrustedCheckpoint, VerifiedCheckpoint,
+    },
+    object::{Object, Owner},
+    storage::{
+        BackingPackageStore, ChildObjectResolver, ObjectKey, ObjectStore, ParentSync,
+        ReceivedMarkerQuery,
+    },
+    transaction::{TrustedTransaction, VerifiedTransaction},
+};
+use typed_store::rocks::{DBMap, MetricConf};
+use typed_store::traits::{TableSummary, TypedStoreDebug};
+use typed_store::{Map, TypedStoreError};
+use typed_store_derive::DBMapUtils;
+
+use super::SimulatorStore;
+
+/// A [`SimulatorStore`] that keeps its data in RocksDB, so that it outlives the process that
+/// created it, and can be checkpointed (see [`PersistedStore::checkpoint`]).
+pub struct PersistedStore {
+    path: PathBuf,
+    tables: PersistedStoreTables,
+}
+
+#[derive(DBMapUtils)]
+pub struct PersistedStoreTables {
+    // Checkpoint data
+    checkpoints: DBMap<CheckpointSequenceNumber, TrustedCheckpoint>,
+    checkpoint_digest_to_sequence_number: DBMap<CheckpointDigest, CheckpointSequenceNumber>,
+    checkpoint_contents: DBMap<CheckpointContentsDigest, CheckpointContents>,
+
+    // Transaction data
+    transactions: DBMap<TransactionDigest, TrustedTransaction>,
+    effects: DBMap<TransactionDigest, TransactionEffects>,
+    events: DBMap<TransactionEventsDigest, TransactionEvents>,
+
+    // Committee data
+    epoch_to_committee: DBMap<EpochId, Committee>,
+
+    // Object data
+    live_objects: DBMap<ObjectID, SequenceNumber>,
+    objects: DBMap<ObjectKey, Object>,
+}
+
+impl PersistedStore {
+    /// Open the store at `path`, creating an empty one if it doesn't exist. Use
+    /// [`SimulatorStore::init_with_genesis`] to populate an empty store.
+    pub fn open(path: PathBuf) -> Self {
+        let tables = PersistedStoreTables::open_tables_read_write(
+            path.clone(),
+            MetricConf::default(),
+            None,
+            None,
+        );
+        Self { path, tables }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write a consistent copy of the store's database to `path`, which must not already exist.
+    pub fn checkpoint(&self, path: &Path) -> Result<(), TypedStoreError> {
+        self.tables.checkpoints.checkpoint_db(path)
+    }
+
+    pub fn get_object_lt_or_eq_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Option<Object> {
+        self.tables
+            .objects
+            .unbounded_iter()
+            .skip_prior_to(&ObjectKey(*id, version))
+            .expect("DB read cannot fail")
+            .next()
+            .filter(|(ObjectKey(object_id, _), _)| object_id == id)
+            .map(|(_, object)| object)
+    }
+}
+
+impl SimulatorStore for PersistedStore {
+    fn get_checkpoint_by_sequence_number(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Option<VerifiedCheckpoint> {
+        self.tables
+            .checkpoints
+            .get(&sequence_number)
+            .expect("DB read cannot fail")
+            .map(VerifiedCheckpoint::from)
+    }
+
+    fn get_checkpoint_by_digest(&self, digest: &CheckpointDigest) -> Option<VerifiedCheckpoint> {
+        self.tables
+            .checkpoint_digest_to_sequence_number
+            .get(digest)
+            .expect("DB read cannot fail")
+            .and_then(|sequence_number| self.get_checkpoint_by_sequence_number(sequence_number))
+    }
+
+    fn get_highest_checkpint(&self) -> Option<VerifiedCheckpoint> {
+        self.tables
+            .checkpoints
+            .unbounded_iter()
+            .skip_to_last()
+            .next()
+            .map(|(_, checkpoint)| checkpoint.into())
+    }
+
+    fn get_checkpoint_contents(
+        &self,
+        digest: &CheckpointContentsDigest,
+    ) -> Option<CheckpointContents> {
+        self.tables
+            .checkpoint_contents
+            .get(digest)
+            .expect("DB read cannot fail")
+    }
+
+    fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<Committee> {
+        self.tables
+            .epoch_to_committee
+            .get(&epoch)
+            .expect("DB read cannot fail")
+    }
+
+    fn get_transaction(&self, digest: &TransactionDigest) -> Option<VerifiedTransaction> {
+        self.tables
+            .transactions
+            .get(digest)
+            .expect("DB read cannot fail")
+            .map(VerifiedTransaction::from)
+    }
+
+    fn get_transaction_effects(&self, digest: &TransactionDigest) -> Option<TransactionEffects> {
+        self.tables
+            .effects
+            .get(digest)
+            .expect("DB read cannot fail")
+    }
+
+    fn get_transaction_events(
+        &self,
+        digest: &TransactionEventsDigest,
+    ) -> Option<TransactionEvents> {
+        self.tables.events.get(digest).expect("DB read cannot fail")
+    }
+
+    fn get_object(&self, id: &ObjectID) -> Option<Object> {
+        let version = self
+            .tables
+            .live_objects
+            .get(id)
+            .expect("DB read cannot fail")?;
+        SimulatorStore::get_object_at_version(self, id, version)
+    }
+
+    fn get_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<Object> {
+        self.tables
+            .objects
+            .get(&ObjectKey(*id, version))
+            .expect("DB read cannot fail")
+    }
+
+    fn get_system_state(&self) -> sui_types::sui_system_state::SuiSystemState {
+        sui_types::sui_system_state::get_sui_system_state(self).expect("system state must exist")
+    }
+
+    fn get_clock(&self) -> sui_types::clock::Clock {
+        SimulatorStore::get_object(self, &sui_types::SUI_CLOCK_OBJECT_ID)
+            .expect("clock should exist")
+            .to_rust()
+            .expect("clock object should deserialize")
+    }
+
+    fn owned_objects(&self, owner: SuiAddress) -> Box<dyn Iterator<Item = Object> + '_> {
+        Box::new(
+            self.tables
+                .live_objects
+                .unbounded_iter()
+                .flat_map(|(id, version)| SimulatorStore::get_object_at_version(self, &id, version))
+                .filter(
+                    move |object| matches!(object.owner, Owner::AddressOwner(addr) if addr == owner),
+                ),
+        )
+    }
+
+    fn insert_checkpoint(&mut self, checkpoint: VerifiedCheckpoint) {
+        if let Some(end_of_epoch_data) = &checkpoint.data().end_of_epoch_data {
+            let next_committee = end_of_epoch_data
+                .next_epoch_committee
+                .iter()
+                .cloned()
+                .collect();
+            let committee = Committee::new(checkpoint.epoch().saturating_add(1), next_committee);
+            self.insert_committee(committee);
+        }
+
+        let mut batch = self.tables.checkpoints.batch();
+
+        batch
+            .insert_batch(
+                &self.tables.checkpoint_digest_to_sequence_number,
+                [(*checkpoint.digest(), *checkpoint.sequence_number())],
+            )
+            .expect("DB write cannot fail");
+
+        batch
+            .insert_batch(
+                &self.tables.checkpoints,
+                [(*checkpoint.sequence_number(), checkpoint.serializable_ref())],
+            )
+            .expect("DB write cannot fail");
+
+        batch.write().expect("DB write cannot fail");
+    }
+
+    fn insert_checkpoint_contents(&mut self, contents: CheckpointContents) {
+        self.tables
+            .checkpoint_contents
+            .insert(contents.digest(), &contents)
+            .expect("DB write cannot fail");
+    }
+
+    fn insert_committee(&mut self, committee: Committee) {
+        let epoch = committee.epoch;
+        let committees = &self.tables.epoch_to_committee;
+
+        if committees
+            .contains_key(&epoch)
+            .expect("DB read cannot fail")
+        {
+            return;
+        }
+
+        if epoch != 0
+            && !committees
+                .contains_key(&(epoch - 1))
+                .expect("DB read cannot fail")
+        {
+            panic!("committee was inserted into EpochCommitteeMap out of order");
+        }
+
+        committees
+            .insert(&epoch, &committee)
+            .expect("DB write cannot fail");
+    }
+
+    fn insert_executed_transaction(
+        &mut self,
+        transaction: VerifiedTransaction,
+        effects: TransactionEffects,
+        events: TransactionEvents,
+        written_objects: BTreeMap<ObjectID, Object>,
+    ) {
+        let deleted_objects = effects.deleted();
+        self.insert_transaction(transaction);
+        self.insert_transaction_effects(effects);
+        self.insert_events(events);
+        self.update_objects(written_objects, deleted_objects);
+    }
+
+    fn insert_transaction(&mut self, transaction: VerifiedTransaction) {
+        self.tables
+            .transactions
+            .insert(transaction.digest(), transaction.serializable_ref())
+            .expect("DB write cannot fail");
+    }
+
+    fn insert_transaction_effects(&mut self, effects: TransactionEffects) {
+        self.tables
+            .effects
+            .insert(effects.transaction_digest(), &effects)
+            .expect("DB write cannot fail");
+    }
+
+    fn insert_events(&mut self, events: TransactionEvents) {
+        self.tables
+            .events
+            .insert(&events.digest(), &events)
+            .expect("DB write cannot fail");
+    }
+
+    fn update_objects(
+        &mut self,
+        written_objects: BTreeMap<ObjectID, Object>,
+        deleted_objects: Vec<(ObjectID, SequenceNumber, ObjectDigest)>,
+    ) {
+        let mut batch = self.tables.live_objects.batch();
+
+        batch
+            .delete_batch(
+                &self.tables.live_objects,
+                deleted_objects
+                    .into_iter()
+                    .map(|(object_id, _, _)| object_id),
+            )
+            .expect("DB write cannot fail");
+
+        batch
+            .insert_batch(
+                &self.tables.live_objects,
+                written_objects
+                    .iter()
+                    .map(|(object_id, object)| (*object_id, object.version())),
+            )
+            .expect("DB write cannot fail");
+
+        batch
+            .insert_batch(
+                &self.tables.objects,
+                written_objects
+                    .iter()
+                    .map(|(object_id, object)| (ObjectKey(*object_id, object.version()), object)),
+            )
+            .expect("DB write cannot fail");
+
+        batch.write().expect("DB write cannot fail");
+    }
+}
+
+impl BackingPackageStore for PersistedStore {
+    fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<Object>> {
+        Ok(SimulatorStore::get_object(self, package_id))
+    }
+}
+
+impl ChildObjectResolver for PersistedStore {
+    fn read_child_object(
+        &self,
+        parent: &ObjectID,
+        child: &ObjectID,
+        child_version_upper_bound: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        let Some(child_object) = self.get_object_lt_or_eq_version(child, child_version_upper_bound)
+        else {
+            return Ok(None);
+        };
+
+        let parent = *parent;
+        if child_object.owner != Owner::ObjectOwner(parent.into()) {
+            return Err(SuiError::InvalidChildObjectAccess {
+                object: *child,
+                given_parent: parent,
+                actual_owner: child_object.owner,
+            });
+        }
+
+        Ok(Some(child_object))
+    }
+
+    fn get_object_received_at_version(
+        &self,
+        owner: &ObjectID,
+        receiving_object_id: &ObjectID,
+        receive_object_at_version: SequenceNumber,
+        _epoch_id: EpochId,
+    ) -> SuiResult<Option<Object>> {
+        let Some(recv_object) = SimulatorStore::get_object(self, receiving_object_id) else {
+            return Ok(None);
+        };
+
+        if recv_object.owner != Owner::AddressOwner((*owner).into()) {
+            return Ok(None);
+        }
+
+        if recv_object.version() != receive_object_at_version {
+            return Ok(None);
+        }
+        Ok(Some(recv_object))
+    }
+}
+
+impl ReceivedMarkerQuery for PersistedStore {
+    fn have_received_object_at_version(
+        &self,
+        _object_id: &ObjectID,
+        _version: sui_types::base_types::VersionNumber,
+        _epoch_id: EpochId,
+    ) -> Result<bool, SuiError> {
+        // As with the `InMemoryStore`, there is no marker table, because there is no need for
+        // equivocation protection in simulation.
+        Ok(false)
+    }
+}
+
+impl GetModule for PersistedStore {
+    type Error = SuiError;
+    type Item = CompiledModule;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(self
+            .get_module(id)?
+            .map(|bytes| CompiledModule::deserialize_with_defaults(&bytes).unwrap()))
+    }
+}
+
+impl ModuleResolver for PersistedStore {
+    type Error = SuiError;
+
+    fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self
+            .get_package(&ObjectID::from(*module_id.address()))?
+            .and_then(|package| {
+                package
+                    .serialized_module_map()
+                    .get(module_id.name().as_str())
+                    .cloned()
+            }))
+    }
+}
+
+impl ObjectStore for PersistedStore {
+    fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
+        Ok(SimulatorStore::get_object(self, object_id))
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: sui_types::base_types::VersionNumber,
+    ) -> Result<Option<Object>, SuiError> {
+        Ok(SimulatorStore::get_object_at_version(
+            self, object_id, version,
+        ))
+    }
+}
+
+impl ParentSync for PersistedStore {
+    fn get_latest_parent_entry_ref_deprecated(
+        &self,
+        _object_id: ObjectID,
+    ) -> SuiResult<Option<sui_types::base_types::ObjectRef>> {
+        panic!("Never called in newer protocol versions")
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use sui_indexer::framework::Handler;
+use sui_json_rpc::{get_balance_changes_from_effect, ObjectProvider};
+use sui_rest_api::{CheckpointData, CheckpointTransaction};
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::object::Object;
+use sui_types::transaction::TransactionDataAPI;
+
+use crate::handlers::{owner_address, owner_type, AnalyticsHandler};
+use crate::tables::BalanceChangeEntry;
+use crate::FileType;
+
+pub struct BalanceChangeHandler {
+    balance_changes: Vec<BalanceChangeEntry>,
+}
+
+#[async_trait::async_trait]
+impl Handler for BalanceChangeHandler {
+    fn name(&self) -> &str {
+        "balance_change"
+    }
+    async fn process_checkpoint(&mut self, checkpoint_data: &CheckpointData) -> Result<()> {
+        let CheckpointData {
+            checkpoint_summary,
+            transactions: checkpoint_transactions,
+            ..
+        } = checkpoint_data;
+        for checkpoint_transaction in checkpoint_transactions {
+            self.process_transaction(
+                checkpoint_summary.epoch,
+                checkpoint_summary.sequence_number,
+                checkpoint_summary.timestamp_ms,
+                checkpoint_transaction,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl AnalyticsHandler<BalanceChangeEntry> for BalanceChangeHandler {
+    fn read(&mut self) -> Result<Vec<BalanceChangeEntry>> {
+        let cloned = self.balance_changes.clone();
+        self.balance_changes.clear();
+        Ok(cloned)
+    }
+
+    fn file_type(&self) -> Result<FileType> {
+        Ok(FileType::BalanceChange)
+    }
+}
+
+impl BalanceChangeHandler {
+    pub fn new() -> Self {
+        BalanceChangeHandler {
+            balance_changes: vec![],
+        }
+    }
+    async fn process_transaction(
+        &mut self,
+        epoch: u64,
+        checkpoint: u64,
+        timestamp_ms: u64,
+        checkpoint_transaction: &CheckpointTransaction,
+    ) -> Result<()> {
+        let transaction = &checkpoint_transaction.transaction;
+        let transaction_digest = transaction.digest().base58_encode();
+        let object_provider = TransactionObjectProvider::new(checkpoint_transaction);
+        let balance_changes = get_balance_changes_from_effect(
+            &object_provider,
+            &checkpoint_transaction.effects,
+            transaction.transaction_data().input_objects()?,
+            None,
+        )
+        .await?;
+        for balance_change in balance_changes {
+            self.balance_changes.push(BalanceChangeEntry {
+                transaction_digest: transaction_digest.clone(),
+                checkpoint,
+                epoch,
+                timestamp_ms,
+                owner_type: owner_type(&balance_change.owner),
+                owner_address: owner_address(&balance_change.owner),
+                coin_type: balance_change.coin_type.to_string(),
+                amount: balance_change.amount,
+            });
+        }
+        Ok(())
+    }
+}
+
+// Serves the objects read and written by a transaction, which are all the objects needed to
+// compute its balance changes.
+struct TransactionObjectProvider<'a> {
+    objects: HashMap<(ObjectID, SequenceNumber), &'a Object>,
+}
+
+impl<'a> TransactionObjectProvider<'a> {
+    fn new(checkpoint_transaction: &'a CheckpointTransaction) -> Self {
+        let objects = checkpoint_transaction
+            .input_objects
+            .iter()
+            .chain(checkpoint_transaction.output_objects.iter())
+            .map(|object| ((object.id(), object.version()), object))
+            .collect();
+        Self { objects }
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectProvider for TransactionObjectProvider<'_> {
+    type Error = anyhow::Error;
+
+    async fn get_object(&self, id: &ObjectID, version: &SequenceNumber) -> Result<Object> {
+        self.objects
+            .get(&(*id, *version))
+            .map(|object| (*object).clone())
+            .ok_or_else(|| anyhow!("Object {id} at version {version} not found in transaction"))
+    }
+
+    async fn find_object_lt_or_eq_version(
+        &self,
+        id: &ObjectID,
+        version: &SequenceNumber,
+    ) -> Result<Option<Object>> {
+        Ok(self
+            .objects
+            .iter()
+            .filter(|((object_id, object_version), _)| object_id == id && object_version <= version)
+            .max_by_key(|((_, object_version), _)| *object_version)
+            .map(|(_, object)| (*object).clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use simulacrum::Simulacrum;
+    use sui_rest_api::Client;
+    use sui_types::base_types::SuiAddress;
+    use sui_types::effects::TransactionEffectsAPI;
+    use sui_types::gas_coin::{GAS, MIST_PER_SUI};
+
+    use super::*;
+    use crate::handlers::tests::serve_rest_api;
+    use crate::tables::OwnerType;
+
+    #[tokio::test]
+    async fn test_balance_changes() -> Result<()> {
+        let mut sim = Simulacrum::new();
+        let recipient = SuiAddress::generate(sim.rng());
+        let effects = sim.request_gas(recipient, MIST_PER_SUI)?;
+        sim.create_checkpoint();
+
+        let rest_url = serve_rest_api(sim)?;
+        let checkpoint = Client::new(format!("{rest_url}/rest"))
+            .get_full_checkpoint(1)
+            .await?;
+
+        let mut handler = BalanceChangeHandler::new();
+        handler.process_checkpoint(&checkpoint).await?;
+        let mut balance_changes = handler.read()?;
+        balance_changes.sort_by_key(|entry| entry.amount);
+
+        let transaction = &checkpoint.transactions[0].transaction;
+        let sender = transaction.transaction_data().sender();
+        let gas_used = effects.gas_cost_summary().net_gas_usage() as i128;
+        let sui = GAS::type_tag().to_string();
+        assert_eq!(
+            balance_changes
+                .iter()
+                .map(|entry| (
+                    entry.owner_address.clone(),
+                    entry.coin_type.clone(),
+                    entry.amount
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    Some(sender.to_string()),
+                    sui.clone(),
+                    -(MIST_PER_SUI as i128) - gas_used
+                ),
+                (Some(recipient.to_string()), sui, MIST_PER_SUI as i128),
+            ]
+        );
+        for entry in &balance_changes {
+            assert_eq!(
+                entry.transaction_digest,
+                transaction.digest().base58_encode()
+            );
+            assert_eq!(entry.checkpoint, 1);
+            assert_eq!(entry.epoch, 0);
+            assert_eq!(
+                entry.timestamp_ms,
+                checkpoint.checkpoint_summary.timestamp_ms
+            );
+            assert!(matches!(entry.owner_type, OwnerType::AddressOwner));
+        }
+
+        // Rows are only read once.
+        assert!(handler.read()?.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,303 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, Result};
+use fastcrypto::encoding::{Base64, Encoding};
+
+use sui_indexer::framework::Handler;
+use sui_json_rpc_types::SuiMoveValue;
+use sui_rest_api::{CheckpointData, CheckpointTransaction};
+use sui_types::base_types::ObjectID;
+use sui_types::dynamic_field::{DynamicFieldInfo, DynamicFieldType};
+use sui_types::object::{Object, ObjectFormatOptions, Owner};
+
+use crate::handlers::{AnalyticsHandler, ObjectStatusTracker};
+use crate::package_cache::PackageCache;
+use crate::tables::{DynamicFieldEntry, ObjectStatus};
+use crate::FileType;
+
+pub struct DynamicFieldHandler {
+    dynamic_fields: Vec<DynamicFieldEntry>,
+    package_cache: PackageCache,
+}
+
+#[async_trait::async_trait]
+impl Handler for DynamicFieldHandler {
+    fn name(&self) -> &str {
+        "dynamic_field"
+    }
+    async fn process_checkpoint(&mut self, checkpoint_data: &CheckpointData) -> Result<()> {
+        let CheckpointData {
+            checkpoint_summary,
+            transactions: checkpoint_transactions,
+            ..
+        } = checkpoint_data;
+        self.package_cache.set_epoch(checkpoint_summary.epoch);
+        for checkpoint_transaction in checkpoint_transactions {
+            self.package_cache.insert_packages(checkpoint_transaction);
+            self.process_transaction(
+                checkpoint_summary.epoch,
+                checkpoint_summary.sequence_number,
+                checkpoint_summary.timestamp_ms,
+                checkpoint_transaction,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl AnalyticsHandler<DynamicFieldEntry> for DynamicFieldHandler {
+    fn read(&mut self) -> Result<Vec<DynamicFieldEntry>> {
+        let cloned = self.dynamic_fields.clone();
+        self.dynamic_fields.clear();
+        Ok(cloned)
+    }
+
+    fn file_type(&self) -> Result<FileType> {
+        Ok(FileType::DynamicField)
+    }
+}
+
+impl DynamicFieldHandler {
+    pub fn new(rest_url: &str) -> Self {
+        DynamicFieldHandler {
+            dynamic_fields: vec![],
+            package_cache: PackageCache::new(rest_url),
+        }
+    }
+    async fn process_transaction(
+        &mut self,
+        epoch: u64,
+        checkpoint: u64,
+        timestamp_ms: u64,
+        checkpoint_transaction: &CheckpointTransaction,
+    ) -> Result<()> {
+        let transaction_digest = checkpoint_transaction.transaction.digest().base58_encode();
+        let object_status_tracker = ObjectStatusTracker::new(&checkpoint_transaction.effects);
+        let objects: HashMap<ObjectID, &Object> = checkpoint_transaction
+            .input_objects
+            .iter()
+            .chain(checkpoint_transaction.output_objects.iter())
+            .map(|object| (object.id(), object))
+            .collect();
+        let written: HashSet<ObjectID> = checkpoint_transaction
+            .output_objects
+            .iter()
+            .map(|object| object.id())
+            .collect();
+        // Fields that were written are created or mutated, fields that were read and not
+        // written are deleted (or wrapped).
+        let fields = checkpoint_transaction
+            .output_objects
+            .iter()
+            .filter_map(|object| {
+                object_status_tracker
+                    .get_object_status(&object.id())
+                    .map(|status| (object, status))
+            })
+            .chain(
+                checkpoint_transaction
+                    .input_objects
+                    .iter()
+                    .filter(|object| !written.contains(&object.id()))
+                    .map(|object| (object, ObjectStatus::Deleted)),
+            );
+        for (object, op) in fields {
+            let Some(move_object) = object.data.try_as_move() else {
+                continue;
+            };
+            if !move_object.type_().is_dynamic_field() {
+                continue;
+            }
+            let Owner::ObjectOwner(parent) = object.owner else {
+                return Err(anyhow!(
+                    "Dynamic field {} is not owned by an object",
+                    object.id()
+                ));
+            };
+
+            self.package_cache
+                .fetch_packages(&move_object.type_().clone().into())
+                .await?;
+            let move_struct = move_object.to_move_struct_with_resolver(
+                ObjectFormatOptions::default(),
+                &self.package_cache,
+            )?;
+            let (name_value, dynamic_field_type, value_object_id) =
+                DynamicFieldInfo::parse_move_object(&move_struct)?;
+            let name_type = move_object
+                .type_()
+                .try_extract_field_name(&dynamic_field_type)?;
+            let bcs_name = bcs::to_bytes(&name_value.clone().undecorate())?;
+
+            let (value_type, value_object_id) = match dynamic_field_type {
+                DynamicFieldType::DynamicField => (
+                    move_object
+                        .type_()
+                        .clone()
+                        .into_type_params()
+                        .get(1)
+                        .map(|value_type| value_type.to_string()),
+                    None,
+                ),
+                DynamicFieldType::DynamicObject => (
+                    objects
+                        .get(&value_object_id)
+                        .and_then(|child| child.data.type_())
+                        .map(|child_type| child_type.to_string()),
+                    Some(value_object_id.to_string()),
+                ),
+            };
+
+            self.dynamic_fields.push(DynamicFieldEntry {
+                parent_object_id: parent.to_string(),
+                transaction_digest: transaction_digest.clone(),
+                checkpoint,
+                epoch,
+                timestamp_ms,
+                object_id: object.id().to_string(),
+                version: object.version().value(),
+                digest: object.digest().to_string(),
+                dynamic_field_type: dynamic_field_type.to_string(),
+                name_type: name_type.to_string(),
+                name_value: SuiMoveValue::from(name_value).to_json_value().to_string(),
+                bcs_name: Base64::encode(bcs_name),
+                value_type,
+                value_object_id,
+                op,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use move_core_types::ident_str;
+    use move_core_types::language_storage::{StructTag, TypeTag};
+    use shared_crypto::intent::Intent;
+    use simulacrum::Simulacrum;
+    use sui_rest_api::Client;
+    use sui_types::base_types::MoveObjectType;
+    use sui_types::gas_coin::{GasCoin, MIST_PER_SUI};
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+    use sui_types::transaction::{
+        Argument, Command, GasData, Transaction, TransactionData, TransactionKind,
+    };
+    use sui_types::{SUI_FRAMEWORK_ADDRESS, SUI_FRAMEWORK_PACKAGE_ID};
+
+    use super::*;
+    use crate::handlers::tests::serve_rest_api;
+
+    #[tokio::test]
+    async fn test_dynamic_object_field() -> Result<()> {
+        let mut sim = Simulacrum::new();
+        let (sender, key) = sim.keystore().accounts().next().unwrap();
+        let sender = *sender;
+        let gas = sim
+            .store()
+            .owned_objects(sender)
+            .find(|object| object.is_gas_coin())
+            .unwrap()
+            .compute_object_reference();
+
+        // Place a coin in a new kiosk, which adds it to the kiosk as a dynamic object field.
+        let pt = {
+            let mut builder = ProgrammableTransactionBuilder::new();
+            builder.programmable_move_call(
+                SUI_FRAMEWORK_PACKAGE_ID,
+                ident_str!("kiosk").to_owned(),
+                ident_str!("new").to_owned(),
+                vec![],
+                vec![],
+            );
+            let (kiosk, cap) = (Argument::NestedResult(0, 0), Argument::NestedResult(0, 1));
+            let amount = builder.pure(MIST_PER_SUI)?;
+            builder.command(Command::SplitCoins(Argument::GasCoin, vec![amount]));
+            let coin = Argument::NestedResult(1, 0);
+            builder.programmable_move_call(
+                SUI_FRAMEWORK_PACKAGE_ID,
+                ident_str!("kiosk").to_owned(),
+                ident_str!("place").to_owned(),
+                vec![GasCoin::type_().into()],
+                vec![kiosk, cap, coin],
+            );
+            builder.transfer_args(sender, vec![kiosk, cap]);
+            builder.finish()
+        };
+        let gas_data = GasData {
+            payment: vec![gas],
+            owner: sender,
+            price: sim.reference_gas_price(),
+            budget: MIST_PER_SUI,
+        };
+        let tx_data = TransactionData::new_with_gas_data(
+            TransactionKind::ProgrammableTransaction(pt),
+            sender,
+            gas_data,
+        );
+        let tx = Transaction::from_data_and_signer(tx_data, Intent::sui_transaction(), vec![key]);
+        let (_, error) = sim.execute_transaction(tx)?;
+        assert!(error.is_none());
+        sim.create_checkpoint();
+
+        let rest_url = serve_rest_api(sim)?;
+        let client = Client::new(format!("{rest_url}/rest"));
+        let mut handler = DynamicFieldHandler::new(&rest_url);
+        // Genesis creates the system packages, and fields of its own, which are skipped.
+        handler
+            .process_checkpoint(&client.get_full_checkpoint(0).await?)
+            .await?;
+        handler.read()?;
+
+        let checkpoint = client.get_full_checkpoint(1).await?;
+        handler.process_checkpoint(&checkpoint).await?;
+        let dynamic_fields = handler.read()?;
+        assert_eq!(dynamic_fields.len(), 1);
+        let field = &dynamic_fields[0];
+
+        let transaction = &checkpoint.transactions[0];
+        let kiosk = transaction
+            .output_objects
+            .iter()
+            .find(|object| {
+                object
+                    .data
+                    .type_()
+                    .is_some_and(|type_| type_.to_string().ends_with("::kiosk::Kiosk"))
+            })
+            .unwrap();
+        let coin = transaction
+            .output_objects
+            .iter()
+            .find(|object| object.is_gas_coin() && object.id() != gas.0)
+            .unwrap();
+        let item = TypeTag::Struct(Box::new(StructTag {
+            address: SUI_FRAMEWORK_ADDRESS,
+            module: ident_str!("kiosk").to_owned(),
+            name: ident_str!("Item").to_owned(),
+            type_params: vec![],
+        }));
+
+        assert_eq!(field.parent_object_id, kiosk.id().to_string());
+        assert_eq!(
+            field.transaction_digest,
+            transaction.transaction.digest().base58_encode()
+        );
+        assert_eq!(field.checkpoint, 1);
+        assert_eq!(field.dynamic_field_type, "DynamicObject");
+        assert_eq!(field.name_type, item.to_string());
+        assert!(field.name_value.contains(&coin.id().to_string()));
+        assert_eq!(
+            field.value_type,
+            Some(MoveObjectType::gas_coin().to_string())
+        );
+        assert_eq!(field.value_object_id, Some(coin.id().to_string()));
+        assert!(matches!(field.op, ObjectStatus::Created));
+        Ok(())
+    }
+}
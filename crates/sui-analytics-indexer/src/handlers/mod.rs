@@ -16,7 +16,9 @@ use sui_types::transaction::TransactionDataAPI;
 use crate::tables::{InputObjectKind, ObjectStatus, OwnerType};
 use crate::FileType;
 
+pub mod balance_change_handler;
 pub mod checkpoint_handler;
+pub mod dynamic_field_handler;
 pub mod event_handler;
 pub mod move_call_handler;
 pub mod object_handler;
@@ -44,7 +46,15 @@ fn initial_shared_version(object: &Object) -> Option<u64> {
 }
 
 fn get_owner_type(object: &Object) -> OwnerType {
-    match object.owner {
+    owner_type(&object.owner)
+}
+
+fn get_owner_address(object: &Object) -> Option<String> {
+    owner_address(&object.owner)
+}
+
+fn owner_type(owner: &Owner) -> OwnerType {
+    match owner {
         Owner::AddressOwner(_) => OwnerType::AddressOwner,
         Owner::ObjectOwner(_) => OwnerType::ObjectOwner,
         Owner::Shared { .. } => OwnerType::Shared,
@@ -52,8 +62,8 @@ fn get_owner_type(object: &Object) -> OwnerType {
     }
 }
 
-fn get_owner_address(object: &Object) -> Option<String> {
-    match object.owner {
+fn owner_address(owner: &Owner) -> Option<String> {
+    match owner {
         Owner::AddressOwner(address) => Some(address.to_string()),
         Owner::ObjectOwner(address) => Some(address.to_string()),
         Owner::Shared { .. } => None,
@@ -142,14 +152,78 @@ impl ObjectStatusTracker {
     }
 
     fn get_object_status(&self, object_id: &ObjectID) -> Option<ObjectStatus> {
-        if self.mutated.contains(object_id) {
+        if self.created.contains(object_id) {
             Some(ObjectStatus::Created)
-        } else if self.deleted.contains(object_id) {
+        } else if self.mutated.contains(object_id) {
             Some(ObjectStatus::Mutated)
-        } else if self.created.contains(object_id) {
+        } else if self.deleted.contains(object_id) {
             Some(ObjectStatus::Deleted)
         } else {
             None
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use simulacrum::Simulacrum;
+    use sui_types::base_types::{random_object_ref, ObjectRef, SuiAddress};
+    use sui_types::digests::TransactionDigest;
+    use sui_types::execution_status::ExecutionStatus;
+    use sui_types::gas::GasCostSummary;
+
+    use super::*;
+
+    /// Serve `simulacrum`'s REST API under `/rest`, as a fullnode does, returning its URL.
+    pub(crate) fn serve_rest_api(simulacrum: Simulacrum) -> Result<String> {
+        let router = axum::Router::new().nest(
+            "/rest",
+            sui_rest_api::rest_router(Arc::new(RwLock::new(simulacrum)), None),
+        );
+        let server = axum::Server::bind(&"127.0.0.1:0".parse()?).serve(router.into_make_service());
+        let rest_url = format!("http://{}", server.local_addr());
+        tokio::spawn(server);
+        Ok(rest_url)
+    }
+
+    #[test]
+    fn test_object_status() {
+        let owner = Owner::AddressOwner(SuiAddress::ZERO);
+        let created = random_object_ref();
+        let mutated = random_object_ref();
+        let unwrapped = random_object_ref();
+        let deleted = random_object_ref();
+        let wrapped = random_object_ref();
+        let gas = random_object_ref();
+
+        let effects = TransactionEffects::new_from_execution_v1(
+            ExecutionStatus::Success,
+            0,
+            GasCostSummary::default(),
+            vec![],
+            vec![],
+            TransactionDigest::random(),
+            vec![(created, owner)],
+            vec![(mutated, owner), (gas, owner)],
+            vec![(unwrapped, owner)],
+            vec![deleted],
+            vec![],
+            vec![wrapped],
+            (gas, owner),
+            None,
+            vec![],
+        );
+
+        let tracker = ObjectStatusTracker::new(&effects);
+        let status = |object_ref: &ObjectRef| tracker.get_object_status(&object_ref.0);
+        assert!(matches!(status(&created), Some(ObjectStatus::Created)));
+        assert!(matches!(status(&mutated), Some(ObjectStatus::Mutated)));
+        assert!(matches!(status(&unwrapped), Some(ObjectStatus::Mutated)));
+        assert!(matches!(status(&gas), Some(ObjectStatus::Mutated)));
+        assert!(matches!(status(&deleted), Some(ObjectStatus::Deleted)));
+        assert!(matches!(status(&wrapped), Some(ObjectStatus::Deleted)));
+        assert!(status(&random_object_ref()).is_none());
+    }
+}
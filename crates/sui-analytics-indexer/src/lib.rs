@@ -24,7 +24,9 @@ use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 
 use crate::analytics_metrics::AnalyticsMetrics;
 use crate::analytics_processor::AnalyticsProcessor;
+use crate::handlers::balance_change_handler::BalanceChangeHandler;
 use crate::handlers::checkpoint_handler::CheckpointHandler;
+use crate::handlers::dynamic_field_handler::DynamicFieldHandler;
 use crate::handlers::event_handler::EventHandler;
 use crate::handlers::move_call_handler::MoveCallHandler;
 use crate::handlers::object_handler::ObjectHandler;
@@ -33,16 +35,18 @@ use crate::handlers::transaction_handler::TransactionHandler;
 use crate::handlers::transaction_objects_handler::TransactionObjectsHandler;
 use crate::handlers::AnalyticsHandler;
 use crate::tables::{
-    CheckpointEntry, EventEntry, MoveCallEntry, MovePackageEntry, ObjectEntry, TransactionEntry,
-    TransactionObjectEntry,
+    BalanceChangeEntry, CheckpointEntry, DynamicFieldEntry, EventEntry, MoveCallEntry,
+    MovePackageEntry, ObjectEntry, TransactionEntry, TransactionObjectEntry,
 };
 use crate::writers::csv_writer::CSVWriter;
-use crate::writers::AnalyticsWriter;
+use crate::writers::parquet_writer::ParquetWriter;
+use crate::writers::{AnalyticsWriter, ParquetSchema};
 
 pub mod analytics_metrics;
 pub mod analytics_processor;
 pub mod errors;
 mod handlers;
+mod package_cache;
 pub mod tables;
 mod writers;
 
@@ -54,6 +58,8 @@ const EVENT_DIR_PREFIX: &str = "events";
 const TRANSACTION_OBJECT_DIR_PREFIX: &str = "transaction_objects";
 const MOVE_CALL_PREFIX: &str = "move_call";
 const MOVE_PACKAGE_PREFIX: &str = "move_package";
+const BALANCE_CHANGE_PREFIX: &str = "balance_changes";
+const DYNAMIC_FIELD_PREFIX: &str = "dynamic_fields";
 
 #[derive(Parser, Clone, Debug)]
 #[clap(
@@ -112,12 +118,14 @@ pub struct AnalyticsIndexerConfig {
 #[repr(u8)]
 pub enum FileFormat {
     CSV = 0,
+    PARQUET,
 }
 
 impl FileFormat {
     pub fn file_suffix(&self) -> &str {
         match self {
             FileFormat::CSV => "csv",
+            FileFormat::PARQUET => "parquet",
         }
     }
 }
@@ -144,6 +152,8 @@ pub enum FileType {
     Event,
     MoveCall,
     MovePackage,
+    BalanceChange,
+    DynamicField,
 }
 
 impl FileType {
@@ -156,6 +166,8 @@ impl FileType {
             FileType::Event => Path::from(EVENT_DIR_PREFIX),
             FileType::MoveCall => Path::from(MOVE_CALL_PREFIX),
             FileType::MovePackage => Path::from(MOVE_PACKAGE_PREFIX),
+            FileType::BalanceChange => Path::from(BALANCE_CHANGE_PREFIX),
+            FileType::DynamicField => Path::from(DYNAMIC_FIELD_PREFIX),
         }
     }
 
@@ -433,7 +445,53 @@ pub async fn make_move_call_processor(
     .await
 }
 
-pub fn make_writer<S: Serialize>(
+pub async fn make_balance_change_processor(
+    config: AnalyticsIndexerConfig,
+    metrics: AnalyticsMetrics,
+) -> Result<Processor> {
+    let handler: Box<dyn AnalyticsHandler<BalanceChangeEntry>> =
+        Box::new(BalanceChangeHandler::new());
+    let starting_checkpoint_seq_num =
+        get_starting_checkpoint_seq_num(config.clone(), FileType::BalanceChange).await?;
+    let writer = make_writer::<BalanceChangeEntry>(
+        config.clone(),
+        FileType::BalanceChange,
+        starting_checkpoint_seq_num,
+    )?;
+    Processor::new::<BalanceChangeEntry>(
+        handler,
+        writer,
+        starting_checkpoint_seq_num,
+        metrics,
+        config,
+    )
+    .await
+}
+
+pub async fn make_dynamic_field_processor(
+    config: AnalyticsIndexerConfig,
+    metrics: AnalyticsMetrics,
+) -> Result<Processor> {
+    let handler: Box<dyn AnalyticsHandler<DynamicFieldEntry>> =
+        Box::new(DynamicFieldHandler::new(&config.rest_url));
+    let starting_checkpoint_seq_num =
+        get_starting_checkpoint_seq_num(config.clone(), FileType::DynamicField).await?;
+    let writer = make_writer::<DynamicFieldEntry>(
+        config.clone(),
+        FileType::DynamicField,
+        starting_checkpoint_seq_num,
+    )?;
+    Processor::new::<DynamicFieldEntry>(
+        handler,
+        writer,
+        starting_checkpoint_seq_num,
+        metrics,
+        config,
+    )
+    .await
+}
+
+pub fn make_writer<S: Serialize + ParquetSchema>(
     config: AnalyticsIndexerConfig,
     file_type: FileType,
     starting_checkpoint_seq_num: u64,
@@ -444,6 +502,11 @@ pub fn make_writer<S: Serialize>(
             file_type,
             starting_checkpoint_seq_num,
         )?),
+        FileFormat::PARQUET => Box::new(ParquetWriter::new::<S>(
+            &config.checkpoint_dir,
+            file_type,
+            starting_checkpoint_seq_num,
+        )?),
     })
 }
 
@@ -471,5 +534,7 @@ pub async fn make_analytics_processor(
         FileType::TransactionObjects => make_transaction_objects_processor(config, metrics).await,
         FileType::MoveCall => make_move_call_processor(config, metrics).await,
         FileType::MovePackage => make_move_package_processor(config, metrics).await,
+        FileType::BalanceChange => make_balance_change_processor(config, metrics).await,
+        FileType::DynamicField => make_dynamic_field_processor(config, metrics).await,
     }
 }
@@ -0,0 +1,190 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::{anyhow, Result};
+use move_binary_format::access::ModuleAccess;
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
+
+use sui_rest_api::{CheckpointTransaction, Client};
+use sui_types::base_types::{EpochId, ObjectID};
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::move_package::MovePackage;
+use sui_types::object::OBJECT_START_VERSION;
+use sui_types::transaction::{Transaction, TransactionDataAPI};
+
+// Packages needed to lay out Move values, i.e. to decode dynamic field names.
+// Packages are fetched from the fullnode the first time they are needed, along with all the
+// packages their modules depend on, and kept in memory from then on. System packages are
+// upgraded in place, so every version seen is kept, and values are laid out with the versions
+// in effect in the epoch of the checkpoint being processed, rather than the latest ones.
+pub(crate) struct PackageCache {
+    client: Client,
+    // Every version of each package seen so far, keyed by the epoch it takes effect in.
+    packages: HashMap<ObjectID, BTreeMap<EpochId, MovePackage>>,
+    // The epoch whose package versions are used to lay out values.
+    epoch: EpochId,
+    // Packages whose dependencies, as of `epoch`, are all in the cache as well.
+    resolved: HashSet<ObjectID>,
+}
+
+impl PackageCache {
+    pub(crate) fn new(rest_url: &str) -> Self {
+        Self {
+            client: Client::new(format!("{rest_url}/rest")),
+            packages: HashMap::new(),
+            epoch: 0,
+            resolved: HashSet::new(),
+        }
+    }
+
+    // Lay out values with the package versions in effect in `epoch` from now on.
+    pub(crate) fn set_epoch(&mut self, epoch: EpochId) {
+        if self.epoch != epoch {
+            self.epoch = epoch;
+            self.resolved.clear();
+        }
+    }
+
+    // Add the packages written by a transaction, so that system package upgrades are picked up.
+    pub(crate) fn insert_packages(&mut self, checkpoint_transaction: &CheckpointTransaction) {
+        let epoch = effective_epoch(
+            &checkpoint_transaction.transaction,
+            checkpoint_transaction.effects.executed_epoch(),
+        );
+        for object in &checkpoint_transaction.output_objects {
+            if let Some(package) = object.data.try_as_package() {
+                self.resolved.remove(&package.id());
+                self.packages
+                    .entry(package.id())
+                    .or_default()
+                    .insert(epoch, package.clone());
+            }
+        }
+    }
+
+    // Make sure every package needed to lay out a value of type `struct_tag` is in the cache.
+    pub(crate) async fn fetch_packages(&mut self, struct_tag: &StructTag) -> Result<()> {
+        let mut pending = vec![];
+        collect_addresses(struct_tag, &mut pending);
+        let mut visited = HashSet::new();
+        while let Some(package_id) = pending.pop() {
+            if self.resolved.contains(&package_id) || !visited.insert(package_id) {
+                continue;
+            }
+            if self.package(&package_id).is_none() {
+                self.fetch_package(package_id).await?;
+            }
+            let package = self.package(&package_id).ok_or_else(|| {
+                anyhow!(
+                    "Package {package_id} is not in effect in epoch {}",
+                    self.epoch
+                )
+            })?;
+            for bytes in package.serialized_module_map().values() {
+                let module = CompiledModule::deserialize_with_defaults(bytes)?;
+                pending.extend(
+                    module
+                        .immediate_dependencies()
+                        .iter()
+                        .map(|id| ObjectID::from(*id.address())),
+                );
+            }
+        }
+        self.resolved.extend(visited);
+        Ok(())
+    }
+
+    // The version of package `package_id` in effect in `epoch`.
+    fn package(&self, package_id: &ObjectID) -> Option<&MovePackage> {
+        self.packages
+            .get(package_id)?
+            .range(..=self.epoch)
+            .next_back()
+            .map(|(_, package)| package)
+    }
+
+    // The fullnode serves the latest version of a package, which may have been written after the
+    // checkpoint being processed, so walk back through its versions until reaching the one in
+    // effect in `epoch`, caching each version along the way.
+    async fn fetch_package(&mut self, package_id: ObjectID) -> Result<()> {
+        let mut object = self.client.get_object(package_id).await?;
+        loop {
+            let package = object
+                .data
+                .try_as_package()
+                .cloned()
+                .ok_or_else(|| anyhow!("Object {package_id} is not a package"))?;
+            let version = package.version();
+            let writer = self
+                .client
+                .get_transaction(object.previous_transaction)
+                .await?;
+            let epoch = effective_epoch(&writer.transaction, writer.effects.executed_epoch());
+            self.packages
+                .entry(package_id)
+                .or_default()
+                .insert(epoch, package);
+            if epoch <= self.epoch {
+                return Ok(());
+            }
+            if version <= OBJECT_START_VERSION {
+                return Err(anyhow!(
+                    "Package {package_id} was published after epoch {}",
+                    self.epoch
+                ));
+            }
+            object = self
+                .client
+                .get_object_with_version(package_id, version.one_before().unwrap())
+                .await?;
+        }
+    }
+}
+
+impl GetModule for PackageCache {
+    type Error = anyhow::Error;
+    type Item = CompiledModule;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> Result<Option<CompiledModule>> {
+        let package_id = ObjectID::from(*id.address());
+        self.package(&package_id)
+            .and_then(|package| {
+                package
+                    .serialized_module_map()
+                    .get(&id.name().to_string())
+                    .cloned()
+            })
+            .map(|bytes| CompiledModule::deserialize_with_defaults(&bytes))
+            .transpose()
+            .map_err(|e| anyhow!("Error deserializing module {id}: {e}"))
+    }
+}
+
+// Packages written by the end of epoch transaction (i.e. system package upgrades) only take effect
+// in the following epoch.
+fn effective_epoch(transaction: &Transaction, executed_epoch: EpochId) -> EpochId {
+    if transaction.transaction_data().is_end_of_epoch_tx() {
+        executed_epoch + 1
+    } else {
+        executed_epoch
+    }
+}
+
+fn collect_addresses(struct_tag: &StructTag, addresses: &mut Vec<ObjectID>) {
+    addresses.push(ObjectID::from(struct_tag.address));
+    for type_param in &struct_tag.type_params {
+        collect_type_addresses(type_param, addresses);
+    }
+}
+
+fn collect_type_addresses(type_tag: &TypeTag, addresses: &mut Vec<ObjectID>) {
+    match type_tag {
+        TypeTag::Struct(struct_tag) => collect_addresses(struct_tag, addresses),
+        TypeTag::Vector(inner) => collect_type_addresses(inner, addresses),
+        _ => {}
+    }
+}
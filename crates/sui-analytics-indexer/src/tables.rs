@@ -5,6 +5,38 @@
 use serde::Serialize;
 // use std::collections::BTreeSet;
 
+use crate::writers::{ParquetSchema, ParquetType, ParquetValue};
+
+// Implements `ParquetSchema` for a table entry, given the type of each of its columns.
+// Columns are listed in the same order as the fields of the entry, which is also the order
+// they are written in the csv files.
+macro_rules! parquet_schema {
+    ($entry:ident { $($field:ident: $column_type:ident),* $(,)? }) => {
+        impl ParquetSchema for $entry {
+            fn schema() -> Vec<(&'static str, ParquetType)> {
+                vec![$((stringify!($field), ParquetType::$column_type)),*]
+            }
+
+            fn row(&self) -> Vec<ParquetValue> {
+                vec![$(self.$field.clone().into()),*]
+            }
+        }
+    };
+}
+
+// Enums are stored in parquet files by name, as they are in the csv files.
+macro_rules! parquet_enum_value {
+    ($($enum_type:ident),*) => {
+        $(
+            impl From<$enum_type> for ParquetValue {
+                fn from(value: $enum_type) -> Self {
+                    ParquetValue::Str(value.to_string())
+                }
+            }
+        )*
+    };
+}
+
 //
 // Table entries for the analytics database.
 // Each entry is a row in the database.
@@ -117,7 +149,7 @@ pub(crate) struct EventEntry {
 }
 
 // Used in the transaction object table to identify the type of input object.
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, strum_macros::Display)]
 pub enum InputObjectKind {
     Input,
     SharedInput,
@@ -126,7 +158,7 @@ pub enum InputObjectKind {
 
 // Used in the object table to identify the status of object, its result in the last transaction
 // effect.
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, strum_macros::Display)]
 pub enum ObjectStatus {
     Created,
     Mutated,
@@ -134,7 +166,7 @@ pub enum ObjectStatus {
 }
 
 // Object owner information.
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Clone, strum_macros::Display)]
 pub enum OwnerType {
     AddressOwner,
     ObjectOwner,
@@ -217,3 +249,197 @@ pub(crate) struct MovePackageEntry {
     // TODO: review and possibly move back to Vec<u8>
     pub(crate) bcs: String,
 }
+
+// Balance change of a coin type for an owner, as a result of a transaction.
+// Computed the same way as the balance changes reported by the json rpc.
+#[derive(Serialize, Clone)]
+pub(crate) struct BalanceChangeEntry {
+    // indexes
+    pub(crate) transaction_digest: String,
+    pub(crate) checkpoint: u64,
+    pub(crate) epoch: u64,
+    pub(crate) timestamp_ms: u64,
+    // owner info
+    pub(crate) owner_type: OwnerType,
+    pub(crate) owner_address: Option<String>,
+    // balance change info
+    pub(crate) coin_type: String,
+    pub(crate) amount: i128,
+}
+
+// A dynamic field created, mutated or deleted by a transaction.
+// The field is identified by `parent_object_id` and its name.
+#[derive(Serialize, Clone)]
+pub(crate) struct DynamicFieldEntry {
+    // indexes
+    pub(crate) parent_object_id: String,
+    pub(crate) transaction_digest: String,
+    pub(crate) checkpoint: u64,
+    pub(crate) epoch: u64,
+    pub(crate) timestamp_ms: u64,
+    // field object info, i.e. the sui::dynamic_field::Field object
+    pub(crate) object_id: String,
+    pub(crate) version: u64,
+    pub(crate) digest: String,
+    pub(crate) dynamic_field_type: String,
+    // name of the field, as json and as base64 encoded bcs bytes
+    pub(crate) name_type: String,
+    pub(crate) name_value: String,
+    pub(crate) bcs_name: String,
+    // type of the value. For dynamic object fields this is the type of the child object,
+    // which is only known if the child was touched by the same transaction.
+    pub(crate) value_type: Option<String>,
+    // id of the child object for dynamic object fields
+    pub(crate) value_object_id: Option<String>,
+    pub(crate) op: ObjectStatus,
+}
+
+parquet_enum_value!(InputObjectKind, ObjectStatus, OwnerType);
+
+parquet_schema!(CheckpointEntry {
+    checkpoint_digest: Str,
+    sequence_number: U64,
+    epoch: U64,
+    timestamp_ms: U64,
+    previous_checkpoint_digest: OptionalStr,
+    end_of_epoch: Bool,
+    total_gas_cost: I64,
+    computation_cost: U64,
+    storage_cost: U64,
+    storage_rebate: U64,
+    non_refundable_storage_fee: U64,
+    total_transaction_blocks: U64,
+    total_transactions: U64,
+    total_successful_transaction_blocks: U64,
+    total_successful_transactions: U64,
+    network_total_transaction: U64,
+    validator_signature: Str,
+});
+
+parquet_schema!(TransactionEntry {
+    transaction_digest: Str,
+    checkpoint: U64,
+    epoch: U64,
+    timestamp_ms: U64,
+    sender: Str,
+    transaction_kind: Str,
+    is_system_txn: Bool,
+    is_sponsored_tx: Bool,
+    transaction_count: U64,
+    execution_success: Bool,
+    input: U64,
+    shared_input: U64,
+    gas_coins: U64,
+    created: U64,
+    mutated: U64,
+    deleted: U64,
+    transfers: U64,
+    split_coins: U64,
+    merge_coins: U64,
+    publish: U64,
+    upgrade: U64,
+    others: U64,
+    move_calls: U64,
+    packages: Str,
+    gas_owner: Str,
+    gas_object_id: Str,
+    gas_object_sequence: U64,
+    gas_object_digest: Str,
+    gas_budget: U64,
+    total_gas_cost: I64,
+    computation_cost: U64,
+    storage_cost: U64,
+    storage_rebate: U64,
+    non_refundable_storage_fee: U64,
+    gas_price: U64,
+    raw_transaction: Str,
+});
+
+parquet_schema!(EventEntry {
+    transaction_digest: Str,
+    event_index: U64,
+    checkpoint: U64,
+    epoch: U64,
+    timestamp_ms: U64,
+    sender: Str,
+    package: Str,
+    module: Str,
+    event_type: Str,
+    bcs: Str,
+});
+
+parquet_schema!(ObjectEntry {
+    object_id: Str,
+    version: U64,
+    digest: Str,
+    type_: OptionalStr,
+    checkpoint: U64,
+    epoch: U64,
+    timestamp_ms: U64,
+    owner_type: Str,
+    owner_address: OptionalStr,
+    object_status: Str,
+    initial_shared_version: OptionalU64,
+    previous_transaction: Str,
+    has_public_transfer: Bool,
+    storage_rebate: U64,
+    bcs: Str,
+});
+
+parquet_schema!(TransactionObjectEntry {
+    object_id: Str,
+    version: OptionalU64,
+    transaction_digest: Str,
+    checkpoint: U64,
+    epoch: U64,
+    timestamp_ms: U64,
+    input_kind: OptionalStr,
+    object_status: OptionalStr,
+});
+
+parquet_schema!(MoveCallEntry {
+    transaction_digest: Str,
+    checkpoint: U64,
+    epoch: U64,
+    timestamp_ms: U64,
+    package: Str,
+    module: Str,
+    function: Str,
+});
+
+parquet_schema!(MovePackageEntry {
+    package_id: Str,
+    checkpoint: U64,
+    epoch: U64,
+    timestamp_ms: U64,
+    bcs: Str,
+});
+
+parquet_schema!(BalanceChangeEntry {
+    transaction_digest: Str,
+    checkpoint: U64,
+    epoch: U64,
+    timestamp_ms: U64,
+    owner_type: Str,
+    owner_address: OptionalStr,
+    coin_type: Str,
+    amount: I128,
+});
+
+parquet_schema!(DynamicFieldEntry {
+    parent_object_id: Str,
+    transaction_digest: Str,
+    checkpoint: U64,
+    epoch: U64,
+    timestamp_ms: U64,
+    object_id: Str,
+    version: U64,
+    digest: Str,
+    dynamic_field_type: Str,
+    name_type: Str,
+    name_value: Str,
+    bcs_name: Str,
+    value_type: OptionalStr,
+    value_object_id: OptionalStr,
+    op: Str,
+});
@@ -7,6 +7,7 @@ use serde::Serialize;
 use sui_types::base_types::EpochId;
 
 pub mod csv_writer;
+pub mod parquet_writer;
 
 pub trait AnalyticsWriter<S: Serialize>: Send + Sync + 'static {
     /// File format i.e. csv, parquet, etc
@@ -18,3 +19,77 @@ pub trait AnalyticsWriter<S: Serialize>: Send + Sync + 'static {
     /// Reset internal state with given epoch and checkpoint sequence number
     fn reset(&mut self, epoch_num: EpochId, start_checkpoint_seq_num: u64) -> Result<()>;
 }
+
+/// The type of a column in a typed (i.e. parquet) file.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ParquetType {
+    U64,
+    I64,
+    /// Written as a 38 digit decimal, so values must be less than 10^38 in magnitude.
+    I128,
+    Bool,
+    Str,
+    OptionalU64,
+    OptionalStr,
+}
+
+impl ParquetType {
+    pub fn is_nullable(&self) -> bool {
+        matches!(self, ParquetType::OptionalU64 | ParquetType::OptionalStr)
+    }
+}
+
+/// A single value in a row of a typed (i.e. parquet) file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParquetValue {
+    U64(u64),
+    I64(i64),
+    I128(i128),
+    Bool(bool),
+    Str(String),
+    Null,
+}
+
+impl From<u64> for ParquetValue {
+    fn from(value: u64) -> Self {
+        Self::U64(value)
+    }
+}
+
+impl From<i64> for ParquetValue {
+    fn from(value: i64) -> Self {
+        Self::I64(value)
+    }
+}
+
+impl From<i128> for ParquetValue {
+    fn from(value: i128) -> Self {
+        Self::I128(value)
+    }
+}
+
+impl From<bool> for ParquetValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<String> for ParquetValue {
+    fn from(value: String) -> Self {
+        Self::Str(value)
+    }
+}
+
+impl<T: Into<ParquetValue>> From<Option<T>> for ParquetValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+/// Describes a table entry as a row of typed columns, for file formats that carry a schema.
+pub trait ParquetSchema {
+    /// Names and types of the columns, in the order they appear in a row.
+    fn schema() -> Vec<(&'static str, ParquetType)>;
+    /// Values of the columns for this entry, in the same order as `schema`.
+    fn row(&self) -> Vec<ParquetValue>;
+}
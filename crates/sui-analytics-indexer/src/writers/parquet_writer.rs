@@ -0,0 +1,333 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fs::{create_dir_all, remove_file, File};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use arrow_array::{
+    ArrayRef, BooleanArray, Decimal128Array, Int64Array, RecordBatch, StringArray, UInt64Array,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, DECIMAL128_MAX_PRECISION};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, ZstdLevel};
+use parquet::file::properties::WriterProperties;
+use serde::Serialize;
+
+use sui_storage::object_store::util::path_to_filesystem;
+use sui_types::base_types::EpochId;
+
+use crate::writers::{AnalyticsWriter, ParquetSchema, ParquetType, ParquetValue};
+use crate::{FileFormat, FileType};
+
+// Save table entries to parquet files.
+// Rows are buffered in memory as record batches, and the whole file is written out on flush,
+// as parquet files can't be appended to.
+pub(crate) struct ParquetWriter {
+    root_dir_path: PathBuf,
+    file_type: FileType,
+    schema: SchemaRef,
+    column_types: Vec<ParquetType>,
+    batches: Vec<RecordBatch>,
+    epoch: EpochId,
+    checkpoint_range: Range<u64>,
+}
+
+impl ParquetWriter {
+    pub(crate) fn new<S: ParquetSchema>(
+        root_dir_path: &Path,
+        file_type: FileType,
+        start_checkpoint_seq_num: u64,
+    ) -> Result<Self> {
+        let columns = S::schema();
+        let schema = Arc::new(Schema::new(
+            columns
+                .iter()
+                .map(|(name, column_type)| {
+                    Field::new(
+                        *name,
+                        Self::data_type(*column_type),
+                        column_type.is_nullable(),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        ));
+        Ok(ParquetWriter {
+            root_dir_path: root_dir_path.to_path_buf(),
+            file_type,
+            schema,
+            column_types: columns
+                .into_iter()
+                .map(|(_, column_type)| column_type)
+                .collect(),
+            batches: vec![],
+            epoch: 0,
+            checkpoint_range: start_checkpoint_seq_num..u64::MAX,
+        })
+    }
+
+    fn data_type(column_type: ParquetType) -> DataType {
+        match column_type {
+            ParquetType::U64 | ParquetType::OptionalU64 => DataType::UInt64,
+            ParquetType::I64 => DataType::Int64,
+            // The widest decimal there is, written without a fractional part. It holds values of
+            // up to 38 digits, which is narrower than an i128, so values are checked when written.
+            ParquetType::I128 => DataType::Decimal128(DECIMAL128_MAX_PRECISION, 0),
+            ParquetType::Bool => DataType::Boolean,
+            ParquetType::Str | ParquetType::OptionalStr => DataType::Utf8,
+        }
+    }
+
+    fn make_array(column_type: ParquetType, values: Vec<ParquetValue>) -> Result<ArrayRef> {
+        let mismatch = |value: &ParquetValue| {
+            anyhow!("Value {value:?} doesn't match column type {column_type:?}")
+        };
+        Ok(match column_type {
+            ParquetType::U64 | ParquetType::OptionalU64 => Arc::new(UInt64Array::from(
+                values
+                    .into_iter()
+                    .map(|value| match value {
+                        ParquetValue::U64(v) => Ok(Some(v)),
+                        ParquetValue::Null => Ok(None),
+                        other => Err(mismatch(&other)),
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            )),
+            ParquetType::I64 => Arc::new(Int64Array::from(
+                values
+                    .into_iter()
+                    .map(|value| match value {
+                        ParquetValue::I64(v) => Ok(Some(v)),
+                        ParquetValue::Null => Ok(None),
+                        other => Err(mismatch(&other)),
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            )),
+            ParquetType::I128 => {
+                let array = Decimal128Array::from(
+                    values
+                        .into_iter()
+                        .map(|value| match value {
+                            ParquetValue::I128(v) => Ok(Some(v)),
+                            ParquetValue::Null => Ok(None),
+                            other => Err(mismatch(&other)),
+                        })
+                        .collect::<Result<Vec<_>>>()?,
+                )
+                .with_precision_and_scale(DECIMAL128_MAX_PRECISION, 0)?;
+                array.validate_decimal_precision(DECIMAL128_MAX_PRECISION)?;
+                Arc::new(array)
+            }
+            ParquetType::Bool => Arc::new(BooleanArray::from(
+                values
+                    .into_iter()
+                    .map(|value| match value {
+                        ParquetValue::Bool(v) => Ok(Some(v)),
+                        ParquetValue::Null => Ok(None),
+                        other => Err(mismatch(&other)),
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            )),
+            ParquetType::Str | ParquetType::OptionalStr => Arc::new(StringArray::from(
+                values
+                    .into_iter()
+                    .map(|value| match value {
+                        ParquetValue::Str(v) => Ok(Some(v)),
+                        ParquetValue::Null => Ok(None),
+                        other => Err(mismatch(&other)),
+                    })
+                    .collect::<Result<Vec<_>>>()?,
+            )),
+        })
+    }
+
+    fn file_path(&self, epoch: EpochId, range: Range<u64>) -> Result<PathBuf> {
+        path_to_filesystem(
+            self.root_dir_path.clone(),
+            &self.file_type.file_path(FileFormat::PARQUET, epoch, range),
+        )
+    }
+}
+
+impl<S: Serialize + ParquetSchema> AnalyticsWriter<S> for ParquetWriter {
+    fn file_format(&self) -> Result<FileFormat> {
+        Ok(FileFormat::PARQUET)
+    }
+
+    fn write(&mut self, rows: &[S]) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let mut columns: Vec<Vec<ParquetValue>> = self
+            .column_types
+            .iter()
+            .map(|_| Vec::with_capacity(rows.len()))
+            .collect();
+        for row in rows {
+            for (column, value) in columns.iter_mut().zip(row.row()) {
+                column.push(value);
+            }
+        }
+        let arrays = self
+            .column_types
+            .iter()
+            .zip(columns)
+            .map(|(column_type, values)| Self::make_array(*column_type, values))
+            .collect::<Result<Vec<_>>>()?;
+        self.batches
+            .push(RecordBatch::try_new(self.schema.clone(), arrays)?);
+        Ok(())
+    }
+
+    fn flush(&mut self, end_checkpoint_seq_num: u64) -> Result<()> {
+        let file_path = self.file_path(
+            self.epoch,
+            self.checkpoint_range.start..end_checkpoint_seq_num,
+        )?;
+        create_dir_all(file_path.parent().ok_or(anyhow!("Bad directory path"))?)?;
+        if file_path.exists() {
+            remove_file(&file_path)?;
+        }
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build();
+        let mut writer = ArrowWriter::try_new(
+            File::create(file_path)?,
+            self.schema.clone(),
+            Some(properties),
+        )?;
+        for batch in self.batches.drain(..) {
+            writer.write(&batch)?;
+        }
+        writer.close()?;
+        Ok(())
+    }
+
+    fn reset(&mut self, epoch_num: EpochId, start_checkpoint_seq_num: u64) -> Result<()> {
+        self.checkpoint_range.start = start_checkpoint_seq_num;
+        self.checkpoint_range.end = u64::MAX;
+        self.epoch = epoch_num;
+        self.batches.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::Array;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    use super::*;
+    use crate::tables::CheckpointEntry;
+
+    fn checkpoint(
+        sequence_number: u64,
+        previous_checkpoint_digest: Option<&str>,
+    ) -> CheckpointEntry {
+        CheckpointEntry {
+            checkpoint_digest: format!("digest{sequence_number}"),
+            sequence_number,
+            epoch: 0,
+            timestamp_ms: 1000 * sequence_number,
+            previous_checkpoint_digest: previous_checkpoint_digest.map(str::to_owned),
+            end_of_epoch: sequence_number == 1,
+            total_gas_cost: -5,
+            computation_cost: 10,
+            storage_cost: 20,
+            storage_rebate: 35,
+            non_refundable_storage_fee: 1,
+            total_transaction_blocks: 2,
+            total_transactions: 3,
+            total_successful_transaction_blocks: 2,
+            total_successful_transactions: 3,
+            network_total_transaction: 3 * sequence_number,
+            validator_signature: "signature".to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_parquet_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let mut writer =
+            ParquetWriter::new::<CheckpointEntry>(dir.path(), FileType::Checkpoint, 0)?;
+        AnalyticsWriter::<CheckpointEntry>::write(&mut writer, &[checkpoint(0, None)])?;
+        AnalyticsWriter::<CheckpointEntry>::write(&mut writer, &[checkpoint(1, Some("digest0"))])?;
+        AnalyticsWriter::<CheckpointEntry>::flush(&mut writer, 2)?;
+
+        let file = File::open(writer.file_path(0, 0..2)?)?;
+        let batches = ParquetRecordBatchReaderBuilder::try_new(file)?
+            .build()?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut previous_digests = vec![];
+        let mut sequence_numbers = vec![];
+        let mut gas_costs = vec![];
+        let mut end_of_epoch = vec![];
+        for batch in &batches {
+            // Every column is read back with the type and nullability it was declared with.
+            let schema = batch.schema();
+            for (field, (name, column_type)) in
+                schema.fields().iter().zip(CheckpointEntry::schema())
+            {
+                assert_eq!(field.name(), name);
+                assert_eq!(field.data_type(), &ParquetWriter::data_type(column_type));
+                assert_eq!(field.is_nullable(), column_type.is_nullable());
+            }
+
+            let column = |name: &str| batch.column(schema.index_of(name).unwrap()).as_any();
+            previous_digests.extend(
+                column("previous_checkpoint_digest")
+                    .downcast_ref::<StringArray>()
+                    .unwrap()
+                    .iter()
+                    .map(|digest| digest.map(str::to_owned)),
+            );
+            sequence_numbers.extend(
+                column("sequence_number")
+                    .downcast_ref::<UInt64Array>()
+                    .unwrap()
+                    .values()
+                    .iter()
+                    .copied(),
+            );
+            gas_costs.extend(
+                column("total_gas_cost")
+                    .downcast_ref::<Int64Array>()
+                    .unwrap()
+                    .values()
+                    .iter()
+                    .copied(),
+            );
+            end_of_epoch.extend(
+                column("end_of_epoch")
+                    .downcast_ref::<BooleanArray>()
+                    .unwrap()
+                    .iter()
+                    .flatten(),
+            );
+        }
+
+        assert_eq!(previous_digests, vec![None, Some("digest0".to_owned())]);
+        assert_eq!(sequence_numbers, vec![0, 1]);
+        assert_eq!(gas_costs, vec![-5, -5]);
+        assert_eq!(end_of_epoch, vec![false, true]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_i128_range() {
+        let max = 10i128.pow(38) - 1;
+        let values = vec![max.into(), (-max).into(), ParquetValue::Null];
+        let array = ParquetWriter::make_array(ParquetType::I128, values).unwrap();
+        assert_eq!(array.len(), 3);
+
+        for out_of_range in [max + 1, -max - 1, i128::MAX, i128::MIN] {
+            assert!(
+                ParquetWriter::make_array(ParquetType::I128, vec![out_of_range.into()]).is_err()
+            );
+        }
+    }
+}
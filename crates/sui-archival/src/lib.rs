@@ -11,7 +11,7 @@ mod tests;
 use crate::reader::{ArchiveReader, ArchiveReaderMetrics};
 use anyhow::{anyhow, Result};
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 use fastcrypto::hash::{HashFunction, Sha3_256};
 use indicatif::{ProgressBar, ProgressStyle};
 use num_enum::IntoPrimitive;
@@ -20,6 +20,7 @@ use object_store::path::Path;
 use object_store::DynObjectStore;
 use prometheus::Registry;
 use serde::{Deserialize, Serialize};
+use std::fs::File;
 use std::io::{BufWriter, Cursor, Read, Seek, SeekFrom, Write};
 use std::num::NonZeroUsize;
 use std::ops::Range;
@@ -31,10 +32,14 @@ use sui_config::node::ArchiveReaderConfig;
 use sui_storage::blob::{Blob, BlobEncoding};
 use sui_storage::object_store::util::{get, put};
 use sui_storage::object_store::ObjectStoreConfig;
-use sui_storage::{compute_sha3_checksum, SHA3_BYTES};
+use sui_storage::{compute_sha3_checksum, FileCompression, SHA3_BYTES};
 use sui_types::base_types::ExecutionData;
-use sui_types::messages_checkpoint::{FullCheckpointContents, VerifiedCheckpointContents};
-use sui_types::storage::{ReadStore, SingleCheckpointSharedInMemoryStore, WriteStore};
+use sui_types::digests::TransactionDigest;
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::messages_checkpoint::{
+    CheckpointSequenceNumber, FullCheckpointContents, VerifiedCheckpointContents,
+};
+use sui_types::storage::{ObjectKey, ReadStore, SingleCheckpointSharedInMemoryStore, WriteStore};
 use tracing::info;
 
 /// Checkpoints and summaries are persisted as blob files. Files are committed to local store
@@ -49,8 +54,10 @@ use tracing::info;
 ///     - epoch_0/
 ///        - 0.chk
 ///        - 0.sum
+///        - 0.idx (optional)
 ///        - 1000.chk
 ///        - 1000.sum
+///        - 1000.idx (optional)
 ///        - 3000.chk
 ///        - 3000.sum
 ///        - ...
@@ -88,12 +95,28 @@ use tracing::info;
 ///├──────────────────────────────┤
 ///│      sha3 <32 bytes>         │
 ///└──────────────────────────────┘
+///
+/// Index files are written alongside checkpoint files when the archive writer is configured to,
+/// and are not part of the MANIFEST, so archives with and without them can be read the same way.
+/// Each one maps the transaction digests in its checkpoint file, and the object versions those
+/// transactions wrote, to the offset of their checkpoint in the uncompressed checkpoint file.
+///
+/// Index File Disk Format
+///┌──────────────────────────────┐
+///│        magic<4 byte>         │
+///├──────────────────────────────┤
+///│   serialized index <blob>    │
+///└──────────────────────────────┘
 const CHECKPOINT_FILE_MAGIC: u32 = 0x0000DEAD;
 const SUMMARY_FILE_MAGIC: u32 = 0x0000CAFE;
 const MANIFEST_FILE_MAGIC: u32 = 0x00C0FFEE;
+const INDEX_FILE_MAGIC: u32 = 0x0000BEEF;
 const MAGIC_BYTES: usize = 4;
+// Magic bytes followed by one byte each for storage format and file compression
+const FILE_HEADER_BYTES: usize = MAGIC_BYTES + 2;
 const CHECKPOINT_FILE_SUFFIX: &str = "chk";
 const SUMMARY_FILE_SUFFIX: &str = "sum";
+const INDEX_FILE_SUFFIX: &str = "idx";
 const EPOCH_DIR_PREFIX: &str = "epoch_";
 const MANIFEST_FILENAME: &str = "MANIFEST";
 
@@ -128,6 +151,63 @@ impl FileMetadata {
             )),
         }
     }
+    /// Path of the index file for the checkpoints in this file, if one was written.
+    pub fn index_file_path(&self) -> Path {
+        Path::from(format!("{}{}", EPOCH_DIR_PREFIX, self.epoch_num)).child(&*format!(
+            "{}.{INDEX_FILE_SUFFIX}",
+            self.checkpoint_seq_range.start
+        ))
+    }
+}
+
+/// Location of a checkpoint's contents blob in a checkpoint file. The offset is into the file as
+/// it was written, before it was compressed.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct BlobLocation {
+    pub offset: u64,
+    pub length: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CheckpointFileIndex {
+    /// Compression of the indexed checkpoint file, which decides whether its blobs can be read
+    /// directly with range reads.
+    pub file_compression: FileCompression,
+    pub checkpoints: Vec<(CheckpointSequenceNumber, BlobLocation)>,
+    pub transactions: Vec<(TransactionDigest, CheckpointSequenceNumber)>,
+    /// Object versions written by the transactions in the file.
+    pub objects: Vec<(ObjectKey, TransactionDigest)>,
+}
+
+impl CheckpointFileIndex {
+    pub fn new(file_compression: FileCompression) -> Self {
+        CheckpointFileIndex {
+            file_compression,
+            checkpoints: vec![],
+            transactions: vec![],
+            objects: vec![],
+        }
+    }
+    pub fn add_checkpoint(
+        &mut self,
+        checkpoint_sequence_number: CheckpointSequenceNumber,
+        location: BlobLocation,
+        checkpoint_contents: &FullCheckpointContents,
+    ) {
+        self.checkpoints
+            .push((checkpoint_sequence_number, location));
+        for execution_data in checkpoint_contents.iter() {
+            let digest = *execution_data.transaction.digest();
+            self.transactions.push((digest, checkpoint_sequence_number));
+            self.objects.extend(
+                execution_data
+                    .effects
+                    .all_changed_objects()
+                    .into_iter()
+                    .map(|((id, version, _), _, _)| (ObjectKey(id, version), digest)),
+            );
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -212,6 +292,7 @@ impl Manifest {
 pub struct CheckpointUpdates {
     checkpoint_file_metadata: FileMetadata,
     summary_file_metadata: FileMetadata,
+    has_index_file: bool,
     manifest: Manifest,
 }
 
@@ -221,6 +302,7 @@ impl CheckpointUpdates {
         checkpoint_sequence_number: u64,
         checkpoint_file_metadata: FileMetadata,
         summary_file_metadata: FileMetadata,
+        has_index_file: bool,
         manifest: &mut Manifest,
     ) -> Self {
         manifest.update(
@@ -232,6 +314,7 @@ impl CheckpointUpdates {
         CheckpointUpdates {
             checkpoint_file_metadata,
             summary_file_metadata,
+            has_index_file,
             manifest: manifest.clone(),
         }
     }
@@ -241,6 +324,10 @@ impl CheckpointUpdates {
     pub fn summary_file_path(&self) -> Path {
         self.summary_file_metadata.file_path()
     }
+    pub fn index_file_path(&self) -> Option<Path> {
+        self.has_index_file
+            .then(|| self.checkpoint_file_metadata.index_file_path())
+    }
     pub fn manifest_file_path(&self) -> Path {
         Path::from(MANIFEST_FILENAME)
     }
@@ -309,6 +396,34 @@ pub async fn write_manifest(manifest: Manifest, remote_store: Arc<DynObjectStore
     Ok(())
 }
 
+pub fn write_index(index: &CheckpointFileIndex, file_path: &std::path::Path) -> Result<()> {
+    let mut wbuf = BufWriter::new(File::create(file_path)?);
+    wbuf.write_u32::<BigEndian>(INDEX_FILE_MAGIC)?;
+    Blob::encode(index, BlobEncoding::Bcs)?.write(&mut wbuf)?;
+    wbuf.flush()?;
+    wbuf.get_ref().sync_data()?;
+    Ok(())
+}
+
+/// Read the index file at `path`, returning None if the checkpoint file it belongs to was written
+/// without an index.
+pub async fn read_index(
+    path: &Path,
+    remote_store: Arc<DynObjectStore>,
+) -> Result<Option<CheckpointFileIndex>> {
+    let bytes = match remote_store.get(path).await {
+        Ok(result) => result.bytes().await?,
+        Err(object_store::Error::NotFound { .. }) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut index_reader = bytes.reader();
+    let magic = index_reader.read_u32::<BigEndian>()?;
+    if magic != INDEX_FILE_MAGIC {
+        return Err(anyhow!("Unexpected magic byte in index file: {}", magic));
+    }
+    Ok(Some(Blob::read(&mut index_reader)?.decode()?))
+}
+
 pub async fn verify_archive_with_genesis_config(
     genesis: &std::path::Path,
     remote_store_config: ObjectStoreConfig,
@@ -2,29 +2,42 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    read_manifest, FileMetadata, FileType, Manifest, CHECKPOINT_FILE_MAGIC, SUMMARY_FILE_MAGIC,
+    read_index, read_manifest, BlobLocation, CheckpointFileIndex, FileMetadata, FileType, Manifest,
+    CHECKPOINT_FILE_MAGIC, FILE_HEADER_BYTES, SUMMARY_FILE_MAGIC,
 };
 use anyhow::{anyhow, Context, Result};
 use bytes::buf::Reader;
 use bytes::{Buf, Bytes};
-use futures::{StreamExt, TryStreamExt};
+use futures::{Stream, StreamExt, TryStreamExt};
+use lru::LruCache;
 use object_store::DynObjectStore;
-use prometheus::{register_int_counter_vec_with_registry, IntCounterVec, Registry};
+use prometheus::{
+    register_histogram_vec_with_registry, register_int_counter_vec_with_registry, HistogramVec,
+    IntCounterVec, Registry,
+};
 use rand::seq::SliceRandom;
 use std::borrow::Borrow;
+use std::collections::HashMap;
 use std::future;
+use std::io::Read;
+use std::num::NonZeroUsize;
 use std::ops::Range;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use sui_config::node::ArchiveReaderConfig;
+use sui_storage::blob::Blob;
 use sui_storage::object_store::util::get;
-use sui_storage::{compute_sha3_checksum_for_bytes, make_iterator, verify_checkpoint};
+use sui_storage::{
+    compute_sha3_checksum_for_bytes, make_iterator, verify_checkpoint, FileCompression,
+};
+use sui_types::base_types::{ExecutionData, ObjectID, SequenceNumber};
+use sui_types::digests::TransactionDigest;
 use sui_types::messages_checkpoint::{
     CertifiedCheckpointSummary, CheckpointSequenceNumber,
     FullCheckpointContents as CheckpointContents, VerifiedCheckpoint, VerifiedCheckpointContents,
 };
-use sui_types::storage::{ReadStore, WriteStore};
+use sui_types::storage::{ObjectKey, ReadStore, WriteStore};
 use tokio::sync::oneshot::Sender;
 use tokio::sync::{oneshot, Mutex};
 use tracing::info;
@@ -33,6 +46,7 @@ use tracing::info;
 pub struct ArchiveReaderMetrics {
     pub archive_txns_read: IntCounterVec,
     pub archive_checkpoints_read: IntCounterVec,
+    pub archive_index_files_read: HistogramVec,
 }
 
 impl ArchiveReaderMetrics {
@@ -52,6 +66,16 @@ impl ArchiveReaderMetrics {
                 registry
             )
             .unwrap(),
+            archive_index_files_read: register_histogram_vec_with_registry!(
+                "archive_index_files_read",
+                "Number of index files read from archive per transaction lookup",
+                &["bucket"],
+                prometheus::exponential_buckets(1.0, 2.0, 16)
+                    .unwrap()
+                    .to_vec(),
+                registry
+            )
+            .unwrap(),
         };
         Arc::new(this)
     }
@@ -128,12 +152,50 @@ impl ArchiveReaderBalancer {
     }
 }
 
+/// Number of index files kept in memory for looking up transactions, evicting the least recently
+/// used one first.
+const INDEX_CACHE_SIZE: usize = 64;
+
+/// The contents of a checkpoint file's index file, for looking up transactions by digest.
+#[derive(Debug)]
+struct FileIndex {
+    file: FileMetadata,
+    file_compression: FileCompression,
+    checkpoints: HashMap<CheckpointSequenceNumber, BlobLocation>,
+    transactions: HashMap<TransactionDigest, CheckpointSequenceNumber>,
+    objects: HashMap<ObjectKey, TransactionDigest>,
+}
+
+impl FileIndex {
+    fn new(file: FileMetadata, index: CheckpointFileIndex) -> Self {
+        FileIndex {
+            file,
+            file_compression: index.file_compression,
+            checkpoints: index.checkpoints.into_iter().collect(),
+            transactions: index.transactions.into_iter().collect(),
+            objects: index.objects.into_iter().collect(),
+        }
+    }
+
+    /// The checkpoint transaction `digest` is in, and where that checkpoint is in the file.
+    fn locate(
+        &self,
+        digest: &TransactionDigest,
+    ) -> Option<(CheckpointSequenceNumber, BlobLocation)> {
+        let checkpoint = *self.transactions.get(digest)?;
+        Some((checkpoint, *self.checkpoints.get(&checkpoint)?))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ArchiveReader {
     bucket: String,
     concurrency: usize,
     sender: Arc<Sender<()>>,
     manifest: Arc<Mutex<Manifest>>,
+    // Index files read recently, by the first checkpoint in their checkpoint file. None if the
+    // checkpoint file was written without an index.
+    index_files: Arc<Mutex<LruCache<CheckpointSequenceNumber, Option<Arc<FileIndex>>>>>,
     use_for_pruning_watermark: bool,
     remote_object_store: Arc<DynObjectStore>,
     archive_reader_metrics: Arc<ArchiveReaderMetrics>,
@@ -154,6 +216,9 @@ impl ArchiveReader {
         Ok(ArchiveReader {
             bucket,
             manifest,
+            index_files: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::new(INDEX_CACHE_SIZE).unwrap(),
+            ))),
             sender: Arc::new(sender),
             remote_object_store,
             use_for_pruning_watermark: config.use_for_pruning_watermark,
@@ -357,41 +422,11 @@ impl ArchiveReader {
         S: WriteStore + Clone,
         <S as ReadStore>::Error: std::error::Error,
     {
-        let manifest = self.manifest.lock().await.clone();
-
-        let latest_available_checkpoint = manifest
-            .next_checkpoint_seq_num()
-            .checked_sub(1)
-            .context("Checkpoint seq num underflow")?;
-
-        if checkpoint_range.start > latest_available_checkpoint {
-            return Err(anyhow!(
-                "Latest available checkpoint is: {}",
-                latest_available_checkpoint
-            ));
-        }
-
-        let files: Vec<(FileMetadata, FileMetadata)> = self.verify_manifest(manifest).await?;
-
-        let start_index = match files.binary_search_by_key(&checkpoint_range.start, |(s, _c)| {
-            s.checkpoint_seq_range.start
-        }) {
-            Ok(index) => index,
-            Err(index) => index - 1,
-        };
-
-        let end_index = match files.binary_search_by_key(&checkpoint_range.end, |(s, _c)| {
-            s.checkpoint_seq_range.start
-        }) {
-            Ok(index) => index,
-            Err(index) => index,
-        };
+        let files = self.get_checkpoint_files(checkpoint_range.clone()).await?;
 
         let remote_object_store = self.remote_object_store.clone();
         futures::stream::iter(files.iter())
-            .enumerate()
-            .filter(|(index, (_s, _c))| future::ready(*index >= start_index && *index < end_index))
-            .map(|(_, (summary_metadata, content_metadata))| {
+            .map(|(summary_metadata, content_metadata)| {
                 let remote_object_store = remote_object_store.clone();
                 async move {
                     let summary_data =
@@ -458,6 +493,235 @@ impl ArchiveReader {
             .await
     }
 
+    /// Stream checkpoint summaries along with their full contents (txns+effects) from the archive
+    /// for the given checkpoint range, in order, without going through a store. Contents are
+    /// checked against the content digest in their summary, but summaries are not verified
+    /// against a committee. If latest available checkpoint in archive is older than the start of
+    /// the input range then this call fails with an error otherwise we stream as many checkpoints
+    /// as possible until the end of the provided checkpoint range.
+    pub async fn stream_checkpoints(
+        &self,
+        checkpoint_range: Range<CheckpointSequenceNumber>,
+    ) -> Result<impl Stream<Item = Result<(CertifiedCheckpointSummary, CheckpointContents)>>> {
+        let files = self.get_checkpoint_files(checkpoint_range.clone()).await?;
+        let remote_object_store = self.remote_object_store.clone();
+        let archive_reader_metrics = self.archive_reader_metrics.clone();
+        let bucket = self.bucket.clone();
+
+        Ok(futures::stream::iter(files)
+            .map(move |(summary_metadata, content_metadata)| {
+                let remote_object_store = remote_object_store.clone();
+                async move {
+                    let summary_data =
+                        get(&summary_metadata.file_path(), remote_object_store.clone()).await?;
+                    let content_data =
+                        get(&content_metadata.file_path(), remote_object_store.clone()).await?;
+                    Ok::<(Bytes, Bytes), anyhow::Error>((summary_data, content_data))
+                }
+            })
+            .buffered(self.concurrency)
+            .map(move |result| {
+                let checkpoints = result.and_then(|(summary_data, content_data)| {
+                    let summary_iter = make_iterator::<CertifiedCheckpointSummary, Reader<Bytes>>(
+                        SUMMARY_FILE_MAGIC,
+                        summary_data.reader(),
+                    )?;
+                    let content_iter = make_iterator::<CheckpointContents, Reader<Bytes>>(
+                        CHECKPOINT_FILE_MAGIC,
+                        content_data.reader(),
+                    )?;
+                    summary_iter
+                        .zip(content_iter)
+                        .filter(|(s, _c)| {
+                            s.sequence_number >= checkpoint_range.start
+                                && s.sequence_number < checkpoint_range.end
+                        })
+                        .map(|(summary, contents)| {
+                            // Verify content
+                            contents.verify_digests(summary.content_digest)?;
+                            archive_reader_metrics
+                                .archive_txns_read
+                                .with_label_values(&[&bucket])
+                                .inc_by(contents.size() as u64);
+                            archive_reader_metrics
+                                .archive_checkpoints_read
+                                .with_label_values(&[&bucket])
+                                .inc_by(1);
+                            Ok((summary, contents))
+                        })
+                        .collect::<Result<Vec<_>>>()
+                });
+                let checkpoints: Vec<Result<_>> = match checkpoints {
+                    Ok(checkpoints) => checkpoints.into_iter().map(Ok).collect(),
+                    Err(e) => vec![Err(e)],
+                };
+                futures::stream::iter(checkpoints)
+            })
+            .flatten())
+    }
+
+    /// Look a transaction up by digest, using the archive's index files, and return it along with
+    /// its effects and the checkpoint it was in. Returns None if the transaction is not in any
+    /// indexed checkpoint file. Only the transaction's checkpoint is read from its checkpoint file,
+    /// with a range read if the file is uncompressed.
+    ///
+    /// There is one index file per checkpoint file, and they are searched newest first, so the
+    /// cost of a lookup grows with the number of checkpoint files in the archive: finding an old
+    /// transaction, or finding that a transaction is not archived, reads every index file that is
+    /// not cached. The number of index files read by each lookup is recorded in the
+    /// `archive_index_files_read` metric.
+    pub async fn get_transaction(
+        &self,
+        digest: &TransactionDigest,
+    ) -> Result<Option<(CheckpointSequenceNumber, ExecutionData)>> {
+        match self.search_index(|index| index.locate(digest)).await? {
+            Some((index, (checkpoint, blob))) => self
+                .read_transaction(&index, checkpoint, blob, digest)
+                .await
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Archives only hold transactions and effects, not objects, so this returns the transaction
+    /// that wrote the given version of an object, along with its effects and checkpoint, using
+    /// the archive's index files. Lookups cost as much as those by [`Self::get_transaction`].
+    pub async fn get_transaction_for_object(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<(CheckpointSequenceNumber, ExecutionData)>> {
+        let key = ObjectKey(object_id, version);
+        let found = self
+            .search_index(|index| {
+                let digest = index.objects.get(&key)?;
+                Some((*digest, index.locate(digest)?))
+            })
+            .await?;
+        match found {
+            Some((index, (digest, (checkpoint, blob)))) => self
+                .read_transaction(&index, checkpoint, blob, &digest)
+                .await
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Search the index files of the checkpoint files in the manifest, newest first, until
+    /// `lookup` finds what it is after in one of them. Index files are read as they are needed,
+    /// and only the most recently used ones are kept in memory, so a lookup may read every index
+    /// file in the archive, but the whole index is never held in memory at once.
+    async fn search_index<T>(
+        &self,
+        lookup: impl Fn(&FileIndex) -> Option<T>,
+    ) -> Result<Option<(Arc<FileIndex>, T)>> {
+        let manifest = self.manifest.lock().await.clone();
+        let files = manifest
+            .files()
+            .into_iter()
+            .filter(|f| f.file_type == FileType::CheckpointContent)
+            .rev();
+        let index_files_read = AtomicU64::new(0);
+        let mut index_files = futures::stream::iter(files)
+            .map(|file| self.read_file_index(file, &index_files_read))
+            .buffered(self.concurrency);
+        let found = async {
+            while let Some(index_file) = index_files.try_next().await? {
+                let Some(index_file) = index_file else {
+                    continue;
+                };
+                if let Some(found) = lookup(&index_file) {
+                    return Ok(Some((index_file, found)));
+                }
+            }
+            Ok(None)
+        }
+        .await;
+        self.archive_reader_metrics
+            .archive_index_files_read
+            .with_label_values(&[&self.bucket])
+            .observe(index_files_read.load(Ordering::Relaxed) as f64);
+        found
+    }
+
+    /// Read the index file of checkpoint file `file`, unless it is still cached, counting the index
+    /// files read from the remote store in `files_read`. Returns None if the checkpoint file was
+    /// written without an index.
+    async fn read_file_index(
+        &self,
+        file: FileMetadata,
+        files_read: &AtomicU64,
+    ) -> Result<Option<Arc<FileIndex>>> {
+        let key = file.checkpoint_seq_range.start;
+        if let Some(index_file) = self.index_files.lock().await.get(&key) {
+            return Ok(index_file.clone());
+        }
+        files_read.fetch_add(1, Ordering::Relaxed);
+        let index_file = read_index(&file.index_file_path(), self.remote_object_store.clone())
+            .await?
+            .map(|index| Arc::new(FileIndex::new(file, index)));
+        self.index_files.lock().await.put(key, index_file.clone());
+        Ok(index_file)
+    }
+
+    /// Read transaction `digest` from checkpoint `checkpoint`, which is at `blob` in the checkpoint
+    /// file `index` belongs to.
+    async fn read_transaction(
+        &self,
+        index: &FileIndex,
+        checkpoint: CheckpointSequenceNumber,
+        blob: BlobLocation,
+        digest: &TransactionDigest,
+    ) -> Result<(CheckpointSequenceNumber, ExecutionData)> {
+        let contents = self
+            .read_checkpoint_blob(&index.file, index.file_compression, blob)
+            .await?;
+        let execution_data = contents
+            .into_iter()
+            .find(|data| data.transaction.digest() == digest)
+            .ok_or_else(|| {
+                anyhow!("Transaction {digest} missing from indexed checkpoint {checkpoint}")
+            })?;
+        self.archive_reader_metrics
+            .archive_txns_read
+            .with_label_values(&[&self.bucket])
+            .inc_by(1);
+        Ok((checkpoint, execution_data))
+    }
+
+    /// Read the contents of a single checkpoint from a checkpoint file.
+    async fn read_checkpoint_blob(
+        &self,
+        file: &FileMetadata,
+        file_compression: FileCompression,
+        blob: BlobLocation,
+    ) -> Result<CheckpointContents> {
+        let blob = match file_compression {
+            FileCompression::None => {
+                let range = blob.offset as usize..(blob.offset + blob.length) as usize;
+                let bytes = self
+                    .remote_object_store
+                    .get_range(&file.file_path(), range)
+                    .await?;
+                Blob::read(&mut bytes.reader())?
+            }
+            // Compressed files can't be read from an offset, so read the whole file, but only
+            // decode the blob we are after.
+            FileCompression::Zstd => {
+                let bytes = get(&file.file_path(), self.remote_object_store.clone()).await?;
+                let (mut reader, _) = sui_storage::read(CHECKPOINT_FILE_MAGIC, bytes.reader())?;
+                let skip = blob.offset - FILE_HEADER_BYTES as u64;
+                std::io::copy(&mut reader.by_ref().take(skip), &mut std::io::sink())?;
+                Blob::read(&mut reader)?
+            }
+        };
+        self.archive_reader_metrics
+            .archive_checkpoints_read
+            .with_label_values(&[&self.bucket])
+            .inc_by(1);
+        blob.decode()
+    }
+
     /// Return latest available checkpoint in archive
     pub async fn latest_available_checkpoint(&self) -> Result<CheckpointSequenceNumber> {
         let manifest = self.manifest.lock().await.clone();
@@ -550,6 +814,45 @@ impl ArchiveReader {
             .map_err(|e| anyhow!("Failed to get verified checkpoint: {:?}", e))
     }
 
+    /// Return the (summary, contents) file pairs which together cover the given checkpoint range,
+    /// failing if the start of the range is past the latest available checkpoint in archive.
+    async fn get_checkpoint_files(
+        &self,
+        checkpoint_range: Range<CheckpointSequenceNumber>,
+    ) -> Result<Vec<(FileMetadata, FileMetadata)>> {
+        let manifest = self.manifest.lock().await.clone();
+
+        let latest_available_checkpoint = manifest
+            .next_checkpoint_seq_num()
+            .checked_sub(1)
+            .context("Checkpoint seq num underflow")?;
+
+        if checkpoint_range.start > latest_available_checkpoint {
+            return Err(anyhow!(
+                "Latest available checkpoint is: {}",
+                latest_available_checkpoint
+            ));
+        }
+
+        let files: Vec<(FileMetadata, FileMetadata)> = self.verify_manifest(manifest).await?;
+
+        let start_index = match files.binary_search_by_key(&checkpoint_range.start, |(s, _c)| {
+            s.checkpoint_seq_range.start
+        }) {
+            Ok(index) => index,
+            Err(index) => index - 1,
+        };
+
+        let end_index = match files.binary_search_by_key(&checkpoint_range.end, |(s, _c)| {
+            s.checkpoint_seq_range.start
+        }) {
+            Ok(index) => index,
+            Err(index) => index,
+        };
+
+        Ok(files[start_index..end_index].to_vec())
+    }
+
     async fn get_summary_files(
         &self,
         checkpoint_range: Range<CheckpointSequenceNumber>,
@@ -3,7 +3,10 @@
 
 use crate::reader::{ArchiveReader, ArchiveReaderMetrics};
 use crate::writer::ArchiveWriter;
-use crate::{read_manifest, verify_archive_with_local_store, write_manifest, Manifest};
+use crate::{
+    read_index, read_manifest, verify_archive_with_local_store, write_index, write_manifest,
+    BlobLocation, CheckpointFileIndex, Manifest, FILE_HEADER_BYTES,
+};
 use anyhow::{anyhow, Context, Result};
 use more_asserts as ma;
 use object_store::DynObjectStore;
@@ -17,10 +20,13 @@ use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 use std::time::Duration;
 use sui_config::node::ArchiveReaderConfig;
+use sui_storage::blob::{Blob, BlobEncoding};
 use sui_storage::object_store::util::path_to_filesystem;
 use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
 use sui_storage::{FileCompression, StorageFormat};
-use sui_swarm_config::test_utils::{empty_contents, CommitteeFixture};
+use sui_swarm_config::test_utils::{empty_contents, random_contents, CommitteeFixture};
+use sui_types::digests::TransactionDigest;
+use sui_types::effects::TransactionEffectsAPI;
 use sui_types::messages_checkpoint::{VerifiedCheckpoint, VerifiedCheckpointContents};
 use sui_types::storage::{ReadStore, SharedInMemoryStore, SingleCheckpointSharedInMemoryStore};
 use tempfile::tempdir;
@@ -86,6 +92,7 @@ async fn setup_test_state(temp_dir: PathBuf) -> anyhow::Result<TestState> {
         StorageFormat::Blob,
         Duration::from_secs(10),
         20,
+        true,
         &Registry::default(),
     )
     .await?;
@@ -193,6 +200,154 @@ async fn test_manifest_serde() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_index_serde() -> Result<()> {
+    let remote_dir = temp_dir();
+    let remote_store = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote_dir.clone()),
+        ..Default::default()
+    }
+    .make()?;
+    let mut original_index = CheckpointFileIndex::new(FileCompression::None);
+    original_index.add_checkpoint(
+        100,
+        BlobLocation {
+            offset: 6,
+            length: 42,
+        },
+        &random_contents().into_inner(),
+    );
+    let index_path = object_store::path::Path::from("epoch_0").child("100.idx");
+    let file_path = path_to_filesystem(remote_dir, &index_path)?;
+    fs::create_dir_all(file_path.parent().unwrap())?;
+    write_index(&original_index, &file_path)?;
+    let downloaded_index = read_index(&index_path, remote_store.clone()).await?;
+    assert_eq!(downloaded_index, Some(original_index));
+    // Checkpoint files written without an index don't have an index file
+    let missing_path = object_store::path::Path::from("epoch_0").child("200.idx");
+    assert_eq!(read_index(&missing_path, remote_store).await?, None);
+    Ok(())
+}
+
+/// Archive checkpoints with transactions in them, with an index, and look each transaction up by
+/// digest, and by the object it wrote.
+async fn test_index_lookups(file_compression: FileCompression) -> Result<()> {
+    let temp_dir = temp_dir();
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(temp_dir.join("remote_dir")),
+        ..Default::default()
+    };
+    let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+    let (checkpoints, contents, _, _) = committee.make_random_checkpoints(10, None);
+
+    // Three checkpoints to a file, so that most checkpoints are read from an offset.
+    let blob_size = Blob::encode(&contents[1].clone().into_inner(), BlobEncoding::Bcs)?.size();
+    let archive_writer = ArchiveWriter::new(
+        ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(temp_dir.join("local_dir")),
+            ..Default::default()
+        },
+        remote_store_config.clone(),
+        file_compression,
+        StorageFormat::Blob,
+        Duration::from_secs(60),
+        FILE_HEADER_BYTES + 3 * blob_size,
+        true,
+        &Registry::default(),
+    )
+    .await?;
+
+    let store = SharedInMemoryStore::default();
+    store.inner_mut().insert_genesis_state(
+        checkpoints[0].clone(),
+        contents[0].clone(),
+        committee.committee().to_owned(),
+    );
+    for (checkpoint, contents) in checkpoints.iter().zip(&contents).skip(1) {
+        store.inner_mut().insert_checkpoint(checkpoint);
+        store
+            .inner_mut()
+            .insert_checkpoint_contents(checkpoint, contents.clone());
+    }
+    let kill = archive_writer.start(store).await?;
+
+    let metrics = ArchiveReaderMetrics::new(&Registry::default());
+    let archive_reader = ArchiveReader::new(
+        ArchiveReaderConfig {
+            remote_store_config,
+            download_concurrency: NonZeroUsize::new(2).unwrap(),
+            use_for_pruning_watermark: false,
+        },
+        &metrics,
+    )?;
+    // A file is only archived once the next checkpoint doesn't fit in it, so the last file isn't.
+    let latest = tokio::time::timeout(Duration::from_secs(60), async {
+        loop {
+            if archive_reader.sync_manifest_once().await.is_ok() {
+                if let Ok(latest) = archive_reader.latest_available_checkpoint().await {
+                    if latest >= 6 {
+                        return latest;
+                    }
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await?;
+
+    let mut lookups = 1;
+    for (checkpoint, contents) in checkpoints.iter().zip(&contents).skip(1) {
+        for execution_data in contents.clone().into_inner().iter() {
+            lookups += 2;
+            let ((object_id, version, _), _, _) = execution_data.effects.all_changed_objects()[0];
+            let by_digest = archive_reader
+                .get_transaction(execution_data.transaction.digest())
+                .await?;
+            let by_object = archive_reader
+                .get_transaction_for_object(object_id, version)
+                .await?;
+            if checkpoint.sequence_number <= latest {
+                let expected = Some((checkpoint.sequence_number, execution_data.clone()));
+                assert_eq!(by_digest, expected);
+                assert_eq!(by_object, expected);
+            } else {
+                assert_eq!(by_digest, None);
+                assert_eq!(by_object, None);
+            }
+        }
+    }
+    assert_eq!(
+        archive_reader
+            .get_transaction(&TransactionDigest::random())
+            .await?,
+        None
+    );
+
+    // Every lookup is measured, and each index file is only read once while it stays cached.
+    let index_files_read = metrics
+        .archive_index_files_read
+        .with_label_values(&["unknown"]);
+    assert_eq!(index_files_read.get_sample_count(), lookups);
+    assert!(index_files_read.get_sample_sum() >= 1.0);
+    assert!(index_files_read.get_sample_sum() <= (latest + 1) as f64);
+
+    kill.send(())?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_index_lookups_uncompressed() -> Result<()> {
+    test_index_lookups(FileCompression::None).await
+}
+
+#[tokio::test]
+async fn test_index_lookups_compressed() -> Result<()> {
+    test_index_lookups(FileCompression::Zstd).await
+}
+
 #[tokio::test]
 async fn test_archive_reader_e2e() -> Result<(), anyhow::Error> {
     let test_store = SharedInMemoryStore::default();
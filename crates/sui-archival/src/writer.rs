@@ -3,9 +3,10 @@
 #![allow(dead_code)]
 
 use crate::{
-    create_file_metadata, read_manifest, write_manifest, CheckpointUpdates, FileMetadata, FileType,
-    Manifest, CHECKPOINT_FILE_MAGIC, CHECKPOINT_FILE_SUFFIX, EPOCH_DIR_PREFIX, MAGIC_BYTES,
-    SUMMARY_FILE_MAGIC, SUMMARY_FILE_SUFFIX,
+    create_file_metadata, read_manifest, write_index, write_manifest, BlobLocation,
+    CheckpointFileIndex, CheckpointUpdates, FileMetadata, FileType, Manifest,
+    CHECKPOINT_FILE_MAGIC, CHECKPOINT_FILE_SUFFIX, EPOCH_DIR_PREFIX, FILE_HEADER_BYTES,
+    INDEX_FILE_SUFFIX, MAGIC_BYTES, SUMMARY_FILE_MAGIC, SUMMARY_FILE_SUFFIX,
 };
 use anyhow::Result;
 use anyhow::{anyhow, Context};
@@ -61,6 +62,10 @@ struct CheckpointWriter {
     summary_wbuf: BufWriter<File>,
     sender: Sender<CheckpointUpdates>,
     checkpoint_buf_offset: usize,
+    // Offset of the next blob in the uncompressed checkpoint file
+    checkpoint_file_offset: u64,
+    // Index of the current checkpoint file, if the writer keeps one
+    index: Option<CheckpointFileIndex>,
     file_compression: FileCompression,
     storage_format: StorageFormat,
     manifest: Manifest,
@@ -78,6 +83,7 @@ impl CheckpointWriter {
         manifest: Manifest,
         commit_duration: Duration,
         commit_file_size: usize,
+        write_index: bool,
     ) -> Result<Self> {
         let epoch_num = manifest.epoch_num();
         let checkpoint_sequence_num = manifest.next_checkpoint_seq_num();
@@ -109,6 +115,8 @@ impl CheckpointWriter {
             wbuf: BufWriter::new(checkpoint_file),
             summary_wbuf: BufWriter::new(summary_file),
             checkpoint_buf_offset: 0,
+            checkpoint_file_offset: FILE_HEADER_BYTES as u64,
+            index: write_index.then(|| CheckpointFileIndex::new(file_compression)),
             sender,
             file_compression,
             storage_format,
@@ -171,7 +179,20 @@ impl CheckpointWriter {
             self.reset()?;
         }
 
-        self.checkpoint_buf_offset += contents_blob.write(&mut self.wbuf)?;
+        let blob_offset = self.checkpoint_file_offset;
+        let bytes_written = contents_blob.write(&mut self.wbuf)?;
+        self.checkpoint_buf_offset += bytes_written;
+        self.checkpoint_file_offset += bytes_written as u64;
+        if let Some(index) = &mut self.index {
+            index.add_checkpoint(
+                checkpoint_summary.sequence_number,
+                BlobLocation {
+                    offset: blob_offset,
+                    length: bytes_written as u64,
+                },
+                &checkpoint_contents,
+            );
+        }
 
         let summary_blob = Blob::encode(&checkpoint_summary, BlobEncoding::Bcs)?;
         summary_blob.write(&mut self.summary_wbuf)?;
@@ -219,15 +240,28 @@ impl CheckpointWriter {
         )?;
         Ok(file_metadata)
     }
+    fn finalize_index(&mut self) -> Result<bool> {
+        let Some(index) = &self.index else {
+            return Ok(false);
+        };
+        let file_path = self.epoch_dir().join(format!(
+            "{}.{INDEX_FILE_SUFFIX}",
+            self.checkpoint_range.start
+        ));
+        write_index(index, &file_path)?;
+        Ok(true)
+    }
     fn cut(&mut self) -> Result<()> {
         if !self.checkpoint_range.is_empty() {
             let checkpoint_file_metadata = self.finalize()?;
             let summary_file_metadata = self.finalize_summary()?;
+            let has_index_file = self.finalize_index()?;
             let checkpoint_updates = CheckpointUpdates::new(
                 self.epoch_num,
                 self.checkpoint_range.end,
                 checkpoint_file_metadata,
                 summary_file_metadata,
+                has_index_file,
                 &mut self.manifest,
             );
             info!("Checkpoint file cut for: {:?}", checkpoint_updates);
@@ -276,6 +310,10 @@ impl CheckpointWriter {
             self.file_compression,
         )?;
         self.checkpoint_buf_offset = MAGIC_BYTES;
+        self.checkpoint_file_offset = FILE_HEADER_BYTES as u64;
+        if let Some(index) = &mut self.index {
+            *index = CheckpointFileIndex::new(self.file_compression);
+        }
         self.wbuf = BufWriter::new(f);
         let f = Self::next_file(
             &self.epoch_dir(),
@@ -310,10 +348,12 @@ impl CheckpointWriter {
 }
 
 /// ArchiveWriter archives history by tailing checkpoints writing them to a local staging dir and
-/// simultaneously uploading them to a remote object store
+/// simultaneously uploading them to a remote object store. It optionally writes an index file
+/// next to each checkpoint file, to look transactions up by digest.
 pub struct ArchiveWriter {
     file_compression: FileCompression,
     storage_format: StorageFormat,
+    write_index: bool,
     local_staging_dir_root: PathBuf,
     local_object_store: Arc<DynObjectStore>,
     remote_object_store: Arc<DynObjectStore>,
@@ -330,11 +370,13 @@ impl ArchiveWriter {
         storage_format: StorageFormat,
         commit_duration: Duration,
         commit_file_size: usize,
+        write_index: bool,
         registry: &Registry,
     ) -> Result<Self> {
         Ok(ArchiveWriter {
             file_compression,
             storage_format,
+            write_index,
             remote_object_store: remote_store_config.make()?,
             local_object_store: local_store_config.make()?,
             local_staging_dir_root: local_store_config.directory.context("Missing local dir")?,
@@ -374,6 +416,7 @@ impl ArchiveWriter {
             manifest,
             self.commit_duration,
             self.commit_file_size,
+            self.write_index,
         )
         .expect("Failed to create checkpoint writer");
         let (kill_sender, kill_receiver) = tokio::sync::broadcast::channel::<()>(1);
@@ -469,6 +512,19 @@ impl ArchiveWriter {
                         .await
                         .expect("Syncing checkpoint content should not fail");
 
+                        // Index files are synced before the manifest is updated, so a checkpoint
+                        // file with an index is never listed without it.
+                        if let Some(index_file_path) = checkpoint_updates.index_file_path() {
+                            Self::sync_file_to_remote(
+                                local_staging_root_dir.clone(),
+                                index_file_path,
+                                local_object_store.clone(),
+                                remote_object_store.clone()
+                            )
+                            .await
+                            .expect("Syncing checkpoint index should not fail");
+                        }
+
                         write_manifest(
                             checkpoint_updates.manifest,
                             remote_object_store.clone()
@@ -7,7 +7,8 @@ use std::net::SocketAddr;
 use std::path::Path;
 use sui_config::Config;
 use sui_config::{PersistedConfig, SUI_KEYSTORE_FILENAME, SUI_NETWORK_CONFIG};
-use sui_indexer::test_utils::start_test_indexer;
+use sui_indexer::db::is_sqlite_url;
+use sui_indexer::test_utils::{start_test_indexer, start_test_indexer_v2};
 use sui_indexer::IndexerConfig;
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
 use sui_sdk::sui_client_config::{SuiClientConfig, SuiEnv};
@@ -224,16 +225,24 @@ impl Cluster for LocalNewCluster {
             vec![]
         };
         if options.pg_address.is_some() && indexer_address.is_some() {
+            let db_url = options.pg_address.clone().unwrap();
+            // Only the V2 indexer supports SQLite.
+            let use_v2 = is_sqlite_url(&db_url);
             let config = IndexerConfig {
-                db_url: Some(options.pg_address.clone().unwrap()),
+                db_url: Some(db_url),
                 rpc_client_url: fullnode_url.clone(),
                 rpc_server_url: indexer_address.as_ref().unwrap().ip().to_string(),
                 rpc_server_port: indexer_address.as_ref().unwrap().port(),
                 migrated_methods,
                 reset_db: true,
+                use_v2,
                 ..Default::default()
             };
-            start_test_indexer(config).await.unwrap();
+            if use_v2 {
+                start_test_indexer_v2(config).await.unwrap();
+            } else {
+                start_test_indexer(config).await.unwrap();
+            }
         }
 
         // Let nodes connect to one another
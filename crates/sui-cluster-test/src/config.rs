@@ -29,7 +29,8 @@ pub struct ClusterTestOpt {
     /// URL for the indexer RPC server
     #[clap(long)]
     pub indexer_address: Option<String>,
-    /// URL for the Indexer Postgres DB
+    /// URL for the Indexer Postgres DB, or `sqlite://<path>` to run the indexer against an
+    /// embedded SQLite database instead.
     #[clap(long)]
     pub pg_address: Option<String>,
     /// TODO(gegao): remove this after indexer migration is complete.
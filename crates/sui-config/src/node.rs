@@ -161,6 +161,10 @@ pub struct NodeConfig {
 #[serde(rename_all = "kebab-case")]
 pub struct TransactionKeyValueStoreReadConfig {
     pub base_url: String,
+    /// If set, history is read from this object store, as written by an uploader with an object
+    /// store in its `TransactionKeyValueStoreWriteConfig`, instead of from `base_url`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object_store_config: Option<ObjectStoreConfig>,
 }
 
 fn default_jwk_fetch_interval_seconds() -> u64 {
@@ -170,6 +174,7 @@ fn default_jwk_fetch_interval_seconds() -> u64 {
 fn default_transaction_kv_store_config() -> TransactionKeyValueStoreReadConfig {
     TransactionKeyValueStoreReadConfig {
         base_url: "https://transactions.sui.io/".to_string(),
+        object_store_config: None,
     }
 }
 
@@ -625,6 +630,10 @@ pub struct StateArchiveConfig {
     pub object_store_config: Option<ObjectStoreConfig>,
     pub concurrency: usize,
     pub use_for_pruning_watermark: bool,
+    /// Write an index file next to each checkpoint file in the archive, so that transactions can
+    /// be looked up by digest.
+    #[serde(default)]
+    pub write_transaction_index: bool,
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
@@ -633,17 +642,30 @@ pub struct StateSnapshotConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub object_store_config: Option<ObjectStoreConfig>,
     pub concurrency: usize,
+    /// Number of delta snapshots, holding only the changes since the previous epoch, to write
+    /// between full snapshots. Zero (the default) writes a full snapshot every epoch.
+    #[serde(default)]
+    pub delta_snapshots_per_full_snapshot: u64,
 }
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct TransactionKeyValueStoreWriteConfig {
+    #[serde(default)]
     pub aws_access_key_id: String,
+    #[serde(default)]
     pub aws_secret_access_key: String,
+    #[serde(default)]
     pub aws_region: String,
+    #[serde(default)]
     pub table_name: String,
+    #[serde(default)]
     pub bucket_name: String,
     pub concurrency: usize,
+    /// If set, the key value store is written to this object store, such as a local or network
+    /// file system, or a self hosted S3 compatible store, instead of to DynamoDB and S3.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object_store_config: Option<ObjectStoreConfig>,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Eq)]
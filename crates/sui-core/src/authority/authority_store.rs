@@ -34,7 +34,8 @@ use typed_store::traits::Map;
 
 use crate::authority::authority_per_epoch_store::AuthorityPerEpochStore;
 use crate::authority::authority_store_types::{
-    get_store_object_pair, ObjectContentDigest, StoreObject, StoreObjectPair, StoreObjectWrapper,
+    get_store_object_pair, ObjectContentDigest, StoreData, StoreObject, StoreObjectPair,
+    StoreObjectWrapper,
 };
 use crate::authority::epoch_start_configuration::{EpochFlag, EpochStartConfiguration};
 
@@ -876,6 +877,46 @@ impl AuthorityStore {
         Ok(())
     }
 
+    /// Removes objects from a db that was restored from a state snapshot, so that a delta snapshot
+    /// can be applied on top of it. Each of `object_refs` must be the current version of a live
+    /// object (or wrapped tombstone) in the db.
+    pub fn bulk_remove_live_objects(
+        perpetual_db: &AuthorityPerpetualTables,
+        object_refs: impl Iterator<Item = ObjectRef>,
+    ) -> SuiResult<()> {
+        let mut batch = perpetual_db.objects.batch();
+        let mut indirect_objects: HashMap<_, i64> = HashMap::new();
+        for object_ref in object_refs {
+            let object_key = ObjectKey::from(object_ref);
+            let store_object = perpetual_db.objects.get(&object_key)?.ok_or_else(|| {
+                SuiError::from(format!("Object to remove is missing: {:?}", object_ref).as_str())
+            })?;
+            if perpetual_db.object_reference(&object_key, store_object.clone())? != object_ref {
+                return Err(SuiError::from(
+                    format!("Object to remove does not match: {:?}", object_ref).as_str(),
+                ));
+            }
+            if let StoreObject::Value(object) = store_object.migrate().into_inner() {
+                if let StoreData::IndirectObject(indirect_object) = object.data {
+                    *indirect_objects.entry(indirect_object.digest).or_default() -= 1;
+                }
+            }
+            batch.delete_batch(&perpetual_db.objects, iter::once(object_key))?;
+            batch.delete_batch(
+                &perpetual_db.owned_object_transaction_locks,
+                iter::once(object_ref),
+            )?;
+        }
+        if !indirect_objects.is_empty() {
+            let ref_count_update = indirect_objects
+                .iter()
+                .map(|(digest, delta)| (digest, delta.to_le_bytes()));
+            batch.partial_merge_batch(&perpetual_db.indirect_move_objects, ref_count_update)?;
+        }
+        batch.write()?;
+        Ok(())
+    }
+
     pub async fn set_epoch_start_configuration(
         &self,
         epoch_start_configuration: &EpochStartConfiguration,
@@ -484,6 +484,16 @@ impl AuthorityPerpetualTables {
         wb.write()?;
         Ok(())
     }
+
+    pub fn insert_tombstone_test_only(&self, object_key: ObjectKey) -> SuiResult {
+        let mut wb = self.objects.batch();
+        wb.insert_batch(
+            &self.objects,
+            std::iter::once((object_key, StoreObjectWrapper::from(StoreObject::Deleted))),
+        )?;
+        wb.write()?;
+        Ok(())
+    }
 }
 
 impl ObjectStore for AuthorityPerpetualTables {
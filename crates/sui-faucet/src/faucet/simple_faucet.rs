@@ -1394,7 +1394,7 @@ mod tests {
         // Now we transfer one gas out
         let res = SuiClientCommands::PayAllSui {
             input_coins: vec![*bad_gas.id()],
-            recipient: SuiAddress::random_for_testing_only(),
+            recipient: SuiAddress::random_for_testing_only().into(),
             gas_budget: 2_000_000,
             serialize_unsigned_transaction: false,
             serialize_signed_transaction: false,
@@ -1617,7 +1617,7 @@ mod tests {
         // Transfer all valid gases away except for 1
         for gas in gases.iter().take(gases.len() - 1) {
             SuiClientCommands::TransferSui {
-                to: destination_address,
+                to: destination_address.into(),
                 sui_coin_object_id: *gas.id(),
                 gas_budget: 50000000,
                 amount: None,
@@ -1690,7 +1690,7 @@ mod tests {
         // Transfer all valid gases away
         for gas in gases {
             SuiClientCommands::TransferSui {
-                to: destination_address,
+                to: destination_address.into(),
                 sui_coin_object_id: *gas.id(),
                 gas_budget: 50000000,
                 amount: None,
@@ -1923,7 +1923,7 @@ mod tests {
     async fn get_current_gases(address: SuiAddress, context: &mut WalletContext) -> Vec<GasCoin> {
         // Get the latest list of gas
         let results = SuiClientCommands::Gas {
-            address: Some(address),
+            address: Some(address.into()),
         }
         .execute(context)
         .await
@@ -183,7 +183,7 @@ pub async fn start_test_indexer(
     let store = PgIndexerStoreV2::new(blocking_pool, indexer_metrics.clone());
     let store_clone = store.clone();
     let handle = tokio::spawn(async move {
-        IndexerV2::start_writer(&config, store_clone, indexer_metrics).await
+        IndexerV2::start_writer(&config, &registry, store_clone, indexer_metrics).await
     });
     (store, handle)
 }
@@ -4,13 +4,17 @@
 use crate::error::Error as SuiGraphQLError;
 use async_graphql::*;
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeSet, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+};
 use sui_json_rpc::name_service::NameServiceConfig;
 
 use crate::functional_group::FunctionalGroup;
 
 const MAX_QUERY_DEPTH: u32 = 10;
 const MAX_QUERY_NODES: u32 = 100;
+const BUDGET_WINDOW_SECS: u64 = 60;
 
 /// Configuration on connections for the RPC, passed in as command-line arguments.
 #[derive(Serialize, Clone, Deserialize, Debug, Eq, PartialEq)]
@@ -34,6 +38,9 @@ pub struct ServiceConfig {
     #[serde(default)]
     pub(crate) disabled_features: BTreeSet<FunctionalGroup>,
 
+    #[serde(default)]
+    pub(crate) budgets: Budgets,
+
     #[serde(default)]
     pub(crate) experiments: Experiments,
 }
@@ -47,6 +54,20 @@ pub struct Limits {
     pub(crate) max_query_nodes: u32,
 }
 
+/// Limits on the total cost of the queries a client can make, over a sliding window of time.  A
+/// query's cost is the number of nodes in it, as counted for the `max-query-nodes` limit.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Budgets {
+    /// Length of the sliding window that query costs are accumulated over, in seconds.
+    pub(crate) window_secs: u64,
+    /// Budget for each client that does not supply a recognised API key.  These clients are told
+    /// apart by IP address.  If this is not set, their queries are not budgeted.
+    pub(crate) default_budget: Option<u32>,
+    /// Budgets for clients that identify themselves by API key.
+    pub(crate) api_keys: BTreeMap<String, u32>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Default)]
 #[serde(rename_all = "kebab-case")]
 pub struct Experiments {
@@ -130,6 +151,16 @@ impl Default for ConnectionConfig {
     }
 }
 
+impl Default for Budgets {
+    fn default() -> Self {
+        Self {
+            window_secs: BUDGET_WINDOW_SECS,
+            default_budget: None,
+            api_keys: BTreeMap::new(),
+        }
+    }
+}
+
 impl Default for Limits {
     fn default() -> Self {
         Self {
@@ -152,6 +183,8 @@ pub struct InternalFeatureConfig {
     pub(crate) query_timeout: bool,
     #[serde(default)]
     pub(crate) metrics: bool,
+    #[serde(default)]
+    pub(crate) persisted_queries: bool,
 }
 
 impl Default for InternalFeatureConfig {
@@ -162,6 +195,7 @@ impl Default for InternalFeatureConfig {
             logger: true,
             query_timeout: true,
             metrics: true,
+            persisted_queries: true,
         }
     }
 }
@@ -256,12 +290,37 @@ mod tests {
         let expect = ServiceConfig {
             limits: Limits::default(),
             disabled_features: BTreeSet::from([G::Coins, G::NameService]),
+            budgets: Budgets::default(),
             experiments: Experiments::default(),
         };
 
         assert_eq!(actual, expect)
     }
 
+    #[test]
+    fn test_read_budgets_in_service_config() {
+        let actual = ServiceConfig::read(
+            r#" [budgets]
+                default-budget = 1000
+
+                [budgets.api-keys]
+                dashboard = 50000
+            "#,
+        )
+        .unwrap();
+
+        let expect = ServiceConfig {
+            budgets: Budgets {
+                window_secs: BUDGET_WINDOW_SECS,
+                default_budget: Some(1000),
+                api_keys: BTreeMap::from([("dashboard".to_string(), 50000)]),
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(actual, expect)
+    }
+
     #[test]
     fn test_read_experiments_in_service_config() {
         let actual = ServiceConfig::read(
@@ -288,6 +347,13 @@ mod tests {
                 max-query-depth = 42
                 max-query-nodes = 320
 
+                [budgets]
+                window-secs = 10
+                default-budget = 100
+
+                [budgets.api-keys]
+                dashboard = 1000
+
                 [experiments]
                 test-flag = true
             "#,
@@ -300,6 +366,11 @@ mod tests {
                 max_query_nodes: 320,
             },
             disabled_features: BTreeSet::from([FunctionalGroup::Analytics]),
+            budgets: Budgets {
+                window_secs: 10,
+                default_budget: Some(100),
+                api_keys: BTreeMap::from([("dashboard".to_string(), 1000)]),
+            },
             experiments: Experiments { test_flag: true },
         };
 
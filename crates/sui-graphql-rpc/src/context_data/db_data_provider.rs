@@ -17,10 +17,8 @@ use crate::{
         epoch::Epoch,
         event::{Event, EventFilter},
         gas::{GasCostSummary, GasInput},
-        move_module::MoveModuleId,
         move_object::MoveObject,
         move_package::MovePackage,
-        move_type::MoveType,
         object::{Object, ObjectFilter, ObjectKind},
         protocol_config::{ProtocolConfigAttr, ProtocolConfigFeatureFlag, ProtocolConfigs},
         safe_mode::SafeMode,
@@ -58,7 +56,7 @@ use sui_indexer::{
         packages::StoredPackage, transactions::StoredTransaction,
     },
     schema_v2::{
-        checkpoints, epochs, objects, packages, transactions, tx_calls, tx_changed_objects,
+        checkpoints, epochs, events, objects, packages, transactions, tx_calls, tx_changed_objects,
         tx_input_objects, tx_recipients, tx_senders,
     },
     PgConnectionPoolConfig,
@@ -159,6 +157,37 @@ impl PgManager {
         .await
     }
 
+    /// Sequence number of the latest transaction in the database, if there is one.
+    async fn get_latest_tx_sequence_number(&self) -> Result<Option<i64>, Error> {
+        self.run_query_async(|conn| {
+            transactions::dsl::transactions
+                .select(transactions::dsl::tx_sequence_number)
+                .order(transactions::dsl::tx_sequence_number.desc())
+                .first::<i64>(conn)
+                .optional()
+        })
+        .await
+    }
+
+    /// Transaction digest and event sequence number of the latest event in the database, if there
+    /// is one.
+    async fn get_latest_event(&self) -> Result<Option<(Vec<u8>, i64)>, Error> {
+        self.run_query_async(|conn| {
+            events::dsl::events
+                .select((
+                    events::dsl::transaction_digest,
+                    events::dsl::event_sequence_number,
+                ))
+                .order((
+                    events::dsl::tx_sequence_number.desc(),
+                    events::dsl::event_sequence_number.desc(),
+                ))
+                .first::<(Vec<u8>, i64)>(conn)
+                .optional()
+        })
+        .await
+    }
+
     async fn get_obj(
         &self,
         address: Vec<u8>,
@@ -735,6 +764,32 @@ impl PgManager {
             .transpose()
     }
 
+    /// Cursor pointing at the latest transaction in the database, or `None` if there are no
+    /// transactions yet.  Paginating forward from this cursor only returns transactions that were
+    /// indexed after it was created.
+    pub(crate) async fn fetch_latest_tx_cursor(&self) -> Result<Option<String>, Error> {
+        Ok(self
+            .get_latest_tx_sequence_number()
+            .await?
+            .map(|tx_sequence_number| tx_sequence_number.to_string()))
+    }
+
+    /// Cursor pointing at the latest event in the database, or `None` if there are no events yet.
+    /// Paginating forward from this cursor only returns events that were indexed after it was
+    /// created.
+    pub(crate) async fn fetch_latest_event_cursor(&self) -> Result<Option<String>, Error> {
+        self.get_latest_event()
+            .await?
+            .map(|(digest, event_seq)| {
+                let tx_digest = TransactionDigest::new(Digest::try_from(digest)?.into_array());
+                Ok(String::from(EventID {
+                    tx_digest,
+                    event_seq: event_seq as u64,
+                }))
+            })
+            .transpose()
+    }
+
     pub(crate) async fn fetch_latest_epoch(&self) -> Result<Epoch, Error> {
         let result = self
             .get_epoch(None)
@@ -1355,22 +1410,7 @@ impl PgManager {
             let mut connection = Connection::new(false, has_next_page);
             connection.edges.extend(results.into_iter().map(|e| {
                 let cursor = String::from(e.id);
-                let event = Event {
-                    id: ID::from(cursor.clone()),
-                    sending_module_id: Some(MoveModuleId {
-                        package: SuiAddress::from_array(**e.package_id),
-                        name: e.transaction_module.to_string(),
-                    }),
-                    event_type: Some(MoveType::new(e.type_.to_string())),
-                    senders: Some(vec![Address {
-                        address: SuiAddress::from_array(e.sender.to_inner()),
-                    }]),
-                    timestamp: e.timestamp_ms.and_then(|t| DateTime::from_ms(t as i64)),
-                    json: Some(e.parsed_json.to_string()),
-                    bcs: Some(Base64::from(e.bcs)),
-                };
-
-                Edge::new(cursor, event)
+                Edge::new(cursor, Event::from(e))
             }));
             Ok(Some(connection))
         } else {
@@ -1489,8 +1529,8 @@ impl TryFrom<StoredTransaction> for TransactionBlock {
             ))
         })?;
 
-        let balance_changes = tx.balance_changes;
-        let object_changes = tx.object_changes;
+        let balance_changes = tx.balance_changes.into();
+        let object_changes = tx.object_changes.into();
         let effects = match SuiTransactionBlockEffects::try_from(effects) {
             Ok(effects) => {
                 let transaction_effects = TransactionBlockEffects::from_stored_transaction(
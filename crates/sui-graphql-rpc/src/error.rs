@@ -72,6 +72,10 @@ pub enum Error {
     _CursorConnectionFetchFailed(String),
     #[error("Error received in multi-get query: {0}")]
     MultiGet(String),
+    #[error("Invalid transaction: {0}")]
+    InvalidTransaction(String),
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
     #[error("Internal error occurred while processing request: {0}")]
     Internal(String),
 }
@@ -90,6 +94,8 @@ impl ErrorExtensions for Error {
             | Error::InvalidCursor(_)
             | Error::_CursorConnectionFetchFailed(_)
             | Error::MultiGet(_)
+            | Error::InvalidTransaction(_)
+            | Error::InvalidSignature(_)
             | Error::InvalidBase58(_)
             | Error::InvalidDigestLength { .. } => {
                 e.set("code", code::BAD_USER_INPUT);
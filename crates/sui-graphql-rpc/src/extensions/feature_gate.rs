@@ -83,7 +83,10 @@ mod tests {
     use async_graphql::{EmptyMutation, EmptySubscription, Schema};
     use expect_test::expect;
 
-    use crate::{functional_group::FunctionalGroup, types::query::Query};
+    use crate::{
+        functional_group::FunctionalGroup,
+        types::{mutation::Mutation, query::Query},
+    };
 
     use super::*;
 
@@ -121,4 +124,30 @@ mod tests {
             ]"#]];
         expect.assert_eq(&format!("{errs:#?}"));
     }
+
+    #[tokio::test]
+    async fn test_accessing_a_disabled_mutation() {
+        let errs: Vec<_> = Schema::build(Query, Mutation, EmptySubscription)
+            .data(ServiceConfig {
+                disabled_features: BTreeSet::from_iter([FunctionalGroup::TransactionExecution]),
+                ..Default::default()
+            })
+            .extension(FeatureGate)
+            .finish()
+            .execute(
+                "mutation { executeTransactionBlock(txBytes: \"\", signatures: []) { errors } }",
+            )
+            .await
+            .into_result()
+            .unwrap_err()
+            .into_iter()
+            .map(|e| e.message)
+            .collect();
+
+        let expect = expect![[r#"
+            [
+                "Cannot query field \"executeTransactionBlock\" on type \"Mutation\". Feature \"transaction-execution\" is disabled.",
+            ]"#]];
+        expect.assert_eq(&format!("{errs:#?}"));
+    }
 }
@@ -0,0 +1,338 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::Budgets;
+use async_graphql::extensions::{Extension, ExtensionContext, ExtensionFactory, NextParseQuery};
+use async_graphql::parser::types::{ExecutableDocument, Selection, SelectionSet};
+use async_graphql::{ServerError, ServerResult, Variables};
+use axum::headers;
+use axum::http::HeaderName;
+use axum::http::HeaderValue;
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+static API_KEY_HEADER: HeaderName = HeaderName::from_static("x-sui-rpc-api-key");
+
+/// API key that a client identifies itself with, to be charged against its own query budget.
+pub(crate) struct ApiKey(pub String);
+
+/// Who a query's cost is charged to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Client {
+    ApiKey(String),
+    Ip(IpAddr),
+}
+
+/// Extension that tracks the cost of queries each client has made over the sliding window
+/// configured in [`Budgets`], to rate limit clients that exceed their budget.  Clients are
+/// identified by the [`ApiKey`] and `SocketAddr` in the request (or session) data.
+#[derive(Clone)]
+pub(crate) struct QueryBudgets {
+    config: Arc<Budgets>,
+    usage: Arc<Mutex<Usage>>,
+}
+
+#[derive(Default)]
+struct Usage {
+    /// Per-client log of when queries were charged, and how much they cost, oldest first.
+    clients: HashMap<Client, VecDeque<(Instant, u32)>>,
+    /// When clients that have not made any queries within the window were last forgotten.
+    last_swept: Option<Instant>,
+}
+
+impl headers::Header for ApiKey {
+    fn name() -> &'static HeaderName {
+        &API_KEY_HEADER
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let value = values.next().ok_or_else(headers::Error::invalid)?;
+        let key = value.to_str().map_err(|_| headers::Error::invalid())?;
+        Ok(ApiKey(key.to_string()))
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        values.extend(HeaderValue::from_str(&self.0))
+    }
+}
+
+impl ExtensionFactory for QueryBudgets {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(self.clone())
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for QueryBudgets {
+    /// Charges the cost of the query to the client's budget once it has been parsed (and passed
+    /// the checks of any extensions after this one), failing the request if it is over budget.
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let doc = next.run(ctx, query, variables).await?;
+        self.charge(
+            ctx.data_opt::<ApiKey>(),
+            ctx.data_opt::<SocketAddr>(),
+            query_cost(&doc),
+        )
+        .await?;
+        Ok(doc)
+    }
+}
+
+impl QueryBudgets {
+    pub(crate) fn new(config: Budgets) -> Self {
+        Self {
+            config: Arc::new(config),
+            usage: Arc::new(Mutex::new(Usage::default())),
+        }
+    }
+
+    /// Charge `cost` to the budget of the client identified by `api_key` (if it is a recognised
+    /// key), or otherwise by `addr`.  Fails without charging anything if the cost would take the
+    /// client over its budget for the current window.  Clients without a budget are not charged.
+    pub(crate) async fn charge(
+        &self,
+        api_key: Option<&ApiKey>,
+        addr: Option<&SocketAddr>,
+        cost: u32,
+    ) -> Result<(), ServerError> {
+        self.charge_at(Instant::now(), api_key, addr, cost).await
+    }
+
+    async fn charge_at(
+        &self,
+        now: Instant,
+        api_key: Option<&ApiKey>,
+        addr: Option<&SocketAddr>,
+        cost: u32,
+    ) -> Result<(), ServerError> {
+        let Some((client, budget)) = self.budget(api_key, addr) else {
+            return Ok(());
+        };
+
+        let window = Duration::from_secs(self.config.window_secs);
+        let mut usage = self.usage.lock().await;
+
+        let expired = |time: &Instant| now.duration_since(*time) >= window;
+
+        // Forget about clients that have not made any queries within the window, at most once per
+        // window, rather than on every query.
+        if usage.last_swept.map_or(true, |swept| expired(&swept)) {
+            usage
+                .clients
+                .retain(|_, log| log.back().is_some_and(|(time, _)| !expired(time)));
+            usage.last_swept = Some(now);
+        }
+
+        // Forget about this client's queries that have fallen out of the window.
+        let log = usage.clients.entry(client).or_default();
+        while log.front().is_some_and(|(time, _)| expired(time)) {
+            log.pop_front();
+        }
+
+        let spent: u64 = log.iter().map(|(_, cost)| *cost as u64).sum();
+        if spent + cost as u64 > budget as u64 {
+            return Err(ServerError::new(
+                format!(
+                    "Query cost budget exceeded. The maximum allowed is {budget} nodes every {} \
+                     seconds, {spent} have already been used",
+                    self.config.window_secs,
+                ),
+                None,
+            ));
+        }
+
+        log.push_back((now, cost));
+        Ok(())
+    }
+
+    /// The client to charge and the budget it has, or `None` if the client is not budgeted.
+    fn budget(&self, api_key: Option<&ApiKey>, addr: Option<&SocketAddr>) -> Option<(Client, u32)> {
+        if let Some(ApiKey(key)) = api_key {
+            if let Some(budget) = self.config.api_keys.get(key) {
+                return Some((Client::ApiKey(key.clone()), *budget));
+            }
+        }
+
+        Some((Client::Ip(addr?.ip()), self.config.default_budget?))
+    }
+}
+
+/// The cost of a query: the number of nodes in it, counted as `QueryLimitsChecker` counts them.
+fn query_cost(doc: &ExecutableDocument) -> u32 {
+    fn num_nodes(selection_set: &SelectionSet) -> u32 {
+        selection_set
+            .items
+            .iter()
+            .map(|sel| match &sel.node {
+                Selection::Field(f) => 1 + num_nodes(&f.node.selection_set.node),
+                _ => 1,
+            })
+            .sum()
+    }
+
+    doc.operations
+        .iter()
+        .map(|(_name, oper)| num_nodes(&oper.node.selection_set.node))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use async_graphql::extensions::apollo_persisted_queries::{
+        ApolloPersistedQueries, LruCacheStorage,
+    };
+    use async_graphql::{value, EmptyMutation, EmptySubscription, Object, Request, Schema};
+
+    use super::*;
+
+    fn budgets() -> QueryBudgets {
+        QueryBudgets::new(Budgets {
+            window_secs: 10,
+            default_budget: Some(10),
+            api_keys: BTreeMap::from([("heavy".to_string(), 100)]),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_budget_replenishes_over_window() {
+        let budgets = budgets();
+        let addr: SocketAddr = "127.0.0.1:8000".parse().unwrap();
+        let t0 = Instant::now();
+        let t = |secs| t0 + Duration::from_secs(secs);
+
+        budgets.charge_at(t(0), None, Some(&addr), 6).await.unwrap();
+        budgets.charge_at(t(5), None, Some(&addr), 4).await.unwrap();
+        budgets
+            .charge_at(t(6), None, Some(&addr), 1)
+            .await
+            .unwrap_err();
+
+        // The first query falls out of the window, freeing up its cost.
+        budgets
+            .charge_at(t(10), None, Some(&addr), 6)
+            .await
+            .unwrap();
+        budgets
+            .charge_at(t(11), None, Some(&addr), 1)
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_budgets_are_per_client() {
+        let budgets = budgets();
+        let alice: SocketAddr = "10.0.0.1:8000".parse().unwrap();
+        let bob: SocketAddr = "10.0.0.2:8000".parse().unwrap();
+        let heavy = ApiKey("heavy".to_string());
+        let unknown = ApiKey("unknown".to_string());
+        let now = Instant::now();
+
+        budgets
+            .charge_at(now, None, Some(&alice), 10)
+            .await
+            .unwrap();
+        budgets.charge_at(now, None, Some(&bob), 10).await.unwrap();
+
+        // Unrecognised keys are charged against the default budget for the client's address.
+        budgets
+            .charge_at(now, Some(&unknown), Some(&alice), 1)
+            .await
+            .unwrap_err();
+
+        // Recognised keys have their own budget.
+        budgets
+            .charge_at(now, Some(&heavy), Some(&alice), 100)
+            .await
+            .unwrap();
+        budgets
+            .charge_at(now, Some(&heavy), Some(&bob), 1)
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_unbudgeted_clients() {
+        let budgets = QueryBudgets::new(Budgets::default());
+        let addr: SocketAddr = "127.0.0.1:8000".parse().unwrap();
+
+        budgets.charge(None, Some(&addr), u32::MAX).await.unwrap();
+        budgets.charge(None, Some(&addr), u32::MAX).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_persisted_queries_are_charged() {
+        struct Query;
+
+        #[Object]
+        impl Query {
+            async fn one(&self) -> u32 {
+                1
+            }
+        }
+
+        // Budgets are charged without any other extensions (such as the `QueryLimitsChecker`).
+        let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+            .extension(QueryBudgets::new(Budgets {
+                window_secs: 60,
+                default_budget: Some(2),
+                api_keys: BTreeMap::new(),
+            }))
+            .extension(ApolloPersistedQueries::new(LruCacheStorage::new(16)))
+            .finish();
+
+        let addr: SocketAddr = "127.0.0.1:8000".parse().unwrap();
+        let request = |query: &str| {
+            let mut request = Request::new(query).data(addr);
+            request.extensions.insert(
+                "persistedQuery".to_string(),
+                value!({ "version": 1, "sha256Hash": "one" }),
+            );
+            request
+        };
+
+        // Persist the query, and then run it by its hash alone.
+        assert!(schema.execute(request("{ one }")).await.is_ok());
+        assert!(schema.execute(request("")).await.is_ok());
+
+        // Both runs were charged, so the budget has been used up.
+        let errors = schema.execute(request("")).await.errors;
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.starts_with("Query cost budget exceeded"));
+    }
+
+    #[tokio::test]
+    async fn test_idle_clients_are_forgotten() {
+        let budgets = budgets();
+        let alice: SocketAddr = "10.0.0.1:8000".parse().unwrap();
+        let bob: SocketAddr = "10.0.0.2:8000".parse().unwrap();
+        let t0 = Instant::now();
+        let t = |secs| t0 + Duration::from_secs(secs);
+
+        budgets
+            .charge_at(t(0), None, Some(&alice), 1)
+            .await
+            .unwrap();
+        budgets.charge_at(t(1), None, Some(&bob), 1).await.unwrap();
+        assert_eq!(budgets.usage.lock().await.clients.len(), 2);
+
+        // Alice's queries have all fallen out of the window by the time the next sweep is due.
+        budgets.charge_at(t(10), None, Some(&bob), 1).await.unwrap();
+        let usage = budgets.usage.lock().await;
+        assert_eq!(usage.clients.len(), 1);
+        assert!(usage.clients.contains_key(&Client::Ip(bob.ip())));
+    }
+}
@@ -32,6 +32,9 @@ pub(crate) enum FunctionalGroup {
     /// Information about the system that changes from epoch to epoch (protocol config, committee,
     /// reference gas price).
     SystemState,
+
+    /// Executing transactions, and simulating them through dry-run or dev-inspect.
+    TransactionExecution,
 }
 
 impl FunctionalGroup {
@@ -51,6 +54,7 @@ impl FunctionalGroup {
             G::NameService,
             G::Subscriptions,
             G::SystemState,
+            G::TransactionExecution,
         ];
         ALL
     }
@@ -72,6 +76,10 @@ fn functional_groups() -> &'static BTreeMap<(&'static str, &'static str), Functi
             (("Address", "nameServiceConnection"), G::NameService),
             (("Checkpoint", "addressMetrics"), G::Analytics),
             (("Checkpoint", "networkTotalTransactions"), G::Analytics),
+            (
+                ("Mutation", "executeTransactionBlock"),
+                G::TransactionExecution,
+            ),
             (("Epoch", "protocolConfigs"), G::SystemState),
             (("Epoch", "referenceGasPrice"), G::SystemState),
             (("Epoch", "validatorSet"), G::SystemState),
@@ -104,6 +112,11 @@ fn functional_groups() -> &'static BTreeMap<(&'static str, &'static str), Functi
             (("Owner", "defaultNameServiceName"), G::NameService),
             (("Owner", "nameServiceConnection"), G::NameService),
             (("Query", "coinMetadata"), G::Coins),
+            (
+                ("Query", "devInspectTransactionBlock"),
+                G::TransactionExecution,
+            ),
+            (("Query", "dryRunTransactionBlock"), G::TransactionExecution),
             (("Query", "moveCallMetrics"), G::Analytics),
             (("Query", "networkMetrics"), G::Analytics),
             (("Query", "protocolConfig"), G::SystemState),
@@ -127,9 +140,9 @@ mod tests {
     use std::collections::BTreeSet;
 
     use async_graphql::registry::Registry;
-    use async_graphql::OutputType;
+    use async_graphql::{OutputType, SubscriptionType};
 
-    use crate::types::query::Query;
+    use crate::types::{mutation::Mutation, query::Query, subscription::Subscription};
 
     use super::*;
 
@@ -140,6 +153,8 @@ mod tests {
     fn test_groups_match_schema() {
         let mut registry = Registry::default();
         Query::create_type_info(&mut registry);
+        Mutation::create_type_info(&mut registry);
+        Subscription::create_type_info(&mut registry);
 
         let unimplemented = BTreeSet::from_iter([
             ("Checkpoint", "addressMetrics"),
@@ -149,8 +164,6 @@ mod tests {
             ("Query", "coinMetadata"),
             ("Query", "moveCallMetrics"),
             ("Query", "networkMetrics"),
-            ("Subscription", "events"),
-            ("Subscription", "transactions"),
         ]);
 
         for (type_, field) in &unimplemented {
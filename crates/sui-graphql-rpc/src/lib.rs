@@ -19,10 +19,10 @@ pub mod utils;
 use async_graphql::*;
 use types::owner::ObjectOwner;
 
-use crate::types::query::Query;
+use crate::types::{mutation::Mutation, query::Query, subscription::Subscription};
 
 pub fn schema_sdl_export() -> String {
-    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+    let schema = Schema::build(Query, Mutation, Subscription)
         .register_output_type::<ObjectOwner>()
         .finish();
     schema.sdl()
@@ -12,26 +12,38 @@ use crate::{
     extensions::{
         feature_gate::FeatureGate,
         logger::Logger,
+        query_budget::{ApiKey, QueryBudgets},
         query_limits_checker::{QueryLimitsChecker, ShowUsage},
         timeout::Timeout,
     },
     metrics::RequestMetrics,
     server::version::{check_version_middleware, set_version_middleware},
-    types::query::{Query, SuiGraphQLSchema},
+    types::{
+        mutation::Mutation,
+        query::{Query, SuiGraphQLSchema},
+        subscription::Subscription,
+    },
+};
+use async_graphql::extensions::apollo_persisted_queries::{
+    ApolloPersistedQueries, LruCacheStorage,
 };
-use async_graphql::{extensions::ExtensionFactory, Schema, SchemaBuilder};
-use async_graphql::{EmptyMutation, EmptySubscription};
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use async_graphql::{extensions::ExtensionFactory, Data, Schema, SchemaBuilder};
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
 use axum::http::HeaderMap;
 use axum::{
-    extract::{connect_info::IntoMakeServiceWithConnectInfo, ConnectInfo},
+    extract::{connect_info::IntoMakeServiceWithConnectInfo, ConnectInfo, WebSocketUpgrade},
     middleware,
+    response::IntoResponse,
+    TypedHeader,
 };
 use axum::{headers::Header, Router};
 use hyper::server::conn::AddrIncoming as HyperAddrIncoming;
 use hyper::Server as HyperServer;
 use std::{any::Any, net::SocketAddr, sync::Arc};
 
+/// Maximum number of query documents to remember for Automatic Persisted Queries.
+pub(crate) const PERSISTED_QUERY_CACHE_SIZE: usize = 1024;
+
 pub struct Server {
     pub server: HyperServer<HyperAddrIncoming, IntoMakeServiceWithConnectInfo<Router, SocketAddr>>,
 }
@@ -84,10 +96,13 @@ impl Server {
             .max_query_nodes(config.service.limits.max_query_nodes)
             .context_data(data_provider)
             .context_data(data_loader)
+            .context_data(sui_sdk_client_v0)
             .context_data(pg_conn_pool)
             .context_data(name_service_config)
             .context_data(Arc::new(metrics))
-            .context_data(config.clone());
+            .context_data(config.clone())
+            // Registered before the `QueryLimitsChecker`, so that queries it rejects aren't charged.
+            .extension(QueryBudgets::new(config.service.budgets.clone()));
 
         if config.internal_features.feature_gate {
             builder = builder.extension(FeatureGate);
@@ -101,6 +116,11 @@ impl Server {
         if config.internal_features.query_timeout {
             builder = builder.extension(Timeout::default());
         }
+        if config.internal_features.persisted_queries {
+            builder = builder.extension(ApolloPersistedQueries::new(LruCacheStorage::new(
+                PERSISTED_QUERY_CACHE_SIZE,
+            )));
+        }
 
         builder.build()
     }
@@ -110,7 +130,7 @@ pub(crate) struct ServerBuilder {
     port: u16,
     host: String,
 
-    schema: SchemaBuilder<Query, EmptyMutation, EmptySubscription>,
+    schema: SchemaBuilder<Query, Mutation, Subscription>,
 }
 
 impl ServerBuilder {
@@ -118,7 +138,7 @@ impl ServerBuilder {
         Self {
             port,
             host,
-            schema: async_graphql::Schema::build(Query, EmptyMutation, EmptySubscription),
+            schema: async_graphql::Schema::build(Query, Mutation, Subscription),
         }
     }
 
@@ -146,7 +166,7 @@ impl ServerBuilder {
         self
     }
 
-    fn build_schema(self) -> Schema<Query, EmptyMutation, EmptySubscription> {
+    pub(crate) fn build_schema(self) -> Schema<Query, Mutation, Subscription> {
         self.schema.finish()
     }
 
@@ -156,6 +176,7 @@ impl ServerBuilder {
 
         let app = axum::Router::new()
             .route("/", axum::routing::get(graphiql).post(graphql_handler))
+            .route("/subscriptions", axum::routing::get(subscription_handler))
             .layer(axum::extract::Extension(schema))
             .layer(middleware::from_fn(check_version_middleware))
             .layer(middleware::from_fn(set_version_middleware));
@@ -174,22 +195,51 @@ async fn graphql_handler(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     schema: axum::Extension<SuiGraphQLSchema>,
     headers: HeaderMap,
+    api_key: Option<TypedHeader<ApiKey>>,
     req: GraphQLRequest,
 ) -> GraphQLResponse {
     let mut req = req.into_inner();
     if headers.contains_key(ShowUsage::name()) {
         req.data.insert(ShowUsage)
     }
+    insert_client_data(&mut req.data, addr, api_key);
+    schema.execute(req).await.into()
+}
+
+async fn subscription_handler(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    schema: axum::Extension<SuiGraphQLSchema>,
+    api_key: Option<TypedHeader<ApiKey>>,
+    protocol: GraphQLProtocol,
+    upgrade: WebSocketUpgrade,
+) -> impl IntoResponse {
+    // The client's identity is shared by every subscription made over the connection.
+    let mut data = Data::default();
+    insert_client_data(&mut data, addr, api_key);
+    upgrade
+        .protocols(async_graphql::http::ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |stream| {
+            GraphQLWebSocket::new(stream, schema.0, protocol)
+                .with_data(data)
+                .serve()
+        })
+}
+
+/// Identify the client making a request, for the purposes of query cost budgeting.
+fn insert_client_data(data: &mut Data, addr: SocketAddr, api_key: Option<TypedHeader<ApiKey>>) {
+    if let Some(TypedHeader(api_key)) = api_key {
+        data.insert(api_key)
+    }
     // Capture the IP address of the client
     // Note: if a load balancer is used it must be configured to forward the client IP address
-    req.data.insert(addr);
-    schema.execute(req).await.into()
+    data.insert(addr);
 }
 
 async fn graphiql() -> impl axum::response::IntoResponse {
     axum::response::Html(
         async_graphql::http::GraphiQLSource::build()
             .endpoint("/")
+            .subscription_endpoint("/subscriptions")
             .finish(),
     )
 }
@@ -198,7 +248,7 @@ async fn graphiql() -> impl axum::response::IntoResponse {
 mod tests {
     use super::*;
     use crate::{
-        config::ServiceConfig,
+        config::{Budgets, ServiceConfig},
         context_data::{
             data_provider::DataProvider, db_data_provider::PgManager,
             sui_sdk_data_provider::sui_sdk_client_v0,
@@ -209,8 +259,10 @@ mod tests {
     };
     use async_graphql::{
         extensions::{Extension, ExtensionContext, NextExecute},
-        Response,
+        Request, Response,
     };
+    use futures::StreamExt;
+    use std::collections::BTreeMap;
     use std::time::Duration;
     use std::{env, sync::Arc};
 
@@ -438,4 +490,28 @@ mod tests {
         assert_eq!(metrics2.num_nodes.get_sample_sum(), 2. + 4.);
         assert_eq!(metrics2.query_depth.get_sample_sum(), 1. + 3.);
     }
+
+    #[tokio::test]
+    async fn test_subscriptions_are_budgeted() {
+        // A budget too small for any query, so that only unidentified clients get through.
+        let schema = ServerBuilder::new(8000, "127.0.0.1".to_string())
+            .extension(QueryBudgets::new(Budgets {
+                window_secs: 60,
+                default_budget: Some(0),
+                api_keys: BTreeMap::new(),
+            }))
+            .build_schema();
+
+        // Subscriptions see the client's identity through the data of the WebSocket connection
+        // they are made over.
+        let mut data = Data::default();
+        insert_client_data(&mut data, "127.0.0.1:8000".parse().unwrap(), None);
+        let request = Request::new("subscription { events(filter: {}) { id } }");
+        let mut stream = Box::pin(schema.execute_stream_with_session_data(request, Arc::new(data)));
+        let resp = stream.next().await.unwrap();
+
+        let errs: Vec<_> = resp.errors.into_iter().map(|e| e.message).collect();
+        assert_eq!(errs.len(), 1);
+        assert!(errs[0].starts_with("Query cost budget exceeded"));
+    }
 }
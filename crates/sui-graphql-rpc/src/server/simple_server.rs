@@ -7,11 +7,15 @@ use crate::context_data::db_data_provider::PgManager;
 use crate::context_data::sui_sdk_data_provider::{lru_cache_data_loader, sui_sdk_client_v0};
 use crate::extensions::feature_gate::FeatureGate;
 use crate::extensions::logger::Logger;
+use crate::extensions::query_budget::QueryBudgets;
 use crate::extensions::query_limits_checker::QueryLimitsChecker;
 use crate::extensions::timeout::Timeout;
 use crate::metrics::RequestMetrics;
-use crate::server::builder::ServerBuilder;
+use crate::server::builder::{ServerBuilder, PERSISTED_QUERY_CACHE_SIZE};
 
+use async_graphql::extensions::apollo_persisted_queries::{
+    ApolloPersistedQueries, LruCacheStorage,
+};
 use prometheus::Registry;
 use std::default::Default;
 use std::net::SocketAddr;
@@ -46,6 +50,7 @@ pub async fn start_example_server(
     let prom_addr: SocketAddr = PROM_ADDR.parse().unwrap();
     let registry = start_prom(prom_addr);
     let metrics = RequestMetrics::new(&registry);
+    let query_budgets = QueryBudgets::new(service_config.budgets.clone());
 
     let builder = ServerBuilder::new(conn.port, conn.host);
     println!("Launch GraphiQL IDE at: http://{}", builder.address());
@@ -55,10 +60,15 @@ pub async fn start_example_server(
         .max_query_nodes(service_config.limits.max_query_nodes)
         .context_data(data_provider)
         .context_data(data_loader)
+        .context_data(sui_sdk_client_v0)
         .context_data(service_config)
         .context_data(pg_conn_pool)
         .context_data(name_service_config)
         .context_data(Arc::new(metrics))
+        .extension(query_budgets)
+        .extension(ApolloPersistedQueries::new(LruCacheStorage::new(
+            PERSISTED_QUERY_CACHE_SIZE,
+        )))
         .extension(QueryLimitsChecker::default())
         .extension(FeatureGate)
         .extension(Logger::default())
@@ -0,0 +1,166 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use move_core_types::language_storage::TypeTag;
+use sui_json_rpc_types::{
+    DevInspectResults, DryRunTransactionBlockResponse, SuiExecutionResult, SuiTypeTag,
+};
+use sui_sdk::types::transaction::{TransactionData, TransactionDataAPI, TransactionKind};
+
+use super::{
+    address::Address,
+    base64::Base64,
+    event::Event,
+    gas::GasInput,
+    move_type::MoveType,
+    sui_address::SuiAddress,
+    transaction_block::{TransactionBlock, TransactionBlockEffects},
+    transaction_block_kind::TransactionBlockKind,
+};
+use crate::error::Error;
+
+/// The result of simulating a transaction, without committing its effects on-chain.
+#[derive(SimpleObject)]
+pub(crate) struct DryRunResult {
+    /// The transaction that was simulated, including the effects it would have had.
+    pub transaction: Option<TransactionBlock>,
+    /// Error encountered while executing the transaction, if it failed.
+    pub errors: Option<String>,
+    /// Events that would be emitted by the transaction.
+    pub events: Option<Vec<Event>>,
+    /// Per-command execution results, only available when the checks that are usually performed
+    /// on transactions are skipped (dev-inspect).
+    pub results: Option<Vec<DryRunEffect>>,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct DryRunEffect {
+    /// Changes made to arguments that were mutably borrowed by this command.
+    pub mutated_references: Option<Vec<DryRunMutation>>,
+    /// Values returned by this command.
+    pub return_values: Option<Vec<DryRunReturn>>,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct DryRunMutation {
+    /// The argument that was mutably borrowed, e.g. `Input(0)` or `NestedResult(1,0)`.
+    pub input: String,
+    #[graphql(name = "type")]
+    pub type_: MoveType,
+    pub bcs: Base64,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct DryRunReturn {
+    #[graphql(name = "type")]
+    pub type_: MoveType,
+    pub bcs: Base64,
+}
+
+impl DryRunResult {
+    pub(crate) fn from_dry_run(
+        tx_data: &TransactionData,
+        tx_bytes: Base64,
+        response: DryRunTransactionBlockResponse,
+    ) -> Self {
+        let effects = TransactionBlockEffects::from(&response.effects);
+        let errors = effects.errors.clone();
+
+        let transaction = TransactionBlock {
+            digest: effects.tx_block_digest,
+            effects: Some(effects),
+            sender: Some(Address {
+                address: SuiAddress::from_array(tx_data.sender().to_inner()),
+            }),
+            bcs: Some(tx_bytes),
+            gas_input: Some(GasInput::from(tx_data.gas_data())),
+            epoch_id: None,
+            kind: Some(TransactionBlockKind::from(tx_data.kind())),
+            signatures: None,
+        };
+
+        Self {
+            transaction: Some(transaction),
+            errors,
+            events: Some(response.events.data.into_iter().map(Event::from).collect()),
+            results: None,
+        }
+    }
+
+    pub(crate) fn from_dev_inspect(
+        tx_kind: &TransactionKind,
+        sender: SuiAddress,
+        results: DevInspectResults,
+    ) -> Result<Self, Error> {
+        let effects = TransactionBlockEffects::from(&results.effects);
+
+        let transaction = TransactionBlock {
+            digest: effects.tx_block_digest,
+            effects: Some(effects),
+            sender: Some(Address { address: sender }),
+            bcs: None,
+            gas_input: None,
+            epoch_id: None,
+            kind: Some(TransactionBlockKind::from(tx_kind)),
+            signatures: None,
+        };
+
+        let execution_results = results
+            .results
+            .map(|rs| {
+                rs.into_iter()
+                    .map(DryRunEffect::try_from)
+                    .collect::<Result<Vec<_>, Error>>()
+            })
+            .transpose()?;
+
+        Ok(Self {
+            transaction: Some(transaction),
+            errors: results.error,
+            events: Some(results.events.data.into_iter().map(Event::from).collect()),
+            results: execution_results,
+        })
+    }
+}
+
+impl TryFrom<SuiExecutionResult> for DryRunEffect {
+    type Error = Error;
+
+    fn try_from(result: SuiExecutionResult) -> Result<Self, Self::Error> {
+        let mutated_references = result
+            .mutable_reference_outputs
+            .into_iter()
+            .map(|(argument, bcs, type_)| {
+                Ok(DryRunMutation {
+                    input: argument.to_string(),
+                    type_: move_type(type_)?,
+                    bcs: Base64::from(bcs),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let return_values = result
+            .return_values
+            .into_iter()
+            .map(|(bcs, type_)| {
+                Ok(DryRunReturn {
+                    type_: move_type(type_)?,
+                    bcs: Base64::from(bcs),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Self {
+            mutated_references: Some(mutated_references),
+            return_values: Some(return_values),
+        })
+    }
+}
+
+fn move_type(tag: SuiTypeTag) -> Result<MoveType, Error> {
+    let tag: TypeTag = tag
+        .try_into()
+        .map_err(|e| Error::Internal(format!("Cannot parse type of execution result: {e}")))?;
+    Ok(MoveType::new(tag.to_string()))
+}
@@ -0,0 +1,16 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+
+use super::transaction_block::TransactionBlockEffects;
+
+/// Either the effects of a transaction that was executed on-chain, or the errors that prevented it
+/// from being executed.
+#[derive(SimpleObject)]
+pub(crate) struct ExecutionResult {
+    /// The effects of the executed transaction, as reported by the fullnode that executed it.
+    pub effects: Option<TransactionBlockEffects>,
+    /// Errors that prevented the transaction from being executed.
+    pub errors: Option<String>,
+}
@@ -11,15 +11,18 @@ pub(crate) mod committee_member;
 pub(crate) mod date_time;
 pub(crate) mod digest;
 pub(crate) mod display;
+pub(crate) mod dry_run_result;
 pub(crate) mod end_of_epoch_data;
 pub(crate) mod epoch;
 pub(crate) mod event;
+pub(crate) mod execution_result;
 pub(crate) mod gas;
 pub(crate) mod move_module;
 pub(crate) mod move_object;
 pub(crate) mod move_package;
 pub(crate) mod move_type;
 pub(crate) mod move_value;
+pub(crate) mod mutation;
 pub(crate) mod name_service;
 pub(crate) mod object;
 pub(crate) mod object_change;
@@ -30,6 +33,7 @@ pub(crate) mod safe_mode;
 pub(crate) mod stake;
 pub(crate) mod stake_subsidy;
 pub(crate) mod storage_fund;
+pub(crate) mod subscription;
 pub(crate) mod sui_address;
 pub(crate) mod sui_system_state_summary;
 pub(crate) mod system_parameters;
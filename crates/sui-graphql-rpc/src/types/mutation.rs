@@ -0,0 +1,83 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use fastcrypto::traits::ToFromBytes;
+use shared_crypto::intent::Intent;
+use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
+use sui_sdk::{
+    types::{
+        quorum_driver_types::ExecuteTransactionRequestType,
+        signature::GenericSignature,
+        transaction::{Transaction, TransactionData},
+    },
+    SuiClient,
+};
+
+use super::{
+    base64::Base64, execution_result::ExecutionResult, transaction_block::TransactionBlockEffects,
+};
+use crate::error::{code, graphql_error, Error};
+
+pub(crate) struct Mutation;
+
+#[Object]
+impl Mutation {
+    /// Execute a transaction, committing its effects on chain.
+    ///
+    /// `txBytes` is a `TransactionData` struct that has been BCS-encoded and then Base64-encoded.
+    /// `signatures` are a list of `flag || signature || pubkey` bytes, Base64-encoded.
+    ///
+    /// Waits until the transaction has been finalized on chain to return its effects.  If the
+    /// transaction could not be finalized, returns the errors that prevented it, instead.
+    async fn execute_transaction_block(
+        &self,
+        ctx: &Context<'_>,
+        tx_bytes: Base64,
+        signatures: Vec<Base64>,
+    ) -> Result<ExecutionResult> {
+        let client: &SuiClient = ctx.data().map_err(|_| {
+            graphql_error(
+                code::INTERNAL_SERVER_ERROR,
+                "Unable to fetch Sui SDK client",
+            )
+        })?;
+
+        let tx_data: TransactionData = bcs::from_bytes(&tx_bytes.0)
+            .map_err(|e| Error::InvalidTransaction(e.to_string()))
+            .extend()?;
+
+        let signatures = signatures
+            .iter()
+            .map(|sig| {
+                GenericSignature::from_bytes(&sig.0)
+                    .map_err(|e| Error::InvalidSignature(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .extend()?;
+
+        let transaction =
+            Transaction::from_generic_sig_data(tx_data, Intent::sui_transaction(), signatures);
+
+        let response = client
+            .quorum_driver_api()
+            .execute_transaction_block(
+                transaction,
+                SuiTransactionBlockResponseOptions::new().with_effects(),
+                Some(ExecuteTransactionRequestType::WaitForEffectsCert),
+            )
+            .await;
+
+        Ok(match response {
+            Ok(response) => ExecutionResult {
+                effects: response.effects.as_ref().map(TransactionBlockEffects::from),
+                errors: (!response.errors.is_empty()).then(|| response.errors.join("\n")),
+            },
+
+            Err(e) => ExecutionResult {
+                effects: None,
+                errors: Some(e.to_string()),
+            },
+        })
+    }
+}
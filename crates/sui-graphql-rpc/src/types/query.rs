@@ -3,15 +3,26 @@
 
 use async_graphql::{connection::Connection, *};
 use sui_json_rpc::name_service::NameServiceConfig;
+use sui_sdk::{
+    types::{
+        base_types::SuiAddress as NativeSuiAddress,
+        transaction::{TransactionData, TransactionKind},
+    },
+    SuiClient,
+};
 
 use super::{
     address::Address,
+    base64::Base64,
     checkpoint::{Checkpoint, CheckpointId},
+    dry_run_result::DryRunResult,
     epoch::Epoch,
     event::{Event, EventFilter},
+    mutation::Mutation,
     object::{Object, ObjectFilter},
     owner::{ObjectOwner, Owner},
     protocol_config::ProtocolConfigs,
+    subscription::Subscription,
     sui_address::SuiAddress,
     sui_system_state_summary::SuiSystemStateSummary,
     transaction_block::{TransactionBlock, TransactionBlockFilter},
@@ -23,7 +34,7 @@ use crate::{
 };
 
 pub(crate) struct Query;
-pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, EmptyMutation, EmptySubscription>;
+pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, Mutation, Subscription>;
 
 #[allow(unreachable_code)]
 #[allow(unused_variables)]
@@ -51,8 +62,83 @@ impl Query {
             .cloned()?)
     }
 
+    /// Simulate running a transaction to inspect its effects without committing to them on-chain.
+    ///
+    /// `txBytes` is a `TransactionData` struct that has been BCS-encoded and then Base64-encoded.
+    /// The transaction is subject to the same checks as it would be during execution (ownership
+    /// of input objects, visibility of called functions, gas budget, etc).
+    async fn dry_run_transaction_block(
+        &self,
+        ctx: &Context<'_>,
+        tx_bytes: Base64,
+    ) -> Result<DryRunResult> {
+        let client: &SuiClient = ctx.data().map_err(|_| {
+            graphql_error(
+                code::INTERNAL_SERVER_ERROR,
+                "Unable to fetch Sui SDK client",
+            )
+        })?;
+
+        let tx_data: TransactionData = bcs::from_bytes(&tx_bytes.0)
+            .map_err(|e| Error::InvalidTransaction(e.to_string()))
+            .extend()?;
+
+        let response = client
+            .read_api()
+            .dry_run_transaction_block(tx_data.clone())
+            .await
+            .map_err(|e| Error::Internal(e.to_string()))
+            .extend()?;
+
+        Ok(DryRunResult::from_dry_run(&tx_data, tx_bytes, response))
+    }
+
+    /// Run a transaction in "dev-inspect" mode, to inspect its effects and the values returned by
+    /// each of its commands, without committing to them on-chain.
+    ///
+    /// `txBytes` is a `TransactionKind` struct that has been BCS-encoded and then Base64-encoded.
+    /// Unlike `dryRunTransactionBlock`, ownership of input objects and visibility of called
+    /// functions are not checked, and gas is not charged to a real coin.  `gasPrice` defaults to
+    /// the reference gas price, and `epoch` defaults to the current epoch.
+    async fn dev_inspect_transaction_block(
+        &self,
+        ctx: &Context<'_>,
+        tx_bytes: Base64,
+        sender: SuiAddress,
+        gas_price: Option<u64>,
+        epoch: Option<u64>,
+    ) -> Result<DryRunResult> {
+        let client: &SuiClient = ctx.data().map_err(|_| {
+            graphql_error(
+                code::INTERNAL_SERVER_ERROR,
+                "Unable to fetch Sui SDK client",
+            )
+        })?;
+
+        let tx_kind: TransactionKind = bcs::from_bytes(&tx_bytes.0)
+            .map_err(|e| Error::InvalidTransaction(e.to_string()))
+            .extend()?;
+
+        let native_sender = NativeSuiAddress::from_bytes(sender.into_array())
+            .map_err(|e| Error::Internal(e.to_string()))
+            .extend()?;
+
+        let results = client
+            .read_api()
+            .dev_inspect_transaction_block(
+                native_sender,
+                tx_kind.clone(),
+                gas_price.map(Into::into),
+                epoch.map(Into::into),
+            )
+            .await
+            .map_err(|e| Error::Internal(e.to_string()))
+            .extend()?;
+
+        DryRunResult::from_dev_inspect(&tx_kind, sender, results).extend()
+    }
+
     // availableRange - pending impl. on IndexerV2
-    // dryRunTransactionBlock
     // coinMetadata
 
     async fn owner(&self, ctx: &Context<'_>, address: SuiAddress) -> Option<ObjectOwner> {
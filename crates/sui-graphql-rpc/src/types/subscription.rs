@@ -0,0 +1,248 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{collections::VecDeque, future::Future, time::Duration};
+
+use async_graphql::{connection::Connection, *};
+use futures::{stream, Stream};
+
+use super::{
+    event::{Event, EventFilter},
+    transaction_block::{TransactionBlock, TransactionBlockFilter},
+};
+use crate::{context_data::db_data_provider::PgManager, error::Error};
+
+/// How long to wait before polling the database again, after a poll that returned no new results.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Maximum number of results to fetch from the database per poll.
+const POLL_PAGE_SIZE: u64 = 50;
+
+pub(crate) struct Subscription;
+
+#[Subscription]
+impl Subscription {
+    /// Stream events as they are indexed, starting from the latest event at the time of
+    /// subscribing.  Accepts the same filters as `Query.eventConnection`.
+    async fn events<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        filter: EventFilter,
+    ) -> Result<impl Stream<Item = Result<Event>> + 'ctx> {
+        let pg = ctx.data_unchecked::<PgManager>();
+        let cursor = pg.fetch_latest_event_cursor().await.extend()?;
+
+        Ok(poll(cursor, move |after| {
+            pg.fetch_events(Some(POLL_PAGE_SIZE), after, None, None, filter.clone())
+        }))
+    }
+
+    /// Stream transaction blocks as they are indexed, starting from the latest transaction block
+    /// at the time of subscribing.  Accepts the same filters as `Query.transactionBlockConnection`.
+    async fn transactions<'ctx>(
+        &self,
+        ctx: &Context<'ctx>,
+        filter: Option<TransactionBlockFilter>,
+    ) -> Result<impl Stream<Item = Result<TransactionBlock>> + 'ctx> {
+        let pg = ctx.data_unchecked::<PgManager>();
+        if let Some(filter) = &filter {
+            pg.validate_tx_block_filter(filter).extend()?;
+        }
+
+        let cursor = pg.fetch_latest_tx_cursor().await.extend()?;
+
+        Ok(poll(cursor, move |after| {
+            pg.fetch_txs(Some(POLL_PAGE_SIZE), after, None, None, filter.clone())
+        }))
+    }
+}
+
+/// Turn a paginated query into a stream of its nodes, by repeatedly fetching the page after
+/// `cursor` (or the first page, if `cursor` is `None`).  Sleeps for `POLL_INTERVAL` whenever there
+/// are no new results, and terminates the stream after yielding the first error it encounters.
+fn poll<'a, N, F, Fut>(
+    cursor: Option<String>,
+    fetch: F,
+) -> impl Stream<Item = Result<N>> + Send + 'a
+where
+    N: OutputType + Send + 'a,
+    F: Fn(Option<String>) -> Fut + Send + 'a,
+    Fut: Future<Output = Result<Option<Connection<String, N>>, Error>> + Send + 'a,
+{
+    let init = Some((cursor, VecDeque::new(), fetch));
+    stream::unfold(init, |state| async move {
+        let (mut cursor, mut buffer, fetch) = state?;
+        loop {
+            if let Some(node) = buffer.pop_front() {
+                return Some((Ok(node), Some((cursor, buffer, fetch))));
+            }
+
+            let page = match fetch(cursor.clone()).await {
+                Ok(page) => page,
+                Err(e) => return Some((Err(e.extend()), None)),
+            };
+
+            for edge in page.into_iter().flat_map(|c| c.edges) {
+                cursor = Some(edge.cursor);
+                buffer.push_back(edge.node);
+            }
+
+            if buffer.is_empty() {
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        }
+    })
+}
+
+#[cfg(all(test, feature = "pg_integration"))]
+mod tests {
+    use std::time::Duration;
+
+    use futures::{stream::BoxStream, StreamExt};
+    use serde_json::Value;
+    use sui_test_transaction_builder::TestTransactionBuilder;
+    use sui_types::base_types::SuiAddress;
+    use test_cluster::TestCluster;
+    use tokio::time::timeout;
+
+    use crate::{
+        cluster::start_cluster, config::ConnectionConfig,
+        context_data::db_data_provider::PgManager, server::builder::ServerBuilder,
+        types::query::SuiGraphQLSchema,
+    };
+
+    /// How long to wait for an item that a subscription should produce.
+    const ITEM_TIMEOUT: Duration = Duration::from_secs(60);
+
+    /// How long to wait for a subscription to start, when it has nothing to produce yet.
+    const IDLE_TIMEOUT: Duration = Duration::from_secs(2);
+
+    fn subscribe<'a>(schema: &'a SuiGraphQLSchema, query: String) -> BoxStream<'a, Value> {
+        schema
+            .execute_stream(query)
+            .map(|resp| {
+                assert!(resp.errors.is_empty(), "{:?}", resp.errors);
+                resp.data.into_json().unwrap()
+            })
+            .boxed()
+    }
+
+    /// Poll `stream` until it produces an item that matches `pred`.
+    async fn next_matching(stream: &mut BoxStream<'_, Value>, pred: impl Fn(&Value) -> bool) {
+        timeout(ITEM_TIMEOUT, async {
+            while let Some(item) = stream.next().await {
+                if pred(&item) {
+                    return;
+                }
+            }
+            panic!("Subscription ended early");
+        })
+        .await
+        .expect("Timed out waiting for subscription");
+    }
+
+    async fn first(stream: &mut BoxStream<'_, Value>) -> Value {
+        timeout(ITEM_TIMEOUT, stream.next())
+            .await
+            .expect("Timed out waiting for subscription")
+            .expect("Subscription ended early")
+    }
+
+    async fn transfer_sui(cluster: &TestCluster, sender: SuiAddress) -> String {
+        let gas = cluster
+            .wallet
+            .get_one_gas_object_owned_by_address(sender)
+            .await
+            .unwrap()
+            .unwrap();
+        let rgp = cluster.get_reference_gas_price().await;
+        let tx = TestTransactionBuilder::new(sender, gas, rgp)
+            .transfer_sui(Some(1), sender)
+            .build();
+        cluster
+            .sign_and_execute_transaction(&tx)
+            .await
+            .digest
+            .to_string()
+    }
+
+    /// Stake from `sender`, which emits an event sent by it.
+    async fn stake(cluster: &TestCluster, sender: SuiAddress) {
+        let coins = cluster
+            .wallet
+            .get_gas_objects_owned_by_address(sender, None)
+            .await
+            .unwrap();
+        let validator = cluster
+            .fullnode_handle
+            .sui_client
+            .governance_api()
+            .get_latest_sui_system_state()
+            .await
+            .unwrap()
+            .active_validators[0]
+            .sui_address;
+        let rgp = cluster.get_reference_gas_price().await;
+        let tx = TestTransactionBuilder::new(sender, coins[0], rgp)
+            .call_staking(coins[1], validator)
+            .build();
+        cluster.sign_and_execute_transaction(&tx).await;
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions() {
+        let connection_config = ConnectionConfig::ci_integration_test_cfg();
+        let pg_conn_pool = PgManager::new(connection_config.db_url.clone(), None).unwrap();
+        let cluster = start_cluster(connection_config, None).await;
+        let schema = ServerBuilder::new(8000, "127.0.0.1".to_string())
+            .context_data(pg_conn_pool)
+            .build_schema();
+
+        let cluster = &cluster.validator_fullnode_handle;
+        let (addr0, addr1) = (cluster.get_address_0(), cluster.get_address_1());
+
+        let mut all_txs = subscribe(&schema, "subscription { transactions { digest } }".into());
+        let mut addr0_txs = subscribe(
+            &schema,
+            format!(
+                r#"subscription {{ transactions(filter: {{ signAddress: "{addr0}" }}) {{ digest }} }}"#
+            ),
+        );
+        let mut all_events = subscribe(
+            &schema,
+            "subscription { events(filter: {}) { senders { location } } }".into(),
+        );
+        let mut addr0_events = subscribe(
+            &schema,
+            format!(
+                r#"subscription {{ events(filter: {{ sender: "{addr0}" }}) {{ senders {{ location }} }} }}"#
+            ),
+        );
+
+        // Subscriptions start from the latest transaction or event at the time they are first
+        // polled, so poll them all before sending anything.  Only the unfiltered subscriptions can
+        // produce anything yet (system transactions and events).
+        let _ = timeout(IDLE_TIMEOUT, all_txs.next()).await;
+        let _ = timeout(IDLE_TIMEOUT, all_events.next()).await;
+        assert!(timeout(IDLE_TIMEOUT, addr0_txs.next()).await.is_err());
+        assert!(timeout(IDLE_TIMEOUT, addr0_events.next()).await.is_err());
+
+        // Send from the address that isn't filtered for first, so that it would come first through
+        // the filtered subscriptions if they weren't filtered.
+        let digest1 = transfer_sui(cluster, addr1).await;
+        let digest0 = transfer_sui(cluster, addr0).await;
+        stake(cluster, addr1).await;
+        stake(cluster, addr0).await;
+
+        next_matching(&mut all_txs, |tx| tx["transactions"]["digest"] == digest1).await;
+        next_matching(&mut all_txs, |tx| tx["transactions"]["digest"] == digest0).await;
+        let tx = first(&mut addr0_txs).await;
+        assert_eq!(tx["transactions"]["digest"], digest0);
+
+        let sender = |event: &Value| event["events"]["senders"][0]["location"].clone();
+        next_matching(&mut all_events, |event| sender(event) == addr1.to_string()).await;
+        next_matching(&mut all_events, |event| sender(event) == addr0.to_string()).await;
+        let event = first(&mut addr0_events).await;
+        assert_eq!(sender(&event), addr0.to_string());
+    }
+}
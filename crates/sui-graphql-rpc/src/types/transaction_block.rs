@@ -244,7 +244,7 @@ pub enum ExecutionStatus {
     Failure,
 }
 
-#[derive(InputObject, Debug, Default)]
+#[derive(InputObject, Clone, Debug, Default)]
 pub(crate) struct TransactionBlockFilter {
     pub package: Option<SuiAddress>,
     pub module: Option<String>,
@@ -82,4 +82,207 @@ mod tests {
 
         assert_eq!(&format!("{}", res), &exp);
     }
+
+    #[tokio::test]
+    async fn test_execute_dry_run_and_dev_inspect_transaction_block() {
+        use fastcrypto::encoding::{Base64, Encoding};
+        use move_core_types::ident_str;
+        use sui_test_transaction_builder::TestTransactionBuilder;
+        use sui_types::gas_coin::{GasCoin, GAS};
+        use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+        use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
+        use sui_types::transaction::{CallArg, ObjectArg, TransactionKind};
+        use sui_types::{SUI_FRAMEWORK_PACKAGE_ID, SUI_SYSTEM_PACKAGE_ID};
+
+        const RPC_PORT: u16 = 9125;
+        let connection_config = ConnectionConfig::new(
+            Some(8001),
+            None,
+            Some(format!("http://127.0.0.1:{RPC_PORT}")),
+            None,
+            None,
+            Some(9185),
+        );
+        let cluster =
+            sui_graphql_rpc::cluster::start_cluster(connection_config, Some(RPC_PORT)).await;
+        let test_cluster = &cluster.validator_fullnode_handle;
+        let sender = test_cluster.get_address_0();
+        let recipient = test_cluster.get_address_1();
+        let rgp = test_cluster.get_reference_gas_price().await;
+        let gas = test_cluster
+            .wallet
+            .get_all_gas_objects_owned_by_address(sender)
+            .await
+            .unwrap();
+
+        // Executing a signed transaction returns its effects.
+        let tx = TestTransactionBuilder::new(sender, gas[0], rgp)
+            .transfer_sui(Some(1000), recipient)
+            .build();
+        let (tx_bytes, signatures) = test_cluster
+            .wallet
+            .sign_transaction(&tx)
+            .to_tx_bytes_and_signatures();
+        let signatures = signatures
+            .iter()
+            .map(|sig| format!("\"{}\"", sig.encoded()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let query = format!(
+            r#"
+            mutation {{
+                executeTransactionBlock(txBytes: "{}", signatures: [{}]) {{
+                    effects {{ status }}
+                    errors
+                }}
+            }}
+            "#,
+            tx_bytes.encoded(),
+            signatures,
+        );
+        let res = cluster.graphql_client.execute(query, vec![]).await.unwrap();
+        let result = &res["data"]["executeTransactionBlock"];
+        assert_eq!(result["effects"]["status"], "SUCCESS", "{res}");
+        assert!(result["errors"].is_null(), "{res}");
+
+        // Transactions that don't deserialize, or carry malformed signatures, are rejected.
+        let junk = Base64::from_bytes(&[1, 2, 3]).encoded();
+        let query = format!(
+            r#"
+            mutation {{
+                executeTransactionBlock(txBytes: "{junk}", signatures: []) {{ errors }}
+            }}
+            "#
+        );
+        let res = cluster.graphql_client.execute(query, vec![]).await.unwrap();
+        let error = &res["errors"][0];
+        assert!(
+            error["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("Invalid transaction"),
+            "{res}"
+        );
+        assert_eq!(error["extensions"]["code"], "BAD_USER_INPUT");
+
+        let query = format!(
+            r#"
+            mutation {{
+                executeTransactionBlock(txBytes: "{}", signatures: ["{junk}"]) {{ errors }}
+            }}
+            "#,
+            tx_bytes.encoded(),
+        );
+        let res = cluster.graphql_client.execute(query, vec![]).await.unwrap();
+        let error = &res["errors"][0];
+        assert!(
+            error["message"]
+                .as_str()
+                .unwrap()
+                .starts_with("Invalid signature"),
+            "{res}"
+        );
+        assert_eq!(error["extensions"]["code"], "BAD_USER_INPUT");
+
+        // Read a coin's value and stake it with a validator, which emits an event.
+        let validator = test_cluster
+            .sui_client()
+            .governance_api()
+            .get_latest_sui_system_state()
+            .await
+            .unwrap()
+            .active_validators[0]
+            .sui_address;
+        let stake = gas[1];
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let coin = builder.obj(ObjectArg::ImmOrOwnedObject(stake)).unwrap();
+        let system = builder.input(CallArg::SUI_SYSTEM_MUT).unwrap();
+        let validator_arg = builder.pure(validator).unwrap();
+        builder.programmable_move_call(
+            SUI_FRAMEWORK_PACKAGE_ID,
+            ident_str!("coin").to_owned(),
+            ident_str!("value").to_owned(),
+            vec![GAS::type_tag()],
+            vec![coin],
+        );
+        builder.programmable_move_call(
+            SUI_SYSTEM_PACKAGE_ID,
+            SUI_SYSTEM_MODULE_NAME.to_owned(),
+            ident_str!("request_add_stake").to_owned(),
+            vec![],
+            vec![system, coin, validator_arg],
+        );
+        let pt = builder.finish();
+
+        let tx = TestTransactionBuilder::new(sender, gas[2], rgp)
+            .programmable(pt.clone())
+            .build();
+        let tx_bytes = Base64::from_bytes(&bcs::to_bytes(&tx).unwrap()).encoded();
+        let query = format!(
+            r#"
+            query {{
+                dryRunTransactionBlock(txBytes: "{tx_bytes}") {{
+                    transaction {{ effects {{ status }} }}
+                    errors
+                    events {{ eventType {{ repr }} }}
+                    results {{ returnValues {{ bcs }} }}
+                }}
+            }}
+            "#
+        );
+        let res = cluster.graphql_client.execute(query, vec![]).await.unwrap();
+        let result = &res["data"]["dryRunTransactionBlock"];
+        assert_eq!(
+            result["transaction"]["effects"]["status"], "SUCCESS",
+            "{res}"
+        );
+        assert!(result["errors"].is_null(), "{res}");
+        assert!(result["results"].is_null(), "{res}");
+        assert_staking_event(&result["events"]);
+
+        let kind = TransactionKind::ProgrammableTransaction(pt);
+        let tx_bytes = Base64::from_bytes(&bcs::to_bytes(&kind).unwrap()).encoded();
+        let query = format!(
+            r#"
+            query {{
+                devInspectTransactionBlock(txBytes: "{tx_bytes}", sender: "{sender}") {{
+                    transaction {{ effects {{ status }} }}
+                    errors
+                    events {{ eventType {{ repr }} }}
+                    results {{ returnValues {{ type {{ repr }} bcs }} }}
+                }}
+            }}
+            "#
+        );
+        let res = cluster.graphql_client.execute(query, vec![]).await.unwrap();
+        let result = &res["data"]["devInspectTransactionBlock"];
+        assert_eq!(
+            result["transaction"]["effects"]["status"], "SUCCESS",
+            "{res}"
+        );
+        assert!(result["errors"].is_null(), "{res}");
+        assert_staking_event(&result["events"]);
+
+        let value = &result["results"][0]["returnValues"][0];
+        assert_eq!(value["type"]["repr"], "u64");
+        let coin = test_cluster
+            .get_object_from_fullnode_store(&stake.0)
+            .await
+            .unwrap();
+        let balance = GasCoin::try_from(&coin).unwrap().value();
+        assert_eq!(
+            value["bcs"].as_str().unwrap(),
+            Base64::from_bytes(&bcs::to_bytes(&balance).unwrap()).encoded()
+        );
+        assert_eq!(result["results"][1]["returnValues"], serde_json::json!([]));
+    }
+
+    fn assert_staking_event(events: &serde_json::Value) {
+        let events = events.as_array().unwrap();
+        assert_eq!(events.len(), 1, "{events:?}");
+        assert!(events[0]["eventType"]["repr"]
+            .as_str()
+            .unwrap()
+            .ends_with("::validator::StakingRequestEvent"));
+    }
 }
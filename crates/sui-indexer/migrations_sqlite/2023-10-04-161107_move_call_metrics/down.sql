@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS move_calls;
+DROP TABLE IF EXISTS move_call_metrics;
@@ -0,0 +1,11 @@
+CREATE TABLE network_metrics 
+(
+    checkpoint      BIGINT  PRIMARY KEY,
+    epoch           BIGINT  NOT NULL,   
+    timestamp_ms    BIGINT  NOT NULL,
+    real_time_tps   DOUBLE  NOT NULL,
+    peak_tps_30d    DOUBLE  NOT NULL, 
+    total_addresses BIGINT  NOT NULL,
+    total_objects   BIGINT  NOT NULL,
+    total_packages  BIGINT  NOT NULL
+);
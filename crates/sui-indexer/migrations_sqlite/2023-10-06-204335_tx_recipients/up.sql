@@ -0,0 +1,7 @@
+-- Your SQL goes here
+CREATE TABLE tx_recipients (
+    tx_sequence_number          BIGINT       NOT NULL,
+    -- SuiAddress in bytes.
+    recipient                   BLOB         NOT NULL,
+    PRIMARY KEY(recipient, tx_sequence_number)
+);
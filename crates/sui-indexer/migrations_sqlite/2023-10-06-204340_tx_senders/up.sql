@@ -0,0 +1,7 @@
+-- Your SQL goes here
+CREATE TABLE tx_senders (
+    tx_sequence_number          BIGINT       NOT NULL,
+    -- SuiAddress in bytes.
+    sender                      BLOB         NOT NULL,
+    PRIMARY KEY(sender, tx_sequence_number)
+);
@@ -0,0 +1,8 @@
+-- Your SQL goes here
+CREATE TABLE display
+(
+    object_type     text        PRIMARY KEY,
+    id              BLOB        NOT NULL,
+    version         SMALLINT    NOT NULL,
+    bcs          BLOB        NOT NULL
+);
@@ -0,0 +1,10 @@
+-- Progress of range backfills, one row for each range of checkpoints assigned to a worker.
+-- Every checkpoint in [range_start, next_checkpoint) has been processed.
+CREATE TABLE backfill_progress
+(
+    task_name           TEXT        NOT NULL,
+    range_start         BIGINT      NOT NULL,
+    range_end           BIGINT      NOT NULL,
+    next_checkpoint     BIGINT      NOT NULL,
+    PRIMARY KEY (task_name, range_start)
+);
@@ -0,0 +1,239 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for the databases the indexer can store its data in: Postgres, for production
+//! deployments, or an embedded SQLite database, for local development and tests that should not
+//! depend on an external service.
+//!
+//! Only the V2 schema (`crate::schema_v2`) is supported on SQLite. Its migrations live in
+//! `migrations_sqlite`, and mirror `migrations_v2`.
+
+use std::ops::Deref;
+use std::time::Duration;
+
+use diesel::backend::Backend;
+use diesel::connection::SimpleConnection;
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::Pg;
+use diesel::r2d2::ConnectionManager;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::{Array, Binary, Nullable};
+use diesel::sqlite::{Sqlite, SqliteConnection};
+use fastcrypto::encoding::{Encoding, Hex};
+
+use crate::errors::IndexerError;
+use crate::schema_v2::sql_types;
+use crate::{new_pg_connection_pool, PgConnectionPool};
+
+pub type SqliteConnectionPool = diesel::r2d2::Pool<ConnectionManager<SqliteConnection>>;
+pub type SqlitePoolConnection = diesel::r2d2::PooledConnection<ConnectionManager<SqliteConnection>>;
+
+const SQLITE_URL_SCHEME: &str = "sqlite://";
+
+// SQLite only supports one writer at a time, so a handful of connections is plenty.
+const SQLITE_POOL_SIZE: u32 = 10;
+const SQLITE_CONNECTION_TIMEOUT: Duration = Duration::from_secs(30);
+// How long a connection waits for another connection's write transaction to finish, before
+// giving up with `SQLITE_BUSY`.
+const SQLITE_BUSY_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A pool of connections to either of the databases supported by the indexer.
+#[derive(Clone)]
+pub enum ConnectionPool {
+    Postgres(PgConnectionPool),
+    Sqlite(SqliteConnectionPool),
+}
+
+impl ConnectionPool {
+    pub fn state(&self) -> diesel::r2d2::State {
+        match self {
+            ConnectionPool::Postgres(pool) => pool.state(),
+            ConnectionPool::Sqlite(pool) => pool.state(),
+        }
+    }
+
+    /// Formats `bytes` as a literal that can be spliced into raw SQL sent to this pool's
+    /// database.
+    pub(crate) fn bytes_literal(&self, bytes: &[u8]) -> String {
+        match self {
+            ConnectionPool::Postgres(_) => format!("'\\x{}'::bytea", Hex::encode(bytes)),
+            ConnectionPool::Sqlite(_) => format!("X'{}'", Hex::encode(bytes)),
+        }
+    }
+}
+
+impl From<PgConnectionPool> for ConnectionPool {
+    fn from(pool: PgConnectionPool) -> Self {
+        ConnectionPool::Postgres(pool)
+    }
+}
+
+impl From<SqliteConnectionPool> for ConnectionPool {
+    fn from(pool: SqliteConnectionPool) -> Self {
+        ConnectionPool::Sqlite(pool)
+    }
+}
+
+/// Whether `db_url` points at a SQLite database (`sqlite://<path>`), rather than Postgres.
+pub fn is_sqlite_url(db_url: &str) -> bool {
+    db_url.starts_with(SQLITE_URL_SCHEME)
+}
+
+/// Creates a connection pool for the database at `db_url`, picking the backend based on the
+/// URL's scheme.
+pub fn new_connection_pool(db_url: &str) -> Result<ConnectionPool, IndexerError> {
+    if is_sqlite_url(db_url) {
+        new_sqlite_connection_pool(db_url).map(ConnectionPool::Sqlite)
+    } else {
+        new_pg_connection_pool(db_url).map(ConnectionPool::Postgres)
+    }
+}
+
+/// Creates a connection pool for the SQLite database at `db_url` (`sqlite://<path>`), creating
+/// the database file if it does not exist yet.
+pub fn new_sqlite_connection_pool(db_url: &str) -> Result<SqliteConnectionPool, IndexerError> {
+    let path = db_url.strip_prefix(SQLITE_URL_SCHEME).unwrap_or(db_url);
+    let manager = ConnectionManager::<SqliteConnection>::new(path);
+
+    diesel::r2d2::Pool::builder()
+        .max_size(SQLITE_POOL_SIZE)
+        .connection_timeout(SQLITE_CONNECTION_TIMEOUT)
+        .connection_customizer(Box::new(SqliteConnectionConfig {
+            busy_timeout: SQLITE_BUSY_TIMEOUT,
+        }))
+        .build(manager)
+        .map_err(|e| {
+            IndexerError::SqliteConnectionPoolInitError(format!(
+                "Failed to initialize connection pool for {path} with error: {:?}",
+                e
+            ))
+        })
+}
+
+pub fn get_sqlite_pool_connection(
+    pool: &SqliteConnectionPool,
+) -> Result<SqlitePoolConnection, IndexerError> {
+    pool.get().map_err(|e| {
+        IndexerError::SqlitePoolConnectionError(format!(
+            "Failed to get connection from SQLite connection pool with error: {:?}",
+            e
+        ))
+    })
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SqliteConnectionConfig {
+    busy_timeout: Duration,
+}
+
+impl diesel::r2d2::CustomizeConnection<SqliteConnection, diesel::r2d2::Error>
+    for SqliteConnectionConfig
+{
+    fn on_acquire(
+        &self,
+        conn: &mut SqliteConnection,
+    ) -> std::result::Result<(), diesel::r2d2::Error> {
+        // Write-ahead logging lets readers proceed while the writer is committing.
+        conn.batch_execute(&format!(
+            "PRAGMA journal_mode = WAL; \
+             PRAGMA synchronous = NORMAL; \
+             PRAGMA busy_timeout = {};",
+            self.busy_timeout.as_millis(),
+        ))
+        .map_err(diesel::r2d2::Error::QueryError)
+    }
+}
+
+/// Runs `$query` in a read-only transaction, on a connection from `$pool`, a [`ConnectionPool`].
+///
+/// `$query` is a closure taking the connection, and is expanded once for each backend, so the
+/// same diesel query is type-checked against both connection types.
+macro_rules! read_only_blocking_any {
+    ($pool:expr, $query:expr) => {{
+        match $pool {
+            $crate::db::ConnectionPool::Postgres(pool) => {
+                let mut pg_pool_conn = $crate::get_pg_pool_connection(pool)?;
+                pg_pool_conn
+                    .build_transaction()
+                    .read_only()
+                    .run($query)
+                    .map_err(|e| $crate::errors::IndexerError::PostgresReadError(e.to_string()))
+            }
+            $crate::db::ConnectionPool::Sqlite(pool) => {
+                let mut sqlite_pool_conn = $crate::db::get_sqlite_pool_connection(pool)?;
+                diesel::Connection::transaction(&mut *sqlite_pool_conn, $query)
+                    .map_err(|e| $crate::errors::IndexerError::SqliteReadError(e.to_string()))
+            }
+        }
+    }};
+}
+
+pub(crate) use read_only_blocking_any;
+
+/// The value of a [`sql_types::BytesArray`] column.
+#[derive(AsExpression, FromSqlRow, Debug, Clone, Default, PartialEq, Eq)]
+#[diesel(sql_type = sql_types::BytesArray)]
+pub struct BytesArray(pub Vec<Option<Vec<u8>>>);
+
+impl Deref for BytesArray {
+    type Target = Vec<Option<Vec<u8>>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Vec<Option<Vec<u8>>>> for BytesArray {
+    fn from(bytes: Vec<Option<Vec<u8>>>) -> Self {
+        BytesArray(bytes)
+    }
+}
+
+impl From<BytesArray> for Vec<Option<Vec<u8>>> {
+    fn from(bytes: BytesArray) -> Self {
+        bytes.0
+    }
+}
+
+impl FromIterator<Option<Vec<u8>>> for BytesArray {
+    fn from_iter<I: IntoIterator<Item = Option<Vec<u8>>>>(iter: I) -> Self {
+        BytesArray(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for BytesArray {
+    type Item = Option<Vec<u8>>;
+    type IntoIter = std::vec::IntoIter<Option<Vec<u8>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl ToSql<sql_types::BytesArray, Pg> for BytesArray {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        <Vec<Option<Vec<u8>>> as ToSql<Array<Nullable<Binary>>, Pg>>::to_sql(&self.0, out)
+    }
+}
+
+impl FromSql<sql_types::BytesArray, Pg> for BytesArray {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+        <Vec<Option<Vec<u8>>> as FromSql<Array<Nullable<Binary>>, Pg>>::from_sql(bytes)
+            .map(BytesArray)
+    }
+}
+
+impl ToSql<sql_types::BytesArray, Sqlite> for BytesArray {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        out.set_value(bcs::to_bytes(&self.0)?);
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<sql_types::BytesArray, Sqlite> for BytesArray {
+    fn from_sql(bytes: <Sqlite as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+        let bytes = <Vec<u8> as FromSql<Binary, Sqlite>>::from_sql(bytes)?;
+        Ok(BytesArray(bcs::from_bytes(&bytes)?))
+    }
+}
@@ -0,0 +1,460 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
+use sui_archival::reader::ArchiveReader;
+use sui_rest_api::{CheckpointData, CheckpointTransaction};
+use sui_storage::key_value_store::TransactionKeyValueStore;
+use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
+use sui_types::messages_checkpoint::{
+    CertifiedCheckpointSummary, CheckpointSequenceNumber, FullCheckpointContents,
+};
+use sui_types::storage::ObjectKey;
+use tracing::{info, warn};
+
+/// Fetches historical checkpoints from a `sui-archival` archive, rather than from a fullnode.
+///
+/// Archives only hold checkpoint summaries, transactions and effects, so the events and objects
+/// that make up the rest of each `CheckpointData` are fetched from a key-value store, which, unlike
+/// a fullnode, keeps the entire history of the network.
+pub struct ArchiveCheckpointFetcher {
+    reader: ArchiveReader,
+    kv_store: TransactionKeyValueStore,
+    last_downloaded_checkpoint: Option<CheckpointSequenceNumber>,
+    sender: mysten_metrics::metered_channel::Sender<CheckpointData>,
+}
+
+impl ArchiveCheckpointFetcher {
+    const RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+    const CHECKPOINT_DOWNLOAD_CONCURRENCY: usize = 10;
+
+    pub fn new(
+        reader: ArchiveReader,
+        kv_store: TransactionKeyValueStore,
+        last_downloaded_checkpoint: Option<CheckpointSequenceNumber>,
+        sender: mysten_metrics::metered_channel::Sender<CheckpointData>,
+    ) -> Self {
+        Self {
+            reader,
+            kv_store,
+            last_downloaded_checkpoint,
+            sender,
+        }
+    }
+
+    /// Sends every checkpoint in the archive after `last_downloaded_checkpoint`, until it has
+    /// caught up with the archive, and returns the last checkpoint it sent, so that a
+    /// `CheckpointFetcher` can carry on from there.
+    pub async fn run(mut self) -> Option<CheckpointSequenceNumber> {
+        info!("ArchiveCheckpointFetcher started");
+
+        loop {
+            match self.download_checkpoints().await {
+                Ok(true) => break,
+                Ok(false) => continue,
+                Err(e) => {
+                    warn!("error downloading checkpoints from archive: {e}");
+                    tokio::time::sleep(Self::RETRY_INTERVAL).await;
+                }
+            }
+        }
+
+        info!(
+            last_downloaded_checkpoint = self.last_downloaded_checkpoint,
+            "ArchiveCheckpointFetcher caught up with the archive"
+        );
+        self.last_downloaded_checkpoint
+    }
+
+    /// Downloads all the checkpoints that are currently in the archive and have not been
+    /// downloaded yet, returning whether there were none left to download.
+    async fn download_checkpoints(&mut self) -> Result<bool> {
+        // The archive keeps growing while it is being read, so pick up any new files.
+        self.reader.sync_manifest_once().await?;
+        let latest_available_checkpoint = self.reader.latest_available_checkpoint().await?;
+
+        let start = self
+            .last_downloaded_checkpoint
+            .map(|i| i.saturating_add(1))
+            .unwrap_or(0);
+        if start > latest_available_checkpoint {
+            return Ok(true);
+        }
+
+        info!(
+            "Starting download of checkpoints {start}..={latest_available_checkpoint} from archive"
+        );
+
+        let kv_store = &self.kv_store;
+        let mut checkpoint_stream = self
+            .reader
+            .stream_checkpoints(start..latest_available_checkpoint.saturating_add(1))
+            .await?
+            .map(|checkpoint| async move {
+                let (summary, contents) = checkpoint?;
+                hydrate_checkpoint(kv_store, summary, contents).await
+            })
+            .buffered(Self::CHECKPOINT_DOWNLOAD_CONCURRENCY);
+
+        while let Some(maybe_checkpoint) = checkpoint_stream.next().await {
+            let checkpoint = maybe_checkpoint?;
+            self.last_downloaded_checkpoint =
+                Some(*checkpoint.checkpoint_summary.sequence_number());
+
+            info!(
+                checkpoint = checkpoint.checkpoint_summary.sequence_number(),
+                "successfully downloaded checkpoint from archive"
+            );
+
+            self.sender
+                .send(checkpoint)
+                .await
+                .expect("channel shouldn't be closed");
+        }
+
+        Ok(false)
+    }
+}
+
+/// Builds the `CheckpointData` for a checkpoint read from the archive, by fetching its events,
+/// and its transactions' input and output objects, from `kv_store`.
+async fn hydrate_checkpoint(
+    kv_store: &TransactionKeyValueStore,
+    checkpoint_summary: CertifiedCheckpointSummary,
+    contents: FullCheckpointContents,
+) -> Result<CheckpointData> {
+    let event_digests = contents
+        .iter()
+        .filter_map(|data| data.effects.events_digest().copied())
+        .collect::<Vec<_>>();
+    let events = kv_store
+        .multi_get_events(&event_digests)
+        .await?
+        .into_iter()
+        .zip(event_digests)
+        .map(|(events, digest)| {
+            events
+                .map(|events| (digest, events))
+                .ok_or_else(|| anyhow!("missing events {digest} from key-value store"))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    // Fetch the objects for every transaction in the checkpoint in one go, and then split them
+    // back up by transaction.
+    let object_keys = contents
+        .iter()
+        .map(|data| object_keys(&data.effects))
+        .collect::<Vec<_>>();
+    let all_object_keys = object_keys
+        .iter()
+        .flat_map(|(input_keys, output_keys)| input_keys.iter().chain(output_keys))
+        .copied()
+        .collect::<Vec<_>>();
+    let mut objects = kv_store
+        .multi_get_objects(&all_object_keys)
+        .await?
+        .into_iter()
+        .zip(all_object_keys)
+        .map(|(object, ObjectKey(object_id, version))| {
+            object.ok_or_else(|| {
+                anyhow!("missing object {object_id} at version {version} from key-value store")
+            })
+        });
+
+    let mut transactions = Vec::with_capacity(contents.size());
+    for (data, (input_keys, output_keys)) in contents.iter().zip(object_keys) {
+        let events = data.effects.events_digest().map(|digest| {
+            events
+                .get(digest)
+                .cloned()
+                .expect("events were already checked to be present")
+        });
+
+        transactions.push(CheckpointTransaction {
+            transaction: data.transaction.clone(),
+            effects: data.effects.clone(),
+            events,
+            input_objects: objects
+                .by_ref()
+                .take(input_keys.len())
+                .collect::<Result<_>>()?,
+            output_objects: objects
+                .by_ref()
+                .take(output_keys.len())
+                .collect::<Result<_>>()?,
+        });
+    }
+
+    Ok(CheckpointData {
+        checkpoint_summary,
+        checkpoint_contents: contents.into_checkpoint_contents(),
+        transactions,
+    })
+}
+
+/// The keys of the objects a transaction read or modified, as they were before it ran, and the keys
+/// of the objects it wrote.
+fn object_keys(effects: &TransactionEffects) -> (Vec<ObjectKey>, Vec<ObjectKey>) {
+    // Note unwrapped_then_deleted contains **updated** versions.
+    let unwrapped_then_deleted_obj_ids = effects
+        .unwrapped_then_deleted()
+        .into_iter()
+        .map(|k| k.0)
+        .collect::<HashSet<_>>();
+
+    let input_object_keys = effects
+        .input_shared_objects()
+        .into_iter()
+        .map(|(object_ref, _kind)| ObjectKey::from(object_ref))
+        .chain(
+            effects
+                .modified_at_versions()
+                .into_iter()
+                .map(|(object_id, version)| ObjectKey(object_id, version)),
+        )
+        .collect::<HashSet<_>>()
+        .into_iter()
+        // Unwrapped-then-deleted objects did not exist before the tx, so there is nothing to fetch.
+        .filter(|key| !unwrapped_then_deleted_obj_ids.contains(&key.0))
+        .collect();
+
+    let output_object_keys = effects
+        .all_changed_objects()
+        .into_iter()
+        .map(|(object_ref, _owner, _kind)| ObjectKey::from(object_ref))
+        .collect();
+
+    (input_object_keys, output_object_keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+    use std::sync::{Arc, RwLock};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use prometheus::{IntGauge, Registry};
+    use simulacrum::Simulacrum;
+    use sui_archival::reader::ArchiveReaderMetrics;
+    use sui_archival::writer::ArchiveWriter;
+    use sui_config::node::ArchiveReaderConfig;
+    use sui_storage::key_value_store::{
+        KVStoreCheckpointData, KVStoreTransactionData, TransactionKeyValueStoreTrait,
+    };
+    use sui_storage::key_value_store_metrics::KeyValueStoreMetrics;
+    use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
+    use sui_storage::{FileCompression, StorageFormat};
+    use sui_types::base_types::{ExecutionData, ObjectID, SequenceNumber, SuiAddress};
+    use sui_types::digests::{
+        CheckpointContentsDigest, CheckpointDigest, TransactionDigest, TransactionEventsDigest,
+    };
+    use sui_types::effects::TransactionEvents;
+    use sui_types::error::SuiResult;
+    use sui_types::gas_coin::MIST_PER_SUI;
+    use sui_types::messages_checkpoint::{VerifiedCheckpoint, VerifiedCheckpointContents};
+    use sui_types::object::Object;
+    use sui_types::storage::SharedInMemoryStore;
+
+    use super::*;
+
+    /// A key-value store that only holds events and objects.
+    #[derive(Default)]
+    struct InMemoryKVStore {
+        events: HashMap<TransactionEventsDigest, TransactionEvents>,
+        objects: HashMap<ObjectKey, Object>,
+    }
+
+    #[async_trait]
+    impl TransactionKeyValueStoreTrait for InMemoryKVStore {
+        async fn multi_get(
+            &self,
+            transactions: &[TransactionDigest],
+            effects: &[TransactionDigest],
+            events: &[TransactionEventsDigest],
+        ) -> SuiResult<KVStoreTransactionData> {
+            Ok((
+                vec![None; transactions.len()],
+                vec![None; effects.len()],
+                events
+                    .iter()
+                    .map(|digest| self.events.get(digest).cloned())
+                    .collect(),
+            ))
+        }
+
+        async fn multi_get_checkpoints(
+            &self,
+            checkpoint_summaries: &[CheckpointSequenceNumber],
+            checkpoint_contents: &[CheckpointSequenceNumber],
+            checkpoint_summaries_by_digest: &[CheckpointDigest],
+            checkpoint_contents_by_digest: &[CheckpointContentsDigest],
+        ) -> SuiResult<KVStoreCheckpointData> {
+            Ok((
+                vec![None; checkpoint_summaries.len()],
+                vec![None; checkpoint_contents.len()],
+                vec![None; checkpoint_summaries_by_digest.len()],
+                vec![None; checkpoint_contents_by_digest.len()],
+            ))
+        }
+
+        async fn deprecated_get_transaction_checkpoint(
+            &self,
+            _digest: TransactionDigest,
+        ) -> SuiResult<Option<CheckpointSequenceNumber>> {
+            Ok(None)
+        }
+
+        async fn get_object(
+            &self,
+            object_id: ObjectID,
+            version: SequenceNumber,
+        ) -> SuiResult<Option<Object>> {
+            Ok(self.objects.get(&ObjectKey(object_id, version)).cloned())
+        }
+
+        async fn multi_get_transaction_checkpoint(
+            &self,
+            digests: &[TransactionDigest],
+        ) -> SuiResult<Vec<Option<CheckpointSequenceNumber>>> {
+            Ok(vec![None; digests.len()])
+        }
+    }
+
+    fn file_store_config(path: std::path::PathBuf) -> ObjectStoreConfig {
+        ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(path),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_checkpoints_from_archive() -> Result<()> {
+        let simulacrum = Arc::new(RwLock::new(Simulacrum::new()));
+        {
+            let mut sim = simulacrum.write().unwrap();
+            let recipient = SuiAddress::generate(sim.rng());
+            sim.request_gas(recipient, MIST_PER_SUI)?;
+            sim.create_checkpoint();
+            sim.advance_clock(Duration::from_millis(1));
+            sim.request_gas(recipient, MIST_PER_SUI)?;
+            sim.create_checkpoint();
+            // The archive only includes a checkpoint once the file it is in has been cut, which
+            // happens when the next checkpoint is written, so checkpoint 3 is not archived.
+            sim.create_checkpoint();
+        }
+
+        // The checkpoints as a fullnode would serve them.
+        let server = axum::Server::bind(&"127.0.0.1:0".parse()?)
+            .serve(sui_rest_api::rest_router(simulacrum.clone(), None).into_make_service());
+        let rest = sui_rest_api::Client::new(format!("http://{}", server.local_addr()));
+        tokio::spawn(server);
+
+        let mut source = vec![];
+        for sequence_number in 0..=3 {
+            source.push(rest.get_full_checkpoint(sequence_number).await?);
+        }
+
+        // Split them between the store the archive is written from, and the key-value store.
+        let store = SharedInMemoryStore::default();
+        let mut kv_store = InMemoryKVStore::default();
+        for checkpoint in &source {
+            let summary = VerifiedCheckpoint::new_unchecked(checkpoint.checkpoint_summary.clone());
+            let contents = VerifiedCheckpointContents::new_unchecked(
+                FullCheckpointContents::from_contents_and_execution_data(
+                    checkpoint.checkpoint_contents.clone(),
+                    checkpoint
+                        .transactions
+                        .iter()
+                        .map(|tx| ExecutionData::new(tx.transaction.clone(), tx.effects.clone())),
+                ),
+            );
+
+            if *summary.sequence_number() == 0 {
+                let committee = simulacrum
+                    .read()
+                    .unwrap()
+                    .store()
+                    .get_committee_by_epoch(0)
+                    .unwrap()
+                    .to_owned();
+                store
+                    .inner_mut()
+                    .insert_genesis_state(summary, contents, committee);
+            } else {
+                store.inner_mut().insert_checkpoint(&summary);
+                store
+                    .inner_mut()
+                    .insert_checkpoint_contents(&summary, contents);
+            }
+
+            for tx in &checkpoint.transactions {
+                if let Some(events) = &tx.events {
+                    kv_store.events.insert(events.digest(), events.clone());
+                }
+                for object in tx.input_objects.iter().chain(&tx.output_objects) {
+                    kv_store
+                        .objects
+                        .insert(ObjectKey(object.id(), object.version()), object.clone());
+                }
+            }
+        }
+
+        let dir = tempfile::tempdir()?;
+        let remote_store_config = file_store_config(dir.path().join("remote"));
+        let writer = ArchiveWriter::new(
+            file_store_config(dir.path().join("local")),
+            remote_store_config.clone(),
+            FileCompression::Zstd,
+            StorageFormat::Blob,
+            Duration::from_secs(60),
+            /* commit_file_size */ 1,
+            /* write_index */ true,
+            &Registry::default(),
+        )
+        .await?;
+        let kill = writer.start(store).await?;
+
+        let reader = ArchiveReader::new(
+            ArchiveReaderConfig {
+                remote_store_config,
+                download_concurrency: NonZeroUsize::new(1).unwrap(),
+                use_for_pruning_watermark: false,
+            },
+            &ArchiveReaderMetrics::new(&Registry::default()),
+        )?;
+        tokio::time::timeout(Duration::from_secs(60), async {
+            loop {
+                if reader.sync_manifest_once().await.is_ok()
+                    && matches!(reader.latest_available_checkpoint().await, Ok(2))
+                {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await?;
+
+        let gauge = IntGauge::new("archive_fetcher_test", "test")?;
+        let (sender, mut receiver) = mysten_metrics::metered_channel::channel(10, &gauge);
+        let kv_store = TransactionKeyValueStore::new(
+            "in_memory",
+            KeyValueStoreMetrics::new_for_tests(),
+            Arc::new(kv_store),
+        );
+        let fetcher = ArchiveCheckpointFetcher::new(reader, kv_store, None, sender);
+        assert_eq!(fetcher.run().await, Some(2));
+
+        for expected in &source[..3] {
+            let fetched = receiver.recv().await.unwrap();
+            assert_eq!(bcs::to_bytes(&fetched)?, bcs::to_bytes(expected)?);
+        }
+
+        kill.send(())?;
+        Ok(())
+    }
+}
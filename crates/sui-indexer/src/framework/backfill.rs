@@ -0,0 +1,356 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use anyhow::Result;
+use futures::{StreamExt, TryStreamExt};
+use sui_rest_api::Client;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use tracing::{info, warn};
+
+use super::interface::OutOfOrderHandler;
+
+const CHECKPOINT_DOWNLOAD_CONCURRENCY: usize = 20;
+const RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// How far a backfill has got through one of the ranges of checkpoints it handed out to its
+/// workers: every checkpoint in `range_start..next_checkpoint` has been processed, and the rest of
+/// `range_start..range_end` has not (or not for sure).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackfillProgress {
+    pub range_start: CheckpointSequenceNumber,
+    pub range_end: CheckpointSequenceNumber,
+    pub next_checkpoint: CheckpointSequenceNumber,
+}
+
+/// Where a [`RangeBackfill`] keeps its progress, so that it can resume after a crash or restart.
+#[async_trait::async_trait]
+pub trait BackfillProgressStore: Send + Sync {
+    async fn get_backfill_progress(&self, task_name: &str) -> Result<Vec<BackfillProgress>>;
+
+    /// Replaces all of the progress recorded for `task_name` with `progress`.
+    async fn reset_backfill_progress(
+        &self,
+        task_name: &str,
+        progress: Vec<BackfillProgress>,
+    ) -> Result<()>;
+
+    async fn update_backfill_progress(
+        &self,
+        task_name: &str,
+        range_start: CheckpointSequenceNumber,
+        next_checkpoint: CheckpointSequenceNumber,
+    ) -> Result<()>;
+}
+
+/// Runs an [`OutOfOrderHandler`] over a range of checkpoints, with a number of workers that each
+/// process their own disjoint ranges of checkpoints, in parallel.
+///
+/// Each worker records how far it has got in a [`BackfillProgressStore`], under the backfill's
+/// task name. When a backfill for the same task is started again, everything that has not been
+/// recorded as processed is shared out among the workers again, whether it was left over by a
+/// crash, or is part of a range that was not covered by earlier runs. Checkpoints that were
+/// processed but not yet recorded are processed again, so handlers must be idempotent.
+pub struct RangeBackfill<H, P> {
+    task_name: String,
+    range: Range<CheckpointSequenceNumber>,
+    client: Client,
+    handler: Arc<H>,
+    progress_store: Arc<P>,
+    num_workers: usize,
+    batch_size: usize,
+}
+
+impl<H, P> RangeBackfill<H, P>
+where
+    H: OutOfOrderHandler + 'static,
+    P: BackfillProgressStore + 'static,
+{
+    const DEFAULT_NUM_WORKERS: usize = 4;
+    const DEFAULT_BATCH_SIZE: usize = 100;
+
+    pub fn new(
+        task_name: impl Into<String>,
+        range: Range<CheckpointSequenceNumber>,
+        client: Client,
+        handler: H,
+        progress_store: P,
+    ) -> Self {
+        Self {
+            task_name: task_name.into(),
+            range,
+            client,
+            handler: Arc::new(handler),
+            progress_store: Arc::new(progress_store),
+            num_workers: Self::DEFAULT_NUM_WORKERS,
+            batch_size: Self::DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    pub fn num_workers(mut self, num_workers: usize) -> Self {
+        self.num_workers = num_workers.max(1);
+        self
+    }
+
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub async fn run(self) -> Result<()> {
+        let progress = self
+            .progress_store
+            .get_backfill_progress(&self.task_name)
+            .await?;
+        let (done, pending) = plan_backfill(&self.range, &progress);
+        let assignments = assign_to_workers(pending, self.num_workers);
+
+        // Record the new assignments before starting on them, so that a restart picks up the same
+        // ranges, and the work already done is not forgotten.
+        let progress = done
+            .into_iter()
+            .map(|range| BackfillProgress {
+                range_start: range.start,
+                range_end: range.end,
+                next_checkpoint: range.end,
+            })
+            .chain(assignments.iter().flatten().map(|range| BackfillProgress {
+                range_start: range.start,
+                range_end: range.end,
+                next_checkpoint: range.start,
+            }))
+            .collect();
+        self.progress_store
+            .reset_backfill_progress(&self.task_name, progress)
+            .await?;
+
+        info!(
+            task = %self.task_name,
+            range = ?self.range,
+            workers = assignments.len(),
+            "Starting backfill"
+        );
+
+        let workers = assignments
+            .into_iter()
+            .enumerate()
+            .map(|(worker_id, ranges)| {
+                let worker = BackfillWorker {
+                    worker_id,
+                    task_name: self.task_name.clone(),
+                    client: self.client.clone(),
+                    handler: self.handler.clone(),
+                    progress_store: self.progress_store.clone(),
+                    batch_size: self.batch_size,
+                };
+                tokio::spawn(worker.run(ranges))
+            })
+            .collect::<Vec<_>>();
+
+        for worker in futures::future::join_all(workers).await {
+            worker?;
+        }
+
+        info!(task = %self.task_name, range = ?self.range, "Backfill complete");
+        Ok(())
+    }
+}
+
+struct BackfillWorker<H, P> {
+    worker_id: usize,
+    task_name: String,
+    client: Client,
+    handler: Arc<H>,
+    progress_store: Arc<P>,
+    batch_size: usize,
+}
+
+impl<H, P> BackfillWorker<H, P>
+where
+    H: OutOfOrderHandler,
+    P: BackfillProgressStore,
+{
+    async fn run(self, ranges: Vec<Range<CheckpointSequenceNumber>>) {
+        for range in ranges {
+            let mut next_checkpoint = range.start;
+            while next_checkpoint < range.end {
+                let batch_end = next_checkpoint
+                    .saturating_add(self.batch_size as u64)
+                    .min(range.end);
+                let batch = next_checkpoint..batch_end;
+
+                // Retry until the batch goes through: giving up on it would leave a gap behind.
+                while let Err(e) = self.process_batch(range.start, batch.clone()).await {
+                    warn!(
+                        worker = self.worker_id,
+                        "error backfilling checkpoints {batch:?}: {e}"
+                    );
+                    tokio::time::sleep(RETRY_INTERVAL).await;
+                }
+                next_checkpoint = batch_end;
+            }
+        }
+        info!(worker = self.worker_id, "Backfill worker finished");
+    }
+
+    async fn process_batch(
+        &self,
+        range_start: CheckpointSequenceNumber,
+        batch: Range<CheckpointSequenceNumber>,
+    ) -> Result<()> {
+        let checkpoints = futures::stream::iter(batch.clone())
+            .map(|seq| self.client.get_full_checkpoint(seq))
+            .buffered(CHECKPOINT_DOWNLOAD_CONCURRENCY)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        self.handler.process_checkpoints(&checkpoints).await?;
+        self.progress_store
+            .update_backfill_progress(&self.task_name, range_start, batch.end)
+            .await?;
+
+        info!(
+            worker = self.worker_id,
+            first = batch.start,
+            last = batch.end - 1,
+            "Backfilled checkpoints"
+        );
+        Ok(())
+    }
+}
+
+/// Splits `range` into the parts that `progress` says are already done, and the parts that are
+/// still pending. The parts that are done include any recorded outside of `range`, so that they
+/// are not forgotten, and are coalesced with each other.
+fn plan_backfill(
+    range: &Range<CheckpointSequenceNumber>,
+    progress: &[BackfillProgress],
+) -> (
+    Vec<Range<CheckpointSequenceNumber>>,
+    Vec<Range<CheckpointSequenceNumber>>,
+) {
+    let mut done: Vec<Range<CheckpointSequenceNumber>> = progress
+        .iter()
+        .map(|p| p.range_start..p.next_checkpoint.min(p.range_end))
+        .filter(|r| !r.is_empty())
+        .collect();
+    done.sort_by_key(|r| r.start);
+
+    let mut coalesced: Vec<Range<CheckpointSequenceNumber>> = Vec::with_capacity(done.len());
+    for r in done {
+        match coalesced.last_mut() {
+            Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+            _ => coalesced.push(r),
+        }
+    }
+
+    let mut pending = vec![];
+    let mut next = range.start;
+    for r in &coalesced {
+        if r.start > next {
+            pending.push(next..r.start.min(range.end));
+        }
+        next = next.max(r.end);
+        if next >= range.end {
+            break;
+        }
+    }
+    if next < range.end {
+        pending.push(next..range.end);
+    }
+    pending.retain(|r| !r.is_empty());
+
+    (coalesced, pending)
+}
+
+/// Shares the `pending` ranges out among at most `num_workers` workers, giving each a roughly equal
+/// number of checkpoints.
+fn assign_to_workers(
+    pending: Vec<Range<CheckpointSequenceNumber>>,
+    num_workers: usize,
+) -> Vec<Vec<Range<CheckpointSequenceNumber>>> {
+    let total: u64 = pending.iter().map(|r| r.end - r.start).sum();
+    if total == 0 {
+        return vec![];
+    }
+    let share = total.div_ceil(num_workers as u64);
+
+    let mut assignments = vec![];
+    let mut current = vec![];
+    let mut current_size = 0;
+    for mut range in pending {
+        while !range.is_empty() {
+            let take = (share - current_size).min(range.end - range.start);
+            current.push(range.start..range.start + take);
+            current_size += take;
+            range.start += take;
+            if current_size == share {
+                assignments.push(std::mem::take(&mut current));
+                current_size = 0;
+            }
+        }
+    }
+    if !current.is_empty() {
+        assignments.push(current);
+    }
+    assignments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(range_start: u64, range_end: u64, next_checkpoint: u64) -> BackfillProgress {
+        BackfillProgress {
+            range_start,
+            range_end,
+            next_checkpoint,
+        }
+    }
+
+    #[test]
+    fn plan_fresh_backfill() {
+        let (done, pending) = plan_backfill(&(10..100), &[]);
+        assert!(done.is_empty());
+        assert_eq!(pending, vec![10..100]);
+    }
+
+    #[test]
+    fn plan_resumed_backfill() {
+        // Two workers crashed part-way through their ranges, and a third finished.
+        let progress = [
+            progress(0, 30, 20),
+            progress(30, 60, 30),
+            progress(60, 90, 90),
+        ];
+        let (done, pending) = plan_backfill(&(0..90), &progress);
+        assert_eq!(done, vec![0..20, 60..90]);
+        assert_eq!(pending, vec![20..60]);
+    }
+
+    #[test]
+    fn plan_backfill_over_a_different_range() {
+        let progress = [progress(50, 100, 100), progress(200, 300, 250)];
+        let (done, pending) = plan_backfill(&(0..220), &progress);
+        assert_eq!(done, vec![50..100, 200..250]);
+        assert_eq!(pending, vec![0..50, 100..200]);
+    }
+
+    #[test]
+    fn assign_evenly() {
+        let assignments = assign_to_workers(vec![0..10, 20..30], 3);
+        assert_eq!(
+            assignments,
+            vec![vec![0..7], vec![7..10, 20..24], vec![24..30]]
+        );
+    }
+
+    #[test]
+    fn assign_fewer_checkpoints_than_workers() {
+        let assignments = assign_to_workers(vec![5..7], 4);
+        assert_eq!(assignments, vec![vec![5..6], vec![6..7]]);
+        assert!(assign_to_workers(vec![], 4).is_empty());
+    }
+}
@@ -1,10 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod backfill;
 mod builder;
 pub mod interface;
 
 // TODO remove the pub(crater) once indexer_v2.rs is renamed to lib.rs
+pub(crate) mod archive_fetcher;
 pub(crate) mod fetcher;
 pub(crate) mod runner;
 
@@ -0,0 +1,99 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use sui_rest_api::CheckpointData;
+use tracing::info;
+
+use crate::framework::interface::OutOfOrderHandler;
+use crate::metrics::IndexerMetrics;
+use crate::store::IndexerStoreV2;
+
+use super::checkpoint_handler_v2::CheckpointHandler;
+
+/// The tables that can be re-indexed on their own, with a
+/// [`crate::framework::backfill::RangeBackfill`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum BackfillTable {
+    Events,
+    Transactions,
+    TxIndices,
+}
+
+impl BackfillTable {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BackfillTable::Events => "events",
+            BackfillTable::Transactions => "transactions",
+            BackfillTable::TxIndices => "tx_indices",
+        }
+    }
+}
+
+/// Indexes checkpoints the same way as [`CheckpointHandler`], but only writes the rows of a single
+/// table. Rows that already exist are left alone, so checkpoints can be processed more than once,
+/// and in any order.
+pub struct TableBackfillHandler<S> {
+    state: S,
+    table: BackfillTable,
+    metrics: IndexerMetrics,
+}
+
+impl<S> TableBackfillHandler<S> {
+    pub fn new(state: S, table: BackfillTable, metrics: IndexerMetrics) -> Self {
+        Self {
+            state,
+            table,
+            metrics,
+        }
+    }
+}
+
+#[async_trait]
+impl<S> OutOfOrderHandler for TableBackfillHandler<S>
+where
+    S: IndexerStoreV2 + Clone + Sync + Send + 'static,
+{
+    fn name(&self) -> &str {
+        self.table.name()
+    }
+
+    async fn process_checkpoints(&self, checkpoints: &[CheckpointData]) -> anyhow::Result<()> {
+        let mut transactions = vec![];
+        let mut events = vec![];
+        let mut tx_indices = vec![];
+        for checkpoint in checkpoints {
+            let (db_transactions, db_events, db_indices, _db_displays) =
+                CheckpointHandler::<S>::index_transactions(
+                    checkpoint.transactions.clone(),
+                    &checkpoint.checkpoint_summary,
+                    &checkpoint.checkpoint_contents,
+                    &self.metrics,
+                )
+                .await?;
+            transactions.extend(db_transactions);
+            events.extend(db_events);
+            tx_indices.extend(db_indices);
+        }
+
+        let rows = match self.table {
+            BackfillTable::Events => {
+                let len = events.len();
+                self.state.persist_events(events).await?;
+                len
+            }
+            BackfillTable::Transactions => {
+                let len = transactions.len();
+                self.state.persist_transactions(transactions).await?;
+                len
+            }
+            BackfillTable::TxIndices => {
+                let len = tx_indices.len();
+                self.state.persist_tx_indices(tx_indices).await?;
+                len
+            }
+        };
+        info!(table = self.table.name(), rows, "Backfilled rows");
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod backfill_handler_v2;
 pub mod checkpoint_handler;
 pub mod checkpoint_handler_v2;
 pub mod committer;
@@ -1,8 +1,12 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::db::{
+    is_sqlite_url, new_sqlite_connection_pool, read_only_blocking_any, BytesArray, ConnectionPool,
+};
 use crate::{
     errors::IndexerError,
+    get_pg_pool_connection,
     models_v2::{
         address_metrics::StoredAddressMetrics,
         checkpoints::StoredCheckpoint,
@@ -22,15 +26,13 @@ use crate::{
         objects, packages, transactions, tx_count_metrics,
     },
     types_v2::{IndexerResult, OwnerType},
-    PgConnectionConfig, PgConnectionPoolConfig, PgPoolConnection,
+    PgConnectionConfig, PgConnectionPoolConfig,
 };
 use anyhow::{anyhow, Result};
 use diesel::{
     r2d2::ConnectionManager, ExpressionMethods, OptionalExtension, PgConnection, QueryDsl,
     RunQueryDsl,
 };
-use fastcrypto::encoding::Encoding;
-use fastcrypto::encoding::Hex;
 use itertools::{any, Itertools};
 use std::{
     collections::{BTreeMap, HashMap},
@@ -58,7 +60,7 @@ pub const EVENT_SEQUENCE_NUMBER_STR: &str = "event_sequence_number";
 
 #[derive(Clone)]
 pub struct IndexerReader {
-    pool: crate::PgConnectionPool,
+    pool: ConnectionPool,
     package_cache: PackageCache,
 }
 
@@ -69,10 +71,20 @@ impl IndexerReader {
         Self::new_with_config(db_url, config)
     }
 
+    /// Creates a reader for the database at `db_url`, which is either a Postgres URL, or
+    /// `sqlite://<path>`. `config` is only used for Postgres.
     pub fn new_with_config<T: Into<String>>(
         db_url: T,
         config: PgConnectionPoolConfig,
     ) -> Result<Self> {
+        let db_url = db_url.into();
+        if is_sqlite_url(&db_url) {
+            return Ok(Self {
+                pool: new_sqlite_connection_pool(&db_url)?.into(),
+                package_cache: Default::default(),
+            });
+        }
+
         let manager = ConnectionManager::<PgConnection>::new(db_url);
 
         let connection_config = PgConnectionConfig {
@@ -88,20 +100,13 @@ impl IndexerReader {
             .map_err(|e| anyhow!("Failed to initialize connection pool. Error: {:?}. If Error is None, please check whether the configured pool size (currently {}) exceeds the maximum number of connections allowed by the database.", e, config.pool_size))?;
 
         Ok(Self {
-            pool,
+            pool: pool.into(),
             package_cache: Default::default(),
         })
     }
 
-    fn get_connection(&self) -> Result<PgPoolConnection, IndexerError> {
-        self.pool.get().map_err(|e| {
-            IndexerError::PgPoolConnectionError(format!(
-                "Failed to get connection from PG connection pool with error: {:?}",
-                e
-            ))
-        })
-    }
-
+    /// Runs `query` in a read-only transaction. Only supported when reading from Postgres: queries
+    /// internal to the reader also support SQLite, through `run_query!`.
     pub fn run_query<T, E, F>(&self, query: F) -> Result<T, IndexerError>
     where
         F: FnOnce(&mut PgConnection) -> Result<T, E>,
@@ -109,7 +114,13 @@ impl IndexerReader {
     {
         blocking_call_is_ok_or_panic();
 
-        let mut connection = self.get_connection()?;
+        let ConnectionPool::Postgres(pool) = &self.pool else {
+            return Err(IndexerError::NotSupportedError(
+                "Running arbitrary queries is only supported on Postgres".to_string(),
+            ));
+        };
+
+        let mut connection = get_pg_pool_connection(pool)?;
         connection
             .build_transaction()
             .read_only()
@@ -168,6 +179,15 @@ fn blocking_call_is_ok_or_panic() {
     }
 }
 
+/// Runs `$query`, a closure taking a connection, in a read-only transaction against `$reader`'s
+/// database, whichever backend that is.
+macro_rules! run_query {
+    ($reader:expr, $query:expr) => {{
+        blocking_call_is_ok_or_panic();
+        read_only_blocking_any!(&$reader.pool, $query)
+    }};
+}
+
 // Impl for reading data from the DB
 impl IndexerReader {
     fn get_object_from_db(
@@ -177,7 +197,7 @@ impl IndexerReader {
     ) -> Result<Option<StoredObject>, IndexerError> {
         let object_id = object_id.to_vec();
 
-        let stored_object = self.run_query(|conn| {
+        let stored_object = run_query!(self, |conn| {
             if let Some(version) = version {
                 objects::dsl::objects
                     .filter(objects::dsl::object_id.eq(object_id))
@@ -227,7 +247,7 @@ impl IndexerReader {
     fn get_object_read(&self, object_id: &ObjectID) -> Result<ObjectRead, IndexerError> {
         let id = object_id.to_vec();
 
-        let stored_object = self.run_query(|conn| {
+        let stored_object = run_query!(self, |conn| {
             objects::dsl::objects
                 .filter(objects::dsl::object_id.eq(id))
                 .first::<StoredObject>(conn)
@@ -246,7 +266,7 @@ impl IndexerReader {
         package_id: &ObjectID,
     ) -> Result<Option<MovePackage>, IndexerError> {
         let package_id = package_id.to_vec();
-        let stored_package = self.run_query(|conn| {
+        let stored_package = run_query!(self, |conn| {
             packages::dsl::packages
                 .filter(packages::dsl::package_id.eq(package_id))
                 .first::<StoredPackage>(conn)
@@ -296,7 +316,7 @@ impl IndexerReader {
         &self,
         epoch: Option<EpochId>,
     ) -> Result<Option<StoredEpochInfo>, IndexerError> {
-        let stored_epoch = self.run_query(|conn| {
+        let stored_epoch = run_query!(self, |conn| {
             if let Some(epoch) = epoch {
                 epochs::dsl::epochs
                     .filter(epochs::epoch.eq(epoch as i64))
@@ -314,7 +334,7 @@ impl IndexerReader {
     }
 
     pub fn get_latest_epoch_info_from_db(&self) -> Result<StoredEpochInfo, IndexerError> {
-        let stored_epoch = self.run_query(|conn| {
+        let stored_epoch = run_query!(self, |conn| {
             epochs::dsl::epochs
                 .order_by(epochs::epoch.desc())
                 .first::<StoredEpochInfo>(conn)
@@ -344,7 +364,7 @@ impl IndexerReader {
         limit: usize,
         descending_order: bool,
     ) -> Result<Vec<StoredEpochInfo>, IndexerError> {
-        self.run_query(|conn| {
+        run_query!(self, |conn| {
             let mut boxed_query = epochs::table.into_boxed();
             if let Some(cursor) = cursor {
                 if descending_order {
@@ -387,7 +407,7 @@ impl IndexerReader {
         &self,
         checkpoint_id: CheckpointId,
     ) -> Result<Option<StoredCheckpoint>, IndexerError> {
-        let stored_checkpoint = self.run_query(|conn| match checkpoint_id {
+        let stored_checkpoint = run_query!(self, |conn| match checkpoint_id {
             CheckpointId::SequenceNumber(seq) => checkpoints::dsl::checkpoints
                 .filter(checkpoints::sequence_number.eq(seq as i64))
                 .first::<StoredCheckpoint>(conn)
@@ -402,7 +422,7 @@ impl IndexerReader {
     }
 
     pub fn get_latest_checkpoint_from_db(&self) -> Result<StoredCheckpoint, IndexerError> {
-        let stored_checkpoint = self.run_query(|conn| {
+        let stored_checkpoint = run_query!(self, |conn| {
             checkpoints::dsl::checkpoints
                 .order_by(checkpoints::sequence_number.desc())
                 .first::<StoredCheckpoint>(conn)
@@ -436,7 +456,7 @@ impl IndexerReader {
         limit: usize,
         descending_order: bool,
     ) -> Result<Vec<StoredCheckpoint>, IndexerError> {
-        self.run_query(|conn| {
+        run_query!(self, |conn| {
             let mut boxed_query = checkpoints::table.into_boxed();
             if let Some(cursor) = cursor {
                 if descending_order {
@@ -479,7 +499,7 @@ impl IndexerReader {
             .iter()
             .map(|digest| digest.inner().to_vec())
             .collect::<Vec<_>>();
-        self.run_query(|conn| {
+        run_query!(self, |conn| {
             transactions::table
                 .filter(transactions::transaction_digest.eq_any(digests))
                 .load::<StoredTransaction>(conn)
@@ -503,19 +523,21 @@ impl IndexerReader {
         // Some(true) for desc, Some(false) for asc, None for undefined order
         is_descending: Option<bool>,
     ) -> Result<Vec<StoredTransaction>, IndexerError> {
-        let mut query = transactions::table
-            .filter(transactions::tx_sequence_number.eq_any(tx_sequence_numbers))
-            .into_boxed();
-        match is_descending {
-            Some(true) => {
-                query = query.order(transactions::dsl::tx_sequence_number.desc());
-            }
-            Some(false) => {
-                query = query.order(transactions::dsl::tx_sequence_number.asc());
+        run_query!(self, |conn| {
+            let mut query = transactions::table
+                .filter(transactions::tx_sequence_number.eq_any(tx_sequence_numbers))
+                .into_boxed();
+            match is_descending {
+                Some(true) => {
+                    query = query.order(transactions::dsl::tx_sequence_number.desc());
+                }
+                Some(false) => {
+                    query = query.order(transactions::dsl::tx_sequence_number.asc());
+                }
+                None => (),
             }
-            None => (),
-        }
-        self.run_query(|conn| query.load::<StoredTransaction>(conn))
+            query.load::<StoredTransaction>(conn)
+        })
     }
 
     pub async fn get_owned_objects_in_blocking_task(
@@ -538,7 +560,7 @@ impl IndexerReader {
         cursor: Option<ObjectID>,
         limit: usize,
     ) -> Result<Vec<StoredObject>, IndexerError> {
-        self.run_query(|conn| {
+        run_query!(self, |conn| {
             let mut query = objects::dsl::objects
                 .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
                 .filter(objects::dsl::owner_id.eq(address.to_vec()))
@@ -582,7 +604,7 @@ impl IndexerReader {
     ) -> Result<Vec<StoredObject>, IndexerError> {
         let object_ids = object_ids.into_iter().map(|id| id.to_vec()).collect_vec();
 
-        self.run_query(|conn| {
+        run_query!(self, |conn| {
             objects::dsl::objects
                 .filter(objects::object_id.eq_any(object_ids))
                 .load::<StoredObject>(conn)
@@ -597,26 +619,27 @@ impl IndexerReader {
         limit: usize,
         is_descending: bool,
     ) -> IndexerResult<Vec<SuiTransactionBlockResponse>> {
-        let mut query = transactions::dsl::transactions
-            .filter(transactions::dsl::checkpoint_sequence_number.eq(checkpoint_seq as i64))
-            .into_boxed();
+        let stored_txes = run_query!(self, |conn| {
+            let mut query = transactions::dsl::transactions
+                .filter(transactions::dsl::checkpoint_sequence_number.eq(checkpoint_seq as i64))
+                .into_boxed();
 
-        // Translate transaction digest cursor to tx sequence number
-        if let Some(cursor_tx_seq) = cursor_tx_seq {
+            // Translate transaction digest cursor to tx sequence number
+            if let Some(cursor_tx_seq) = cursor_tx_seq {
+                if is_descending {
+                    query = query.filter(transactions::dsl::tx_sequence_number.le(cursor_tx_seq));
+                } else {
+                    query = query.filter(transactions::dsl::tx_sequence_number.ge(cursor_tx_seq));
+                }
+            }
             if is_descending {
-                query = query.filter(transactions::dsl::tx_sequence_number.le(cursor_tx_seq));
+                query = query.order(transactions::dsl::tx_sequence_number.desc());
             } else {
-                query = query.filter(transactions::dsl::tx_sequence_number.ge(cursor_tx_seq));
+                query = query.order(transactions::dsl::tx_sequence_number.asc());
             }
-        }
-        if is_descending {
-            query = query.order(transactions::dsl::tx_sequence_number.desc());
-        } else {
-            query = query.order(transactions::dsl::tx_sequence_number.asc());
-        }
 
-        let stored_txes =
-            self.run_query(|conn| query.limit((limit) as i64).load::<StoredTransaction>(conn))?;
+            query.limit((limit) as i64).load::<StoredTransaction>(conn)
+        })?;
 
         self.stored_transaction_to_transaction_block(stored_txes, options)
     }
@@ -644,7 +667,7 @@ impl IndexerReader {
         is_descending: bool,
     ) -> IndexerResult<Vec<SuiTransactionBlockResponse>> {
         let cursor_tx_seq = if let Some(cursor) = cursor {
-            Some(self.run_query(|conn| {
+            Some(run_query!(self, |conn| {
                 transactions::dsl::transactions
                     .select(transactions::tx_sequence_number)
                     .filter(transactions::dsl::transaction_digest.eq(cursor.into_inner().to_vec()))
@@ -680,64 +703,55 @@ impl IndexerReader {
                 module,
                 function,
             }) => {
-                let package = Hex::encode(package.to_vec());
+                let package = self.pool.bytes_literal(&package.to_vec());
                 match (module, function) {
                     (Some(module), Some(function)) => (
                         "tx_calls".into(),
                         format!(
-                            "package = '\\x{}'::bytea AND module = '{}' AND func = '{}'",
+                            "package = {} AND module = '{}' AND func = '{}'",
                             package, module, function
                         ),
                     ),
                     (Some(module), None) => (
                         "tx_calls".into(),
-                        format!(
-                            "package = '\\x{}'::bytea AND module = '{}'",
-                            package, module
-                        ),
+                        format!("package = {} AND module = '{}'", package, module),
                     ),
                     (None, Some(_)) => {
                         return Err(IndexerError::InvalidArgumentError(
                             "Function cannot be present wihtout Module.".into(),
                         ));
                     }
-                    (None, None) => (
-                        "tx_calls".into(),
-                        format!("package = '\\x{}'::bytea", package),
-                    ),
+                    (None, None) => ("tx_calls".into(), format!("package = {}", package)),
                 }
             }
             Some(TransactionFilter::InputObject(object_id)) => {
-                let object_id = Hex::encode(object_id.to_vec());
+                let object_id = self.pool.bytes_literal(&object_id.to_vec());
                 (
                     "tx_input_objects".into(),
-                    format!("object_id = '\\x{}'::bytea", object_id),
+                    format!("object_id = {}", object_id),
                 )
             }
             Some(TransactionFilter::ChangedObject(object_id)) => {
-                let object_id = Hex::encode(object_id.to_vec());
+                let object_id = self.pool.bytes_literal(&object_id.to_vec());
                 (
                     "tx_changed_objects".into(),
-                    format!("object_id = '\\x{}'::bytea", object_id),
+                    format!("object_id = {}", object_id),
                 )
             }
             Some(TransactionFilter::FromAddress(from_address)) => {
-                let from_address = Hex::encode(from_address.to_vec());
-                (
-                    "tx_senders".into(),
-                    format!("sender = '\\x{}'::bytea", from_address),
-                )
+                let from_address = self.pool.bytes_literal(&from_address.to_vec());
+                ("tx_senders".into(), format!("sender = {}", from_address))
             }
             Some(TransactionFilter::ToAddress(to_address)) => {
-                let to_address = Hex::encode(to_address.to_vec());
+                let to_address = self.pool.bytes_literal(&to_address.to_vec());
                 (
                     "tx_recipients".into(),
-                    format!("recipient = '\\x{}'::bytea", to_address),
+                    format!("recipient = {}", to_address),
                 )
             }
             Some(TransactionFilter::FromAndToAddress { from, to }) => {
-                let from_address = Hex::encode(from.to_vec());
-                let to_address = Hex::encode(to.to_vec());
+                let from_address = self.pool.bytes_literal(&from.to_vec());
+                let to_address = self.pool.bytes_literal(&to.to_vec());
                 // Need to remove ambiguities for tx_sequence_number column
                 let cursor_clause = if let Some(cursor_tx_seq) = cursor_tx_seq {
                     if is_descending {
@@ -759,8 +773,8 @@ impl IndexerReader {
                     FROM tx_senders \
                     JOIN tx_recipients \
                     ON tx_senders.{TX_SEQUENCE_NUMBER_STR} = tx_recipients.{TX_SEQUENCE_NUMBER_STR} \
-                    WHERE tx_senders.sender = '\\x{}'::BYTEA \
-                    AND tx_recipients.recipient = '\\x{}'::BYTEA \
+                    WHERE tx_senders.sender = {} \
+                    AND tx_recipients.recipient = {} \
                     {} \
                     ORDER BY {TX_SEQUENCE_NUMBER_STR} {} \
                     LIMIT {}) AS inner_query
@@ -774,22 +788,24 @@ impl IndexerReader {
                 (inner_query, "1 = 1".into())
             }
             Some(TransactionFilter::FromOrToAddress { addr }) => {
-                let address = Hex::encode(addr.to_vec());
+                let address = self.pool.bytes_literal(&addr.to_vec());
+                // Each side of the UNION is wrapped in a subquery, rather than parentheses, for
+                // SQLite's sake, which does not support ORDER BY and LIMIT in compound selects.
                 let inner_query = format!(
                     "( \
-                        ( \
+                        SELECT {TX_SEQUENCE_NUMBER_STR} FROM ( \
                             SELECT {TX_SEQUENCE_NUMBER_STR} FROM tx_senders \
-                            WHERE sender = '\\x{}'::BYTEA {} \
+                            WHERE sender = {} {} \
                             ORDER BY {TX_SEQUENCE_NUMBER_STR} {} \
                             LIMIT {} \
-                        ) \
+                        ) AS s \
                         UNION \
-                        ( \
+                        SELECT {TX_SEQUENCE_NUMBER_STR} FROM ( \
                             SELECT {TX_SEQUENCE_NUMBER_STR} FROM tx_recipients \
-                            WHERE recipient = '\\x{}'::BYTEA {} \
+                            WHERE recipient = {} {} \
                             ORDER BY {TX_SEQUENCE_NUMBER_STR} {} \
                             LIMIT {} \
-                        ) \
+                        ) AS r \
                     ) AS combined",
                     address,
                     cursor_clause,
@@ -826,11 +842,11 @@ impl IndexerReader {
 
         tracing::debug!("query transaction blocks: {}", query);
 
-        let tx_sequence_numbers = self
-            .run_query(|conn| diesel::sql_query(query.clone()).load::<TxSequenceNumber>(conn))?
-            .into_iter()
-            .map(|tsn| tsn.tx_sequence_number)
-            .collect::<Vec<_>>();
+        let tx_sequence_numbers = run_query!(self, |conn| diesel::sql_query(query)
+            .load::<TxSequenceNumber>(conn))?
+        .into_iter()
+        .map(|tsn| tsn.tx_sequence_number)
+        .collect::<Vec<_>>();
 
         self.multi_get_transaction_block_response_by_sequence_numbers(
             tx_sequence_numbers,
@@ -875,11 +891,11 @@ impl IndexerReader {
         &self,
         digest: TransactionDigest,
     ) -> Result<Vec<sui_json_rpc_types::SuiEvent>, IndexerError> {
-        let (timestamp_ms, serialized_events) = self.run_query(|conn| {
+        let (timestamp_ms, serialized_events) = run_query!(self, |conn| {
             transactions::table
                 .filter(transactions::transaction_digest.eq(digest.into_inner().to_vec()))
                 .select((transactions::timestamp_ms, transactions::events))
-                .first::<(i64, Vec<Option<Vec<u8>>>)>(conn)
+                .first::<(i64, BytesArray)>(conn)
         })?;
 
         let events = serialized_events
@@ -930,16 +946,16 @@ impl IndexerReader {
 
         let order_clause = if descending_order { "DESC" } else { "ASC" };
         Ok(format!(
-            "SELECT * \
+            "SELECT e.* \
             FROM EVENTS e \
             JOIN TRANSACTIONS t \
             ON t.tx_sequence_number = e.tx_sequence_number \
-            AND t.transaction_digest = '\\x{}'::bytea \
+            AND t.transaction_digest = {} \
             WHERE {cursor} \
             ORDER BY e.{EVENT_SEQUENCE_NUMBER_STR} {order_clause} \
             LIMIT {limit}
             ",
-            Hex::encode(tx_digest.into_inner()),
+            self.pool.bytes_literal(&tx_digest.into_inner()),
         ))
     }
 
@@ -956,7 +972,7 @@ impl IndexerReader {
                 event_seq,
             } = cursor;
             (
-                self.run_query(|conn| {
+                run_query!(self, |conn| {
                     transactions::dsl::transactions
                         .select(transactions::tx_sequence_number)
                         .filter(
@@ -968,7 +984,7 @@ impl IndexerReader {
                 event_seq,
             )
         } else if descending_order {
-            let max_tx_seq: i64 = self.run_query(|conn| {
+            let max_tx_seq: i64 = run_query!(self, |conn| {
                 events::dsl::events
                     .select(events::tx_sequence_number)
                     .order(events::dsl::tx_sequence_number.desc())
@@ -992,17 +1008,17 @@ impl IndexerReader {
                 format!("e.{TX_SEQUENCE_NUMBER_STR} ASC, e.{EVENT_SEQUENCE_NUMBER_STR} ASC")
             };
             format!(
-                "( \
-                    SELECT *
+                "
+                    SELECT e.*
                     FROM tx_senders s
                     JOIN events e
                     ON e.tx_sequence_number = s.tx_sequence_number
-                    AND s.sender = '\\x{}'::bytea
+                    AND s.sender = {}
                     WHERE {} \
                     ORDER BY {} \
                     LIMIT {}
-                )",
-                Hex::encode(sender.to_vec()),
+                ",
+                self.pool.bytes_literal(&sender.to_vec()),
                 cursor_clause,
                 order_clause,
                 limit,
@@ -1012,12 +1028,15 @@ impl IndexerReader {
         } else {
             let main_where_clause = match filter {
                 EventFilter::Package(package_id) => {
-                    format!("package = '\\x{}'::bytea", package_id.to_hex())
+                    format!(
+                        "package = {}",
+                        self.pool.bytes_literal(&package_id.to_vec())
+                    )
                 }
                 EventFilter::MoveModule { package, module } => {
                     format!(
-                        "package = '\\x{}'::bytea AND module = '{}'",
-                        package.to_hex(),
+                        "package = {} AND module = '{}'",
+                        self.pool.bytes_literal(&package.to_vec()),
                         module,
                     )
                 }
@@ -1070,8 +1089,8 @@ impl IndexerReader {
             )
         };
         tracing::debug!("query events: {}", query);
-        let stored_events =
-            self.run_query(|conn| diesel::sql_query(query).load::<StoredEvent>(conn))?;
+        let stored_events = run_query!(self, |conn| diesel::sql_query(query)
+            .load::<StoredEvent>(conn))?;
         stored_events
             .into_iter()
             .map(|se| se.try_into_sui_event(self))
@@ -1162,7 +1181,7 @@ impl IndexerReader {
         cursor: Option<ObjectID>,
         limit: usize,
     ) -> Result<Vec<StoredObject>, IndexerError> {
-        let objects: Vec<StoredObject> = self.run_query(|conn| {
+        let objects: Vec<StoredObject> = run_query!(self, |conn| {
             let mut query = objects::dsl::objects
                 .filter(objects::dsl::owner_type.eq(OwnerType::Object as i16))
                 .filter(objects::dsl::owner_id.eq(parent_object_id.to_vec()))
@@ -1193,7 +1212,7 @@ impl IndexerReader {
         &self,
         object_ids: Vec<Vec<u8>>,
     ) -> IndexerResult<HashMap<ObjectID, ObjectRef>> {
-        self.run_query(|conn| {
+        run_query!(self, |conn| {
             let query = objects::dsl::objects
                 .select((
                     objects::dsl::object_id,
@@ -1238,7 +1257,7 @@ impl IndexerReader {
         &self,
         object_type: String,
     ) -> Result<Option<sui_types::display::DisplayVersionUpdatedEvent>, IndexerError> {
-        let stored_display = self.run_query(|conn| {
+        let stored_display = run_query!(self, |conn| {
             display::table
                 .filter(display::object_type.eq(object_type))
                 .first::<StoredDisplay>(conn)
@@ -1274,21 +1293,22 @@ impl IndexerReader {
         cursor: ObjectID,
         limit: usize,
     ) -> Result<Vec<SuiCoin>, IndexerError> {
-        let mut query = objects::dsl::objects
-            .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
-            .filter(objects::dsl::owner_id.eq(owner.to_vec()))
-            .filter(objects::dsl::object_id.gt(cursor.to_vec()))
-            .into_boxed();
-        if let Some(coin_type) = coin_type {
-            query = query.filter(objects::dsl::coin_type.eq(Some(coin_type)));
-        } else {
-            query = query.filter(objects::dsl::coin_type.is_not_null());
-        }
-        query = query
-            .order((objects::dsl::coin_type.asc(), objects::dsl::object_id.asc()))
-            .limit(limit as i64);
-
-        let stored_objects = self.run_query(|conn| query.load::<StoredObject>(conn))?;
+        let stored_objects = run_query!(self, |conn| {
+            let mut query = objects::dsl::objects
+                .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
+                .filter(objects::dsl::owner_id.eq(owner.to_vec()))
+                .filter(objects::dsl::object_id.gt(cursor.to_vec()))
+                .into_boxed();
+            if let Some(coin_type) = coin_type {
+                query = query.filter(objects::dsl::coin_type.eq(Some(coin_type)));
+            } else {
+                query = query.filter(objects::dsl::coin_type.is_not_null());
+            }
+            query
+                .order((objects::dsl::coin_type.asc(), objects::dsl::object_id.asc()))
+                .limit(limit as i64)
+                .load::<StoredObject>(conn)
+        })?;
 
         stored_objects
             .into_iter()
@@ -1325,24 +1345,24 @@ impl IndexerReader {
             CAST(SUM(coin_balance) AS BIGINT) AS coin_balance \
             FROM objects \
             WHERE owner_type = {} \
-            AND owner_id = '\\x{}'::BYTEA \
+            AND owner_id = {} \
             AND coin_type {} \
             GROUP BY coin_type \
             ORDER BY coin_type ASC
         ",
             OwnerType::Address as i16,
-            Hex::encode(owner.to_vec()),
+            self.pool.bytes_literal(&owner.to_vec()),
             coin_type_filter,
         );
 
         tracing::debug!("get coin balances query: {query}");
-        let coin_balances =
-            self.run_query(|conn| diesel::sql_query(query).load::<CoinBalance>(conn))?;
+        let coin_balances = run_query!(self, |conn| diesel::sql_query(query)
+            .load::<CoinBalance>(conn))?;
         Ok(coin_balances.into_iter().map(|cb| cb.into()).collect())
     }
 
     pub fn get_latest_network_metrics(&self) -> IndexerResult<NetworkMetrics> {
-        let stored_network_metrics = self.run_query(|conn| {
+        let stored_network_metrics = run_query!(self, |conn| {
             network_metrics::table
                 .order(network_metrics::dsl::checkpoint.desc())
                 .first::<StoredNetworkMetrics>(conn)
@@ -1351,21 +1371,21 @@ impl IndexerReader {
     }
 
     pub fn get_latest_move_call_metrics(&self) -> IndexerResult<MoveCallMetrics> {
-        let latest_3d_move_call_metrics = self.run_query(|conn| {
+        let latest_3d_move_call_metrics = run_query!(self, |conn| {
             move_call_metrics::table
                 .filter(move_call_metrics::dsl::day.eq(3))
                 .order(move_call_metrics::dsl::id.desc())
                 .limit(10)
                 .load::<QueriedMoveCallMetrics>(conn)
         })?;
-        let latest_7d_move_call_metrics = self.run_query(|conn| {
+        let latest_7d_move_call_metrics = run_query!(self, |conn| {
             move_call_metrics::table
                 .filter(move_call_metrics::dsl::day.eq(7))
                 .order(move_call_metrics::dsl::id.desc())
                 .limit(10)
                 .load::<QueriedMoveCallMetrics>(conn)
         })?;
-        let latest_30d_move_call_metrics = self.run_query(|conn| {
+        let latest_30d_move_call_metrics = run_query!(self, |conn| {
             move_call_metrics::table
                 .filter(move_call_metrics::dsl::day.eq(30))
                 .order(move_call_metrics::dsl::id.desc())
@@ -1406,7 +1426,7 @@ impl IndexerReader {
     }
 
     pub fn get_latest_address_metrics(&self) -> IndexerResult<AddressMetrics> {
-        let stored_address_metrics = self.run_query(|conn| {
+        let stored_address_metrics = run_query!(self, |conn| {
             address_metrics::table
                 .order(address_metrics::dsl::checkpoint.desc())
                 .first::<StoredAddressMetrics>(conn)
@@ -1418,7 +1438,7 @@ impl IndexerReader {
         &self,
         checkpoint_seq: u64,
     ) -> IndexerResult<AddressMetrics> {
-        let stored_address_metrics = self.run_query(|conn| {
+        let stored_address_metrics = run_query!(self, |conn| {
             address_metrics::table
                 .filter(address_metrics::dsl::checkpoint.eq(checkpoint_seq as i64))
                 .first::<StoredAddressMetrics>(conn)
@@ -1445,7 +1465,7 @@ impl IndexerReader {
               WHERE row_num = 1 ORDER BY epoch {}",
               if is_descending { "DESC" } else { "ASC" },
         );
-        let epoch_address_metrics = self.run_query(|conn| {
+        let epoch_address_metrics = run_query!(self, |conn| {
             diesel::sql_query(epoch_address_metrics_query).load::<StoredAddressMetrics>(conn)
         })?;
 
@@ -1456,7 +1476,7 @@ impl IndexerReader {
     }
 
     pub fn get_total_transactions(&self) -> IndexerResult<i64> {
-        let latest_tx_count_metrics = self.run_query(|conn| {
+        let latest_tx_count_metrics = run_query!(self, |conn| {
             tx_count_metrics::table
                 .order(tx_count_metrics::dsl::checkpoint_sequence_number.desc())
                 .first::<StoredTxCountMetrics>(conn)
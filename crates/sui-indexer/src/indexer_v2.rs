@@ -14,12 +14,23 @@ use mysten_metrics::spawn_monitored_task;
 use prometheus::Registry;
 use std::env;
 use std::net::SocketAddr;
+use std::num::NonZeroUsize;
+use sui_archival::reader::{ArchiveReader, ArchiveReaderMetrics};
+use sui_config::node::ArchiveReaderConfig;
 use sui_json_rpc::ServerType;
 use sui_json_rpc::{JsonRpcServerBuilder, ServerHandle};
+use sui_rest_api::CheckpointData;
+use sui_storage::http_key_value_store::HttpKVStore;
+use sui_storage::key_value_store_metrics::KeyValueStoreMetrics;
+use sui_storage::object_store::ObjectStoreConfig;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 use tokio::runtime::Handle;
 use tracing::info;
 
+use crate::framework::archive_fetcher::ArchiveCheckpointFetcher;
+use crate::framework::backfill::{BackfillProgressStore, RangeBackfill};
 use crate::framework::fetcher::CheckpointFetcher;
+use crate::handlers::backfill_handler_v2::TableBackfillHandler;
 use crate::handlers::checkpoint_handler_v2::new_handlers;
 use crate::processors_v2::processor_orchestrator_v2::ProcessorOrchestratorV2;
 use crate::store::{IndexerStoreV2, PgIndexerAnalyticalStore};
@@ -27,10 +38,12 @@ use crate::store::{IndexerStoreV2, PgIndexerAnalyticalStore};
 pub struct IndexerV2;
 
 const DOWNLOAD_QUEUE_SIZE: usize = 1000;
+const ARCHIVE_DOWNLOAD_CONCURRENCY: usize = 5;
 
 impl IndexerV2 {
     pub async fn start_writer<S: IndexerStoreV2 + Sync + Send + Clone + 'static>(
         config: &IndexerConfig,
+        registry: &Registry,
         store: S,
         metrics: IndexerMetrics,
     ) -> Result<(), IndexerError> {
@@ -53,14 +66,33 @@ impl IndexerV2 {
                     .with_label_values(&["checkpoint_tx_downloading"]),
             );
 
+        let archive_fetcher = match config.archive_object_store_config()? {
+            Some(remote_store_config) => Some(new_archive_fetcher(
+                config,
+                registry,
+                remote_store_config,
+                last_seq_from_db,
+                downloaded_checkpoint_data_sender.clone(),
+            )?),
+            None => None,
+        };
+
         let rest_api_url = format!("{}/rest", config.rpc_client_url);
         let rest_client = sui_rest_api::Client::new(&rest_api_url);
-        let fetcher = CheckpointFetcher::new(
-            rest_client.clone(),
-            last_seq_from_db,
-            downloaded_checkpoint_data_sender,
-        );
-        spawn_monitored_task!(fetcher.run());
+        spawn_monitored_task!(async move {
+            // Backfill from the archive first, if there is one, and only then switch over to the
+            // fullnode, for the checkpoints that have not made it into the archive yet.
+            let last_downloaded_checkpoint = match archive_fetcher {
+                Some(archive_fetcher) => archive_fetcher.run().await,
+                None => last_seq_from_db,
+            };
+            let fetcher = CheckpointFetcher::new(
+                rest_client,
+                last_downloaded_checkpoint,
+                downloaded_checkpoint_data_sender,
+            );
+            fetcher.run().await
+        });
 
         let checkpoint_handler = new_handlers(store, metrics, config).await?;
 
@@ -75,6 +107,49 @@ impl IndexerV2 {
         Ok(())
     }
 
+    /// Re-indexes `config.backfill_table` over the configured range of checkpoints, with
+    /// `config.backfill_workers` workers, resuming from wherever an earlier backfill of the same
+    /// table left off.
+    pub async fn start_backfill<S>(
+        config: &IndexerConfig,
+        store: S,
+        metrics: IndexerMetrics,
+    ) -> Result<(), IndexerError>
+    where
+        S: IndexerStoreV2 + BackfillProgressStore + Sync + Send + Clone + 'static,
+    {
+        let (Some(table), Some(start), Some(end)) = (
+            config.backfill_table,
+            config.backfill_start,
+            config.backfill_end,
+        ) else {
+            return Err(IndexerError::InvalidArgumentError(
+                "--backfill-table, --backfill-start and --backfill-end are all required to run a backfill"
+                    .to_string(),
+            ));
+        };
+        info!(
+            "Sui indexerV2 Backfill of {} (version {:?}) started...",
+            table.name(),
+            env!("CARGO_PKG_VERSION")
+        );
+
+        let rest_api_url = format!("{}/rest", config.rpc_client_url);
+        let handler = TableBackfillHandler::new(store.clone(), table, metrics);
+        RangeBackfill::new(
+            table.name(),
+            start..end,
+            sui_rest_api::Client::new(rest_api_url),
+            handler,
+            store,
+        )
+        .num_workers(config.backfill_workers)
+        .run()
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn start_reader(
         config: &IndexerConfig,
         registry: &Registry,
@@ -108,6 +183,38 @@ impl IndexerV2 {
     }
 }
 
+fn new_archive_fetcher(
+    config: &IndexerConfig,
+    registry: &Registry,
+    remote_store_config: ObjectStoreConfig,
+    last_downloaded_checkpoint: Option<CheckpointSequenceNumber>,
+    sender: mysten_metrics::metered_channel::Sender<CheckpointData>,
+) -> Result<ArchiveCheckpointFetcher, IndexerError> {
+    let kv_store_url = config.archive_kv_store_url.as_ref().ok_or_else(|| {
+        IndexerError::InvalidArgumentError(
+            "--archive-kv-store-url is required to read checkpoints from an archive".to_string(),
+        )
+    })?;
+    let reader_config = ArchiveReaderConfig {
+        remote_store_config,
+        download_concurrency: NonZeroUsize::new(ARCHIVE_DOWNLOAD_CONCURRENCY).unwrap(),
+        use_for_pruning_watermark: false,
+    };
+    let reader = ArchiveReader::new(reader_config, &ArchiveReaderMetrics::new(registry))
+        .map_err(|e| IndexerError::GenericError(format!("Failed to open archive: {e}")))?;
+    let kv_store =
+        HttpKVStore::new_kv(kv_store_url, KeyValueStoreMetrics::new(registry)).map_err(|e| {
+            IndexerError::GenericError(format!("Failed to connect to key-value store: {e}"))
+        })?;
+
+    Ok(ArchiveCheckpointFetcher::new(
+        reader,
+        kv_store,
+        last_downloaded_checkpoint,
+        sender,
+    ))
+}
+
 pub async fn build_json_rpc_server(
     prometheus_registry: &Registry,
     reader: IndexerReader,
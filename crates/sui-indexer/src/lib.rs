@@ -4,6 +4,7 @@
 
 use std::env;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::{collections::HashMap, time::Duration};
 
 use anyhow::{anyhow, Result};
@@ -29,12 +30,15 @@ use processors::processor_orchestrator::ProcessorOrchestrator;
 use store::IndexerStore;
 use sui_json_rpc::{JsonRpcServerBuilder, ServerHandle, ServerType, CLIENT_SDK_TYPE_HEADER};
 use sui_sdk::{SuiClient, SuiClientBuilder};
+use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
 
 use crate::apis::MoveUtilsApi;
 use crate::framework::IndexerBuilder;
+use crate::handlers::backfill_handler_v2::BackfillTable;
 use crate::handlers::checkpoint_handler::new_handlers;
 
 pub mod apis;
+pub mod db;
 pub mod errors;
 pub mod framework;
 mod handlers;
@@ -81,6 +85,8 @@ const IMPLEMENTED_METHODS: [&str; 9] = [
     rename_all = "kebab-case"
 )]
 pub struct IndexerConfig {
+    /// URL of the database to use, either a Postgres URL, or `sqlite://<path>` for an embedded
+    /// SQLite database (only supported with `--use-v2`).
     #[clap(long)]
     pub db_url: Option<String>,
     #[clap(long)]
@@ -118,6 +124,35 @@ pub struct IndexerConfig {
     pub skip_db_commit: bool,
     #[clap(long)]
     pub use_v2: bool,
+    /// Local directory holding a `sui-archival` archive to backfill checkpoints from, before
+    /// switching over to the fullnode at `--rpc-client-url`. Only supported with `--use-v2`.
+    #[clap(long, requires = "archive_kv_store_url")]
+    pub archive_path: Option<PathBuf>,
+    /// YAML file describing the object store (S3, GCS, ...) holding a `sui-archival` archive to
+    /// backfill checkpoints from, as an alternative to `--archive-path`.
+    #[clap(
+        long,
+        conflicts_with = "archive_path",
+        requires = "archive_kv_store_url"
+    )]
+    pub archive_store_config: Option<PathBuf>,
+    /// URL of the key-value store to fetch the events and objects of checkpoints read from the
+    /// archive from, as archives do not hold them.
+    #[clap(long)]
+    pub archive_kv_store_url: Option<String>,
+    /// Re-index only this table, over checkpoints `--backfill-start..--backfill-end`, instead of
+    /// running the regular writer. Only supported with `--use-v2`.
+    #[clap(long, value_enum, requires_all = ["backfill_start", "backfill_end"])]
+    pub backfill_table: Option<BackfillTable>,
+    /// First checkpoint to backfill.
+    #[clap(long)]
+    pub backfill_start: Option<u64>,
+    /// Checkpoint to stop backfilling at (exclusive).
+    #[clap(long)]
+    pub backfill_end: Option<u64>,
+    /// Number of workers to split the backfill between.
+    #[clap(long, default_value = "4")]
+    pub backfill_workers: usize,
 }
 
 impl IndexerConfig {
@@ -151,6 +186,26 @@ impl IndexerConfig {
             _ => Err(anyhow!("Invalid db connection config, either db_url or (db_user_name, db_password, db_host, db_port, db_name) must be provided")),
         }
     }
+
+    /// The object store holding the archive to backfill checkpoints from, if one was configured.
+    pub fn archive_object_store_config(&self) -> Result<Option<ObjectStoreConfig>, anyhow::Error> {
+        match (&self.archive_path, &self.archive_store_config) {
+            (Some(path), _) => Ok(Some(ObjectStoreConfig {
+                object_store: Some(ObjectStoreType::File),
+                directory: Some(path.clone()),
+                ..Default::default()
+            })),
+            (None, Some(config_path)) => {
+                let config = std::fs::read_to_string(config_path).map_err(|e| {
+                    anyhow!("Failed to read archive store config {config_path:?}: {e}")
+                })?;
+                Ok(Some(serde_yaml::from_str(&config).map_err(|e| {
+                    anyhow!("Failed to parse archive store config {config_path:?}: {e}")
+                })?))
+            }
+            (None, None) => Ok(None),
+        }
+    }
 }
 
 impl Default for IndexerConfig {
@@ -174,6 +229,13 @@ impl Default for IndexerConfig {
             analytical_worker: false,
             skip_db_commit: false,
             use_v2: false,
+            archive_path: None,
+            archive_store_config: None,
+            archive_kv_store_url: None,
+            backfill_table: None,
+            backfill_start: None,
+            backfill_end: None,
+            backfill_workers: 4,
         }
     }
 }
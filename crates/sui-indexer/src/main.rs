@@ -4,6 +4,7 @@
 use clap::Parser;
 use tracing::{error, info};
 
+use sui_indexer::db::{new_connection_pool, ConnectionPool};
 use sui_indexer::errors::IndexerError;
 use sui_indexer::indexer_v2::IndexerV2;
 use sui_indexer::metrics::IndexerMetrics;
@@ -11,8 +12,9 @@ use sui_indexer::start_prometheus_server;
 use sui_indexer::store::PgIndexerAnalyticalStore;
 use sui_indexer::store::PgIndexerStore;
 use sui_indexer::store::PgIndexerStoreV2;
-use sui_indexer::utils::reset_database;
-use sui_indexer::{get_pg_pool_connection, new_pg_connection_pool, Indexer, IndexerConfig};
+use sui_indexer::store::SqliteIndexerStoreV2;
+use sui_indexer::utils::{reset_database, reset_database_v2};
+use sui_indexer::{get_pg_pool_connection, Indexer, IndexerConfig};
 
 #[tokio::main]
 async fn main() -> Result<(), IndexerError> {
@@ -44,15 +46,12 @@ async fn main() -> Result<(), IndexerError> {
             e
         ))
     })?;
-    let blocking_cp = new_pg_connection_pool(&db_url).map_err(|e| {
-        error!(
-            "Failed creating Postgres connection pool with error {:?}",
-            e
-        );
+    let connection_pool = new_connection_pool(&db_url).map_err(|e| {
+        error!("Failed creating connection pool with error {:?}", e);
         e
     })?;
 
-    let report_cp = blocking_cp.clone();
+    let report_cp = connection_pool.clone();
     let report_metrics = indexer_metrics.clone();
     tokio::spawn(async move {
         loop {
@@ -71,6 +70,64 @@ async fn main() -> Result<(), IndexerError> {
         }
     });
 
+    if indexer_config.use_v2 {
+        info!("Use v2");
+        if indexer_config.reset_db {
+            reset_database_v2(&connection_pool, /* drop_all */ true).map_err(|e| {
+                let db_err_msg = format!(
+                    "Failed resetting database with url: {:?} and error: {:?}",
+                    db_url, e
+                );
+                error!("{}", db_err_msg);
+                IndexerError::PostgresResetError(db_err_msg)
+            })?;
+        }
+
+        if indexer_config.backfill_table.is_some() {
+            return match connection_pool {
+                ConnectionPool::Postgres(blocking_cp) => {
+                    let store = PgIndexerStoreV2::new(blocking_cp, indexer_metrics.clone());
+                    IndexerV2::start_backfill(&indexer_config, store, indexer_metrics).await
+                }
+                ConnectionPool::Sqlite(blocking_cp) => {
+                    let store = SqliteIndexerStoreV2::new(blocking_cp, indexer_metrics.clone());
+                    IndexerV2::start_backfill(&indexer_config, store, indexer_metrics).await
+                }
+            };
+        } else if indexer_config.fullnode_sync_worker {
+            return match connection_pool {
+                ConnectionPool::Postgres(blocking_cp) => {
+                    let store = PgIndexerStoreV2::new(blocking_cp, indexer_metrics.clone());
+                    IndexerV2::start_writer(&indexer_config, &registry, store, indexer_metrics)
+                        .await
+                }
+                ConnectionPool::Sqlite(blocking_cp) => {
+                    let store = SqliteIndexerStoreV2::new(blocking_cp, indexer_metrics.clone());
+                    IndexerV2::start_writer(&indexer_config, &registry, store, indexer_metrics)
+                        .await
+                }
+            };
+        } else if indexer_config.rpc_server_worker {
+            return IndexerV2::start_reader(&indexer_config, &registry, db_url).await;
+        } else if indexer_config.analytical_worker {
+            let ConnectionPool::Postgres(blocking_cp) = connection_pool else {
+                return Err(IndexerError::NotSupportedError(
+                    "The analytical worker requires a Postgres database".to_string(),
+                ));
+            };
+            let store = PgIndexerAnalyticalStore::new(blocking_cp);
+            return IndexerV2::start_analytical_worker(store).await;
+        } else {
+            panic!("No worker is specified");
+        }
+    }
+
+    let ConnectionPool::Postgres(blocking_cp) = connection_pool else {
+        return Err(IndexerError::NotSupportedError(
+            "The V1 indexer requires a Postgres database, use --use-v2 for SQLite".to_string(),
+        ));
+    };
+
     if indexer_config.reset_db {
         let mut conn = get_pg_pool_connection(&blocking_cp).map_err(|e| {
             error!(
@@ -79,7 +136,7 @@ async fn main() -> Result<(), IndexerError> {
             );
             e
         })?;
-        reset_database(&mut conn, /* drop_all */ true, indexer_config.use_v2).map_err(|e| {
+        reset_database(&mut conn, /* drop_all */ true, /* use_v2 */ false).map_err(|e| {
             let db_err_msg = format!(
                 "Failed resetting database with url: {:?} and error: {:?}",
                 db_url, e
@@ -88,21 +145,6 @@ async fn main() -> Result<(), IndexerError> {
             IndexerError::PostgresResetError(db_err_msg)
         })?;
     }
-    if indexer_config.use_v2 {
-        info!("Use v2");
-        if indexer_config.fullnode_sync_worker {
-            let store = PgIndexerStoreV2::new(blocking_cp, indexer_metrics.clone());
-            return IndexerV2::start_writer(&indexer_config, store, indexer_metrics).await;
-        } else if indexer_config.rpc_server_worker {
-            return IndexerV2::start_reader(&indexer_config, &registry, db_url).await;
-        } else if indexer_config.analytical_worker {
-            let store = PgIndexerAnalyticalStore::new(blocking_cp);
-            return IndexerV2::start_analytical_worker(store).await;
-        } else {
-            panic!("No worker is specified");
-        }
-    }
-
     let store = PgIndexerStore::new(blocking_cp, indexer_metrics.clone());
     Indexer::start(&indexer_config, &registry, store, indexer_metrics, None).await
 }
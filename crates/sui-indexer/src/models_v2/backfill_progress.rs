@@ -0,0 +1,37 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use diesel::prelude::*;
+
+use crate::framework::backfill::BackfillProgress;
+use crate::schema_v2::backfill_progress;
+
+#[derive(Queryable, Insertable, Debug, Clone)]
+#[diesel(table_name = backfill_progress)]
+pub struct StoredBackfillProgress {
+    pub task_name: String,
+    pub range_start: i64,
+    pub range_end: i64,
+    pub next_checkpoint: i64,
+}
+
+impl StoredBackfillProgress {
+    pub fn new(task_name: &str, progress: BackfillProgress) -> Self {
+        Self {
+            task_name: task_name.to_string(),
+            range_start: progress.range_start as i64,
+            range_end: progress.range_end as i64,
+            next_checkpoint: progress.next_checkpoint as i64,
+        }
+    }
+}
+
+impl From<StoredBackfillProgress> for BackfillProgress {
+    fn from(stored: StoredBackfillProgress) -> Self {
+        Self {
+            range_start: stored.range_start as u64,
+            range_end: stored.range_end as u64,
+            next_checkpoint: stored.next_checkpoint as u64,
+        }
+    }
+}
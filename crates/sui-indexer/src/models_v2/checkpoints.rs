@@ -8,6 +8,7 @@ use sui_types::base_types::TransactionDigest;
 use sui_types::digests::CheckpointDigest;
 use sui_types::gas::GasCostSummary;
 
+use crate::db::BytesArray;
 use crate::errors::IndexerError;
 use crate::schema_v2::checkpoints;
 use crate::types_v2::IndexedCheckpoint;
@@ -21,7 +22,7 @@ pub struct StoredCheckpoint {
     pub network_total_transactions: i64,
     pub previous_checkpoint_digest: Option<Vec<u8>>,
     pub end_of_epoch: bool,
-    pub tx_digests: Vec<Option<Vec<u8>>>,
+    pub tx_digests: BytesArray,
     pub timestamp_ms: i64,
     pub total_gas_cost: i64,
     pub computation_cost: i64,
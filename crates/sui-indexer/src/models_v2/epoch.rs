@@ -3,6 +3,7 @@
 
 use diesel::{Insertable, Queryable};
 
+use crate::db::BytesArray;
 use crate::errors::IndexerError;
 use crate::schema_v2::epochs;
 use crate::types_v2::IndexedEpochInfo;
@@ -12,7 +13,7 @@ use sui_json_rpc_types::{EndOfEpochInfo, EpochInfo};
 #[diesel(table_name = epochs)]
 pub struct StoredEpochInfo {
     pub epoch: i64,
-    pub validators: Vec<Option<Vec<u8>>>,
+    pub validators: BytesArray,
     pub first_checkpoint_id: i64,
     pub epoch_start_timestamp: i64,
     pub reference_gas_price: i64,
@@ -77,7 +78,7 @@ impl StoredEpochInfo {
             // we don't update these columns when persisting EndOfEpoch data.
             // However if the data is partial, diesel would interpret them
             // as Null and hence cause errors.
-            validators: vec![],
+            validators: BytesArray::default(),
             first_checkpoint_id: 0,
             epoch_start_timestamp: 0,
             reference_gas_price: 0,
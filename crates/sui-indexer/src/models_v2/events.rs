@@ -15,6 +15,7 @@ use sui_types::event::EventID;
 use sui_types::object::{MoveObject, ObjectFormatOptions};
 use sui_types::parse_sui_struct_tag;
 
+use crate::db::BytesArray;
 use crate::errors::IndexerError;
 use crate::schema_v2::events;
 use crate::types_v2::IndexedEvent;
@@ -34,8 +35,8 @@ pub struct StoredEvent {
     #[diesel(sql_type = diesel::sql_types::BigInt)]
     pub checkpoint_sequence_number: i64,
 
-    #[diesel(sql_type = diesel::sql_types::Array<diesel::sql_types::Nullable<diesel::pg::sql_types::Bytea>>)]
-    pub senders: Vec<Option<Vec<u8>>>,
+    #[diesel(sql_type = crate::schema_v2::sql_types::BytesArray)]
+    pub senders: BytesArray,
 
     #[diesel(sql_type = diesel::sql_types::Bytea)]
     pub package: Vec<u8>,
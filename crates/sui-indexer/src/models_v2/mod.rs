@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod address_metrics;
+pub mod backfill_progress;
 pub mod checkpoints;
 pub mod display;
 pub mod epoch;
@@ -16,6 +16,7 @@ use sui_types::effects::TransactionEvents;
 use sui_types::event::Event;
 use sui_types::transaction::SenderSignedData;
 
+use crate::db::BytesArray;
 use crate::errors::IndexerError;
 use crate::schema_v2::transactions;
 use crate::types_v2::IndexedObjectChange;
@@ -31,9 +32,9 @@ pub struct StoredTransaction {
     pub raw_effects: Vec<u8>,
     pub checkpoint_sequence_number: i64,
     pub timestamp_ms: i64,
-    pub object_changes: Vec<Option<Vec<u8>>>,
-    pub balance_changes: Vec<Option<Vec<u8>>>,
-    pub events: Vec<Option<Vec<u8>>>,
+    pub object_changes: BytesArray,
+    pub balance_changes: BytesArray,
+    pub events: BytesArray,
     pub transaction_kind: i16,
 }
 
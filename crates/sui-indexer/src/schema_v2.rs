@@ -2,6 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 // @generated automatically by Diesel CLI.
 
+pub mod sql_types {
+    /// An array of nullable byte strings: `bytea[]` in Postgres, and BCS encoded into a `BLOB` in
+    /// SQLite, which has no array type. See `crate::db` for how values are (de)serialized.
+    #[derive(diesel::query_builder::QueryId, diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(oid = 1001, array_oid = 0))]
+    #[diesel(sqlite_type(name = "Binary"))]
+    pub struct BytesArray;
+}
+
 diesel::table! {
     active_addresses (address) {
         address -> Bytea,
@@ -34,6 +43,18 @@ diesel::table! {
 }
 
 diesel::table! {
+    backfill_progress (task_name, range_start) {
+        task_name -> Text,
+        range_start -> Int8,
+        range_end -> Int8,
+        next_checkpoint -> Int8,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::BytesArray;
+
     checkpoints (sequence_number) {
         sequence_number -> Int8,
         checkpoint_digest -> Bytea,
@@ -41,7 +62,7 @@ diesel::table! {
         network_total_transactions -> Int8,
         previous_checkpoint_digest -> Nullable<Bytea>,
         end_of_epoch -> Bool,
-        tx_digests -> Array<Nullable<Bytea>>,
+        tx_digests -> BytesArray,
         timestamp_ms -> Int8,
         total_gas_cost -> Int8,
         computation_cost -> Int8,
@@ -64,9 +85,12 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::BytesArray;
+
     epochs (epoch) {
         epoch -> Int8,
-        validators -> Array<Nullable<Bytea>>,
+        validators -> BytesArray,
         first_checkpoint_id -> Int8,
         epoch_start_timestamp -> Int8,
         reference_gas_price -> Int8,
@@ -90,12 +114,15 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::BytesArray;
+
     events (tx_sequence_number, event_sequence_number) {
         tx_sequence_number -> Int8,
         event_sequence_number -> Int8,
         transaction_digest -> Bytea,
         checkpoint_sequence_number -> Int8,
-        senders -> Array<Nullable<Bytea>>,
+        senders -> BytesArray,
         package -> Bytea,
         module -> Text,
         event_type -> Text,
@@ -169,6 +196,9 @@ diesel::table! {
 }
 
 diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::BytesArray;
+
     transactions (tx_sequence_number) {
         tx_sequence_number -> Int8,
         transaction_digest -> Bytea,
@@ -176,9 +206,9 @@ diesel::table! {
         raw_effects -> Bytea,
         checkpoint_sequence_number -> Int8,
         timestamp_ms -> Int8,
-        object_changes -> Array<Nullable<Bytea>>,
-        balance_changes -> Array<Nullable<Bytea>>,
-        events -> Array<Nullable<Bytea>>,
+        object_changes -> BytesArray,
+        balance_changes -> BytesArray,
+        events -> BytesArray,
         transaction_kind -> Int2,
     }
 }
@@ -254,6 +284,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     active_addresses,
     address_metrics,
     addresses,
+    backfill_progress,
     checkpoints,
     display,
     epochs,
@@ -16,19 +16,18 @@ use std::sync::{Arc, Mutex};
 use sui_types::base_types::ObjectID;
 use sui_types::move_package::MovePackage;
 
+use crate::db::{read_only_blocking_any, ConnectionPool};
 use crate::errors::{Context, IndexerError};
 use crate::models_v2::packages::StoredPackage;
-use crate::store::diesel_macro::read_only_blocking;
-use crate::PgConnectionPool;
 
 /// A package resolver that reads packages from the database.
 pub struct IndexerStoreModuleResolver {
-    cp: PgConnectionPool,
+    cp: ConnectionPool,
 }
 
 impl IndexerStoreModuleResolver {
     #[allow(dead_code)]
-    pub fn new(cp: PgConnectionPool) -> Self {
+    pub fn new(cp: ConnectionPool) -> Self {
         Self { cp }
     }
 }
@@ -42,7 +41,7 @@ impl ModuleResolver for IndexerStoreModuleResolver {
 
         // Note: this implementation is potentially vulnerable to package upgrade race conditions
         // for framework packages because they reuse the same package IDs.
-        let stored_package: StoredPackage = read_only_blocking!(&self.cp, |conn| {
+        let stored_package: StoredPackage = read_only_blocking_any!(&self.cp, |conn| {
             packages::dsl::packages
                 .filter(packages::dsl::package_id.eq(package_id))
                 .first::<StoredPackage>(conn)
@@ -25,10 +25,12 @@ use sui_types::base_types::{ObjectID, SequenceNumber};
 use sui_types::object::ObjectRead;
 
 use crate::errors::{Context, IndexerError};
+use crate::framework::backfill::{BackfillProgress, BackfillProgressStore};
 use crate::handlers::EpochToCommit;
 use crate::handlers::TransactionObjectChangesToCommit;
 use crate::metrics::IndexerMetrics;
 
+use crate::models_v2::backfill_progress::StoredBackfillProgress;
 use crate::models_v2::checkpoints::StoredCheckpoint;
 use crate::models_v2::display::StoredDisplay;
 use crate::models_v2::epoch::StoredEpochInfo;
@@ -37,8 +39,8 @@ use crate::models_v2::objects::StoredObject;
 use crate::models_v2::packages::StoredPackage;
 use crate::models_v2::transactions::StoredTransaction;
 use crate::schema_v2::{
-    checkpoints, display, epochs, events, objects, packages, transactions, tx_calls,
-    tx_changed_objects, tx_input_objects, tx_recipients, tx_senders,
+    backfill_progress, checkpoints, display, epochs, events, objects, packages, transactions,
+    tx_calls, tx_changed_objects, tx_input_objects, tx_recipients, tx_senders,
 };
 use crate::store::diesel_macro::{read_only_blocking, transactional_blocking_with_retry};
 use crate::store::module_resolver_v2::IndexerStoreModuleResolver;
@@ -85,7 +87,7 @@ pub struct PgIndexerStoreV2 {
 impl PgIndexerStoreV2 {
     pub fn new(blocking_cp: PgConnectionPool, metrics: IndexerMetrics) -> Self {
         let module_cache: Arc<SyncModuleCache<IndexerStoreModuleResolver>> = Arc::new(
-            SyncModuleCache::new(IndexerStoreModuleResolver::new(blocking_cp.clone())),
+            SyncModuleCache::new(IndexerStoreModuleResolver::new(blocking_cp.clone().into())),
         );
         let parallel_chunk_size = std::env::var("PG_COMMIT_PARALLEL_CHUNK_SIZE")
             .unwrap_or_else(|_e| PG_COMMIT_PARALLEL_CHUNK_SIZE_PER_DB_TX.to_string())
@@ -648,6 +650,71 @@ impl PgIndexerStoreV2 {
         .map(|v| v as u64)
     }
 
+    fn get_backfill_progress(
+        &self,
+        task_name: String,
+    ) -> Result<Vec<BackfillProgress>, IndexerError> {
+        read_only_blocking!(&self.blocking_cp, |conn| {
+            backfill_progress::table
+                .filter(backfill_progress::task_name.eq(task_name))
+                .load::<StoredBackfillProgress>(conn)
+        })
+        .context("Failed reading backfill progress from PostgresDB")
+        .map(|progress| progress.into_iter().map(BackfillProgress::from).collect())
+    }
+
+    fn reset_backfill_progress(
+        &self,
+        task_name: String,
+        progress: Vec<BackfillProgress>,
+    ) -> Result<(), IndexerError> {
+        let progress = progress
+            .into_iter()
+            .map(|p| StoredBackfillProgress::new(&task_name, p))
+            .collect::<Vec<_>>();
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::delete(
+                    backfill_progress::table
+                        .filter(backfill_progress::task_name.eq(task_name.as_str())),
+                )
+                .execute(conn)?;
+                for chunk in progress.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(backfill_progress::table)
+                        .values(chunk)
+                        .execute(conn)?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+        .context("Failed to write backfill progress to PostgresDB")
+    }
+
+    fn update_backfill_progress(
+        &self,
+        task_name: String,
+        range_start: u64,
+        next_checkpoint: u64,
+    ) -> Result<(), IndexerError> {
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                diesel::update(
+                    backfill_progress::table
+                        .filter(backfill_progress::task_name.eq(task_name.as_str()))
+                        .filter(backfill_progress::range_start.eq(range_start as i64)),
+                )
+                .set(backfill_progress::next_checkpoint.eq(next_checkpoint as i64))
+                .execute(conn)?;
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+        .context("Failed to update backfill progress in PostgresDB")
+    }
+
     async fn execute_in_blocking_worker<F, R>(&self, f: F) -> Result<R, IndexerError>
     where
         F: FnOnce(Self) -> Result<R, IndexerError> + Send + 'static,
@@ -889,7 +956,7 @@ impl IndexerStoreV2 for PgIndexerStoreV2 {
 /// Construct deleted objects and mutated objects to commit.
 /// In particular, filter mutated objects updates that would
 /// be override immediately.
-fn make_final_list_of_objects_to_commit(
+pub(super) fn make_final_list_of_objects_to_commit(
     tx_object_changes: Vec<TransactionObjectChangesToCommit>,
 ) -> Vec<ObjectChangeToCommit> {
     let deleted_objects = tx_object_changes
@@ -930,7 +997,47 @@ fn make_final_list_of_objects_to_commit(
 }
 
 #[allow(clippy::large_enum_variant)]
-enum ObjectChangeToCommit {
+pub(super) enum ObjectChangeToCommit {
     MutatedObject(StoredObject),
     DeletedObject(ObjectID),
 }
+
+#[async_trait]
+impl BackfillProgressStore for PgIndexerStoreV2 {
+    async fn get_backfill_progress(
+        &self,
+        task_name: &str,
+    ) -> anyhow::Result<Vec<BackfillProgress>> {
+        let task_name = task_name.to_string();
+        Ok(self
+            .execute_in_blocking_worker(move |this| this.get_backfill_progress(task_name))
+            .await?)
+    }
+
+    async fn reset_backfill_progress(
+        &self,
+        task_name: &str,
+        progress: Vec<BackfillProgress>,
+    ) -> anyhow::Result<()> {
+        let task_name = task_name.to_string();
+        Ok(self
+            .execute_in_blocking_worker(move |this| {
+                this.reset_backfill_progress(task_name, progress)
+            })
+            .await?)
+    }
+
+    async fn update_backfill_progress(
+        &self,
+        task_name: &str,
+        range_start: u64,
+        next_checkpoint: u64,
+    ) -> anyhow::Result<()> {
+        let task_name = task_name.to_string();
+        Ok(self
+            .execute_in_blocking_worker(move |this| {
+                this.update_backfill_progress(task_name, range_start, next_checkpoint)
+            })
+            .await?)
+    }
+}
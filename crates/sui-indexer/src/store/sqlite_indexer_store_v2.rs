@@ -0,0 +1,703 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tap::Tap;
+
+use async_trait::async_trait;
+use diesel::dsl::max;
+use diesel::upsert::excluded;
+use diesel::ExpressionMethods;
+use diesel::OptionalExtension;
+use diesel::SqliteConnection;
+use diesel::{QueryDsl, RunQueryDsl};
+use move_bytecode_utils::module_cache::SyncModuleCache;
+use tracing::info;
+
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::object::ObjectRead;
+
+use crate::db::{get_sqlite_pool_connection, SqliteConnectionPool};
+use crate::errors::{Context, IndexerError};
+use crate::framework::backfill::{BackfillProgress, BackfillProgressStore};
+use crate::handlers::EpochToCommit;
+use crate::handlers::TransactionObjectChangesToCommit;
+use crate::metrics::IndexerMetrics;
+
+use crate::models_v2::backfill_progress::StoredBackfillProgress;
+use crate::models_v2::checkpoints::StoredCheckpoint;
+use crate::models_v2::display::StoredDisplay;
+use crate::models_v2::epoch::StoredEpochInfo;
+use crate::models_v2::events::StoredEvent;
+use crate::models_v2::objects::StoredObject;
+use crate::models_v2::packages::StoredPackage;
+use crate::models_v2::transactions::StoredTransaction;
+use crate::schema_v2::{
+    backfill_progress, checkpoints, display, epochs, events, objects, packages, transactions,
+    tx_calls, tx_changed_objects, tx_input_objects, tx_recipients, tx_senders,
+};
+use crate::store::module_resolver_v2::IndexerStoreModuleResolver;
+use crate::types_v2::{
+    IndexedCheckpoint, IndexedEvent, IndexedPackage, IndexedTransaction, TxIndex,
+};
+
+use super::pg_indexer_store_v2::{make_final_list_of_objects_to_commit, ObjectChangeToCommit};
+use super::IndexerStoreV2;
+
+// The amount of rows to insert in one statement. SQLite limits the number of bound parameters per
+// statement, so this needs to stay well below that limit divided by the widest table's columns.
+const SQLITE_COMMIT_CHUNK_SIZE: usize = 500;
+
+/// An [`IndexerStoreV2`] backed by an embedded SQLite database.
+///
+/// SQLite only allows one writer at a time, so unlike [`super::PgIndexerStoreV2`], each batch of
+/// data is written serially, in a single transaction.
+#[derive(Clone)]
+pub struct SqliteIndexerStoreV2 {
+    blocking_cp: SqliteConnectionPool,
+    module_cache: Arc<SyncModuleCache<IndexerStoreModuleResolver>>,
+    metrics: IndexerMetrics,
+}
+
+impl SqliteIndexerStoreV2 {
+    pub fn new(blocking_cp: SqliteConnectionPool, metrics: IndexerMetrics) -> Self {
+        let module_cache: Arc<SyncModuleCache<IndexerStoreModuleResolver>> = Arc::new(
+            SyncModuleCache::new(IndexerStoreModuleResolver::new(blocking_cp.clone().into())),
+        );
+        Self {
+            blocking_cp,
+            module_cache,
+            metrics,
+        }
+    }
+
+    fn read_only<T>(
+        &self,
+        query: impl FnOnce(&mut SqliteConnection) -> Result<T, IndexerError>,
+    ) -> Result<T, IndexerError> {
+        let mut conn = get_sqlite_pool_connection(&self.blocking_cp)?;
+        diesel::Connection::transaction(&mut *conn, query)
+            .map_err(|e| IndexerError::SqliteReadError(e.to_string()))
+    }
+
+    /// Runs `query` in a transaction that takes the database's write lock up front, so that it
+    /// waits for (rather than fails on) other writers.
+    fn transactional<T>(
+        &self,
+        query: impl FnOnce(&mut SqliteConnection) -> Result<T, IndexerError>,
+    ) -> Result<T, IndexerError> {
+        let mut conn = get_sqlite_pool_connection(&self.blocking_cp)?;
+        conn.immediate_transaction(query)
+            .map_err(|e| IndexerError::SqliteWriteError(e.to_string()))
+    }
+
+    fn get_latest_tx_checkpoint_sequence_number(&self) -> Result<Option<u64>, IndexerError> {
+        self.read_only(|conn| {
+            Ok(checkpoints::dsl::checkpoints
+                .select(max(checkpoints::sequence_number))
+                .first::<Option<i64>>(conn)?
+                .map(|v| v as u64))
+        })
+        .context("Failed reading latest checkpoint sequence number from SQLite DB")
+    }
+
+    // Note: here we treat Deleted as NotExists too
+    fn get_object_read(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> Result<ObjectRead, IndexerError> {
+        self.read_only(|conn| {
+            let query =
+                objects::dsl::objects.filter(objects::dsl::object_id.eq(object_id.to_vec()));
+            let boxed_query = if let Some(version) = version {
+                query
+                    .filter(objects::dsl::object_version.eq(version.value() as i64))
+                    .into_boxed()
+            } else {
+                query.into_boxed()
+            };
+            match boxed_query.first::<StoredObject>(conn).optional()? {
+                None => Ok(ObjectRead::NotExists(object_id)),
+                Some(obj) => obj.try_into_object_read(self.module_cache.as_ref()),
+            }
+        })
+        .context("Failed to read object from SQLite DB")
+    }
+
+    fn persist_display_updates(
+        &self,
+        display_updates: BTreeMap<String, StoredDisplay>,
+    ) -> Result<(), IndexerError> {
+        self.transactional(|conn| {
+            diesel::insert_into(display::table)
+                .values(display_updates.values().collect::<Vec<_>>())
+                .on_conflict(display::object_type)
+                .do_update()
+                .set((
+                    display::id.eq(excluded(display::id)),
+                    display::version.eq(excluded(display::version)),
+                    display::bcs.eq(excluded(display::bcs)),
+                ))
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed to write display updates to SQLite DB")?;
+            Ok(())
+        })
+    }
+
+    fn persist_objects(
+        &self,
+        object_changes: Vec<TransactionObjectChangesToCommit>,
+    ) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_objects
+            .start_timer();
+
+        let mut mutated_objects = vec![];
+        let mut deleted_object_ids = vec![];
+        for object in make_final_list_of_objects_to_commit(object_changes) {
+            match object {
+                ObjectChangeToCommit::MutatedObject(o) => {
+                    mutated_objects.push(o);
+                }
+                ObjectChangeToCommit::DeletedObject(id) => {
+                    deleted_object_ids.push(id);
+                }
+            }
+        }
+
+        self.transactional(|conn| {
+            for mutated_object_change_chunk in mutated_objects.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                diesel::insert_into(objects::table)
+                    .values(mutated_object_change_chunk)
+                    .on_conflict(objects::object_id)
+                    .do_update()
+                    .set((
+                        objects::object_id.eq(excluded(objects::object_id)),
+                        objects::object_version.eq(excluded(objects::object_version)),
+                        objects::object_digest.eq(excluded(objects::object_digest)),
+                        objects::checkpoint_sequence_number
+                            .eq(excluded(objects::checkpoint_sequence_number)),
+                        objects::owner_type.eq(excluded(objects::owner_type)),
+                        objects::owner_id.eq(excluded(objects::owner_id)),
+                        objects::object_type.eq(excluded(objects::object_type)),
+                        objects::serialized_object.eq(excluded(objects::serialized_object)),
+                        objects::coin_type.eq(excluded(objects::coin_type)),
+                        objects::coin_balance.eq(excluded(objects::coin_balance)),
+                        objects::df_kind.eq(excluded(objects::df_kind)),
+                        objects::df_name.eq(excluded(objects::df_name)),
+                        objects::df_object_type.eq(excluded(objects::df_object_type)),
+                        objects::df_object_id.eq(excluded(objects::df_object_id)),
+                    ))
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write object mutation to SQLite DB")?;
+            }
+
+            for deleted_objects_chunk in deleted_object_ids.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                diesel::delete(
+                    objects::table.filter(
+                        objects::object_id.eq_any(
+                            deleted_objects_chunk
+                                .iter()
+                                .map(|o| o.to_vec())
+                                .collect::<Vec<_>>(),
+                        ),
+                    ),
+                )
+                .execute(conn)
+                .map_err(IndexerError::from)
+                .context("Failed to write object deletion to SQLite DB")?;
+            }
+
+            Ok(())
+        })
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(
+                elapsed,
+                "Persisted {} objects",
+                mutated_objects.len() + deleted_object_ids.len(),
+            )
+        })
+    }
+
+    fn persist_checkpoints(&self, checkpoints: Vec<IndexedCheckpoint>) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_checkpoints
+            .start_timer();
+
+        let checkpoints = checkpoints
+            .iter()
+            .map(StoredCheckpoint::from)
+            .collect::<Vec<_>>();
+        self.transactional(|conn| {
+            for checkpoint_chunk in checkpoints.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                diesel::insert_into(checkpoints::table)
+                    .values(checkpoint_chunk)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write checkpoints to SQLite DB")?;
+            }
+            Ok(())
+        })
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(elapsed, "Persisted {} checkpoints", checkpoints.len());
+        })
+    }
+
+    fn persist_transactions(
+        &self,
+        transactions: Vec<IndexedTransaction>,
+    ) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_transactions
+            .start_timer();
+        let transactions = transactions
+            .iter()
+            .map(StoredTransaction::from)
+            .collect::<Vec<_>>();
+
+        self.transactional(|conn| {
+            for transaction_chunk in transactions.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                diesel::insert_into(transactions::table)
+                    .values(transaction_chunk)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write transactions to SQLite DB")?;
+            }
+            Ok(())
+        })
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(elapsed, "Persisted {} transactions", transactions.len())
+        })
+    }
+
+    fn persist_events(&self, events: Vec<IndexedEvent>) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_events
+            .start_timer();
+        let events = events
+            .into_iter()
+            .map(StoredEvent::from)
+            .collect::<Vec<_>>();
+
+        self.transactional(|conn| {
+            for event_chunk in events.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                diesel::insert_into(events::table)
+                    .values(event_chunk)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write events to SQLite DB")?;
+            }
+            Ok(())
+        })
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(elapsed, "Persisted {} events", events.len())
+        })
+    }
+
+    fn persist_packages(&self, packages: Vec<IndexedPackage>) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_packages
+            .start_timer();
+        let packages = packages
+            .into_iter()
+            .map(StoredPackage::from)
+            .collect::<Vec<_>>();
+        self.transactional(|conn| {
+            for packages_chunk in packages.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                diesel::insert_into(packages::table)
+                    .values(packages_chunk)
+                    // System packages such as 0x2/0x9 will have their package_id
+                    // unchanged during upgrades. In this case, we override the modules
+                    .on_conflict(packages::package_id)
+                    .do_update()
+                    .set(packages::move_package.eq(excluded(packages::move_package)))
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write packages to SQLite DB")?;
+            }
+            Ok(())
+        })
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(elapsed, "Persisted {} packages", packages.len())
+        })
+    }
+
+    fn persist_tx_indices(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_tx_indices
+            .start_timer();
+        let len = indices.len();
+        let (mut senders, mut recipients, mut input_objects, mut changed_objects, mut calls) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for index in indices {
+            let (s, r, i, c, f) = index.split();
+            senders.extend(s);
+            recipients.extend(r);
+            input_objects.extend(i);
+            changed_objects.extend(c);
+            calls.extend(f);
+        }
+
+        self.transactional(|conn| {
+            for chunk in senders.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                diesel::insert_into(tx_senders::table)
+                    .values(chunk)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write tx_senders to SQLite DB")?;
+            }
+            for chunk in recipients.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                diesel::insert_into(tx_recipients::table)
+                    .values(chunk)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write tx_recipients to SQLite DB")?;
+            }
+            for chunk in input_objects.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                diesel::insert_into(tx_input_objects::table)
+                    .values(chunk)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write tx_input_objects to SQLite DB")?;
+            }
+            for chunk in changed_objects.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                diesel::insert_into(tx_changed_objects::table)
+                    .values(chunk)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write tx_changed_objects to SQLite DB")?;
+            }
+            for chunk in calls.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                diesel::insert_into(tx_calls::table)
+                    .values(chunk)
+                    .on_conflict_do_nothing()
+                    .execute(conn)
+                    .map_err(IndexerError::from)
+                    .context("Failed to write tx_calls to SQLite DB")?;
+            }
+            Ok(())
+        })
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(elapsed, "Persisted {} tx_indices", len)
+        })
+    }
+
+    fn persist_epoch(&self, data: &[EpochToCommit]) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_epochs
+            .start_timer();
+        self.transactional(|conn| {
+            for epoch_data in data {
+                if let Some(last_epoch) = &epoch_data.last_epoch {
+                    let last_epoch_id = last_epoch.epoch;
+                    let last_epoch = StoredEpochInfo::from_epoch_end_info(last_epoch);
+                    info!(last_epoch_id, "Persisting epoch end data: {:?}", last_epoch);
+                    diesel::insert_into(epochs::table)
+                        .values(last_epoch)
+                        .on_conflict(epochs::epoch)
+                        .do_update()
+                        .set((
+                            // Note: as with Postgres, the epoch beginning info (validators,
+                            // first_checkpoint_id, epoch_start_timestamp, etc.) must not be
+                            // overridden here.
+                            epochs::epoch_total_transactions
+                                .eq(excluded(epochs::epoch_total_transactions)),
+                            epochs::last_checkpoint_id.eq(excluded(epochs::last_checkpoint_id)),
+                            epochs::epoch_end_timestamp.eq(excluded(epochs::epoch_end_timestamp)),
+                            epochs::storage_fund_reinvestment
+                                .eq(excluded(epochs::storage_fund_reinvestment)),
+                            epochs::storage_charge.eq(excluded(epochs::storage_charge)),
+                            epochs::storage_rebate.eq(excluded(epochs::storage_rebate)),
+                            epochs::storage_fund_balance.eq(excluded(epochs::storage_fund_balance)),
+                            epochs::stake_subsidy_amount.eq(excluded(epochs::stake_subsidy_amount)),
+                            epochs::total_gas_fees.eq(excluded(epochs::total_gas_fees)),
+                            epochs::total_stake_rewards_distributed
+                                .eq(excluded(epochs::total_stake_rewards_distributed)),
+                            epochs::leftover_storage_fund_inflow
+                                .eq(excluded(epochs::leftover_storage_fund_inflow)),
+                            epochs::new_total_stake.eq(excluded(epochs::new_total_stake)),
+                            epochs::epoch_commitments.eq(excluded(epochs::epoch_commitments)),
+                            epochs::next_epoch_reference_gas_price
+                                .eq(excluded(epochs::next_epoch_reference_gas_price)),
+                            epochs::next_epoch_protocol_version
+                                .eq(excluded(epochs::next_epoch_protocol_version)),
+                        ))
+                        .execute(conn)?;
+                }
+                let epoch_id = epoch_data.new_epoch.epoch;
+                info!(epoch_id, "Persisting initial epoch state");
+                let new_epoch = StoredEpochInfo::from_epoch_beginning_info(&epoch_data.new_epoch);
+                diesel::insert_into(epochs::table)
+                    .values(new_epoch)
+                    .on_conflict_do_nothing()
+                    .execute(conn)?;
+            }
+            Ok(())
+        })
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(elapsed, "Persisted {} epochs", data.len())
+        })
+    }
+
+    fn get_network_total_transactions_by_end_of_epoch(
+        &self,
+        epoch: u64,
+    ) -> Result<u64, IndexerError> {
+        self.read_only(|conn| {
+            Ok(checkpoints::table
+                .filter(checkpoints::epoch.eq(epoch as i64))
+                .select(max(checkpoints::network_total_transactions))
+                .first::<Option<i64>>(conn)?
+                .unwrap_or(0))
+        })
+        .context("Failed to get network total transactions in epoch")
+        .map(|v| v as u64)
+    }
+
+    fn get_backfill_progress(
+        &self,
+        task_name: String,
+    ) -> Result<Vec<BackfillProgress>, IndexerError> {
+        self.read_only(|conn| {
+            Ok(backfill_progress::table
+                .filter(backfill_progress::task_name.eq(task_name))
+                .load::<StoredBackfillProgress>(conn)?)
+        })
+        .context("Failed reading backfill progress from SQLite DB")
+        .map(|progress| progress.into_iter().map(BackfillProgress::from).collect())
+    }
+
+    fn reset_backfill_progress(
+        &self,
+        task_name: String,
+        progress: Vec<BackfillProgress>,
+    ) -> Result<(), IndexerError> {
+        let progress = progress
+            .into_iter()
+            .map(|p| StoredBackfillProgress::new(&task_name, p))
+            .collect::<Vec<_>>();
+        self.transactional(|conn| {
+            diesel::delete(
+                backfill_progress::table
+                    .filter(backfill_progress::task_name.eq(task_name.as_str())),
+            )
+            .execute(conn)?;
+            for chunk in progress.chunks(SQLITE_COMMIT_CHUNK_SIZE) {
+                diesel::insert_into(backfill_progress::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            Ok(())
+        })
+        .context("Failed to write backfill progress to SQLite DB")
+    }
+
+    fn update_backfill_progress(
+        &self,
+        task_name: String,
+        range_start: u64,
+        next_checkpoint: u64,
+    ) -> Result<(), IndexerError> {
+        self.transactional(|conn| {
+            diesel::update(
+                backfill_progress::table
+                    .filter(backfill_progress::task_name.eq(task_name.as_str()))
+                    .filter(backfill_progress::range_start.eq(range_start as i64)),
+            )
+            .set(backfill_progress::next_checkpoint.eq(next_checkpoint as i64))
+            .execute(conn)?;
+            Ok(())
+        })
+        .context("Failed to update backfill progress in SQLite DB")
+    }
+
+    async fn execute_in_blocking_worker<F, R>(&self, f: F) -> Result<R, IndexerError>
+    where
+        F: FnOnce(Self) -> Result<R, IndexerError> + Send + 'static,
+        R: Send + 'static,
+    {
+        let this = self.clone();
+        let current_span = tracing::Span::current();
+        tokio::task::spawn_blocking(move || {
+            let _guard = current_span.enter();
+            f(this)
+        })
+        .await
+        .map_err(Into::into)
+        .and_then(std::convert::identity)
+    }
+}
+
+#[async_trait]
+impl IndexerStoreV2 for SqliteIndexerStoreV2 {
+    type ModuleCache = SyncModuleCache<IndexerStoreModuleResolver>;
+
+    async fn get_latest_tx_checkpoint_sequence_number(&self) -> Result<Option<u64>, IndexerError> {
+        self.execute_in_blocking_worker(|this| this.get_latest_tx_checkpoint_sequence_number())
+            .await
+    }
+
+    async fn get_object_read(
+        &self,
+        object_id: ObjectID,
+        version: Option<SequenceNumber>,
+    ) -> Result<ObjectRead, IndexerError> {
+        self.execute_in_blocking_worker(move |this| this.get_object_read(object_id, version))
+            .await
+    }
+
+    async fn persist_objects(
+        &self,
+        object_changes: Vec<TransactionObjectChangesToCommit>,
+    ) -> Result<(), IndexerError> {
+        if object_changes.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_objects(object_changes))
+            .await
+    }
+
+    async fn persist_checkpoints(
+        &self,
+        checkpoints: Vec<IndexedCheckpoint>,
+    ) -> Result<(), IndexerError> {
+        if checkpoints.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_checkpoints(checkpoints))
+            .await
+    }
+
+    async fn persist_transactions(
+        &self,
+        transactions: Vec<IndexedTransaction>,
+    ) -> Result<(), IndexerError> {
+        if transactions.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_transactions(transactions))
+            .await
+    }
+
+    async fn persist_events(&self, events: Vec<IndexedEvent>) -> Result<(), IndexerError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_events(events))
+            .await
+    }
+
+    async fn persist_displays(
+        &self,
+        display_updates: BTreeMap<String, StoredDisplay>,
+    ) -> Result<(), IndexerError> {
+        if display_updates.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_display_updates(display_updates))
+            .await
+    }
+
+    async fn persist_packages(&self, packages: Vec<IndexedPackage>) -> Result<(), IndexerError> {
+        if packages.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_packages(packages))
+            .await
+    }
+
+    async fn persist_tx_indices(&self, indices: Vec<TxIndex>) -> Result<(), IndexerError> {
+        if indices.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_tx_indices(indices))
+            .await
+    }
+
+    async fn persist_epoch(&self, data: Vec<EpochToCommit>) -> Result<(), IndexerError> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        self.execute_in_blocking_worker(move |this| this.persist_epoch(&data))
+            .await
+    }
+
+    async fn get_network_total_transactions_by_end_of_epoch(
+        &self,
+        epoch: u64,
+    ) -> Result<u64, IndexerError> {
+        self.execute_in_blocking_worker(move |this| {
+            this.get_network_total_transactions_by_end_of_epoch(epoch)
+        })
+        .await
+    }
+
+    fn module_cache(&self) -> Arc<Self::ModuleCache> {
+        self.module_cache.clone()
+    }
+}
+
+#[async_trait]
+impl BackfillProgressStore for SqliteIndexerStoreV2 {
+    async fn get_backfill_progress(
+        &self,
+        task_name: &str,
+    ) -> anyhow::Result<Vec<BackfillProgress>> {
+        let task_name = task_name.to_string();
+        Ok(self
+            .execute_in_blocking_worker(move |this| this.get_backfill_progress(task_name))
+            .await?)
+    }
+
+    async fn reset_backfill_progress(
+        &self,
+        task_name: &str,
+        progress: Vec<BackfillProgress>,
+    ) -> anyhow::Result<()> {
+        let task_name = task_name.to_string();
+        Ok(self
+            .execute_in_blocking_worker(move |this| {
+                this.reset_backfill_progress(task_name, progress)
+            })
+            .await?)
+    }
+
+    async fn update_backfill_progress(
+        &self,
+        task_name: &str,
+        range_start: u64,
+        next_checkpoint: u64,
+    ) -> anyhow::Result<()> {
+        let task_name = task_name.to_string();
+        Ok(self
+            .execute_in_blocking_worker(move |this| {
+                this.update_backfill_progress(task_name, range_start, next_checkpoint)
+            })
+            .await?)
+    }
+}
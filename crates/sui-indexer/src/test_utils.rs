@@ -7,9 +7,11 @@ use tokio::task::JoinHandle;
 
 use sui_json_rpc_types::SuiTransactionBlockResponse;
 
+use crate::db::{new_connection_pool, ConnectionPool};
 use crate::errors::IndexerError;
-use crate::store::PgIndexerStore;
-use crate::utils::reset_database;
+use crate::indexer_v2::IndexerV2;
+use crate::store::{PgIndexerStore, PgIndexerStoreV2, SqliteIndexerStoreV2};
+use crate::utils::{reset_database, reset_database_v2};
 use crate::IndexerMetrics;
 use crate::{new_pg_connection_pool, Indexer, IndexerConfig};
 
@@ -41,6 +43,41 @@ pub async fn start_test_indexer(
     Ok((store, handle))
 }
 
+/// Spawns a V2 indexer, writing to and serving JSON-RPC requests from the database at
+/// `config.db_url`, which can be either Postgres or SQLite.
+pub async fn start_test_indexer_v2(
+    config: IndexerConfig,
+) -> Result<JoinHandle<Result<(), IndexerError>>, anyhow::Error> {
+    let db_url = config.get_db_url()?;
+    let pool = new_connection_pool(&db_url)
+        .map_err(|e| anyhow!("unable to connect to the database at {db_url}: {e}"))?;
+    if config.reset_db {
+        reset_database_v2(&pool, true)?;
+    }
+
+    let registry = Registry::default();
+    mysten_metrics::init_metrics(&registry);
+    let indexer_metrics = IndexerMetrics::new(&registry);
+
+    let handle = tokio::spawn(async move {
+        let writer = async {
+            match pool {
+                ConnectionPool::Postgres(pool) => {
+                    let store = PgIndexerStoreV2::new(pool, indexer_metrics.clone());
+                    IndexerV2::start_writer(&config, &registry, store, indexer_metrics).await
+                }
+                ConnectionPool::Sqlite(pool) => {
+                    let store = SqliteIndexerStoreV2::new(pool, indexer_metrics.clone());
+                    IndexerV2::start_writer(&config, &registry, store, indexer_metrics).await
+                }
+            }
+        };
+        let reader = IndexerV2::start_reader(&config, &registry, db_url.clone());
+        tokio::try_join!(writer, reader).map(|_| ())
+    });
+    Ok(handle)
+}
+
 #[derive(Clone)]
 pub struct SuiTransactionBlockResponseBuilder<'a> {
     response: SuiTransactionBlockResponse,
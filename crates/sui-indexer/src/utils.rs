@@ -1,15 +1,17 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::PgPoolConnection;
+use crate::db::{get_sqlite_pool_connection, ConnectionPool};
+use crate::{get_pg_pool_connection, PgPoolConnection};
 use anyhow::anyhow;
 use diesel::migration::MigrationSource;
-use diesel::{PgConnection, RunQueryDsl};
+use diesel::{PgConnection, RunQueryDsl, SqliteConnection};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use tracing::info;
 
 const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 const MIGRATIONS_V2: EmbeddedMigrations = embed_migrations!("migrations_v2");
+const MIGRATIONS_SQLITE: EmbeddedMigrations = embed_migrations!("migrations_sqlite");
 
 /// Resets the database by reverting all migrations and reapplying them.
 ///
@@ -64,3 +66,49 @@ pub fn drop_all_tables(conn: &mut PgConnection) -> Result<(), diesel::result::Er
     info!("Dropped all tables in the database");
     Ok(())
 }
+
+/// Resets a database using the V2 schema, on either of the supported backends. See
+/// [`reset_database`] for the meaning of `drop_all`.
+pub fn reset_database_v2(pool: &ConnectionPool, drop_all: bool) -> Result<(), anyhow::Error> {
+    match pool {
+        ConnectionPool::Postgres(pool) => {
+            let mut conn = get_pg_pool_connection(pool)?;
+            reset_database(&mut conn, drop_all, /* use_v2 */ true)
+        }
+        ConnectionPool::Sqlite(pool) => {
+            let mut conn = get_sqlite_pool_connection(pool)?;
+            reset_sqlite_database(&mut conn, drop_all)
+        }
+    }
+}
+
+fn reset_sqlite_database(conn: &mut SqliteConnection, drop_all: bool) -> Result<(), anyhow::Error> {
+    info!("Resetting SQLite database ...");
+    if drop_all {
+        drop_all_sqlite_tables(conn)
+            .map_err(|e| anyhow!("Encountering error when dropping all tables {e}"))?;
+    } else {
+        conn.revert_all_migrations(MIGRATIONS_SQLITE)
+            .map_err(|e| anyhow!("Error reverting all migrations {e}"))?;
+    }
+    conn.run_pending_migrations(MIGRATIONS_SQLITE)
+        .map_err(|e| anyhow!("Failed to run migrations {e}"))?;
+    info!("Reset SQLite database complete.");
+    Ok(())
+}
+
+/// Drops every table, including diesel's migrations table, which is recreated when the migrations
+/// are next run.
+fn drop_all_sqlite_tables(conn: &mut SqliteConnection) -> Result<(), diesel::result::Error> {
+    info!("Dropping all tables in the SQLite database");
+    let table_names: Vec<String> = diesel::dsl::sql::<diesel::sql_types::Text>(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+    )
+    .load(conn)?;
+
+    for table_name in table_names {
+        diesel::sql_query(format!("DROP TABLE IF EXISTS {}", table_name)).execute(conn)?;
+    }
+    info!("Dropped all tables in the SQLite database");
+    Ok(())
+}
@@ -0,0 +1,67 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use sui_indexer::indexer_reader::IndexerReader;
+use sui_indexer::test_utils::start_test_indexer_v2;
+use sui_indexer::IndexerConfig;
+use sui_json_rpc_types::{CheckpointId, SuiTransactionBlockResponseOptions};
+use test_cluster::TestClusterBuilder;
+
+const WAIT_UNTIL_TIME_LIMIT: Duration = Duration::from_secs(60);
+
+/// Runs the V2 indexer against an embedded SQLite database, with no external services, and checks
+/// that what it wrote can be read back.
+#[tokio::test]
+async fn test_sqlite_indexer_v2() {
+    let dir = tempfile::tempdir().unwrap();
+    let db_url = format!("sqlite://{}", dir.path().join("indexer.db").display());
+
+    let cluster = TestClusterBuilder::new().build().await;
+    let config = IndexerConfig {
+        db_url: Some(db_url.clone()),
+        rpc_client_url: cluster.rpc_url().to_string(),
+        rpc_server_url: "127.0.0.1".to_string(),
+        rpc_server_port: 0,
+        reset_db: true,
+        use_v2: true,
+        ..Default::default()
+    };
+    let _handle = start_test_indexer_v2(config).await.unwrap();
+
+    let reader = IndexerReader::new(db_url).unwrap();
+    let checkpoint = tokio::time::timeout(WAIT_UNTIL_TIME_LIMIT, async {
+        loop {
+            let checkpoint = reader
+                .spawn_blocking(|this| this.get_checkpoint(CheckpointId::SequenceNumber(1)))
+                .await;
+            if let Ok(Some(checkpoint)) = checkpoint {
+                return checkpoint;
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    })
+    .await
+    .expect("Timed out waiting for the indexer to write checkpoint 1");
+    assert_eq!(checkpoint.sequence_number, 1);
+
+    // The genesis transaction is in checkpoint 0, which must have been written before checkpoint 1.
+    let genesis = reader
+        .spawn_blocking(|this| this.get_checkpoint(CheckpointId::SequenceNumber(0)))
+        .await
+        .unwrap()
+        .expect("Checkpoint 0 should have been indexed");
+    let responses = reader
+        .multi_get_transaction_block_response_in_blocking_task(
+            genesis.transactions.clone(),
+            SuiTransactionBlockResponseOptions::full_content(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(responses.len(), genesis.transactions.len());
+    for (response, digest) in responses.iter().zip(&genesis.transactions) {
+        assert_eq!(&response.digest, digest);
+        assert!(response.effects.is_some());
+    }
+}
@@ -0,0 +1,413 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, bail};
+use argon2::{Algorithm, Argon2, Params, Version};
+use fastcrypto::encoding::{Base64, Encoding};
+use once_cell::sync::OnceCell;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair};
+
+use crate::keystore::{AccountKeystore, Aliases};
+
+/// The passphrase an encrypted keystore is unlocked with is read from this environment variable,
+/// if it is set, instead of being prompted for.
+pub const SUI_KEYSTORE_PASSPHRASE_ENV_VAR: &str = "SUI_KEYSTORE_PASSPHRASE";
+/// The passphrase a keystore is newly encrypted with is read from this environment variable, if
+/// it is set, instead of being prompted for.
+pub const SUI_KEYSTORE_NEW_PASSPHRASE_ENV_VAR: &str = "SUI_KEYSTORE_NEW_PASSPHRASE";
+
+const ENCRYPTED_KEYSTORE_VERSION: u8 = 1;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+/// The contents of an encrypted keystore file. The private keys are encrypted with AES-256-GCM,
+/// under a key derived from the passphrase with Argon2id. The public keys are kept in the clear,
+/// so that the keystore's addresses can be listed without unlocking it, but are authenticated
+/// along with the private keys.
+#[derive(Serialize, Deserialize)]
+struct EncryptedKeystoreFile {
+    version: u8,
+    public_keys: Vec<String>,
+    kdf: KdfParams,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct KdfParams {
+    salt: String,
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+}
+
+impl KdfParams {
+    fn new() -> Self {
+        let mut salt = [0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            salt: Base64::encode(salt),
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        }
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<[u8; KEY_LENGTH], anyhow::Error> {
+        let salt = Base64::decode(&self.salt).map_err(|e| anyhow!("Invalid salt: {e}"))?;
+        let params = Params::new(self.m_cost, self.t_cost, self.p_cost, Some(KEY_LENGTH))
+            .map_err(|e| anyhow!("Invalid key derivation parameters: {e}"))?;
+        let mut key = [0u8; KEY_LENGTH];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| anyhow!("Cannot derive key from passphrase: {e}"))?;
+        Ok(key)
+    }
+}
+
+struct UnlockedKeys {
+    keys: BTreeMap<SuiAddress, SuiKeyPair>,
+    kdf: KdfParams,
+    key: [u8; KEY_LENGTH],
+}
+
+/// A keystore file like `FileBasedKeystore`'s, with the private keys encrypted under a passphrase.
+///
+/// The keystore is only unlocked, with the passphrase from the `SUI_KEYSTORE_PASSPHRASE`
+/// environment variable or from a prompt, the first time a private key is needed, so reading its
+/// addresses never asks for the passphrase.
+pub struct EncryptedFileBasedKeystore {
+    path: PathBuf,
+    public_keys: BTreeMap<SuiAddress, PublicKey>,
+    aliases: Aliases,
+    /// `None` until the keystore is first saved.
+    file: Option<EncryptedKeystoreFile>,
+    unlocked: OnceCell<UnlockedKeys>,
+}
+
+impl Serialize for EncryptedFileBasedKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.path.to_str().unwrap_or(""))
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptedFileBasedKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        EncryptedFileBasedKeystore::new(&PathBuf::from(String::deserialize(deserializer)?))
+            .map_err(D::Error::custom)
+    }
+}
+
+impl AccountKeystore for EncryptedFileBasedKeystore {
+    fn aliases(&self) -> &Aliases {
+        &self.aliases
+    }
+    fn aliases_mut(&mut self) -> &mut Aliases {
+        &mut self.aliases
+    }
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        Ok(Signature::new_hashed(
+            msg,
+            self.get_key(address)
+                .map_err(|e| signature::Error::from_source(e.to_string()))?,
+        ))
+    }
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        Ok(Signature::new_secure(
+            &IntentMessage::new(intent, msg),
+            self.get_key(address)
+                .map_err(|e| signature::Error::from_source(e.to_string()))?,
+        ))
+    }
+
+    fn add_key(&mut self, keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        self.unlock()?;
+        let address: SuiAddress = (&keypair.public()).into();
+        self.public_keys.insert(address, keypair.public());
+        self.unlocked
+            .get_mut()
+            .expect("keystore was unlocked above")
+            .keys
+            .insert(address, keypair);
+        self.save()
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        self.public_keys.values().cloned().collect()
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        if !self.public_keys.contains_key(address) {
+            bail!("Cannot find key for address: [{address}]");
+        }
+        self.unlock()?
+            .keys
+            .get(address)
+            .ok_or_else(|| anyhow!("Cannot find key for address: [{address}]"))
+    }
+}
+
+impl EncryptedFileBasedKeystore {
+    /// Opens the encrypted keystore at `path`, without unlocking it. If there is no keystore at
+    /// `path`, an empty one is created there when it is first saved.
+    pub fn new(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        let file = if path.exists() {
+            let contents = fs::read_to_string(path).map_err(|e| {
+                anyhow!("Can't open EncryptedFileBasedKeystore from {:?}: {e}", path)
+            })?;
+            let file: EncryptedKeystoreFile = serde_json::from_str(&contents).map_err(|e| {
+                anyhow!(
+                    "Can't deserialize EncryptedFileBasedKeystore from {:?}: {e}",
+                    path
+                )
+            })?;
+            if file.version != ENCRYPTED_KEYSTORE_VERSION {
+                bail!(
+                    "Unsupported encrypted keystore version {} in {:?}",
+                    file.version,
+                    path
+                );
+            }
+            Some(file)
+        } else {
+            None
+        };
+
+        let public_keys = file
+            .iter()
+            .flat_map(|file| &file.public_keys)
+            .map(|key| PublicKey::decode_base64(key).map(|key| (SuiAddress::from(&key), key)))
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .map_err(|e| anyhow!("Invalid public key in {:?}: {e}", path))?;
+
+        Ok(Self {
+            path: path.clone(),
+            public_keys,
+            aliases: Aliases::load(path)?,
+            file,
+            unlocked: OnceCell::new(),
+        })
+    }
+
+    /// Creates an encrypted keystore at `path` holding `keys`, replacing any file already there.
+    pub fn create(
+        path: &Path,
+        keys: impl IntoIterator<Item = SuiKeyPair>,
+        passphrase: &str,
+    ) -> Result<Self, anyhow::Error> {
+        let keys = keys
+            .into_iter()
+            .map(|key| ((&key.public()).into(), key))
+            .collect::<BTreeMap<SuiAddress, _>>();
+        let kdf = KdfParams::new();
+        let keystore = Self {
+            path: path.to_path_buf(),
+            public_keys: keys
+                .iter()
+                .map(|(address, key)| (*address, key.public()))
+                .collect(),
+            aliases: Aliases::load(path)?,
+            file: None,
+            unlocked: OnceCell::with_value(UnlockedKeys {
+                keys,
+                key: kdf.derive_key(passphrase)?,
+                kdf,
+            }),
+        };
+        keystore.save()?;
+        Ok(keystore)
+    }
+
+    /// Returns whether the file at `path` is an encrypted keystore, rather than a plaintext one.
+    pub fn is_encrypted(path: &Path) -> bool {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<EncryptedKeystoreFile>(&contents).ok())
+            .is_some()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Unlocks the keystore with `passphrase`, unless it is already unlocked.
+    pub fn unlock_with_passphrase(&self, passphrase: &str) -> Result<(), anyhow::Error> {
+        self.unlocked
+            .get_or_try_init(|| self.decrypt(passphrase))
+            .map(|_| ())
+    }
+
+    /// Encrypts the keystore under `new_passphrase` instead of its current passphrase, which it is
+    /// unlocked with first if it is not already.
+    pub fn change_passphrase(&mut self, new_passphrase: &str) -> Result<(), anyhow::Error> {
+        self.unlock()?;
+        let kdf = KdfParams::new();
+        let key = kdf.derive_key(new_passphrase)?;
+        let unlocked = self
+            .unlocked
+            .get_mut()
+            .expect("keystore was unlocked above");
+        unlocked.kdf = kdf;
+        unlocked.key = key;
+        self.save()
+    }
+
+    pub fn save(&self) -> Result<(), anyhow::Error> {
+        let unlocked = self.unlock()?;
+        let public_keys = self
+            .public_keys
+            .values()
+            .map(EncodeDecodeBase64::encode_base64)
+            .collect::<Vec<_>>();
+        let private_keys = serde_json::to_vec(
+            &unlocked
+                .keys
+                .values()
+                .map(EncodeDecodeBase64::encode_base64)
+                .collect::<Vec<_>>(),
+        )?;
+
+        let mut nonce = [0u8; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+        let aad = serde_json::to_vec(&public_keys)?;
+        let ciphertext = cipher(&unlocked.key)?
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &private_keys,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| anyhow!("Cannot encrypt keystore"))?;
+
+        let file = EncryptedKeystoreFile {
+            version: ENCRYPTED_KEYSTORE_VERSION,
+            public_keys,
+            kdf: unlocked.kdf.clone(),
+            nonce: Base64::encode(nonce),
+            ciphertext: Base64::encode(ciphertext),
+        };
+
+        // Write to a temporary file first, so that a failed write can't lose the keystore.
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(&file)?)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    /// Unlocks the keystore if it is not already, with the passphrase from the environment or a
+    /// prompt, or for a keystore that has never been saved, with a new passphrase.
+    fn unlock(&self) -> Result<&UnlockedKeys, anyhow::Error> {
+        self.unlocked.get_or_try_init(|| {
+            if self.file.is_none() {
+                let kdf = KdfParams::new();
+                let key = kdf.derive_key(&read_new_passphrase()?)?;
+                return Ok(UnlockedKeys {
+                    keys: BTreeMap::new(),
+                    kdf,
+                    key,
+                });
+            }
+            self.decrypt(&read_passphrase(&self.path)?)
+        })
+    }
+
+    fn decrypt(&self, passphrase: &str) -> Result<UnlockedKeys, anyhow::Error> {
+        let Some(file) = &self.file else {
+            bail!("Keystore {:?} has not been saved yet", self.path);
+        };
+        let key = file.kdf.derive_key(passphrase)?;
+        let nonce = Base64::decode(&file.nonce).map_err(|e| anyhow!("Invalid nonce: {e}"))?;
+        if nonce.len() != NONCE_LENGTH {
+            bail!("Invalid nonce length {}", nonce.len());
+        }
+        let ciphertext =
+            Base64::decode(&file.ciphertext).map_err(|e| anyhow!("Invalid ciphertext: {e}"))?;
+        let aad = serde_json::to_vec(&file.public_keys)?;
+        let private_keys = cipher(&key)?
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| {
+                anyhow!(
+                    "Cannot decrypt keystore {:?}, the passphrase is incorrect or the file is corrupted",
+                    self.path
+                )
+            })?;
+
+        let keys = serde_json::from_slice::<Vec<String>>(&private_keys)?
+            .iter()
+            .map(|key| {
+                SuiKeyPair::decode_base64(key).map(|key| (SuiAddress::from(&key.public()), key))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .map_err(|e| anyhow!("Invalid keypair in {:?}: {e}", self.path))?;
+        Ok(UnlockedKeys {
+            keys,
+            kdf: file.kdf.clone(),
+            key,
+        })
+    }
+}
+
+fn cipher(key: &[u8; KEY_LENGTH]) -> Result<Aes256Gcm, anyhow::Error> {
+    Aes256Gcm::new_from_slice(key).map_err(|e| anyhow!("Invalid encryption key: {e}"))
+}
+
+/// Reads the passphrase to unlock the keystore at `path` with, from the `SUI_KEYSTORE_PASSPHRASE`
+/// environment variable if it is set, or else from a prompt.
+pub fn read_passphrase(path: &Path) -> Result<String, anyhow::Error> {
+    if let Ok(passphrase) = std::env::var(SUI_KEYSTORE_PASSPHRASE_ENV_VAR) {
+        return Ok(passphrase);
+    }
+    inquire::Password::new(&format!("Enter the passphrase for keystore {:?}:", path))
+        .without_confirmation()
+        .prompt()
+        .map_err(|e| anyhow!("Cannot read keystore passphrase: {e}"))
+}
+
+/// Reads a new passphrase to encrypt a keystore with, from the `SUI_KEYSTORE_NEW_PASSPHRASE`
+/// environment variable if it is set, or else from a prompt, which asks for it twice.
+pub fn read_new_passphrase() -> Result<String, anyhow::Error> {
+    let passphrase = match std::env::var(SUI_KEYSTORE_NEW_PASSPHRASE_ENV_VAR) {
+        Ok(passphrase) => passphrase,
+        Err(_) => inquire::Password::new("Enter a new passphrase for the keystore:")
+            .with_custom_confirmation_message("Confirm the new passphrase:")
+            .prompt()
+            .map_err(|e| anyhow!("Cannot read keystore passphrase: {e}"))?,
+    };
+    if passphrase.is_empty() {
+        bail!("The keystore passphrase cannot be empty");
+    }
+    Ok(passphrase)
+}
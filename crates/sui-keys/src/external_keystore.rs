@@ -0,0 +1,246 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{anyhow, bail};
+use fastcrypto::encoding::{Base64, Encoding};
+use fastcrypto::hash::HashFunction;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shared_crypto::intent::{Intent, IntentMessage};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{
+    DefaultHash, EncodeDecodeBase64, PublicKey, Signature, SuiKeyPair, SuiSignature,
+};
+
+use crate::keystore::{AccountKeystore, Aliases};
+
+/// How to run an external signer: a program, such as a shim in front of an HSM or a remote
+/// signing service, that holds the private keys and signs on the keystore's behalf.
+///
+/// The signer is run once per request. It reads a single JSON request from stdin, and writes a
+/// single JSON response to stdout, or `{"error": "..."}` if the request fails:
+///
+/// - `{"method": "keys"}` is answered with `{"keys": [...]}`, the Base64 encoded `flag || pk` of
+///   every key the signer holds.
+/// - `{"method": "sign", "address": "0x...", "public_key": "...", "message": "...",
+///   "intent_message": "..."}` is answered with `{"signature": "..."}`, the Base64 encoded
+///   `flag || sig || pk` of the signature of `message` by `address`' key. `message` is Base64
+///   encoded, and is signed as is, as by `Signature::new_hashed`. When signing an intent message,
+///   `message` is its Blake2b256 digest, and `intent_message` holds its BCS bytes, so that the
+///   signer can check what it is signing.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExternalSignerConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum SignerRequest {
+    Keys,
+    Sign {
+        address: SuiAddress,
+        public_key: String,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        intent_message: Option<String>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SignerResponse<T> {
+    Error { error: String },
+    Ok(T),
+}
+
+#[derive(Deserialize)]
+struct KeysResponse {
+    keys: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    signature: String,
+}
+
+/// A keystore whose private keys never leave an external signer. The signer's public keys are
+/// listed when the keystore is opened, and every signature is checked against them. Aliases of
+/// its addresses are only kept for the lifetime of the keystore.
+pub struct ExternalKeystore {
+    config: ExternalSignerConfig,
+    public_keys: BTreeMap<SuiAddress, PublicKey>,
+    aliases: Aliases,
+}
+
+impl Serialize for ExternalKeystore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.config.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExternalKeystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+        ExternalKeystore::new(ExternalSignerConfig::deserialize(deserializer)?)
+            .map_err(D::Error::custom)
+    }
+}
+
+impl AccountKeystore for ExternalKeystore {
+    fn aliases(&self) -> &Aliases {
+        &self.aliases
+    }
+    fn aliases_mut(&mut self) -> &mut Aliases {
+        &mut self.aliases
+    }
+    fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
+        let signature = self
+            .sign(address, msg, None)
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        let public_key = &self.public_keys[address];
+        if signature.scheme().flag() != public_key.flag()
+            || signature.public_key_bytes() != public_key.as_ref()
+        {
+            return Err(signature::Error::from_source(format!(
+                "External signer signed with the wrong key for address: [{address}]"
+            )));
+        }
+        Ok(signature)
+    }
+    fn sign_secure<T>(
+        &self,
+        address: &SuiAddress,
+        msg: &T,
+        intent: Intent,
+    ) -> Result<Signature, signature::Error>
+    where
+        T: Serialize,
+    {
+        let intent_msg = IntentMessage::new(intent, msg);
+        let bytes =
+            bcs::to_bytes(&intent_msg).map_err(|e| signature::Error::from_source(e.to_string()))?;
+        let mut hasher = DefaultHash::default();
+        hasher.update(&bytes);
+        let digest = hasher.finalize().digest;
+
+        let signature = self
+            .sign(address, &digest, Some(&bytes))
+            .map_err(|e| signature::Error::from_source(e.to_string()))?;
+        signature
+            .verify_secure(&intent_msg, *address, signature.scheme())
+            .map_err(|e| {
+                signature::Error::from_source(format!(
+                    "External signer's signature is invalid: {e}"
+                ))
+            })?;
+        Ok(signature)
+    }
+
+    fn add_key(&mut self, _keypair: SuiKeyPair) -> Result<(), anyhow::Error> {
+        Err(anyhow!(
+            "Keys cannot be added to an external signer's keystore, add them to the signer instead"
+        ))
+    }
+
+    fn keys(&self) -> Vec<PublicKey> {
+        self.public_keys.values().cloned().collect()
+    }
+
+    fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error> {
+        if !self.public_keys.contains_key(address) {
+            bail!("Cannot find key for address: [{address}]");
+        }
+        Err(anyhow!(
+            "The private key for address [{address}] is held by an external signer, and cannot be read"
+        ))
+    }
+}
+
+impl ExternalKeystore {
+    /// Opens the keystore of the signer run as `config` describes, listing its keys.
+    pub fn new(config: ExternalSignerConfig) -> Result<Self, anyhow::Error> {
+        let mut keystore = Self {
+            config,
+            public_keys: BTreeMap::new(),
+            aliases: Aliases::default(),
+        };
+        let KeysResponse { keys } = keystore.request(&SignerRequest::Keys)?;
+        keystore.public_keys = keys
+            .iter()
+            .map(|key| PublicKey::decode_base64(key).map(|key| (SuiAddress::from(&key), key)))
+            .collect::<Result<BTreeMap<_, _>, _>>()
+            .map_err(|e| anyhow!("Invalid public key from external signer: {e}"))?;
+        Ok(keystore)
+    }
+
+    pub fn config(&self) -> &ExternalSignerConfig {
+        &self.config
+    }
+
+    fn sign(
+        &self,
+        address: &SuiAddress,
+        message: &[u8],
+        intent_message: Option<&[u8]>,
+    ) -> Result<Signature, anyhow::Error> {
+        let public_key = self
+            .public_keys
+            .get(address)
+            .ok_or_else(|| anyhow!("Cannot find key for address: [{address}]"))?;
+        let SignResponse { signature } = self.request(&SignerRequest::Sign {
+            address: *address,
+            public_key: public_key.encode_base64(),
+            message: Base64::encode(message),
+            intent_message: intent_message.map(Base64::encode),
+        })?;
+        Signature::decode_base64(&signature)
+            .map_err(|e| anyhow!("Invalid signature from external signer: {e}"))
+    }
+
+    /// Runs the signer, sending it `request` and reading back its response.
+    fn request<T: DeserializeOwned>(&self, request: &SignerRequest) -> Result<T, anyhow::Error> {
+        let mut child = Command::new(&self.config.command)
+            .args(&self.config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| anyhow!("Cannot run external signer {:?}: {e}", self.config.command))?;
+
+        // Closing stdin once the request is written lets the signer read to its end.
+        let mut stdin = child.stdin.take().expect("stdin of the signer is piped");
+        let mut line = serde_json::to_vec(request)?;
+        line.push(b'\n');
+        stdin.write_all(&line)?;
+        drop(stdin);
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!(
+                "External signer {:?} failed with {}",
+                self.config.command,
+                output.status
+            );
+        }
+        match serde_json::from_slice(&output.stdout).map_err(|e| {
+            anyhow!(
+                "Can't deserialize response from external signer {:?}: {e}",
+                self.config.command
+            )
+        })? {
+            SignerResponse::Ok(response) => Ok(response),
+            SignerResponse::Error { error } => Err(anyhow!("External signer error: {error}")),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use sui_types::base_types::SuiAddress;
+
+use crate::keystore::{AccountKeystore, Keystore};
+
+/// An address given on the command line, either as a `SuiAddress` or as the alias of one of the
+/// keystore's addresses.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyIdentity {
+    Address(SuiAddress),
+    Alias(String),
+}
+
+impl KeyIdentity {
+    /// Resolves the identity to an address, looking aliases up in `keystore`.
+    pub fn resolve(&self, keystore: &Keystore) -> Result<SuiAddress, anyhow::Error> {
+        match self {
+            KeyIdentity::Address(address) => Ok(*address),
+            KeyIdentity::Alias(alias) => keystore.get_address_by_alias(alias),
+        }
+    }
+}
+
+impl FromStr for KeyIdentity {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("0x") {
+            // Aliases never start with `0x`, so this can only be meant as an address.
+            return SuiAddress::from_str(s)
+                .map(KeyIdentity::Address)
+                .map_err(|e| anyhow!("Invalid address {s}: {e}"));
+        }
+        Ok(match SuiAddress::from_str(s) {
+            Ok(address) => KeyIdentity::Address(address),
+            Err(_) => KeyIdentity::Alias(s.to_string()),
+        })
+    }
+}
+
+impl Display for KeyIdentity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyIdentity::Address(address) => write!(f, "{address}"),
+            KeyIdentity::Alias(alias) => write!(f, "{alias}"),
+        }
+    }
+}
+
+impl From<SuiAddress> for KeyIdentity {
+    fn from(address: SuiAddress) -> Self {
+        KeyIdentity::Address(address)
+    }
+}
@@ -1,6 +1,8 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::encrypted_keystore::EncryptedFileBasedKeystore;
+use crate::external_keystore::{ExternalKeystore, ExternalSignerConfig};
 use crate::key_derive::{derive_key_pair_from_path, generate_new_key};
 use anyhow::anyhow;
 use bip32::DerivationPath;
@@ -15,17 +17,62 @@ use std::fs;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use sui_types::base_types::SuiAddress;
 use sui_types::crypto::get_key_pair_from_rng;
 use sui_types::crypto::{
     enum_dispatch, EncodeDecodeBase64, PublicKey, Signature, SignatureScheme, SuiKeyPair,
 };
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize)]
 #[enum_dispatch(AccountKeystore)]
 pub enum Keystore {
     File(FileBasedKeystore),
     InMem(InMemKeystore),
+    Encrypted(EncryptedFileBasedKeystore),
+    External(ExternalKeystore),
+}
+
+impl Keystore {
+    /// Opens the keystore file at `path`, as an `EncryptedFileBasedKeystore` if it has been
+    /// encrypted, or else as a plaintext `FileBasedKeystore`.
+    pub fn from_file(path: &PathBuf) -> Result<Self, anyhow::Error> {
+        Ok(if EncryptedFileBasedKeystore::is_encrypted(path) {
+            Keystore::Encrypted(EncryptedFileBasedKeystore::new(path)?)
+        } else {
+            Keystore::File(FileBasedKeystore::new(path)?)
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Keystore {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        #[derive(Deserialize)]
+        enum KeystoreConfig {
+            File(PathBuf),
+            InMem(InMemKeystore),
+            Encrypted(PathBuf),
+            External(ExternalSignerConfig),
+        }
+
+        // A `File` keystore may since have been encrypted in place with `sui keytool
+        // encrypt-keystore`, so its config is left as is, and its contents decide how it is opened.
+        match KeystoreConfig::deserialize(deserializer)? {
+            KeystoreConfig::File(path) => Keystore::from_file(&path).map_err(D::Error::custom),
+            KeystoreConfig::InMem(keystore) => Ok(Keystore::InMem(keystore)),
+            KeystoreConfig::Encrypted(path) => EncryptedFileBasedKeystore::new(&path)
+                .map(Keystore::Encrypted)
+                .map_err(D::Error::custom),
+            KeystoreConfig::External(config) => ExternalKeystore::new(config)
+                .map(Keystore::External)
+                .map_err(D::Error::custom),
+        }
+    }
 }
 #[enum_dispatch]
 pub trait AccountKeystore: Send + Sync {
@@ -33,6 +80,9 @@ pub trait AccountKeystore: Send + Sync {
     fn keys(&self) -> Vec<PublicKey>;
     fn get_key(&self, address: &SuiAddress) -> Result<&SuiKeyPair, anyhow::Error>;
 
+    fn aliases(&self) -> &Aliases;
+    fn aliases_mut(&mut self) -> &mut Aliases;
+
     fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error>;
 
     fn sign_secure<T>(
@@ -47,6 +97,39 @@ pub trait AccountKeystore: Send + Sync {
         self.keys().iter().map(|k| k.into()).collect()
     }
 
+    fn get_alias_by_address(&self, address: &SuiAddress) -> Option<String> {
+        self.aliases().get(address).map(str::to_string)
+    }
+
+    fn get_address_by_alias(&self, alias: &str) -> Result<SuiAddress, anyhow::Error> {
+        self.aliases()
+            .address(alias)
+            .ok_or_else(|| anyhow!("Cannot find address for alias: [{alias}]"))
+    }
+
+    /// Sets the alias of `address` to `new_alias`, or removes its alias if `new_alias` is `None`.
+    fn update_alias(
+        &mut self,
+        address: &SuiAddress,
+        new_alias: Option<&str>,
+    ) -> Result<(), anyhow::Error> {
+        if !self.addresses().contains(address) {
+            return Err(anyhow!("Cannot find key for address: [{address}]"));
+        }
+        if let Some(alias) = new_alias {
+            validate_alias(alias)?;
+            if let Some(other) = self.aliases().address(alias) {
+                if &other != address {
+                    return Err(anyhow!(
+                        "Alias {alias} is already used for address: [{other}]"
+                    ));
+                }
+            }
+        }
+        self.aliases_mut()
+            .set(*address, new_alias.map(str::to_string))
+    }
+
     fn generate_and_add_new_key(
         &mut self,
         key_scheme: SignatureScheme,
@@ -91,6 +174,16 @@ impl Display for Keystore {
                 writeln!(writer, "Keystore Type : InMem")?;
                 write!(f, "{}", writer)
             }
+            Keystore::Encrypted(file) => {
+                writeln!(writer, "Keystore Type : Encrypted File")?;
+                write!(writer, "Keystore Path : {:?}", file.path())?;
+                write!(f, "{}", writer)
+            }
+            Keystore::External(external) => {
+                writeln!(writer, "Keystore Type : External Signer")?;
+                write!(writer, "Signer Command : {:?}", external.config().command)?;
+                write!(f, "{}", writer)
+            }
         }
     }
 }
@@ -98,6 +191,7 @@ impl Display for Keystore {
 #[derive(Default)]
 pub struct FileBasedKeystore {
     keys: BTreeMap<SuiAddress, SuiKeyPair>,
+    aliases: Aliases,
     path: Option<PathBuf>,
 }
 
@@ -128,6 +222,12 @@ impl<'de> Deserialize<'de> for FileBasedKeystore {
 }
 
 impl AccountKeystore for FileBasedKeystore {
+    fn aliases(&self) -> &Aliases {
+        &self.aliases
+    }
+    fn aliases_mut(&mut self) -> &mut Aliases {
+        &mut self.aliases
+    }
     fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
         Ok(Signature::new_hashed(
             msg,
@@ -195,11 +295,13 @@ impl FileBasedKeystore {
 
         Ok(Self {
             keys,
+            aliases: Aliases::load(path)?,
             path: Some(path.to_path_buf()),
         })
     }
 
     pub fn set_path(&mut self, path: &Path) {
+        self.aliases.set_keystore_path(path);
         self.path = Some(path.to_path_buf());
     }
 
@@ -221,14 +323,30 @@ impl FileBasedKeystore {
     pub fn key_pairs(&self) -> Vec<&SuiKeyPair> {
         self.keys.values().collect()
     }
+
+    pub fn into_key_pairs(self) -> Vec<SuiKeyPair> {
+        self.keys.into_values().collect()
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
 }
 
 #[derive(Default, Serialize, Deserialize)]
 pub struct InMemKeystore {
     keys: BTreeMap<SuiAddress, SuiKeyPair>,
+    #[serde(skip)]
+    aliases: Aliases,
 }
 
 impl AccountKeystore for InMemKeystore {
+    fn aliases(&self) -> &Aliases {
+        &self.aliases
+    }
+    fn aliases_mut(&mut self) -> &mut Aliases {
+        &mut self.aliases
+    }
     fn sign_hashed(&self, address: &SuiAddress, msg: &[u8]) -> Result<Signature, signature::Error> {
         Ok(Signature::new_hashed(
             msg,
@@ -280,6 +398,108 @@ impl InMemKeystore {
             .map(|(ad, k)| (ad, SuiKeyPair::Ed25519(k)))
             .collect::<BTreeMap<SuiAddress, SuiKeyPair>>();
 
-        Self { keys }
+        Self {
+            keys,
+            aliases: Aliases::default(),
+        }
+    }
+}
+
+/// A human readable name for one of a keystore's addresses.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Alias {
+    pub alias: String,
+    pub address: SuiAddress,
+}
+
+/// The aliases of a keystore's addresses, which are kept in a `.aliases` file next to the
+/// keystore file, so that they stay readable even if the keystore is encrypted.
+#[derive(Default)]
+pub struct Aliases {
+    aliases: BTreeMap<SuiAddress, String>,
+    /// `None` for a keystore that is not backed by a file, whose aliases are only kept in memory.
+    path: Option<PathBuf>,
+}
+
+impl Aliases {
+    /// Loads the aliases of the keystore at `keystore_path`, if any have been set.
+    pub fn load(keystore_path: &Path) -> Result<Self, anyhow::Error> {
+        let path = Self::aliases_path(keystore_path);
+        let aliases = if path.exists() {
+            let reader = BufReader::new(
+                File::open(&path)
+                    .map_err(|e| anyhow!("Can't open aliases from {:?}: {e}", path))?,
+            );
+            let aliases: Vec<Alias> = serde_json::from_reader(reader)
+                .map_err(|e| anyhow!("Can't deserialize aliases from {:?}: {e}", path))?;
+            aliases
+                .into_iter()
+                .map(|Alias { alias, address }| (address, alias))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            aliases,
+            path: Some(path),
+        })
+    }
+
+    fn aliases_path(keystore_path: &Path) -> PathBuf {
+        keystore_path.with_extension("aliases")
+    }
+
+    pub(crate) fn set_keystore_path(&mut self, keystore_path: &Path) {
+        self.path = Some(Self::aliases_path(keystore_path));
+    }
+
+    pub fn get(&self, address: &SuiAddress) -> Option<&str> {
+        self.aliases.get(address).map(String::as_str)
+    }
+
+    pub fn address(&self, alias: &str) -> Option<SuiAddress> {
+        self.aliases
+            .iter()
+            .find_map(|(address, a)| (a == alias).then_some(*address))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Alias> + '_ {
+        self.aliases.iter().map(|(address, alias)| Alias {
+            alias: alias.clone(),
+            address: *address,
+        })
+    }
+
+    fn set(&mut self, address: SuiAddress, alias: Option<String>) -> Result<(), anyhow::Error> {
+        match alias {
+            Some(alias) => self.aliases.insert(address, alias),
+            None => self.aliases.remove(&address),
+        };
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), anyhow::Error> {
+        if let Some(path) = &self.path {
+            let aliases = serde_json::to_string_pretty(&self.iter().collect::<Vec<_>>())?;
+            fs::write(path, aliases)?
+        }
+        Ok(())
+    }
+}
+
+/// Checks that `alias` can be told apart from an address wherever either can be given: it must
+/// start with a letter and contain only letters, digits, `-` and `_`.
+pub fn validate_alias(alias: &str) -> Result<(), anyhow::Error> {
+    let valid = alias.starts_with(|c: char| c.is_ascii_alphabetic())
+        && alias
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        && SuiAddress::from_str(alias).is_err();
+    if !valid {
+        return Err(anyhow!(
+            "Invalid alias {alias:?}: an alias must start with a letter, contain only letters, \
+            digits, '-' and '_', and not be a valid address"
+        ));
     }
+    Ok(())
 }
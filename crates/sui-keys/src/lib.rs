@@ -1,6 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod encrypted_keystore;
+pub mod external_keystore;
 pub mod key_derive;
+pub mod key_identity;
 pub mod keypair_file;
 pub mod keystore;
@@ -5,8 +5,14 @@ use std::str::FromStr;
 use fastcrypto::hash::HashFunction;
 use tempfile::TempDir;
 
+use shared_crypto::intent::{Intent, IntentMessage};
+use sui_keys::encrypted_keystore::EncryptedFileBasedKeystore;
+use sui_keys::key_derive::generate_new_key;
+use sui_keys::key_identity::KeyIdentity;
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, Keystore};
-use sui_types::crypto::{DefaultHash, SignatureScheme, SuiSignatureInner};
+use sui_types::crypto::{
+    DefaultHash, EncodeDecodeBase64, Signature, SignatureScheme, SuiSignature, SuiSignatureInner,
+};
 use sui_types::{
     base_types::{SuiAddress, SUI_ADDRESS_LENGTH},
     crypto::Ed25519SuiSignature,
@@ -69,3 +75,151 @@ fn keystore_display_test() -> Result<(), anyhow::Error> {
     assert!(!keystore.to_string().contains("keys:"));
     Ok(())
 }
+
+#[test]
+fn encrypted_keystore_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let (address, keypair, _, _) = generate_new_key(SignatureScheme::ED25519, None, None)?;
+    let private_key = keypair.encode_base64();
+    EncryptedFileBasedKeystore::create(&keystore_path, [keypair], "passphrase")?;
+
+    // The private key is not stored in the clear.
+    assert!(!std::fs::read_to_string(&keystore_path)?.contains(&private_key));
+
+    // The addresses can be read without unlocking the keystore.
+    let keystore = EncryptedFileBasedKeystore::new(&keystore_path)?;
+    assert_eq!(keystore.addresses(), vec![address]);
+
+    assert!(keystore.unlock_with_passphrase("wrong passphrase").is_err());
+    keystore.unlock_with_passphrase("passphrase")?;
+    assert_eq!(keystore.get_key(&address)?.encode_base64(), private_key);
+    let signature = keystore.sign_secure(&address, &"message", Intent::sui_transaction())?;
+    signature.verify_secure(
+        &IntentMessage::new(Intent::sui_transaction(), "message"),
+        address,
+        SignatureScheme::ED25519,
+    )?;
+    Ok(())
+}
+
+#[test]
+fn encrypted_keystore_change_passphrase_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let (address, keypair, _, _) = generate_new_key(SignatureScheme::Secp256k1, None, None)?;
+    let mut keystore = EncryptedFileBasedKeystore::create(&keystore_path, [keypair], "old")?;
+    keystore.change_passphrase("new")?;
+
+    // The keystore's contents decide how it is opened.
+    let Keystore::Encrypted(keystore) = Keystore::from_file(&keystore_path)? else {
+        panic!("expected an encrypted keystore");
+    };
+    assert!(keystore.unlock_with_passphrase("old").is_err());
+    keystore.unlock_with_passphrase("new")?;
+    assert!(keystore.get_key(&address).is_ok());
+    Ok(())
+}
+
+#[test]
+fn keystore_alias_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let keystore_path = temp_dir.path().join("sui.keystore");
+    let mut keystore = Keystore::from(FileBasedKeystore::new(&keystore_path).unwrap());
+    let (address, _, _) =
+        keystore.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+    let (other, _, _) = keystore.generate_and_add_new_key(SignatureScheme::ED25519, None, None)?;
+
+    keystore.update_alias(&address, Some("alice"))?;
+    assert_eq!(
+        keystore.get_alias_by_address(&address).as_deref(),
+        Some("alice")
+    );
+    assert_eq!(KeyIdentity::from_str("alice")?.resolve(&keystore)?, address);
+    assert_eq!(
+        KeyIdentity::from_str(&other.to_string())?.resolve(&keystore)?,
+        other
+    );
+
+    // Aliases must be unique, must not look like addresses, and can only name the keystore's own
+    // addresses.
+    assert!(keystore.update_alias(&other, Some("alice")).is_err());
+    assert!(keystore.update_alias(&other, Some("0xalice")).is_err());
+    assert!(keystore
+        .update_alias(&other, Some(&"a".repeat(64)))
+        .is_err());
+    assert!(keystore
+        .update_alias(&SuiAddress::random_for_testing_only(), Some("bob"))
+        .is_err());
+
+    // Aliases are kept next to the keystore, and survive it being reopened.
+    let mut keystore = Keystore::from_file(&keystore_path)?;
+    assert_eq!(keystore.get_address_by_alias("alice")?, address);
+
+    keystore.update_alias(&address, None)?;
+    assert!(keystore.get_address_by_alias("alice").is_err());
+    assert!(KeyIdentity::from_str("alice")?.resolve(&keystore).is_err());
+    Ok(())
+}
+
+/// Writes a mock external signer, which answers `keys` with `keys` and any other request with
+/// `signature`, and logs the requests it gets to `requests.log`.
+#[cfg(unix)]
+fn mock_external_signer(dir: &std::path::Path, keys: &[String], signature: &str) -> String {
+    let script = format!(
+        r#"read request
+echo "$request" >> {log}
+case "$request" in
+  *'"method":"keys"'*) echo '{{"keys":{keys}}}' ;;
+  *) echo '{{"signature":"{signature}"}}' ;;
+esac
+"#,
+        log = dir.join("requests.log").display(),
+        keys = serde_json::to_string(keys).unwrap(),
+    );
+    let path = dir.join("signer.sh");
+    std::fs::write(&path, script).unwrap();
+    serde_json::json!({ "External": { "command": "sh", "args": [path] } }).to_string()
+}
+
+#[cfg(unix)]
+#[test]
+fn external_keystore_test() -> Result<(), anyhow::Error> {
+    let temp_dir = TempDir::new().unwrap();
+    let (address, keypair, _, _) = generate_new_key(SignatureScheme::ED25519, None, None)?;
+    let (_, other_keypair, _, _) = generate_new_key(SignatureScheme::ED25519, None, None)?;
+    let intent_msg = IntentMessage::new(Intent::sui_transaction(), "message");
+    let signature = Signature::new_secure(&intent_msg, &keypair);
+
+    let config = mock_external_signer(
+        temp_dir.path(),
+        &[keypair.public().encode_base64()],
+        &signature.encode_base64(),
+    );
+    let mut keystore: Keystore = serde_json::from_str(&config)?;
+    assert!(keystore.to_string().contains("External Signer"));
+    assert_eq!(keystore.addresses(), vec![address]);
+
+    let signed = keystore.sign_secure(&address, &"message", Intent::sui_transaction())?;
+    assert_eq!(signed, signature);
+    let log = std::fs::read_to_string(temp_dir.path().join("requests.log"))?;
+    assert!(log.contains(r#""method":"sign""#));
+    assert!(log.contains(r#""intent_message""#));
+
+    // The private keys never leave the signer.
+    assert!(keystore.get_key(&address).is_err());
+    let (_, new_keypair, _, _) = generate_new_key(SignatureScheme::ED25519, None, None)?;
+    assert!(keystore.add_key(new_keypair).is_err());
+
+    // A signature by any other key than the address' is rejected.
+    let config = mock_external_signer(
+        temp_dir.path(),
+        &[keypair.public().encode_base64()],
+        &Signature::new_secure(&intent_msg, &other_keypair).encode_base64(),
+    );
+    let keystore: Keystore = serde_json::from_str(&config)?;
+    assert!(keystore
+        .sign_secure(&address, &"message", Intent::sui_transaction())
+        .is_err());
+    Ok(())
+}
@@ -1,16 +1,24 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::anyhow;
 use async_trait::async_trait;
 use aws_sdk_dynamodb as dynamodb;
 use aws_sdk_dynamodb::config::{Credentials, Region};
 use aws_sdk_dynamodb::primitives::Blob;
 use aws_sdk_dynamodb::types::{AttributeValue, PutRequest, WriteRequest};
 use aws_sdk_s3 as s3;
+use bytes::Bytes;
+use object_store::path::Path;
+use object_store::DynObjectStore;
 use serde::Serialize;
 use std::borrow::Borrow;
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use sui_config::node::TransactionKeyValueStoreWriteConfig;
+use sui_storage::object_store::util::{get, put};
+use sui_storage::object_store::ObjectStoreConfig;
+use sui_storage::object_store_key_value_store::object_store_kv_path;
 
 #[derive(Hash, Eq, PartialEq, Debug, Copy, Clone)]
 pub enum KVTable {
@@ -24,10 +32,30 @@ pub enum KVTable {
     State,
 }
 
+impl KVTable {
+    /// The name the table's values are stored under, which readers of the key value store use to
+    /// tell the tables apart.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            KVTable::Transactions => "tx",
+            KVTable::Effects => "fx",
+            KVTable::Events => "ev",
+            KVTable::Objects => "ob",
+            KVTable::State => "state",
+            KVTable::CheckpointContent => "cc",
+            KVTable::CheckpointSummary => "cs",
+            KVTable::TransactionToCheckpoint => "tx2c",
+        }
+    }
+}
+
 const UPLOAD_PROGRESS_KEY: [u8; 1] = [0];
 
 #[async_trait]
 pub trait KVWriteClient {
+    async fn from_config(config: &TransactionKeyValueStoreWriteConfig) -> anyhow::Result<Self>
+    where
+        Self: Sized;
     async fn multi_set<V: Serialize>(
         &mut self,
         table: KVTable,
@@ -79,24 +107,14 @@ impl DynamoDbClient {
             bucket_name: config.bucket_name.clone(),
         }
     }
-
-    fn type_name(table: KVTable) -> String {
-        match table {
-            KVTable::Transactions => "tx",
-            KVTable::Effects => "fx",
-            KVTable::Events => "ev",
-            KVTable::Objects => "ob",
-            KVTable::State => "state",
-            KVTable::CheckpointContent => "cc",
-            KVTable::CheckpointSummary => "cs",
-            KVTable::TransactionToCheckpoint => "tx2c",
-        }
-        .to_string()
-    }
 }
 
 #[async_trait]
 impl KVWriteClient for DynamoDbClient {
+    async fn from_config(config: &TransactionKeyValueStoreWriteConfig) -> anyhow::Result<Self> {
+        Ok(Self::new(config).await)
+    }
+
     async fn multi_set<V: Serialize>(
         &mut self,
         table: KVTable,
@@ -113,7 +131,7 @@ impl KVWriteClient for DynamoDbClient {
                 .set_put_request(Some(
                     PutRequest::builder()
                         .item("digest", AttributeValue::B(Blob::new(digest)))
-                        .item("type", AttributeValue::S(Self::type_name(table)))
+                        .item("type", AttributeValue::S(table.type_name().to_string()))
                         .item(
                             "bcs",
                             AttributeValue::B(Blob::new(bcs::to_bytes(value.borrow())?)),
@@ -145,7 +163,10 @@ impl KVWriteClient for DynamoDbClient {
             .get_item()
             .table_name(self.table_name.clone())
             .key("digest", AttributeValue::B(Blob::new(UPLOAD_PROGRESS_KEY)))
-            .key("type", AttributeValue::S("state".to_string()))
+            .key(
+                "type",
+                AttributeValue::S(KVTable::State.type_name().to_string()),
+            )
             .send()
             .await?;
         if let Some(output) = item.item() {
@@ -161,7 +182,10 @@ impl KVWriteClient for DynamoDbClient {
             .put_item()
             .table_name(self.table_name.clone())
             .item("digest", AttributeValue::B(Blob::new(UPLOAD_PROGRESS_KEY)))
-            .item("type", AttributeValue::S("state".to_string()))
+            .item(
+                "type",
+                AttributeValue::S(KVTable::State.type_name().to_string()),
+            )
             .item(
                 "value",
                 AttributeValue::B(Blob::new(bcs::to_bytes(&value)?)),
@@ -188,3 +212,87 @@ impl KVWriteClient for DynamoDbClient {
         Ok(())
     }
 }
+
+/// Writes the key value store to an object store, such as a local or network file system, or a
+/// self hosted S3 compatible store, in the layout read back by `ObjectStoreKVStore`.
+pub struct ObjectStoreKVClient {
+    store: Arc<DynObjectStore>,
+}
+
+impl ObjectStoreKVClient {
+    pub fn new(config: &ObjectStoreConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            store: config.make()?,
+        })
+    }
+
+    fn path(table: KVTable, key: &[u8]) -> Path {
+        object_store_kv_path(table.type_name(), &base64_url::encode(key))
+    }
+}
+
+#[async_trait]
+impl KVWriteClient for ObjectStoreKVClient {
+    async fn from_config(config: &TransactionKeyValueStoreWriteConfig) -> anyhow::Result<Self> {
+        let object_store_config = config
+            .object_store_config
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing object store config for key value store"))?;
+        Self::new(object_store_config)
+    }
+
+    async fn multi_set<V: Serialize>(
+        &mut self,
+        table: KVTable,
+        values: impl IntoIterator<Item = (Vec<u8>, V)> + std::marker::Send,
+    ) -> anyhow::Result<()> {
+        let mut items = vec![];
+        let mut seen = HashSet::new();
+        for (digest, value) in values {
+            if !seen.insert(digest.clone()) {
+                continue;
+            }
+            items.push((
+                Self::path(table, &digest),
+                Bytes::from(bcs::to_bytes(value.borrow())?),
+            ));
+        }
+        for (path, bytes) in items {
+            put(&path, bytes, self.store.clone()).await?;
+        }
+        Ok(())
+    }
+
+    async fn get_state(&self) -> anyhow::Result<Option<u64>> {
+        let path = Self::path(KVTable::State, &UPLOAD_PROGRESS_KEY);
+        match self.store.head(&path).await {
+            Ok(_) => {}
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        let bytes = get(&path, self.store.clone()).await?;
+        Ok(Some(bcs::from_bytes(&bytes)?))
+    }
+
+    async fn update_state(&mut self, value: u64) -> anyhow::Result<()> {
+        let path = Self::path(KVTable::State, &UPLOAD_PROGRESS_KEY);
+        put(
+            &path,
+            Bytes::from(bcs::to_bytes(&value)?),
+            self.store.clone(),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn upload_blob<V: Serialize + std::marker::Send>(
+        &mut self,
+        table: KVTable,
+        key: Vec<u8>,
+        value: V,
+    ) -> anyhow::Result<()> {
+        let bytes = Bytes::from(bcs::to_bytes(value.borrow())?);
+        put(&Self::path(table, &key), bytes, self.store.clone()).await?;
+        Ok(())
+    }
+}
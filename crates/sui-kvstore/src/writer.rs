@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::client::{DynamoDbClient, KVTable, KVWriteClient};
+use crate::client::{DynamoDbClient, KVTable, KVWriteClient, ObjectStoreKVClient};
 use anyhow::{anyhow, Result};
 use mysten_metrics::spawn_monitored_task;
 use prometheus::{register_int_gauge_with_registry, IntGauge, Registry};
@@ -11,8 +11,12 @@ use std::time::Duration;
 use sui_config::node::TransactionKeyValueStoreWriteConfig;
 use sui_core::storage::RocksDbStore;
 use sui_storage::http_key_value_store::TaggedKey;
-use sui_types::effects::TransactionEffectsAPI;
-use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use sui_types::digests::TransactionEventsDigest;
+use sui_types::effects::{TransactionEffectsAPI, TransactionEvents};
+use sui_types::messages_checkpoint::{
+    CheckpointSequenceNumber, FullCheckpointContents, VerifiedCheckpoint,
+};
+use sui_types::object::Object;
 use sui_types::storage::{ObjectKey, ReadStore};
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
@@ -48,22 +52,24 @@ pub async fn setup_key_value_store_uploader(
     let metrics = KVStoreMetrics::new(registry);
 
     spawn_monitored_task!(async move {
-        upload_to_kv_store(store, receiver, config, metrics)
-            .await
-            .expect("Upload failed to key value store")
+        let result = if config.object_store_config.is_some() {
+            upload_to_kv_store::<ObjectStoreKVClient>(store, receiver, config, metrics).await
+        } else {
+            upload_to_kv_store::<DynamoDbClient>(store, receiver, config, metrics).await
+        };
+        result.expect("Upload failed to key value store")
     });
     Ok(Some(sender))
 }
 
-async fn upload_to_kv_store(
+async fn upload_to_kv_store<C: KVWriteClient + Send + Sync + 'static>(
     store: RocksDbStore,
     mut receiver: oneshot::Receiver<()>,
     config: TransactionKeyValueStoreWriteConfig,
     metrics: KVStoreMetrics,
 ) -> Result<()> {
-    let mut updates: HashSet<u64> = HashSet::new();
-    let mut client = DynamoDbClient::new(&config).await;
-    let mut checkpoint_number = client
+    let mut client = C::from_config(&config).await?;
+    let checkpoint_number = client
         .get_state()
         .await
         .expect("failed to fetch key value uploader state")
@@ -72,6 +78,7 @@ async fn upload_to_kv_store(
         "Key value store backfill. Current checkpoint is {}",
         checkpoint_number
     );
+    let mut progress = UploadProgress::new(checkpoint_number);
 
     let (progress_sender, mut progress_receiver) = mpsc::channel(1000);
     let mut child_handles = vec![];
@@ -83,7 +90,7 @@ async fn upload_to_kv_store(
         let (term_sender, term_receiver) = oneshot::channel();
         child_handles.push(term_sender);
         spawn_monitored_task!(async move {
-            uploader(
+            uploader::<C>(
                 shard_id as u64,
                 checkpoint_number,
                 cloned_store,
@@ -100,12 +107,7 @@ async fn upload_to_kv_store(
         tokio::select! {
             _ = &mut receiver => break,
             Some(status_update) = progress_receiver.recv() => {
-                updates.insert(status_update);
-                let update_db_state = status_update == checkpoint_number;
-                if update_db_state {
-                    while updates.remove(&checkpoint_number) {
-                        checkpoint_number += 1;
-                    }
+                if let Some(checkpoint_number) = progress.record(status_update) {
                     client.update_state(checkpoint_number).await?;
                     metrics
                         .latest_checkpoint_uploaded_to_kv_store
@@ -117,7 +119,7 @@ async fn upload_to_kv_store(
     Ok(())
 }
 
-pub async fn uploader(
+pub async fn uploader<C: KVWriteClient + Send + Sync>(
     shard_id: u64,
     mut checkpoint_number: CheckpointSequenceNumber,
     store: RocksDbStore,
@@ -125,7 +127,7 @@ pub async fn uploader(
     progress_sender: mpsc::Sender<u64>,
     mut receiver: oneshot::Receiver<()>,
 ) -> Result<()> {
-    let mut client = DynamoDbClient::new(&config).await;
+    let mut client = C::from_config(&config).await?;
     while receiver.try_recv().is_err() {
         let last_executed_checkpoint = store
             .get_last_executed_checkpoint()?
@@ -140,27 +142,15 @@ pub async fn uploader(
                     .get_full_checkpoint_contents(&checkpoint_summary.content_digest)
                     .map_err(|_| anyhow!("Failed to read checkpoint content from store"))?
                 {
-                    let mut transactions = vec![];
-                    let mut effects = vec![];
                     let mut events = vec![];
                     let mut objects = vec![];
-                    let mut transactions_to_checkpoint = vec![];
-
                     for content in contents.iter() {
-                        let transaction_digest = content.transaction.digest().into_inner().to_vec();
-                        effects.push((transaction_digest.clone(), content.effects.clone()));
-                        transactions_to_checkpoint.push((
-                            transaction_digest.clone(),
-                            checkpoint_summary.sequence_number,
-                        ));
-                        transactions.push((transaction_digest, content.transaction.clone()));
-
                         if let Some(event_digest) = content.effects.events_digest() {
                             if let Some(tx_events) = store
                                 .get_transaction_events(event_digest)
                                 .map_err(|_| anyhow!("Failed to fetch events from the store"))?
                             {
-                                events.push((event_digest.into_inner().to_vec(), tx_events));
+                                events.push((*event_digest, tx_events));
                             }
                         }
                         let object_keys: Vec<_> = content
@@ -174,49 +164,15 @@ pub async fn uploader(
                             object_keys.into_iter().zip(object_values.into_iter())
                         {
                             objects.push((
-                                bcs::to_bytes(&object_key)?,
+                                object_key,
                                 value.unwrap_or_else(|| {
                                     panic!("kv store: missing object {:?}", object_key)
                                 }),
                             ));
                         }
                     }
-                    client
-                        .multi_set(KVTable::Transactions, transactions)
-                        .await?;
-                    client.multi_set(KVTable::Effects, effects).await?;
-                    client.multi_set(KVTable::Events, events).await?;
-                    client.multi_set(KVTable::Objects, objects).await?;
-                    client
-                        .multi_set(KVTable::TransactionToCheckpoint, transactions_to_checkpoint)
+                    upload_checkpoint(&mut client, &checkpoint_summary, &contents, events, objects)
                         .await?;
-
-                    let serialized_checkpoint_number = bcs::to_bytes(
-                        &TaggedKey::CheckpointSequenceNumber(checkpoint_summary.sequence_number),
-                    )?;
-                    client
-                        .multi_set(
-                            KVTable::CheckpointSummary,
-                            [
-                                serialized_checkpoint_number.clone(),
-                                checkpoint_summary.digest().into_inner().to_vec(),
-                            ]
-                            .into_iter()
-                            .zip(repeat(checkpoint_summary.inner())),
-                        )
-                        .await?;
-                    for key in [
-                        serialized_checkpoint_number,
-                        checkpoint_summary.content_digest.into_inner().to_vec(),
-                    ] {
-                        client
-                            .upload_blob(
-                                KVTable::CheckpointContent,
-                                key,
-                                contents.checkpoint_contents(),
-                            )
-                            .await?;
-                    }
                     progress_sender.send(checkpoint_number + shard_id).await?;
                     checkpoint_number += config.concurrency as u64;
                     continue;
@@ -227,3 +183,211 @@ pub async fn uploader(
     }
     Ok(())
 }
+
+/// Upload a checkpoint to the key value store: its summary and contents, along with the
+/// transactions and effects in it, and the events and objects they wrote.
+pub async fn upload_checkpoint<C: KVWriteClient + Send + Sync>(
+    client: &mut C,
+    checkpoint_summary: &VerifiedCheckpoint,
+    contents: &FullCheckpointContents,
+    events: Vec<(TransactionEventsDigest, TransactionEvents)>,
+    objects: Vec<(ObjectKey, Object)>,
+) -> Result<()> {
+    let mut transactions = vec![];
+    let mut effects = vec![];
+    let mut transactions_to_checkpoint = vec![];
+    for content in contents.iter() {
+        let transaction_digest = content.transaction.digest().into_inner().to_vec();
+        effects.push((transaction_digest.clone(), content.effects.clone()));
+        transactions_to_checkpoint.push((
+            transaction_digest.clone(),
+            checkpoint_summary.sequence_number,
+        ));
+        transactions.push((transaction_digest, content.transaction.clone()));
+    }
+    let events: Vec<_> = events
+        .into_iter()
+        .map(|(digest, events)| (digest.into_inner().to_vec(), events))
+        .collect();
+    let objects = objects
+        .into_iter()
+        .map(|(object_key, object)| Ok((bcs::to_bytes(&object_key)?, object)))
+        .collect::<Result<Vec<_>>>()?;
+
+    client
+        .multi_set(KVTable::Transactions, transactions)
+        .await?;
+    client.multi_set(KVTable::Effects, effects).await?;
+    client.multi_set(KVTable::Events, events).await?;
+    client.multi_set(KVTable::Objects, objects).await?;
+    client
+        .multi_set(KVTable::TransactionToCheckpoint, transactions_to_checkpoint)
+        .await?;
+
+    let serialized_checkpoint_number = bcs::to_bytes(&TaggedKey::CheckpointSequenceNumber(
+        checkpoint_summary.sequence_number,
+    ))?;
+    client
+        .multi_set(
+            KVTable::CheckpointSummary,
+            [
+                serialized_checkpoint_number.clone(),
+                checkpoint_summary.digest().into_inner().to_vec(),
+            ]
+            .into_iter()
+            .zip(repeat(checkpoint_summary.inner())),
+        )
+        .await?;
+    for key in [
+        serialized_checkpoint_number,
+        checkpoint_summary.content_digest.into_inner().to_vec(),
+    ] {
+        client
+            .upload_blob(
+                KVTable::CheckpointContent,
+                key,
+                contents.checkpoint_contents(),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// Tracks which checkpoints have been uploaded, which may happen out of order, to work out how far
+/// the key value store has been uploaded without gaps. Uploading resumes from there on restart.
+pub struct UploadProgress {
+    next_checkpoint: CheckpointSequenceNumber,
+    uploaded: HashSet<CheckpointSequenceNumber>,
+}
+
+impl UploadProgress {
+    pub fn new(next_checkpoint: CheckpointSequenceNumber) -> Self {
+        Self {
+            next_checkpoint,
+            uploaded: HashSet::new(),
+        }
+    }
+
+    /// Record that `checkpoint` has been uploaded. Returns the next checkpoint to upload if every
+    /// checkpoint before it has now been uploaded, and it moved as a result.
+    pub fn record(
+        &mut self,
+        checkpoint: CheckpointSequenceNumber,
+    ) -> Option<CheckpointSequenceNumber> {
+        self.uploaded.insert(checkpoint);
+        if checkpoint != self.next_checkpoint {
+            return None;
+        }
+        while self.uploaded.remove(&self.next_checkpoint) {
+            self.next_checkpoint += 1;
+        }
+        Some(self.next_checkpoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sui_storage::key_value_store_metrics::KeyValueStoreMetrics;
+    use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
+    use sui_storage::object_store_key_value_store::ObjectStoreKVStore;
+    use sui_swarm_config::test_utils::CommitteeFixture;
+    use sui_types::base_types::SuiAddress;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_object_store_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let config = ObjectStoreConfig {
+            object_store: Some(ObjectStoreType::File),
+            directory: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let mut client = ObjectStoreKVClient::new(&config)?;
+        assert_eq!(client.get_state().await?, None);
+
+        let committee = CommitteeFixture::generate(rand::rngs::OsRng, 0, 4);
+        let (checkpoints, contents, _, _) = committee.make_random_checkpoints(3, None);
+        let contents: Vec<_> = contents.into_iter().map(|c| c.into_inner()).collect();
+        let events = TransactionEvents::default();
+        let mut progress = UploadProgress::new(1);
+
+        // Upload checkpoints out of order, as concurrent uploaders do.
+        for checkpoint in [2, 1] {
+            let objects = contents[checkpoint]
+                .iter()
+                .flat_map(|data| data.effects.all_changed_objects())
+                .map(|((id, version, _), _, _)| {
+                    let object =
+                        Object::with_id_owner_version_for_testing(id, version, SuiAddress::ZERO);
+                    (ObjectKey(id, version), object)
+                })
+                .collect();
+            upload_checkpoint(
+                &mut client,
+                &checkpoints[checkpoint],
+                &contents[checkpoint],
+                vec![(events.digest(), events.clone())],
+                objects,
+            )
+            .await?;
+            if let Some(next_checkpoint) = progress.record(checkpoint as u64) {
+                client.update_state(next_checkpoint).await?;
+            }
+        }
+
+        let kv_store = ObjectStoreKVStore::new_kv(&config, KeyValueStoreMetrics::new_for_tests())?;
+        for (checkpoint, contents) in checkpoints.iter().zip(&contents).skip(1) {
+            let sequence_number = checkpoint.sequence_number;
+            assert_eq!(
+                kv_store.get_checkpoint_summary(sequence_number).await?,
+                *checkpoint.inner()
+            );
+            assert_eq!(
+                kv_store
+                    .get_checkpoint_summary_by_digest(*checkpoint.digest())
+                    .await?,
+                *checkpoint.inner()
+            );
+            assert_eq!(
+                kv_store.get_checkpoint_contents(sequence_number).await?,
+                *contents.checkpoint_contents()
+            );
+            assert_eq!(
+                kv_store
+                    .get_checkpoint_contents_by_digest(checkpoint.content_digest)
+                    .await?,
+                *contents.checkpoint_contents()
+            );
+            for data in contents.iter() {
+                let digest = *data.transaction.digest();
+                assert_eq!(kv_store.get_tx(digest).await?, data.transaction);
+                assert_eq!(kv_store.get_fx_by_tx_digest(digest).await?, data.effects);
+                assert_eq!(
+                    kv_store.multi_get_transaction_checkpoint(&[digest]).await?,
+                    vec![Some(sequence_number)]
+                );
+                for ((id, version, _), _, _) in data.effects.all_changed_objects() {
+                    let object = kv_store.get_object(id, version).await?.unwrap();
+                    assert_eq!((object.id(), object.version()), (id, version));
+                }
+            }
+        }
+        assert_eq!(kv_store.get_events(events.digest()).await?, events);
+
+        // Uploading resumes after the last checkpoint uploaded without gaps before it.
+        let client = ObjectStoreKVClient::new(&config)?;
+        assert_eq!(client.get_state().await?, Some(3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upload_progress() {
+        let mut progress = UploadProgress::new(5);
+        assert_eq!(progress.record(7), None);
+        assert_eq!(progress.record(6), None);
+        assert_eq!(progress.record(5), Some(8));
+        assert_eq!(progress.record(9), None);
+        assert_eq!(progress.record(8), Some(10));
+    }
+}
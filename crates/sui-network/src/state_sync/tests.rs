@@ -292,6 +292,7 @@ async fn test_state_sync_using_archive() -> anyhow::Result<()> {
         StorageFormat::Blob,
         Duration::from_secs(10),
         20,
+        false,
         &Registry::default(),
     )
     .await?;
@@ -106,6 +106,7 @@ use sui_storage::{
     http_key_value_store::HttpKVStore,
     key_value_store::{FallbackTransactionKVStore, TransactionKeyValueStore},
     key_value_store_metrics::KeyValueStoreMetrics,
+    object_store_key_value_store::ObjectStoreKVStore,
 };
 use sui_storage::{FileCompression, IndexStore, StorageFormat};
 use sui_types::base_types::{AuthorityName, EpochId};
@@ -781,6 +782,7 @@ impl SuiNode {
                 StorageFormat::Blob,
                 Duration::from_secs(600),
                 256 * 1024 * 1024,
+                config.state_archive_write_config.write_transaction_index,
                 prometheus_registry,
             )
             .await?;
@@ -800,6 +802,9 @@ impl SuiNode {
                 &config.snapshot_path(),
                 remote_store_config.clone(),
                 60,
+                config
+                    .state_snapshot_write_config
+                    .delta_snapshots_per_full_snapshot,
                 prometheus_registry,
             )?;
             Ok(Some(snapshot_uploader.start()))
@@ -1636,6 +1641,18 @@ fn build_kv_store(
     let metrics = KeyValueStoreMetrics::new(registry);
     let db_store = TransactionKeyValueStore::new("rocksdb", metrics.clone(), state.clone());
 
+    if let Some(object_store_config) = &config.transaction_kv_store_read_config.object_store_config
+    {
+        let object_store = ObjectStoreKVStore::new_kv(object_store_config, metrics.clone())?;
+        info!("using local key-value store with fallback to object store key-value store");
+        return Ok(Arc::new(FallbackTransactionKVStore::new_kv(
+            db_store,
+            object_store,
+            metrics,
+            "json_rpc_fallback",
+        )));
+    }
+
     let base_url = &config.transaction_kv_store_read_config.base_url;
 
     if base_url.is_empty() {
@@ -1742,7 +1759,10 @@ pub fn build_http_server(
     router = router.merge(json_rpc_router);
 
     if config.enable_experimental_rest_api {
-        let rest_router = sui_rest_api::rest_router(state);
+        let executor = transaction_orchestrator
+            .clone()
+            .map(|orchestrator| orchestrator as Arc<dyn sui_rest_api::TransactionExecutor>);
+        let rest_router = sui_rest_api::rest_router(state, executor);
         router = router.nest("/rest", rest_router);
     }
 
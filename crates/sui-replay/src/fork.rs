@@ -0,0 +1,717 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Forking a [`Simulacrum`] from a live network.
+//!
+//! A forked chain starts from the state of a network as of one of its checkpoints. Objects are
+//! loaded from the network's fullnode the first time they are read, and everything written after
+//! the fork stays local, so transactions can be rehearsed against real state without affecting
+//! (or paying for) the original network.
+
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::time::Duration;
+
+use futures::executor::block_on;
+use move_binary_format::CompiledModule;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::{language_storage::ModuleId, resolver::ModuleResolver};
+use parking_lot::RwLock;
+use simulacrum::{Simulacrum, SimulatorStore};
+use sui_json_rpc_types::{
+    SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponseOptions,
+    SuiTransactionBlockResponseQuery, TransactionFilter,
+};
+use sui_sdk::SuiClientBuilder;
+use sui_types::{
+    base_types::{ObjectID, SequenceNumber, SuiAddress},
+    committee::{Committee, EpochId},
+    digests::{ObjectDigest, TransactionDigest, TransactionEventsDigest},
+    effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
+    error::{SuiError, SuiResult},
+    messages_checkpoint::{
+        CheckpointContents, CheckpointContentsDigest, CheckpointDigest, CheckpointSequenceNumber,
+        CheckpointSummary, VerifiedCheckpoint,
+    },
+    object::{Object, Owner},
+    storage::{
+        BackingPackageStore, ChildObjectResolver, ObjectStore, ParentSync, ReceivedMarkerQuery,
+    },
+    transaction::VerifiedTransaction,
+};
+use tracing::warn;
+
+use crate::data_fetcher::{DataFetcher, RemoteFetcher};
+use crate::types::{
+    ReplayEngineError, MAX_CONCURRENT_REQUESTS, RPC_TIMEOUT_ERR_NUM_RETRIES,
+    RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD,
+};
+
+/// Fork the network served over JSON-RPC at `http_url` at `checkpoint` (or at its latest
+/// checkpoint, if none is given), using `rng` as the forked chain's source of randomness.
+///
+/// See [`Simulacrum::new_forked`] for how the forked chain differs from the original. Objects are
+/// loaded by blocking on requests to the network, so the forked chain must be driven from a
+/// multi-threaded tokio runtime.
+pub async fn fork<R>(
+    http_url: &str,
+    checkpoint: Option<CheckpointSequenceNumber>,
+    rng: R,
+) -> Result<Simulacrum<R, ForkedStore>, ReplayEngineError>
+where
+    R: rand::RngCore + rand::CryptoRng,
+{
+    let rpc_client = SuiClientBuilder::default()
+        .request_timeout(RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD)
+        .max_concurrent_requests(MAX_CONCURRENT_REQUESTS)
+        .build(http_url)
+        .await?;
+
+    let checkpoint = match checkpoint {
+        Some(checkpoint) => checkpoint,
+        None => {
+            rpc_client
+                .read_api()
+                .get_latest_checkpoint_sequence_number()
+                .await?
+        }
+    };
+
+    let checkpoint = rpc_client
+        .read_api()
+        .get_checkpoint(checkpoint.into())
+        .await?;
+
+    // The digest of the checkpoint's contents is not available over JSON-RPC, and the contents
+    // are not needed to continue the chain, so a placeholder is used instead.
+    let summary = CheckpointSummary {
+        epoch: checkpoint.epoch,
+        sequence_number: checkpoint.sequence_number,
+        network_total_transactions: checkpoint.network_total_transactions,
+        content_digest: CheckpointContentsDigest::new([0; 32]),
+        previous_digest: checkpoint.previous_digest,
+        epoch_rolling_gas_cost_summary: checkpoint.epoch_rolling_gas_cost_summary,
+        end_of_epoch_data: checkpoint.end_of_epoch_data,
+        timestamp_ms: checkpoint.timestamp_ms,
+        version_specific_data: Vec::new(),
+        checkpoint_commitments: checkpoint.checkpoint_commitments,
+    };
+
+    let store = ForkedStore::new(RemoteFetcher::new(rpc_client), checkpoint.sequence_number);
+    Ok(Simulacrum::new_forked(rng, store, summary))
+}
+
+/// A [`SimulatorStore`] for a chain forked from a live network.
+///
+/// Checkpoints, transactions and objects written after the fork are kept in memory. Objects that
+/// have not been written since the fork are loaded from the network the first time they are read,
+/// at the version they had as of the fork checkpoint, including objects that have been deleted or
+/// wrapped on the network since. Requests that fail with transient errors are retried.
+pub struct ForkedStore {
+    fetcher: RemoteFetcher,
+
+    /// The sequence number of the checkpoint the chain was forked from.
+    fork_checkpoint: CheckpointSequenceNumber,
+
+    // Retry policies due to RPC errors
+    num_retries_for_timeout: u32,
+    sleep_period_for_timeout: Duration,
+
+    // Checkpoint data
+    checkpoints: BTreeMap<CheckpointSequenceNumber, VerifiedCheckpoint>,
+    checkpoint_digest_to_sequence_number: HashMap<CheckpointDigest, CheckpointSequenceNumber>,
+    checkpoint_contents: HashMap<CheckpointContentsDigest, CheckpointContents>,
+
+    // Transaction data
+    transactions: HashMap<TransactionDigest, VerifiedTransaction>,
+    effects: HashMap<TransactionDigest, TransactionEffects>,
+    events: HashMap<TransactionEventsDigest, TransactionEvents>,
+
+    // Committee data
+    epoch_to_committee: BTreeMap<EpochId, Committee>,
+
+    // Object data
+    /// The latest version of each object written since the fork, or `None` if it has since been
+    /// deleted or wrapped.
+    live_objects: HashMap<ObjectID, Option<SequenceNumber>>,
+    objects: HashMap<ObjectID, BTreeMap<SequenceNumber, Object>>,
+    /// Objects as of the fork checkpoint, loaded from the network on demand, or `None` if they did
+    /// not exist then.
+    fork_objects: RwLock<HashMap<ObjectID, Option<Object>>>,
+}
+
+impl ForkedStore {
+    pub(crate) fn new(fetcher: RemoteFetcher, fork_checkpoint: CheckpointSequenceNumber) -> Self {
+        Self {
+            fetcher,
+            fork_checkpoint,
+            num_retries_for_timeout: RPC_TIMEOUT_ERR_NUM_RETRIES,
+            sleep_period_for_timeout: RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD,
+            checkpoints: BTreeMap::new(),
+            checkpoint_digest_to_sequence_number: HashMap::new(),
+            checkpoint_contents: HashMap::new(),
+            transactions: HashMap::new(),
+            effects: HashMap::new(),
+            events: HashMap::new(),
+            epoch_to_committee: BTreeMap::new(),
+            live_objects: HashMap::new(),
+            objects: HashMap::new(),
+            fork_objects: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn fork_checkpoint(&self) -> CheckpointSequenceNumber {
+        self.fork_checkpoint
+    }
+
+    /// The latest version of object `id` on the forked chain, surfacing errors loading it from the
+    /// network that [`SimulatorStore::get_object`] panics on.
+    pub fn try_get_object(&self, id: &ObjectID) -> Result<Option<Object>, ReplayEngineError> {
+        match self.live_objects.get(id) {
+            Some(Some(version)) => Ok(self.get_written_object(id, *version)),
+            Some(None) => Ok(None),
+            None => self.get_fork_object(id),
+        }
+    }
+
+    /// Object `id` at `version` on the forked chain, surfacing errors loading it from the network
+    /// that [`SimulatorStore::get_object_at_version`] panics on.
+    ///
+    /// Objects are only fetched from the network at versions that precede their version as of the
+    /// fork, so that versions written on the network after the fork are never seen.
+    // TODO: remove this after `futures::executor::block_on` is removed.
+    #[allow(clippy::disallowed_methods)]
+    pub fn try_get_object_at_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>, ReplayEngineError> {
+        if let Some(object) = self.get_written_object(id, version) {
+            return Ok(Some(object));
+        }
+
+        match self.get_fork_object(id)? {
+            Some(object) if object.version() == version => Ok(Some(object)),
+            Some(object) if object.version() > version => {
+                match block_on(
+                    self.with_retries(|| self.fetcher.multi_get_versioned(&[(*id, version)])),
+                ) {
+                    Ok(mut objects) => Ok(objects.pop()),
+                    Err(ReplayEngineError::ObjectNotExist { .. })
+                    | Err(ReplayEngineError::ObjectDeleted { .. })
+                    | Err(ReplayEngineError::ObjectVersionNotFound { .. }) => Ok(None),
+                    Err(e) => Err(e),
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// The latest version of the object at or before `version`.
+    fn try_get_object_lt_or_eq_version(
+        &self,
+        id: &ObjectID,
+        version: SequenceNumber,
+    ) -> Result<Option<Object>, ReplayEngineError> {
+        if let Some(None) = self.live_objects.get(id) {
+            return Ok(None);
+        }
+
+        if let Some((_, object)) = self
+            .objects
+            .get(id)
+            .and_then(|versions| versions.range(..=version).next_back())
+        {
+            return Ok(Some(object.clone()));
+        }
+
+        Ok(self
+            .get_fork_object(id)?
+            .filter(|object| object.version() <= version))
+    }
+
+    fn get_written_object(&self, id: &ObjectID, version: SequenceNumber) -> Option<Object> {
+        self.objects
+            .get(id)
+            .and_then(|versions| versions.get(&version))
+            .cloned()
+    }
+
+    // TODO: remove this after `futures::executor::block_on` is removed.
+    #[allow(clippy::disallowed_methods)]
+    fn get_fork_object(&self, id: &ObjectID) -> Result<Option<Object>, ReplayEngineError> {
+        if let Some(object) = self.fork_objects.read().get(id) {
+            return Ok(object.clone());
+        }
+
+        let object = block_on(self.with_retries(|| self.load_fork_object(*id)))?;
+        self.fork_objects.write().insert(*id, object.clone());
+        Ok(object)
+    }
+
+    /// Run `request` against the network, retrying it while it fails with transient errors, like
+    /// [`crate::LocalExec`] does.
+    async fn with_retries<T, F, Fut>(&self, request: F) -> Result<T, ReplayEngineError>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T, ReplayEngineError>>,
+    {
+        let mut num_retries_for_timeout = self.num_retries_for_timeout;
+        loop {
+            match request().await {
+                Err(e @ ReplayEngineError::SuiRpcRequestTimeout)
+                | Err(e @ ReplayEngineError::SuiRpcError { .. })
+                    if num_retries_for_timeout > 0 =>
+                {
+                    warn!(
+                        "RPC request failed: {e}. Retries left {}. Sleeping for {}s",
+                        num_retries_for_timeout,
+                        self.sleep_period_for_timeout.as_secs()
+                    );
+                    num_retries_for_timeout -= 1;
+                    tokio::time::sleep(self.sleep_period_for_timeout).await;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Load the version of object `id` that was live as of the fork checkpoint from the network,
+    /// by starting from its latest version, and following the transactions that modified it
+    /// backwards until reaching one from before the fork.
+    async fn load_fork_object(&self, id: ObjectID) -> Result<Option<Object>, ReplayEngineError> {
+        let mut object = match self.fetcher.multi_get_latest(&[id]).await {
+            Ok(mut objects) => objects
+                .pop()
+                .ok_or(ReplayEngineError::ObjectNotExist { id })?,
+            Err(ReplayEngineError::ObjectNotExist { .. })
+            | Err(ReplayEngineError::ObjectDeleted { .. }) => {
+                return self.load_removed_fork_object(id).await
+            }
+            Err(e) => return Err(e),
+        };
+
+        loop {
+            let digest = object.previous_transaction;
+            let transaction = self.fetcher.get_transaction(&digest).await?;
+
+            let checkpoint =
+                transaction
+                    .checkpoint
+                    .ok_or_else(|| ReplayEngineError::GeneralError {
+                        err: format!("Transaction {digest} is not in a checkpoint yet"),
+                    })?;
+
+            if checkpoint <= self.fork_checkpoint {
+                return Ok(Some(object));
+            }
+
+            let effects = transaction
+                .effects
+                .ok_or_else(|| ReplayEngineError::GeneralError {
+                    err: format!("Effects for transaction {digest} are not available"),
+                })?;
+
+            // If the transaction didn't modify the object, it created (or unwrapped) it, so it
+            // didn't exist (or wasn't accessible) at the fork.
+            let Some((_, version)) = effects
+                .modified_at_versions()
+                .into_iter()
+                .find(|(object_id, _)| *object_id == id)
+            else {
+                return Ok(None);
+            };
+
+            object = self
+                .fetcher
+                .multi_get_versioned(&[(id, version)])
+                .await?
+                .pop()
+                .ok_or(ReplayEngineError::ObjectVersionNotFound { id, version })?;
+        }
+    }
+
+    /// Load the version of object `id` that was live as of the fork checkpoint, for an object that
+    /// no longer exists on the network, so has no latest version to follow backwards from.
+    ///
+    /// The network's transaction indices are searched for the last transactions before the fork
+    /// that changed the object or took it as input, and whichever of them came later decides its
+    /// state at the fork: the object is loaded at the version that transaction wrote, or is `None`
+    /// if the transaction deleted or wrapped it. Objects that no transaction before the fork
+    /// touched were created after it, and are also `None`.
+    async fn load_removed_fork_object(
+        &self,
+        id: ObjectID,
+    ) -> Result<Option<Object>, ReplayEngineError> {
+        let changed = self
+            .last_fork_change(TransactionFilter::ChangedObject(id), id)
+            .await?;
+        let input = self
+            .last_fork_change(TransactionFilter::InputObject(id), id)
+            .await?;
+
+        // Each transaction that touches an object leaves it at a greater version, so the later
+        // transaction is the one with the greater version.
+        match changed.into_iter().chain(input).max_by_key(|(v, _)| *v) {
+            Some((version, true)) => Ok(self
+                .fetcher
+                .multi_get_versioned(&[(id, version)])
+                .await?
+                .pop()),
+            Some((_, false)) | None => Ok(None),
+        }
+    }
+
+    /// The version that the last transaction before the fork matching `filter` left object `id`
+    /// at, and whether the object still existed afterwards (`false` if it was deleted or wrapped).
+    async fn last_fork_change(
+        &self,
+        filter: TransactionFilter,
+        id: ObjectID,
+    ) -> Result<Option<(SequenceNumber, bool)>, ReplayEngineError> {
+        let query = SuiTransactionBlockResponseQuery::new(
+            Some(filter),
+            Some(SuiTransactionBlockResponseOptions::new().with_effects()),
+        );
+
+        let mut cursor = None;
+        loop {
+            let page = self
+                .fetcher
+                .rpc_client
+                .read_api()
+                .query_transaction_blocks(query.clone(), cursor, None, true)
+                .await?;
+
+            for transaction in page.data {
+                if !matches!(transaction.checkpoint, Some(cp) if cp <= self.fork_checkpoint) {
+                    continue;
+                }
+
+                let Some(effects) = transaction.effects else {
+                    continue;
+                };
+
+                let changed = effects
+                    .all_changed_objects()
+                    .into_iter()
+                    .find(|(oref, _)| oref.reference.object_id == id)
+                    .map(|(oref, _)| (oref.reference.version, true));
+
+                let removed = effects
+                    .all_deleted_objects()
+                    .into_iter()
+                    .find(|(oref, _)| oref.object_id == id)
+                    .map(|(oref, _)| (oref.version, false));
+
+                // Transactions that only read the object leave it as it was.
+                if let Some(change) = changed.or(removed) {
+                    return Ok(Some(change));
+                }
+            }
+
+            if !page.has_next_page {
+                return Ok(None);
+            }
+            cursor = page.next_cursor;
+        }
+    }
+}
+
+impl SimulatorStore for ForkedStore {
+    fn get_checkpoint_by_sequence_number(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> Option<VerifiedCheckpoint> {
+        self.checkpoints.get(&sequence_number).cloned()
+    }
+
+    fn get_checkpoint_by_digest(&self, digest: &CheckpointDigest) -> Option<VerifiedCheckpoint> {
+        self.checkpoint_digest_to_sequence_number
+            .get(digest)
+            .and_then(|sequence_number| self.checkpoints.get(sequence_number))
+            .cloned()
+    }
+
+    fn get_highest_checkpint(&self) -> Option<VerifiedCheckpoint> {
+        self.checkpoints
+            .last_key_value()
+            .map(|(_, checkpoint)| checkpoint.clone())
+    }
+
+    fn get_checkpoint_contents(
+        &self,
+        digest: &CheckpointContentsDigest,
+    ) -> Option<CheckpointContents> {
+        self.checkpoint_contents.get(digest).cloned()
+    }
+
+    fn get_committee_by_epoch(&self, epoch: EpochId) -> Option<Committee> {
+        self.epoch_to_committee.get(&epoch).cloned()
+    }
+
+    fn get_transaction(&self, digest: &TransactionDigest) -> Option<VerifiedTransaction> {
+        self.transactions.get(digest).cloned()
+    }
+
+    fn get_transaction_effects(&self, digest: &TransactionDigest) -> Option<TransactionEffects> {
+        self.effects.get(digest).cloned()
+    }
+
+    fn get_transaction_events(
+        &self,
+        digest: &TransactionEventsDigest,
+    ) -> Option<TransactionEvents> {
+        self.events.get(digest).cloned()
+    }
+
+    /// Panics if the object can't be loaded from the network, even after retrying. Use
+    /// [`ForkedStore::try_get_object`] to handle the error instead.
+    fn get_object(&self, id: &ObjectID) -> Option<Object> {
+        self.try_get_object(id)
+            .unwrap_or_else(|e| panic!("Failed to load object {id} from the network: {e}"))
+    }
+
+    fn get_object_at_version(&self, id: &ObjectID, version: SequenceNumber) -> Option<Object> {
+        self.try_get_object_at_version(id, version)
+            .unwrap_or_else(|e| panic!("Failed to load object {id} from the network: {e}"))
+    }
+
+    fn get_system_state(&self) -> sui_types::sui_system_state::SuiSystemState {
+        sui_types::sui_system_state::get_sui_system_state(self).expect("system state must exist")
+    }
+
+    fn get_clock(&self) -> sui_types::clock::Clock {
+        SimulatorStore::get_object(self, &sui_types::SUI_CLOCK_OBJECT_ID)
+            .expect("clock should exist")
+            .to_rust()
+            .expect("clock object should deserialize")
+    }
+
+    /// Only objects that have been loaded from the network, or written since the fork, are
+    /// included.
+    fn owned_objects(&self, owner: SuiAddress) -> Box<dyn Iterator<Item = Object> + '_> {
+        let fork_objects = self.fork_objects.read();
+
+        let written = self
+            .live_objects
+            .iter()
+            .filter_map(|(id, version)| self.get_written_object(id, (*version)?));
+
+        let loaded = fork_objects
+            .iter()
+            .filter(|(id, _)| !self.live_objects.contains_key(id))
+            .filter_map(|(_, object)| object.clone());
+
+        let objects: Vec<_> = written
+            .chain(loaded)
+            .filter(|object| matches!(object.owner, Owner::AddressOwner(addr) if addr == owner))
+            .collect();
+
+        Box::new(objects.into_iter())
+    }
+
+    fn insert_checkpoint(&mut self, checkpoint: VerifiedCheckpoint) {
+        if let Some(end_of_epoch_data) = &checkpoint.data().end_of_epoch_data {
+            let next_committee = end_of_epoch_data
+                .next_epoch_committee
+                .iter()
+                .cloned()
+                .collect();
+            let committee = Committee::new(checkpoint.epoch().saturating_add(1), next_committee);
+            self.insert_committee(committee);
+        }
+
+        self.checkpoint_digest_to_sequence_number
+            .insert(*checkpoint.digest(), *checkpoint.sequence_number());
+        self.checkpoints
+            .insert(*checkpoint.sequence_number(), checkpoint);
+    }
+
+    fn insert_checkpoint_contents(&mut self, contents: CheckpointContents) {
+        self.checkpoint_contents
+            .insert(*contents.digest(), contents);
+    }
+
+    /// The first committee inserted can be from any epoch (the epoch the chain was forked in), but
+    /// the committees that follow it must be inserted in order.
+    fn insert_committee(&mut self, committee: Committee) {
+        let epoch = committee.epoch;
+
+        if self.epoch_to_committee.contains_key(&epoch) {
+            return;
+        }
+
+        if let Some((last, _)) = self.epoch_to_committee.last_key_value() {
+            assert_eq!(
+                *last + 1,
+                epoch,
+                "committee was inserted into EpochCommitteeMap out of order"
+            );
+        }
+
+        self.epoch_to_committee.insert(epoch, committee);
+    }
+
+    fn insert_executed_transaction(
+        &mut self,
+        transaction: VerifiedTransaction,
+        effects: TransactionEffects,
+        events: TransactionEvents,
+        written_objects: BTreeMap<ObjectID, Object>,
+    ) {
+        // Objects that are wrapped need to be hidden as well, otherwise reads would fall back to
+        // their version as of the fork.
+        let mut deleted_objects = effects.deleted();
+        deleted_objects.extend(effects.wrapped());
+        self.insert_transaction(transaction);
+        self.insert_transaction_effects(effects);
+        self.insert_events(events);
+        self.update_objects(written_objects, deleted_objects);
+    }
+
+    fn insert_transaction(&mut self, transaction: VerifiedTransaction) {
+        self.transactions.insert(*transaction.digest(), transaction);
+    }
+
+    fn insert_transaction_effects(&mut self, effects: TransactionEffects) {
+        self.effects.insert(*effects.transaction_digest(), effects);
+    }
+
+    fn insert_events(&mut self, events: TransactionEvents) {
+        self.events.insert(events.digest(), events);
+    }
+
+    fn update_objects(
+        &mut self,
+        written_objects: BTreeMap<ObjectID, Object>,
+        deleted_objects: Vec<(ObjectID, SequenceNumber, ObjectDigest)>,
+    ) {
+        for (object_id, _, _) in deleted_objects {
+            self.live_objects.insert(object_id, None);
+        }
+
+        for (object_id, object) in written_objects {
+            let version = object.version();
+            self.live_objects.insert(object_id, Some(version));
+            self.objects
+                .entry(object_id)
+                .or_default()
+                .insert(version, object);
+        }
+    }
+}
+
+impl BackingPackageStore for ForkedStore {
+    fn get_package_object(&self, package_id: &ObjectID) -> SuiResult<Option<Object>> {
+        Ok(self.try_get_object(package_id)?)
+    }
+}
+
+impl ChildObjectResolver for ForkedStore {
+    fn read_child_object(
+        &self,
+        parent: &ObjectID,
+        child: &ObjectID,
+        child_version_upper_bound: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        let Some(child_object) =
+            self.try_get_object_lt_or_eq_version(child, child_version_upper_bound)?
+        else {
+            return Ok(None);
+        };
+
+        let parent = *parent;
+        if child_object.owner != Owner::ObjectOwner(parent.into()) {
+            return Err(SuiError::InvalidChildObjectAccess {
+                object: *child,
+                given_parent: parent,
+                actual_owner: child_object.owner,
+            });
+        }
+
+        Ok(Some(child_object))
+    }
+
+    fn get_object_received_at_version(
+        &self,
+        owner: &ObjectID,
+        receiving_object_id: &ObjectID,
+        receive_object_at_version: SequenceNumber,
+        _epoch_id: EpochId,
+    ) -> SuiResult<Option<Object>> {
+        let Some(recv_object) = self.try_get_object(receiving_object_id)? else {
+            return Ok(None);
+        };
+
+        if recv_object.owner != Owner::AddressOwner((*owner).into()) {
+            return Ok(None);
+        }
+
+        if recv_object.version() != receive_object_at_version {
+            return Ok(None);
+        }
+        Ok(Some(recv_object))
+    }
+}
+
+impl ReceivedMarkerQuery for ForkedStore {
+    fn have_received_object_at_version(
+        &self,
+        _object_id: &ObjectID,
+        _version: sui_types::base_types::VersionNumber,
+        _epoch_id: EpochId,
+    ) -> Result<bool, SuiError> {
+        // There is no need for equivocation protection in simulation, so there is no marker table.
+        Ok(false)
+    }
+}
+
+impl GetModule for ForkedStore {
+    type Error = SuiError;
+    type Item = CompiledModule;
+
+    fn get_module_by_id(&self, id: &ModuleId) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(self
+            .get_module(id)?
+            .map(|bytes| CompiledModule::deserialize_with_defaults(&bytes).unwrap()))
+    }
+}
+
+impl ModuleResolver for ForkedStore {
+    type Error = SuiError;
+
+    fn get_module(&self, module_id: &ModuleId) -> Result<Option<Vec<u8>>, Self::Error> {
+        Ok(self
+            .get_package(&ObjectID::from(*module_id.address()))?
+            .and_then(|package| {
+                package
+                    .serialized_module_map()
+                    .get(module_id.name().as_str())
+                    .cloned()
+            }))
+    }
+}
+
+impl ObjectStore for ForkedStore {
+    fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
+        Ok(self.try_get_object(object_id)?)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: sui_types::base_types::VersionNumber,
+    ) -> Result<Option<Object>, SuiError> {
+        Ok(self.try_get_object_at_version(object_id, version)?)
+    }
+}
+
+impl ParentSync for ForkedStore {
+    fn get_latest_parent_entry_ref_deprecated(
+        &self,
+        _object_id: ObjectID,
+    ) -> SuiResult<Option<sui_types::base_types::ObjectRef>> {
+        panic!("Never called in newer protocol versions")
+    }
+}
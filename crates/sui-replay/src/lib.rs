@@ -1,6 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use anyhow::Context;
 use async_recursion::async_recursion;
 use clap::Parser;
 use config::ReplayableNetworkConfigSet;
@@ -15,13 +16,14 @@ use crate::replay::ExecutionSandboxState;
 use crate::replay::LocalExec;
 use crate::replay::ProtocolVersionSummary;
 use std::io::BufRead;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use sui_config::node::ExpensiveSafetyCheckConfig;
 use sui_types::digests::TransactionDigest;
 use tracing::{error, info};
 pub mod config;
 mod data_fetcher;
+pub mod fork;
 pub mod fuzz;
 pub mod fuzz_mutations;
 mod replay;
@@ -68,6 +70,10 @@ pub enum ReplayToolCommand {
         executor_version_override: Option<i64>,
         #[arg(long, short, allow_hyphen_values = true)]
         protocol_version_override: Option<i64>,
+        /// Profile the gas used by the transaction, and write the profile to this file, in the
+        /// speedscope (https://www.speedscope.app) format
+        #[arg(long)]
+        profile_output: Option<PathBuf>,
     },
 
     /// Replay transactions listed in a file
@@ -88,6 +94,10 @@ pub enum ReplayToolCommand {
         path: String,
         #[arg(long, short)]
         show_effects: bool,
+        /// Profile the gas used by the transaction, and write the profile to this file, in the
+        /// speedscope (https://www.speedscope.app) format
+        #[arg(long)]
+        profile_output: Option<PathBuf>,
     },
 
     /// Replay all transactions in a range of checkpoints
@@ -168,6 +178,7 @@ pub async fn execute_replay_command(
                 use_authority,
                 None,
                 None,
+                None,
             )
             .await?;
 
@@ -200,8 +211,16 @@ pub async fn execute_replay_command(
             fuzzer.run(num_base_transactions).await.unwrap();
             None
         }
-        ReplayToolCommand::ReplayDump { path, show_effects } => {
+        ReplayToolCommand::ReplayDump {
+            path,
+            show_effects,
+            profile_output,
+        } => {
+            if let Some(profile_output) = &profile_output {
+                check_profile_output(profile_output)?;
+            }
             let mut lx = LocalExec::new_for_state_dump(&path, rpc_url).await?;
+            lx.enable_profiler = profile_output;
             let (sandbox_state, node_dump_state) = lx.execute_state_dump(safety).await?;
             if show_effects {
                 println!("{:#?}", sandbox_state.local_exec_effects);
@@ -249,6 +268,7 @@ pub async fn execute_replay_command(
                             use_authority,
                             None,
                             None,
+                            None,
                         )
                         .await?;
 
@@ -341,7 +361,16 @@ pub async fn execute_replay_command(
             diag,
             executor_version_override,
             protocol_version_override,
+            profile_output,
         } => {
+            if let Some(profile_output) = &profile_output {
+                if use_authority {
+                    anyhow::bail!(
+                        "Gas profiling is not supported when executing with an authority"
+                    );
+                }
+                check_profile_output(profile_output)?;
+            }
             let tx_digest = TransactionDigest::from_str(&tx_digest)?;
             info!("Executing tx: {}", tx_digest);
             let sandbox_state = LocalExec::replay_with_network_config(
@@ -352,6 +381,7 @@ pub async fn execute_replay_command(
                 use_authority,
                 executor_version_override,
                 protocol_version_override,
+                profile_output,
             )
             .await?;
 
@@ -526,3 +556,14 @@ pub async fn execute_replay_command(
         }
     })
 }
+
+/// Checks that a gas profile can be written to `path` before replaying, as the profile is only
+/// written once the transaction has been executed.
+fn check_profile_output(path: &Path) -> anyhow::Result<()> {
+    if !cfg!(any(debug_assertions, feature = "gas-profiler")) {
+        anyhow::bail!("Gas profiling requires building with the `gas-profiler` feature");
+    }
+    std::fs::File::create(path)
+        .with_context(|| format!("Cannot write the gas profile to {}", path.display()))?;
+    Ok(())
+}
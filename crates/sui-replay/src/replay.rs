@@ -233,6 +233,9 @@ pub struct LocalExec {
     // -1 implies use latest version
     // None implies use the protocol version at the time of execution
     pub protocol_version_override: Option<i64>,
+    // If set, the gas used by each transaction executed is profiled, and the profile is written
+    // to this path
+    pub enable_profiler: Option<PathBuf>,
     // Retry policies due to RPC errors
     pub num_retries_for_timeout: u32,
     pub sleep_period_for_timeout: std::time::Duration,
@@ -316,6 +319,7 @@ impl LocalExec {
         use_authority: bool,
         executor_version_override: Option<i64>,
         protocol_version_override: Option<i64>,
+        enable_profiler: Option<PathBuf>,
     ) -> Result<ExecutionSandboxState, ReplayEngineError> {
         async fn inner_exec(
             rpc_url: String,
@@ -324,19 +328,21 @@ impl LocalExec {
             use_authority: bool,
             executor_version_override: Option<i64>,
             protocol_version_override: Option<i64>,
+            enable_profiler: Option<PathBuf>,
         ) -> Result<ExecutionSandboxState, ReplayEngineError> {
-            LocalExec::new_from_fn_url(&rpc_url)
+            let mut exec = LocalExec::new_from_fn_url(&rpc_url)
                 .await?
                 .init_for_execution()
-                .await?
-                .execute_transaction(
-                    &tx_digest,
-                    expensive_safety_check_config,
-                    use_authority,
-                    executor_version_override,
-                    protocol_version_override,
-                )
-                .await
+                .await?;
+            exec.enable_profiler = enable_profiler;
+            exec.execute_transaction(
+                &tx_digest,
+                expensive_safety_check_config,
+                use_authority,
+                executor_version_override,
+                protocol_version_override,
+            )
+            .await
         }
 
         if let Some(url) = rpc_url.clone() {
@@ -348,6 +354,7 @@ impl LocalExec {
                 use_authority,
                 executor_version_override,
                 protocol_version_override,
+                enable_profiler.clone(),
             )
             .await
             {
@@ -374,6 +381,7 @@ impl LocalExec {
                 use_authority,
                 executor_version_override,
                 protocol_version_override,
+                enable_profiler.clone(),
             )
             .await
             {
@@ -433,6 +441,7 @@ impl LocalExec {
             diag: Default::default(),
             executor_version_override: None,
             protocol_version_override: None,
+            enable_profiler: None,
         })
     }
 
@@ -475,6 +484,7 @@ impl LocalExec {
             diag: Default::default(),
             executor_version_override: None,
             protocol_version_override: None,
+            enable_profiler: None,
         })
     }
 
@@ -729,7 +739,12 @@ impl LocalExec {
         let ov = self.executor_version_override;
 
         // We could probably cache the executor per protocol config
-        let executor = get_executor(ov, protocol_config, expensive_safety_check_config);
+        let executor = get_executor(
+            ov,
+            protocol_config,
+            expensive_safety_check_config,
+            self.enable_profiler.clone(),
+        );
 
         // All prep done
         let expensive_checks = true;
@@ -1986,6 +2001,7 @@ pub fn get_executor(
     executor_version_override: Option<i64>,
     protocol_config: &ProtocolConfig,
     expensive_safety_check_config: ExpensiveSafetyCheckConfig,
+    enable_profiler: Option<PathBuf>,
 ) -> Arc<dyn Executor + Send + Sync> {
     let protocol_config = executor_version_override
         .map(|q| {
@@ -2006,6 +2022,7 @@ pub fn get_executor(
         &protocol_config,
         expensive_safety_check_config.enable_move_vm_paranoid_checks(),
         silent,
+        enable_profiler,
     )
     .expect("Creating an executor should not fail here")
 }
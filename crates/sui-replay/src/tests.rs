@@ -2,15 +2,37 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::ReplayableNetworkConfigSet;
+use crate::fork::fork;
 use crate::types::ReplayEngineError;
 use crate::types::{MAX_CONCURRENT_REQUESTS, RPC_TIMEOUT_ERR_SLEEP_RETRY_PERIOD};
 use crate::LocalExec;
+use crate::{execute_replay_command, ReplayToolCommand};
+use move_core_types::identifier::Identifier;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use shared_crypto::intent::Intent;
+use simulacrum::{Simulacrum, SimulatorStore};
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use sui_config::node::ExpensiveSafetyCheckConfig;
+use sui_core::authority::NodeStateDump;
+use sui_framework::BuiltInFramework;
 use sui_json_rpc::api::QUERY_MAX_RESULT_LIMIT;
 use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
 use sui_sdk::{SuiClient, SuiClientBuilder};
-use sui_types::base_types::SuiAddress;
+use sui_types::base_types::{ObjectRef, SuiAddress};
+use sui_types::crypto::{get_key_pair_from_rng, AccountKeyPair};
 use sui_types::digests::TransactionDigest;
+use sui_types::effects::TransactionEffectsAPI;
+use sui_types::gas_coin::{GasCoin, GAS};
+use sui_types::message_envelope::Message;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::sui_system_state::SuiSystemStateTrait;
+use sui_types::transaction::{
+    Argument, Command, GasData, ObjectArg, Transaction, TransactionData, TransactionKind,
+};
+use sui_types::SUI_FRAMEWORK_PACKAGE_ID;
 
 /// Keep searching for non-system TXs in the checkppints for this long
 /// Very unlikely to take this long, but we want to be sure we find one
@@ -156,3 +178,314 @@ async fn execute_replay(url: &str, tx: &TransactionDigest) -> Result<(), ReplayE
 
     Ok(())
 }
+
+/// Transfer `amount` of the SUI in `gas` from `sender` to `recipient`.
+fn transfer_sui(
+    sender: SuiAddress,
+    key: &AccountKeyPair,
+    gas: ObjectRef,
+    recipient: SuiAddress,
+    amount: u64,
+    gas_price: u64,
+) -> Transaction {
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.transfer_sui(recipient, Some(amount));
+        builder.finish()
+    };
+
+    let kind = TransactionKind::ProgrammableTransaction(pt);
+    let gas_data = GasData {
+        payment: vec![gas],
+        owner: sender,
+        price: gas_price,
+        budget: 1_000_000_000,
+    };
+
+    let tx_data = TransactionData::new_with_gas_data(kind, sender, gas_data);
+    Transaction::from_data_and_signer(tx_data, Intent::sui_transaction(), vec![key])
+}
+
+/// Fork a simulated network served by a local stand-in server, and check that the fork sees the
+/// network's state as of the fork checkpoint, and can be driven independently of it.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fork_from_stand_in_network() {
+    let mut rng = StdRng::from_seed([7; 32]);
+    let (sender, key): (_, AccountKeyPair) = get_key_pair_from_rng(&mut rng);
+    let recipient = SuiAddress::random_for_testing_only();
+
+    let mut network = Simulacrum::new_with_rng(rng);
+    network.request_gas(sender, 5_000_000_000).unwrap();
+    let fork_point = network.create_checkpoint();
+    let fork_time_ms = network.store().get_clock().timestamp_ms();
+
+    let coin = network
+        .store()
+        .owned_objects(sender)
+        .find(|object| object.is_gas_coin())
+        .unwrap()
+        .clone();
+    let coin_ref = coin.compute_object_reference();
+    let coin_value = GasCoin::try_from(&coin).unwrap().value();
+    let gas_price = network.reference_gas_price();
+
+    // The network moves on after the fork point: `coin` is modified and a new coin is created.
+    let tx = transfer_sui(sender, &key, coin_ref, recipient, 1_000_000_000, gas_price);
+    let (effects, error) = network.execute_transaction(tx).unwrap();
+    assert!(error.is_none(), "{error:?}");
+    let (created, _) = effects.created()[0];
+    network.advance_clock(Duration::from_secs(1));
+    network.create_checkpoint();
+
+    let network = Arc::new(RwLock::new(network));
+    let server = simulacrum_server::start(network.clone(), SocketAddr::from(([127, 0, 0, 1], 0)))
+        .await
+        .unwrap();
+
+    let mut forked = fork(
+        &format!("http://{}", server.local_addr()),
+        Some(*fork_point.sequence_number()),
+        StdRng::from_seed([8; 32]),
+    )
+    .await
+    .unwrap();
+
+    // The fork sees objects as they were at the fork checkpoint.
+    let forked_coin = SimulatorStore::get_object(forked.store(), &coin_ref.0).unwrap();
+    assert_eq!(forked_coin.compute_object_reference(), coin_ref);
+    assert_eq!(GasCoin::try_from(&forked_coin).unwrap().value(), coin_value);
+    assert!(SimulatorStore::get_object(forked.store(), &created.0).is_none());
+    assert_eq!(forked.store().get_clock().timestamp_ms(), fork_time_ms);
+
+    // The same transfer can be executed on the fork, and the fork can make progress.
+    let tx = transfer_sui(sender, &key, coin_ref, recipient, 1_000_000_000, gas_price);
+    let (forked_effects, error) = forked.execute_transaction(tx).unwrap();
+    assert!(error.is_none(), "{error:?}");
+    assert_eq!(forked_effects.created().len(), 1);
+
+    forked.advance_clock(Duration::from_secs(1));
+    let checkpoint = forked.create_checkpoint();
+    assert_eq!(
+        *checkpoint.sequence_number(),
+        fork_point.sequence_number() + 1
+    );
+
+    forked.advance_epoch();
+    let checkpoint = forked.store().get_highest_checkpint().unwrap();
+    assert_eq!(checkpoint.epoch, fork_point.epoch);
+    assert!(checkpoint.end_of_epoch_data.is_some());
+
+    // The network is unaffected by what happens on the fork.
+    let network = network.read().unwrap();
+    let network_coin = network.store().get_object(&coin_ref.0).unwrap();
+    assert_eq!(
+        network_coin.compute_object_reference(),
+        effects.gas_object().0
+    );
+    assert_eq!(
+        network
+            .store()
+            .get_highest_checkpint()
+            .unwrap()
+            .sequence_number,
+        fork_point.sequence_number + 1,
+    );
+}
+
+/// Fork a stand-in network, after which objects are deleted on the network, and check that the
+/// fork still sees the objects that existed at the fork checkpoint, and can use them.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fork_sees_objects_deleted_after_fork() {
+    let mut rng = StdRng::from_seed([10; 32]);
+    let (sender, key): (_, AccountKeyPair) = get_key_pair_from_rng(&mut rng);
+    let recipient = SuiAddress::random_for_testing_only();
+
+    let mut network = Simulacrum::new_with_rng(rng);
+    let gas_effects = network.request_gas(sender, 5_000_000_000).unwrap();
+    let coin_effects = network.request_gas(sender, 2_000_000_000).unwrap();
+    let fork_point = network.create_checkpoint();
+    let gas_price = network.reference_gas_price();
+
+    let (mut gas, _) = gas_effects.created()[0];
+    let (coin, _) = coin_effects.created()[0];
+
+    // After the fork point, a coin is created and merged away again, and `coin`, which existed at
+    // the fork, is merged away too.
+    let tx = transfer_sui(sender, &key, gas, sender, 1_000_000_000, gas_price);
+    let (effects, error) = network.execute_transaction(tx).unwrap();
+    assert!(error.is_none(), "{error:?}");
+    let (created, _) = effects.created()[0];
+    gas = effects.gas_object().0;
+
+    for merged in [created, coin] {
+        let tx = merge_coins(sender, &key, gas, merged, gas_price);
+        let (effects, error) = network.execute_transaction(tx).unwrap();
+        assert!(error.is_none(), "{error:?}");
+        assert_eq!(effects.deleted()[0].0, merged.0);
+        gas = effects.gas_object().0;
+    }
+
+    network.create_checkpoint();
+    assert!(network.store().get_object(&coin.0).is_none());
+
+    let network = Arc::new(RwLock::new(network));
+    let server = simulacrum_server::start(network, SocketAddr::from(([127, 0, 0, 1], 0)))
+        .await
+        .unwrap();
+
+    let mut forked = fork(
+        &format!("http://{}", server.local_addr()),
+        Some(*fork_point.sequence_number()),
+        StdRng::from_seed([11; 32]),
+    )
+    .await
+    .unwrap();
+
+    // The deleted coin is seen as it was at the fork, but the coin created after the fork is not.
+    let forked_coin = forked.store().try_get_object(&coin.0).unwrap().unwrap();
+    assert_eq!(forked_coin.compute_object_reference(), coin);
+    assert_eq!(
+        GasCoin::try_from(&forked_coin).unwrap().value(),
+        2_000_000_000
+    );
+    assert!(forked.store().try_get_object(&created.0).unwrap().is_none());
+
+    // The deleted coin can be spent on the fork.
+    let tx = transfer_sui(sender, &key, coin, recipient, 1_000_000_000, gas_price);
+    let (effects, error) = forked.execute_transaction(tx).unwrap();
+    assert!(error.is_none(), "{error:?}");
+    assert_eq!(effects.gas_object().0 .0, coin.0);
+}
+
+/// Merge `coin` into `gas`, both owned by `sender`.
+fn merge_coins(
+    sender: SuiAddress,
+    key: &AccountKeyPair,
+    gas: ObjectRef,
+    coin: ObjectRef,
+    gas_price: u64,
+) -> Transaction {
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let coin = builder.obj(ObjectArg::ImmOrOwnedObject(coin)).unwrap();
+        builder.command(Command::MergeCoins(Argument::GasCoin, vec![coin]));
+        builder.finish()
+    };
+
+    let kind = TransactionKind::ProgrammableTransaction(pt);
+    let gas_data = GasData {
+        payment: vec![gas],
+        owner: sender,
+        price: gas_price,
+        budget: 1_000_000_000,
+    };
+
+    let tx_data = TransactionData::new_with_gas_data(kind, sender, gas_data);
+    Transaction::from_data_and_signer(tx_data, Intent::sui_transaction(), vec![key])
+}
+
+/// Replay a transaction from a state dump with a gas profile, and check that the profile is
+/// written in the speedscope format, with a frame for the function the transaction calls.
+#[tokio::test]
+async fn replay_with_gas_profile() {
+    let mut rng = StdRng::from_seed([9; 32]);
+    let (sender, key): (_, AccountKeyPair) = get_key_pair_from_rng(&mut rng);
+
+    let mut network = Simulacrum::new_with_rng(rng);
+    network.request_gas(sender, 5_000_000_000).unwrap();
+    let coin = network
+        .store()
+        .owned_objects(sender)
+        .find(|object| object.is_gas_coin())
+        .unwrap()
+        .clone();
+    let gas_price = network.reference_gas_price();
+
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        let amount = builder.pure(1_000_000_000u64).unwrap();
+        builder.programmable_move_call(
+            SUI_FRAMEWORK_PACKAGE_ID,
+            Identifier::new("pay").unwrap(),
+            Identifier::new("split").unwrap(),
+            vec![GAS::type_tag()],
+            vec![Argument::GasCoin, amount],
+        );
+        builder.finish()
+    };
+    let gas_data = GasData {
+        payment: vec![coin.compute_object_reference()],
+        owner: sender,
+        price: gas_price,
+        budget: 1_000_000_000,
+    };
+    let tx_data = TransactionData::new_with_gas_data(
+        TransactionKind::ProgrammableTransaction(pt),
+        sender,
+        gas_data,
+    );
+    let tx = Transaction::from_data_and_signer(tx_data, Intent::sui_transaction(), vec![&key]);
+    let tx_digest = *tx.digest();
+    let sender_signed_data = tx.data().clone();
+
+    let (effects, error) = network.execute_transaction(tx).unwrap();
+    assert!(error.is_none(), "{error:?}");
+
+    let system_state = network.store().get_system_state();
+    let relevant_system_packages = BuiltInFramework::all_package_ids()
+        .into_iter()
+        .filter_map(|id| SimulatorStore::get_object(network.store(), &id))
+        .collect();
+    let dump = NodeStateDump {
+        tx_digest,
+        sender_signed_data,
+        executed_epoch: system_state.epoch(),
+        reference_gas_price: system_state.reference_gas_price(),
+        protocol_version: system_state.protocol_version(),
+        epoch_start_timestamp_ms: system_state.epoch_start_timestamp_ms(),
+        expected_effects_digest: effects.digest(),
+        computed_effects: effects,
+        relevant_system_packages,
+        shared_objects: vec![],
+        loaded_child_objects: vec![],
+        modified_at_versions: vec![coin.clone()],
+        runtime_reads: vec![],
+        input_objects: vec![coin],
+    };
+
+    let dir = tempfile::tempdir().unwrap();
+    let dump_path = dump.write_to_file(dir.path()).unwrap();
+    let profile_output = dir.path().join("profile.json");
+    let cmd = ReplayToolCommand::ReplayDump {
+        path: dump_path.to_str().unwrap().to_string(),
+        show_effects: false,
+        profile_output: Some(profile_output.clone()),
+    };
+    execute_replay_command(None, false, false, None, cmd)
+        .await
+        .unwrap();
+
+    let profile: serde_json::Value =
+        serde_json::from_reader(std::fs::File::open(&profile_output).unwrap()).unwrap();
+    assert_eq!(
+        profile["$schema"],
+        "https://www.speedscope.app/file-format-schema.json"
+    );
+    let frames = profile["shared"]["frames"].as_array().unwrap();
+    assert!(
+        frames.iter().any(|frame| frame["name"] == "split"),
+        "{frames:?}"
+    );
+    let events = profile["profiles"][0]["events"].as_array().unwrap();
+    assert!(!events.is_empty());
+
+    // The profile can't be written to a directory, which is reported before replaying.
+    let cmd = ReplayToolCommand::ReplayDump {
+        path: dump_path.to_str().unwrap().to_string(),
+        show_effects: false,
+        profile_output: Some(dir.path().to_path_buf()),
+    };
+    assert!(execute_replay_command(None, false, false, None, cmd)
+        .await
+        .is_err());
+}
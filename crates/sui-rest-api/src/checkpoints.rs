@@ -4,41 +4,116 @@
 use std::{
     collections::{HashMap, HashSet},
     sync::Arc,
+    time::Duration,
 };
 
-use anyhow::Result;
 use axum::{
+    body::StreamBody,
     extract::{Path, State},
+    response::IntoResponse,
     Json, TypedHeader,
 };
 use serde::{Deserialize, Serialize};
 use sui_types::{
     effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
+    error::{SuiError, UserInputError},
     messages_checkpoint::{
         CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber,
+        VerifiedCheckpoint,
     },
     object::Object,
     storage::ObjectKey,
     transaction::Transaction,
 };
 
-use crate::{headers::Accept, node_state_getter::NodeStateGetter, AppError, Bcs};
+use crate::{headers::Accept, node_state_getter::NodeStateGetter, Bcs, RestError};
 
 pub const GET_LATEST_CHECKPOINT_PATH: &str = "/checkpoints";
 pub const GET_CHECKPOINT_PATH: &str = "/checkpoints/:checkpoint";
 pub const GET_FULL_CHECKPOINT_PATH: &str = "/checkpoints/:checkpoint/full";
+pub const STREAM_CHECKPOINTS_PATH: &str = "/checkpoints/:checkpoint/stream";
+
+/// How often to check whether the next checkpoint is available, while streaming checkpoints.
+const STREAM_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 pub async fn get_full_checkpoint(
     //TODO support digest as well as sequence number
     Path(checkpoint_id): Path<CheckpointSequenceNumber>,
     TypedHeader(accept): TypedHeader<Accept>,
     State(state): State<Arc<dyn NodeStateGetter>>,
-) -> Result<Bcs<CheckpointData>, AppError> {
+) -> Result<Bcs<CheckpointData>, RestError> {
     if accept.as_str() != crate::APPLICATION_BCS {
-        return Err(AppError(anyhow::anyhow!("invalid accept type")));
+        return Err(RestError::BadRequest(format!(
+            "invalid accept type, expected {}",
+            crate::APPLICATION_BCS
+        )));
+    }
+
+    Ok(Bcs(load_full_checkpoint(state.as_ref(), checkpoint_id)?))
+}
+
+/// Streams every checkpoint from `checkpoint` onwards, waiting for each one to be certified (and
+/// executed) before sending it. Each checkpoint is sent as a BCS encoded `CheckpointData`,
+/// prefixed by its length in bytes as a little-endian `u32`. Clients that are disconnected can
+/// resume from the checkpoint after the last one they received.
+pub async fn stream_checkpoints(
+    Path(checkpoint_id): Path<CheckpointSequenceNumber>,
+    TypedHeader(accept): TypedHeader<Accept>,
+    State(state): State<Arc<dyn NodeStateGetter>>,
+) -> Result<impl IntoResponse, RestError> {
+    if accept.as_str() != crate::APPLICATION_BCS {
+        return Err(RestError::BadRequest(format!(
+            "invalid accept type, expected {}",
+            crate::APPLICATION_BCS
+        )));
+    }
+
+    let frames = futures::stream::try_unfold(
+        (state, checkpoint_id),
+        |(state, checkpoint_id)| async move {
+            while state.get_latest_checkpoint_sequence_number()? < checkpoint_id {
+                tokio::time::sleep(STREAM_POLL_INTERVAL).await;
+            }
+
+            let checkpoint = bcs::to_bytes(&load_full_checkpoint(state.as_ref(), checkpoint_id)?)?;
+            let mut frame = Vec::with_capacity(4 + checkpoint.len());
+            frame.extend_from_slice(&(checkpoint.len() as u32).to_le_bytes());
+            frame.extend_from_slice(&checkpoint);
+
+            Ok::<_, RestError>(Some((frame, (state, checkpoint_id + 1))))
+        },
+    );
+
+    Ok((
+        [(
+            axum::http::header::CONTENT_TYPE,
+            axum::http::HeaderValue::from_static(crate::APPLICATION_BCS),
+        )],
+        StreamBody::new(frames),
+    ))
+}
+
+/// Fetch checkpoint `checkpoint_id`, distinguishing checkpoints that do not exist yet from
+/// checkpoints that have been pruned.
+fn get_verified_checkpoint(
+    state: &dyn NodeStateGetter,
+    checkpoint_id: CheckpointSequenceNumber,
+) -> Result<VerifiedCheckpoint, RestError> {
+    match state.get_verified_checkpoint_by_sequence_number(checkpoint_id) {
+        Err(SuiError::UserInputError {
+            error: UserInputError::VerifiedCheckpointNotFound(_),
+        }) if checkpoint_id < state.get_lowest_available_checkpoint()? => Err(RestError::Gone(
+            format!("checkpoint {checkpoint_id} has been pruned"),
+        )),
+        result => Ok(result?),
     }
+}
 
-    let verified_summary = state.get_verified_checkpoint_by_sequence_number(checkpoint_id)?;
+fn load_full_checkpoint(
+    state: &dyn NodeStateGetter,
+    checkpoint_id: CheckpointSequenceNumber,
+) -> Result<CheckpointData, RestError> {
+    let verified_summary = get_verified_checkpoint(state, checkpoint_id)?;
     let checkpoint_contents = state.get_checkpoint_contents(verified_summary.content_digest)?;
 
     let transaction_digests = checkpoint_contents
@@ -50,15 +125,17 @@ pub async fn get_full_checkpoint(
         .multi_get_transaction_blocks(&transaction_digests)?
         .into_iter()
         .map(|maybe_transaction| {
-            maybe_transaction.ok_or_else(|| anyhow::anyhow!("missing transaction"))
+            maybe_transaction.ok_or_else(|| RestError::Internal("missing transaction".to_owned()))
         })
-        .collect::<Result<Vec<_>>>()?;
+        .collect::<Result<Vec<_>, _>>()?;
 
     let effects = state
         .multi_get_executed_effects(&transaction_digests)?
         .into_iter()
-        .map(|maybe_effects| maybe_effects.ok_or_else(|| anyhow::anyhow!("missing effects")))
-        .collect::<Result<Vec<_>>>()?;
+        .map(|maybe_effects| {
+            maybe_effects.ok_or_else(|| RestError::Internal("missing effects".to_owned()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
     let event_digests = effects
         .iter()
@@ -68,8 +145,10 @@ pub async fn get_full_checkpoint(
     let events = state
         .multi_get_events(&event_digests)?
         .into_iter()
-        .map(|maybe_event| maybe_event.ok_or_else(|| anyhow::anyhow!("missing event")))
-        .collect::<Result<Vec<_>>>()?;
+        .map(|maybe_event| {
+            maybe_event.ok_or_else(|| RestError::Internal("missing event".to_owned()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
 
     let events = event_digests
         .into_iter()
@@ -112,14 +191,14 @@ pub async fn get_full_checkpoint(
             .enumerate()
             .map(|(idx, maybe_object)| {
                 maybe_object.ok_or_else(|| {
-                    anyhow::anyhow!(
+                    RestError::Internal(format!(
                         "missing input object key {:?} from tx {}",
                         input_object_keys[idx],
                         tx.digest()
-                    )
+                    ))
                 })
             })
-            .collect::<Result<Vec<_>>>()?;
+            .collect::<Result<Vec<_>, _>>()?;
 
         let output_object_keys = fx
             .all_changed_objects()
@@ -133,14 +212,14 @@ pub async fn get_full_checkpoint(
             .enumerate()
             .map(|(idx, maybe_object)| {
                 maybe_object.ok_or_else(|| {
-                    anyhow::anyhow!(
+                    RestError::Internal(format!(
                         "missing output object key {:?} from tx {}",
                         output_object_keys[idx],
                         tx.digest()
-                    )
+                    ))
                 })
             })
-            .collect::<Result<Vec<_>>>()?;
+            .collect::<Result<Vec<_>, _>>()?;
 
         let full_transaction = CheckpointTransaction {
             transaction: tx.into(),
@@ -153,11 +232,11 @@ pub async fn get_full_checkpoint(
         full_transactions.push(full_transaction);
     }
 
-    Ok(Bcs(CheckpointData {
+    Ok(CheckpointData {
         checkpoint_summary: verified_summary.into(),
         checkpoint_contents,
         transactions: full_transactions,
-    }))
+    })
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -207,7 +286,7 @@ pub struct CheckpointTransaction {
 
 pub async fn get_latest_checkpoint(
     State(state): State<Arc<dyn NodeStateGetter>>,
-) -> Result<Json<CertifiedCheckpointSummary>, AppError> {
+) -> Result<Json<CertifiedCheckpointSummary>, RestError> {
     let latest_checkpoint_sequence_number = state.get_latest_checkpoint_sequence_number()?;
     let verified_summary =
         state.get_verified_checkpoint_by_sequence_number(latest_checkpoint_sequence_number)?;
@@ -218,7 +297,150 @@ pub async fn get_checkpoint(
     //TODO support digest as well as sequence number
     Path(checkpoint_id): Path<CheckpointSequenceNumber>,
     State(state): State<Arc<dyn NodeStateGetter>>,
-) -> Result<Json<CertifiedCheckpointSummary>, AppError> {
-    let verified_summary = state.get_verified_checkpoint_by_sequence_number(checkpoint_id)?;
+) -> Result<Json<CertifiedCheckpointSummary>, RestError> {
+    let verified_summary = get_verified_checkpoint(state.as_ref(), checkpoint_id)?;
     Ok(Json(verified_summary.into()))
 }
+
+#[cfg(test)]
+mod tests {
+    use simulacrum::Simulacrum;
+    use sui_types::{
+        base_types::{ObjectID, VersionNumber},
+        digests::{CheckpointContentsDigest, TransactionDigest, TransactionEventsDigest},
+        error::SuiResult,
+        transaction::VerifiedTransaction,
+    };
+
+    use super::*;
+    use crate::{rest_router, Client};
+
+    /// A `Simulacrum` whose checkpoints before `lowest_available` have been pruned.
+    struct Pruned {
+        simulacrum: Simulacrum,
+        lowest_available: CheckpointSequenceNumber,
+    }
+
+    impl NodeStateGetter for Pruned {
+        fn get_verified_checkpoint_by_sequence_number(
+            &self,
+            sequence_number: CheckpointSequenceNumber,
+        ) -> SuiResult<VerifiedCheckpoint> {
+            if sequence_number < self.lowest_available {
+                return Err(SuiError::UserInputError {
+                    error: UserInputError::VerifiedCheckpointNotFound(sequence_number),
+                });
+            }
+
+            self.simulacrum
+                .get_verified_checkpoint_by_sequence_number(sequence_number)
+        }
+
+        fn get_latest_checkpoint_sequence_number(&self) -> SuiResult<CheckpointSequenceNumber> {
+            self.simulacrum.get_latest_checkpoint_sequence_number()
+        }
+
+        fn get_lowest_available_checkpoint(&self) -> SuiResult<CheckpointSequenceNumber> {
+            Ok(self.lowest_available)
+        }
+
+        fn get_checkpoint_contents(
+            &self,
+            content_digest: CheckpointContentsDigest,
+        ) -> SuiResult<CheckpointContents> {
+            self.simulacrum.get_checkpoint_contents(content_digest)
+        }
+
+        fn multi_get_transaction_blocks(
+            &self,
+            tx_digests: &[TransactionDigest],
+        ) -> SuiResult<Vec<Option<VerifiedTransaction>>> {
+            self.simulacrum.multi_get_transaction_blocks(tx_digests)
+        }
+
+        fn multi_get_executed_effects(
+            &self,
+            digests: &[TransactionDigest],
+        ) -> SuiResult<Vec<Option<TransactionEffects>>> {
+            self.simulacrum.multi_get_executed_effects(digests)
+        }
+
+        fn multi_get_events(
+            &self,
+            event_digests: &[TransactionEventsDigest],
+        ) -> SuiResult<Vec<Option<TransactionEvents>>> {
+            self.simulacrum.multi_get_events(event_digests)
+        }
+
+        fn multi_get_object_by_key(
+            &self,
+            object_keys: &[ObjectKey],
+        ) -> Result<Vec<Option<Object>>, SuiError> {
+            self.simulacrum.multi_get_object_by_key(object_keys)
+        }
+
+        fn get_object_by_key(
+            &self,
+            object_id: &ObjectID,
+            version: VersionNumber,
+        ) -> Result<Option<Object>, SuiError> {
+            NodeStateGetter::get_object_by_key(&self.simulacrum, object_id, version)
+        }
+
+        fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
+            NodeStateGetter::get_object(&self.simulacrum, object_id)
+        }
+    }
+
+    #[tokio::test]
+    async fn pruned_checkpoints_are_gone() {
+        let mut simulacrum = Simulacrum::new();
+        simulacrum.create_checkpoint();
+        let state = Arc::new(Pruned {
+            simulacrum,
+            lowest_available: 1,
+        });
+
+        let server = axum::Server::bind(&"127.0.0.1:0".parse().unwrap())
+            .serve(rest_router(state, None).into_make_service());
+        let url = format!("http://{}", server.local_addr());
+        tokio::spawn(server);
+
+        // The error is sent as BCS to clients that ask for BCS...
+        let err = Client::new(&url).get_full_checkpoint(0).await.unwrap_err();
+        assert!(
+            matches!(err.rest_error(), Some(RestError::Gone(_))),
+            "{err}"
+        );
+
+        let response = reqwest::Client::new()
+            .get(format!("{url}/checkpoints/0"))
+            .header(reqwest::header::ACCEPT, crate::APPLICATION_BCS)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::GONE);
+        assert_eq!(
+            response.headers()[reqwest::header::CONTENT_TYPE],
+            crate::APPLICATION_BCS
+        );
+        let body = response.bytes().await.unwrap();
+        let err: RestError = bcs::from_bytes(&body).unwrap();
+        assert!(matches!(err, RestError::Gone(_)), "{err}");
+
+        // ...and as JSON otherwise.
+        let response = reqwest::Client::new()
+            .get(format!("{url}/checkpoints/0"))
+            .header(reqwest::header::ACCEPT, crate::APPLICATION_JSON)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::GONE);
+        let err: RestError = response.json().await.unwrap();
+        assert!(matches!(err, RestError::Gone(_)), "{err}");
+
+        // Checkpoints that have not been pruned are still available.
+        let checkpoint = Client::new(&url).get_full_checkpoint(1).await.unwrap();
+        assert_eq!(checkpoint.checkpoint_summary.sequence_number, 1);
+    }
+}
@@ -1,12 +1,48 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::Result;
+use futures::Stream;
 use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::digests::TransactionDigest;
 use sui_types::messages_checkpoint::{CertifiedCheckpointSummary, CheckpointSequenceNumber};
 use sui_types::object::Object;
+use sui_types::transaction::Transaction;
 
 use crate::checkpoints::CheckpointData;
+use crate::transactions::TransactionResponse;
+use crate::RestError;
+
+pub type Result<T, E = ClientError> = std::result::Result<T, E>;
+
+/// Errors returned by `Client`.
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// The server responded with an error.
+    #[error(transparent)]
+    Rest(#[from] RestError),
+
+    /// The request could not be sent, or its response could not be received.
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+
+    /// The response could not be decoded.
+    #[error(transparent)]
+    Bcs(#[from] bcs::Error),
+
+    /// The server sent a stream of checkpoints that ended part-way through a checkpoint.
+    #[error("Checkpoint stream ended mid-checkpoint")]
+    TruncatedStream,
+}
+
+impl ClientError {
+    /// The error the server responded with, if the request reached the server.
+    pub fn rest_error(&self) -> Option<&RestError> {
+        match self {
+            ClientError::Rest(err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct Client {
@@ -24,14 +60,11 @@ impl Client {
 
     pub async fn get_latest_checkpoint(&self) -> Result<CertifiedCheckpointSummary> {
         let url = format!("{}/checkpoints", self.base_url);
-        let checkpoint = self
+        let request = self
             .inner
             .get(url)
-            .header(reqwest::header::ACCEPT, crate::APPLICATION_JSON)
-            .send()
-            .await?
-            .json()
-            .await?;
+            .header(reqwest::header::ACCEPT, crate::APPLICATION_JSON);
+        let checkpoint = self.send(request).await?.json().await?;
         Ok(checkpoint)
     }
 
@@ -44,29 +77,66 @@ impl Client {
             self.base_url
         );
 
-        let bytes = self
+        let request = self
             .inner
             .get(url)
-            .header(reqwest::header::ACCEPT, crate::APPLICATION_BCS)
-            .send()
-            .await?
-            .bytes()
-            .await?;
+            .header(reqwest::header::ACCEPT, crate::APPLICATION_BCS);
+        let bytes = self.send(request).await?.bytes().await?;
 
         bcs::from_bytes(&bytes).map_err(Into::into)
     }
 
+    /// Stream full checkpoints from `start` onwards, as soon as each one is available. The stream
+    /// only ends if the connection to the server does, at which point it can be resumed by calling
+    /// this again, starting from the checkpoint after the last one that was received.
+    pub async fn stream_checkpoints(
+        &self,
+        start: CheckpointSequenceNumber,
+    ) -> Result<impl Stream<Item = Result<CheckpointData>>> {
+        let url = format!("{}/checkpoints/{start}/stream", self.base_url);
+
+        let request = self
+            .inner
+            .get(url)
+            .header(reqwest::header::ACCEPT, crate::APPLICATION_BCS);
+        let response = self.send(request).await?.error_for_status()?;
+
+        // Each checkpoint is framed by its length, as a little-endian u32.
+        Ok(futures::stream::try_unfold(
+            (response, Vec::new()),
+            |(mut response, mut buf)| async move {
+                loop {
+                    if buf.len() >= 4 {
+                        let len = u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as usize;
+                        if buf.len() >= 4 + len {
+                            let checkpoint = bcs::from_bytes(&buf[4..4 + len])?;
+                            buf.drain(..4 + len);
+                            return Ok(Some((checkpoint, (response, buf))));
+                        }
+                    }
+
+                    let Some(chunk) = response.chunk().await? else {
+                        return if buf.is_empty() {
+                            Ok(None)
+                        } else {
+                            Err(ClientError::TruncatedStream)
+                        };
+                    };
+
+                    buf.extend_from_slice(&chunk);
+                }
+            },
+        ))
+    }
+
     pub async fn get_object(&self, object_id: ObjectID) -> Result<Object> {
         let url = format!("{}/objects/{object_id}", self.base_url);
 
-        let bytes = self
+        let request = self
             .inner
             .get(url)
-            .header(reqwest::header::ACCEPT, crate::APPLICATION_BCS)
-            .send()
-            .await?
-            .bytes()
-            .await?;
+            .header(reqwest::header::ACCEPT, crate::APPLICATION_BCS);
+        let bytes = self.send(request).await?.bytes().await?;
 
         bcs::from_bytes(&bytes).map_err(Into::into)
     }
@@ -78,15 +148,71 @@ impl Client {
     ) -> Result<Object> {
         let url = format!("{}/objects/{object_id}/version/{version}", self.base_url);
 
-        let bytes = self
+        let request = self
             .inner
             .get(url)
+            .header(reqwest::header::ACCEPT, crate::APPLICATION_BCS);
+        let bytes = self.send(request).await?.bytes().await?;
+
+        bcs::from_bytes(&bytes).map_err(Into::into)
+    }
+
+    pub async fn get_transaction(&self, digest: TransactionDigest) -> Result<TransactionResponse> {
+        let url = format!("{}/transactions/{digest}", self.base_url);
+
+        let request = self
+            .inner
+            .get(url)
+            .header(reqwest::header::ACCEPT, crate::APPLICATION_BCS);
+        let bytes = self.send(request).await?.bytes().await?;
+
+        bcs::from_bytes(&bytes).map_err(Into::into)
+    }
+
+    /// Execute `transaction`, which must already be signed, returning once the node serving the
+    /// request has executed it.
+    pub async fn execute_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<TransactionResponse> {
+        let url = format!("{}/transactions", self.base_url);
+
+        let request = self
+            .inner
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, crate::APPLICATION_BCS)
             .header(reqwest::header::ACCEPT, crate::APPLICATION_BCS)
-            .send()
-            .await?
-            .bytes()
-            .await?;
+            .body(bcs::to_bytes(transaction)?);
+        let bytes = self.send(request).await?.bytes().await?;
 
         bcs::from_bytes(&bytes).map_err(Into::into)
     }
+
+    /// Send `request`, and turn error responses into `RestError`s.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let response = request.send().await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let is_bcs = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .is_some_and(|content_type| content_type == crate::APPLICATION_BCS);
+
+        let body = response.bytes().await?;
+        let error = if is_bcs {
+            bcs::from_bytes(&body).ok()
+        } else {
+            serde_json::from_slice(&body).ok()
+        };
+
+        let error = error.unwrap_or_else(|| {
+            RestError::from_status_code(status, String::from_utf8_lossy(&body).into_owned())
+        });
+
+        Err(error.into())
+    }
 }
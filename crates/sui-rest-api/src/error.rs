@@ -0,0 +1,143 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use axum::{
+    http::{header::ACCEPT, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use sui_types::error::{SuiError, UserInputError};
+use sui_types::quorum_driver_types::QuorumDriverError;
+
+/// Errors returned by the REST API.
+///
+/// Errors are sent to clients in the body of a response with the corresponding status code, so
+/// that clients can tell them apart, e.g. to decide whether a request is worth retrying. The body
+/// is BCS if the request's `Accept` header asked for it, and JSON otherwise (see
+/// [`negotiate_error_format`]).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+pub enum RestError {
+    /// The request was malformed, and retrying it will not help (400).
+    #[error("Bad request: {0}")]
+    BadRequest(String),
+
+    /// The requested data does not exist, but might in future (404).
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    /// The requested data existed, but has been pruned, and will not be available again (410).
+    #[error("Gone: {0}")]
+    Gone(String),
+
+    /// The server failed to serve the request (500).
+    #[error("Internal error: {0}")]
+    Internal(String),
+}
+
+impl RestError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            RestError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            RestError::NotFound(_) => StatusCode::NOT_FOUND,
+            RestError::Gone(_) => StatusCode::GONE,
+            RestError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Fallback for responses with an error status whose body could not be parsed as a
+    /// `RestError`, e.g. because it came from a proxy in front of the server.
+    pub fn from_status_code(status: StatusCode, message: String) -> Self {
+        match status {
+            StatusCode::NOT_FOUND => RestError::NotFound(message),
+            StatusCode::GONE => RestError::Gone(message),
+            status if status.is_client_error() => RestError::BadRequest(message),
+            _ => RestError::Internal(message),
+        }
+    }
+}
+
+impl IntoResponse for RestError {
+    fn into_response(self) -> Response {
+        // The request's headers are not available here, so the error is also stashed in the
+        // response, for `negotiate_error_format` to re-encode if the client asked for BCS.
+        let mut response = (self.status_code(), axum::Json(self.clone())).into_response();
+        response.extensions_mut().insert(self);
+        response
+    }
+}
+
+/// Middleware that encodes `RestError`s in the format requested by the `Accept` header, the same
+/// way successful responses are (see [`crate::BcsOrJson`]).
+pub(crate) async fn negotiate_error_format<B>(request: Request<B>, next: Next<B>) -> Response {
+    let accept = request
+        .headers()
+        .get(ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned);
+
+    let mut response = next.run(request).await;
+    match response.extensions_mut().remove::<RestError>() {
+        Some(err) if accept.as_deref() == Some(crate::APPLICATION_BCS) => {
+            (err.status_code(), crate::Bcs(err)).into_response()
+        }
+        _ => response,
+    }
+}
+
+impl From<SuiError> for RestError {
+    fn from(err: SuiError) -> Self {
+        match &err {
+            SuiError::UserInputError { error } => match error {
+                UserInputError::ObjectNotFound { .. }
+                | UserInputError::VerifiedCheckpointNotFound(_)
+                | UserInputError::CheckpointContentsNotFound(_)
+                | UserInputError::LatestCheckpointSequenceNumberNotFound => {
+                    RestError::NotFound(err.to_string())
+                }
+                _ => RestError::BadRequest(err.to_string()),
+            },
+            SuiError::TransactionNotFound { .. } | SuiError::TransactionEventsNotFound { .. } => {
+                RestError::NotFound(err.to_string())
+            }
+            _ => RestError::Internal(err.to_string()),
+        }
+    }
+}
+
+impl From<QuorumDriverError> for RestError {
+    fn from(err: QuorumDriverError) -> Self {
+        match err {
+            QuorumDriverError::InvalidUserSignature(_)
+            | QuorumDriverError::ObjectsDoubleUsed { .. }
+            | QuorumDriverError::NonRecoverableTransactionError { .. } => {
+                RestError::BadRequest(err.to_string())
+            }
+            _ => RestError::Internal(err.to_string()),
+        }
+    }
+}
+
+impl From<anyhow::Error> for RestError {
+    fn from(err: anyhow::Error) -> Self {
+        if let Some(err) = err.downcast_ref::<RestError>() {
+            return err.clone();
+        }
+
+        if let Some(err) = err.downcast_ref::<SuiError>() {
+            return err.clone().into();
+        }
+
+        if let Some(err) = err.downcast_ref::<QuorumDriverError>() {
+            return err.clone().into();
+        }
+
+        RestError::Internal(format!("{err:#}"))
+    }
+}
+
+impl From<bcs::Error> for RestError {
+    fn from(err: bcs::Error) -> Self {
+        RestError::Internal(err.to_string())
+    }
+}
@@ -1,17 +1,27 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use axum::{http::StatusCode, routing::get, Router};
+use axum::{
+    http::StatusCode,
+    routing::{get, post},
+    Router,
+};
 
 mod checkpoints;
 mod client;
+mod error;
 pub mod headers;
 pub mod node_state_getter;
 mod objects;
+pub mod transaction_executor;
+mod transactions;
 
 pub use checkpoints::{CheckpointData, CheckpointTransaction};
-pub use client::Client;
+pub use client::{Client, ClientError};
+pub use error::RestError;
 use node_state_getter::NodeStateGetter;
+pub use transaction_executor::TransactionExecutor;
+pub use transactions::TransactionResponse;
 
 async fn health_check() -> StatusCode {
     StatusCode::OK
@@ -50,13 +60,50 @@ where
     }
 }
 
-pub fn rest_router(state: std::sync::Arc<dyn NodeStateGetter>) -> Router {
-    Router::new()
+/// Serializes `T` as BCS if the request asked for it in its `Accept` header, and as JSON
+/// otherwise.
+pub enum BcsOrJson<T> {
+    Bcs(T),
+    Json(T),
+}
+
+impl<T> BcsOrJson<T> {
+    pub fn new(accept: Option<&headers::Accept>, value: T) -> Self {
+        match accept {
+            Some(accept) if accept.as_str() == APPLICATION_BCS => Self::Bcs(value),
+            _ => Self::Json(value),
+        }
+    }
+}
+
+impl<T> axum::response::IntoResponse for BcsOrJson<T>
+where
+    T: serde::Serialize,
+{
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            Self::Bcs(value) => Bcs(value).into_response(),
+            Self::Json(value) => axum::Json(value).into_response(),
+        }
+    }
+}
+
+/// Build the REST API's router. Transactions can only be executed through it if an `executor` is
+/// provided.
+pub fn rest_router(
+    state: std::sync::Arc<dyn NodeStateGetter>,
+    executor: Option<std::sync::Arc<dyn TransactionExecutor>>,
+) -> Router {
+    let router = Router::new()
         .route("/", get(health_check))
         .route(
             checkpoints::GET_FULL_CHECKPOINT_PATH,
             get(checkpoints::get_full_checkpoint),
         )
+        .route(
+            checkpoints::STREAM_CHECKPOINTS_PATH,
+            get(checkpoints::stream_checkpoints),
+        )
         .route(
             checkpoints::GET_CHECKPOINT_PATH,
             get(checkpoints::get_checkpoint),
@@ -70,7 +117,26 @@ pub fn rest_router(state: std::sync::Arc<dyn NodeStateGetter>) -> Router {
             objects::GET_OBJECT_WITH_VERSION_PATH,
             get(objects::get_object_with_version),
         )
-        .with_state(state)
+        .route(
+            transactions::GET_TRANSACTION_PATH,
+            get(transactions::get_transaction),
+        )
+        .with_state(state);
+
+    let router = if let Some(executor) = executor {
+        router.merge(
+            Router::new()
+                .route(
+                    transactions::EXECUTE_TRANSACTION_PATH,
+                    post(transactions::execute_transaction),
+                )
+                .with_state(executor),
+        )
+    } else {
+        router
+    };
+
+    router.layer(axum::middleware::from_fn(error::negotiate_error_format))
 }
 
 pub async fn start_service(
@@ -79,9 +145,9 @@ pub async fn start_service(
     base: Option<String>,
 ) {
     let app = if let Some(base) = base {
-        Router::new().nest(&base, rest_router(state))
+        Router::new().nest(&base, rest_router(state, None))
     } else {
-        rest_router(state)
+        rest_router(state, None)
     };
 
     axum::Server::bind(&socket_address)
@@ -89,28 +155,3 @@ pub async fn start_service(
         .await
         .unwrap();
 }
-
-// Make our own error that wraps `anyhow::Error`.
-pub struct AppError(anyhow::Error);
-
-// Tell axum how to convert `AppError` into a response.
-impl axum::response::IntoResponse for AppError {
-    fn into_response(self) -> axum::response::Response {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Something went wrong: {}", self.0),
-        )
-            .into_response()
-    }
-}
-
-// This enables using `?` on functions that return `Result<_, anyhow::Error>` to turn them into
-// `Result<_, AppError>`. That way you don't need to do that manually.
-impl<E> From<E> for AppError
-where
-    E: Into<anyhow::Error>,
-{
-    fn from(err: E) -> Self {
-        Self(err.into())
-    }
-}
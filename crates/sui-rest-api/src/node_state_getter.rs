@@ -26,6 +26,9 @@ pub trait NodeStateGetter: Sync + Send {
 
     fn get_latest_checkpoint_sequence_number(&self) -> SuiResult<CheckpointSequenceNumber>;
 
+    /// Checkpoints before this one have been pruned.
+    fn get_lowest_available_checkpoint(&self) -> SuiResult<CheckpointSequenceNumber>;
+
     fn get_checkpoint_contents(
         &self,
         content_digest: CheckpointContentsDigest,
@@ -72,6 +75,13 @@ impl NodeStateGetter for AuthorityState {
         self.get_latest_checkpoint_sequence_number()
     }
 
+    fn get_lowest_available_checkpoint(&self) -> SuiResult<CheckpointSequenceNumber> {
+        Ok(self
+            .get_checkpoint_store()
+            .get_highest_pruned_checkpoint_seq_number()?
+            + 1)
+    }
+
     fn get_checkpoint_contents(
         &self,
         content_digest: CheckpointContentsDigest,
@@ -141,6 +151,11 @@ impl<T: Sync + Send> NodeStateGetter for simulacrum::Simulacrum<T> {
             .unwrap_or(0))
     }
 
+    fn get_lowest_available_checkpoint(&self) -> SuiResult<CheckpointSequenceNumber> {
+        // Simulacrum never prunes.
+        Ok(0)
+    }
+
     fn get_checkpoint_contents(
         &self,
         content_digest: CheckpointContentsDigest,
@@ -208,3 +223,73 @@ impl<T: Sync + Send> NodeStateGetter for simulacrum::Simulacrum<T> {
         ObjectStore::get_object(&self.store(), object_id)
     }
 }
+
+/// Allows a `Simulacrum` to be served while it is being driven from elsewhere (e.g. from RPCs that
+/// advance its clock or create checkpoints).
+impl<T: Sync + Send> NodeStateGetter for std::sync::RwLock<simulacrum::Simulacrum<T>> {
+    fn get_verified_checkpoint_by_sequence_number(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+    ) -> SuiResult<VerifiedCheckpoint> {
+        self.read()
+            .unwrap()
+            .get_verified_checkpoint_by_sequence_number(sequence_number)
+    }
+
+    fn get_latest_checkpoint_sequence_number(&self) -> SuiResult<CheckpointSequenceNumber> {
+        self.read().unwrap().get_latest_checkpoint_sequence_number()
+    }
+
+    fn get_lowest_available_checkpoint(&self) -> SuiResult<CheckpointSequenceNumber> {
+        self.read().unwrap().get_lowest_available_checkpoint()
+    }
+
+    fn get_checkpoint_contents(
+        &self,
+        content_digest: CheckpointContentsDigest,
+    ) -> SuiResult<CheckpointContents> {
+        self.read().unwrap().get_checkpoint_contents(content_digest)
+    }
+
+    fn multi_get_transaction_blocks(
+        &self,
+        tx_digests: &[TransactionDigest],
+    ) -> SuiResult<Vec<Option<VerifiedTransaction>>> {
+        self.read()
+            .unwrap()
+            .multi_get_transaction_blocks(tx_digests)
+    }
+
+    fn multi_get_executed_effects(
+        &self,
+        digests: &[TransactionDigest],
+    ) -> SuiResult<Vec<Option<TransactionEffects>>> {
+        self.read().unwrap().multi_get_executed_effects(digests)
+    }
+
+    fn multi_get_events(
+        &self,
+        event_digests: &[TransactionEventsDigest],
+    ) -> SuiResult<Vec<Option<TransactionEvents>>> {
+        self.read().unwrap().multi_get_events(event_digests)
+    }
+
+    fn multi_get_object_by_key(
+        &self,
+        object_keys: &[ObjectKey],
+    ) -> Result<Vec<Option<Object>>, SuiError> {
+        self.read().unwrap().multi_get_object_by_key(object_keys)
+    }
+
+    fn get_object_by_key(
+        &self,
+        object_id: &ObjectID,
+        version: VersionNumber,
+    ) -> Result<Option<Object>, SuiError> {
+        NodeStateGetter::get_object_by_key(&*self.read().unwrap(), object_id, version)
+    }
+
+    fn get_object(&self, object_id: &ObjectID) -> Result<Option<Object>, SuiError> {
+        NodeStateGetter::get_object(&*self.read().unwrap(), object_id)
+    }
+}
@@ -9,17 +9,17 @@ use sui_types::{
     object::Object,
 };
 
-use crate::{node_state_getter::NodeStateGetter, AppError, Bcs};
+use crate::{node_state_getter::NodeStateGetter, Bcs, RestError};
 
 pub const GET_OBJECT_PATH: &str = "/objects/:object_id";
 
 pub async fn get_object(
     Path(object_id): Path<ObjectID>,
     State(state): State<Arc<dyn NodeStateGetter>>,
-) -> Result<Bcs<Object>, AppError> {
+) -> Result<Bcs<Object>, RestError> {
     let object = state
         .get_object(&object_id)?
-        .ok_or_else(|| anyhow::anyhow!("object not found"))?;
+        .ok_or_else(|| RestError::NotFound(format!("object {object_id} not found")))?;
 
     Ok(Bcs(object))
 }
@@ -29,10 +29,12 @@ pub const GET_OBJECT_WITH_VERSION_PATH: &str = "/objects/:object_id/version/:ver
 pub async fn get_object_with_version(
     Path((object_id, version)): Path<(ObjectID, SequenceNumber)>,
     State(state): State<Arc<dyn NodeStateGetter>>,
-) -> Result<Bcs<Object>, AppError> {
+) -> Result<Bcs<Object>, RestError> {
     let object = state
         .get_object_by_key(&object_id, version)?
-        .ok_or_else(|| anyhow::anyhow!("object not found"))?;
+        .ok_or_else(|| {
+            RestError::NotFound(format!("object {object_id} at version {version} not found"))
+        })?;
 
     Ok(Bcs(object))
 }
@@ -0,0 +1,63 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use async_trait::async_trait;
+use sui_core::authority_client::NetworkAuthorityClient;
+use sui_core::transaction_orchestrator::TransactiondOrchestrator;
+use sui_types::{
+    effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
+    quorum_driver_types::{
+        ExecuteTransactionRequest, ExecuteTransactionRequestType, ExecuteTransactionResponse,
+    },
+    transaction::Transaction,
+};
+
+/// Trait for executing transactions on behalf of REST clients.
+#[async_trait]
+pub trait TransactionExecutor: Sync + Send {
+    /// Execute `transaction`, returning once its effects are available from the node's state.
+    async fn execute_transaction(
+        &self,
+        transaction: Transaction,
+    ) -> Result<(TransactionEffects, TransactionEvents)>;
+}
+
+#[async_trait]
+impl TransactionExecutor for TransactiondOrchestrator<NetworkAuthorityClient> {
+    async fn execute_transaction(
+        &self,
+        transaction: Transaction,
+    ) -> Result<(TransactionEffects, TransactionEvents)> {
+        let ExecuteTransactionResponse::EffectsCert(response) = self
+            .execute_transaction_block(ExecuteTransactionRequest {
+                transaction,
+                request_type: ExecuteTransactionRequestType::WaitForLocalExecution,
+            })
+            .await?;
+
+        let (effects, events, _) = *response;
+        Ok((effects.effects, events))
+    }
+}
+
+/// Executes transactions against a `Simulacrum`. Transactions are not added to a checkpoint until
+/// one is created through some other means.
+#[async_trait]
+impl<T: Sync + Send> TransactionExecutor for std::sync::RwLock<simulacrum::Simulacrum<T>> {
+    async fn execute_transaction(
+        &self,
+        transaction: Transaction,
+    ) -> Result<(TransactionEffects, TransactionEvents)> {
+        let mut simulacrum = self.write().unwrap();
+        let (effects, _) = simulacrum.execute_transaction(transaction)?;
+
+        let events = effects
+            .events_digest()
+            .and_then(|digest| simulacrum.store().get_transaction_events(digest))
+            .cloned()
+            .unwrap_or_default();
+
+        Ok((effects, events))
+    }
+}
@@ -0,0 +1,92 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    TypedHeader,
+};
+use serde::{Deserialize, Serialize};
+use sui_types::{
+    digests::TransactionDigest,
+    effects::{TransactionEffects, TransactionEffectsAPI, TransactionEvents},
+    transaction::Transaction,
+};
+
+use crate::{
+    headers::Accept, node_state_getter::NodeStateGetter, transaction_executor::TransactionExecutor,
+    BcsOrJson, RestError,
+};
+
+pub const GET_TRANSACTION_PATH: &str = "/transactions/:transaction";
+pub const EXECUTE_TRANSACTION_PATH: &str = "/transactions";
+
+pub async fn get_transaction(
+    Path(digest): Path<TransactionDigest>,
+    accept: Option<TypedHeader<Accept>>,
+    State(state): State<Arc<dyn NodeStateGetter>>,
+) -> Result<BcsOrJson<TransactionResponse>, RestError> {
+    let transaction = state
+        .multi_get_transaction_blocks(&[digest])?
+        .pop()
+        .flatten()
+        .ok_or_else(|| RestError::NotFound(format!("transaction {digest} not found")))?;
+
+    let effects = state
+        .multi_get_executed_effects(&[digest])?
+        .pop()
+        .flatten()
+        .ok_or_else(|| RestError::Internal(format!("missing effects for transaction {digest}")))?;
+
+    let events = match effects.events_digest() {
+        Some(events_digest) => Some(
+            state
+                .multi_get_events(&[*events_digest])?
+                .pop()
+                .flatten()
+                .ok_or_else(|| {
+                    RestError::Internal(format!("missing events for transaction {digest}"))
+                })?,
+        ),
+        None => None,
+    };
+
+    let response = TransactionResponse {
+        transaction: transaction.into(),
+        effects,
+        events,
+    };
+
+    Ok(BcsOrJson::new(accept.as_ref().map(|h| &h.0), response))
+}
+
+/// Executes the BCS encoded, signed `Transaction` in the request body, and responds once it has
+/// been executed by the node serving the request.
+pub async fn execute_transaction(
+    accept: Option<TypedHeader<Accept>>,
+    State(executor): State<Arc<dyn TransactionExecutor>>,
+    body: Bytes,
+) -> Result<BcsOrJson<TransactionResponse>, RestError> {
+    let transaction: Transaction = bcs::from_bytes(&body)
+        .map_err(|e| RestError::BadRequest(format!("invalid transaction: {e}")))?;
+    let (effects, events) = executor.execute_transaction(transaction.clone()).await?;
+
+    let response = TransactionResponse {
+        transaction,
+        effects,
+        events: (!events.data.is_empty()).then_some(events),
+    };
+
+    Ok(BcsOrJson::new(accept.as_ref().map(|h| &h.0), response))
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionResponse {
+    pub transaction: Transaction,
+    /// The effects produced by executing this transaction
+    pub effects: TransactionEffects,
+    /// The events, if any, emitted by this transaction during execution
+    pub events: Option<TransactionEvents>,
+}
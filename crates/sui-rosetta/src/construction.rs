@@ -223,6 +223,29 @@ pub async fn metadata(
             let amount = amounts.iter().sum::<u64>();
             (Some(amount), vec![])
         }
+        InternalOperation::PayCoin {
+            sender,
+            amounts,
+            currency,
+            ..
+        } => {
+            // The coins to pay with are passed as objects, the gas coins only have to cover gas.
+            let amount = amounts.iter().map(|amount| *amount as u128).sum::<u128>();
+            let coin_refs = context
+                .client
+                .coin_read_api()
+                .select_coins(
+                    *sender,
+                    Some(currency.coin_type()?.to_string()),
+                    amount,
+                    vec![],
+                )
+                .await?
+                .into_iter()
+                .map(|coin| coin.object_ref())
+                .collect();
+            (Some(0), coin_refs)
+        }
         InternalOperation::Stake { amount, .. } => (*amount, vec![]),
         InternalOperation::WithdrawStake { sender, stake_ids } => {
             let stake_ids = if stake_ids.is_empty() {
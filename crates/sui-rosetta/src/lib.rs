@@ -30,6 +30,7 @@ pub mod types;
 pub static SUI: Lazy<Currency> = Lazy::new(|| Currency {
     symbol: "SUI".to_string(),
     decimals: 9,
+    metadata: Default::default(),
 });
 
 pub struct RosettaOnlineServer {
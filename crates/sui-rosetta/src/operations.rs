@@ -8,20 +8,21 @@ use std::vec;
 
 use anyhow::anyhow;
 use move_core_types::ident_str;
-use move_core_types::language_storage::{ModuleId, StructTag};
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
 use move_core_types::resolver::ModuleResolver;
 use serde::Deserialize;
 use serde::Serialize;
 
 use sui_json_rpc_types::SuiProgrammableMoveCall;
 use sui_json_rpc_types::SuiProgrammableTransactionBlock;
-use sui_json_rpc_types::{BalanceChange, SuiArgument};
+use sui_json_rpc_types::{BalanceChange, ObjectChange, SuiArgument};
 use sui_json_rpc_types::{SuiCallArg, SuiCommand};
 use sui_sdk::rpc_types::{
     SuiTransactionBlockData, SuiTransactionBlockDataAPI, SuiTransactionBlockEffectsAPI,
     SuiTransactionBlockKind, SuiTransactionBlockResponse,
 };
 use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::coin::Coin;
 use sui_types::gas_coin::{GasCoin, GAS};
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::object::Owner;
@@ -30,10 +31,10 @@ use sui_types::transaction::TransactionData;
 use sui_types::{SUI_SYSTEM_ADDRESS, SUI_SYSTEM_PACKAGE_ID};
 
 use crate::types::{
-    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, InternalOperation,
-    OperationIdentifier, OperationStatus, OperationType,
+    AccountIdentifier, Amount, CoinAction, CoinChange, CoinID, CoinIdentifier, Currency,
+    InternalOperation, OperationIdentifier, OperationStatus, OperationType,
 };
-use crate::Error;
+use crate::{Error, SUI};
 
 #[cfg(test)]
 #[path = "unit_tests/operations_tests.rs"]
@@ -101,6 +102,7 @@ impl Operations {
             .ok_or_else(|| Error::MissingInput("Operation type".into()))?;
         match type_ {
             OperationType::PaySui => self.pay_sui_ops_to_internal(),
+            OperationType::PayCoin => self.pay_coin_ops_to_internal(),
             OperationType::Stake => self.stake_ops_to_internal(),
             OperationType::WithdrawStake => self.withdraw_stake_ops_to_internal(),
             op => Err(Error::UnsupportedOperation(op)),
@@ -113,6 +115,11 @@ impl Operations {
         let mut sender = None;
         for op in self {
             if let (Some(amount), Some(account)) = (op.amount.clone(), op.account.clone()) {
+                if !amount.currency.is_sui() {
+                    return Err(Error::InvalidInput(
+                        "PaySui amounts should be in SUI, use PayCoin for other currencies".into(),
+                    ));
+                }
                 if amount.value.is_negative() {
                     sender = Some(account.address)
                 } else {
@@ -135,6 +142,50 @@ impl Operations {
         })
     }
 
+    fn pay_coin_ops_to_internal(self) -> Result<InternalOperation, Error> {
+        let mut recipients = vec![];
+        let mut amounts = vec![];
+        let mut sender = None;
+        let mut currency: Option<Currency> = None;
+        for op in self {
+            if let (Some(amount), Some(account)) = (op.amount, op.account) {
+                match &currency {
+                    Some(currency) if *currency != amount.currency => {
+                        return Err(Error::InvalidInput(
+                            "PayCoin amounts should all be in the same currency".into(),
+                        ));
+                    }
+                    Some(_) => {}
+                    None => {
+                        // Validate the coin type up front, rather than when building the transaction.
+                        amount.currency.coin_type()?;
+                        currency = Some(amount.currency.clone());
+                    }
+                }
+                if amount.value.is_negative() {
+                    sender = Some(account.address)
+                } else {
+                    recipients.push(account.address);
+                    let amount = amount.value.abs();
+                    if amount > u64::MAX as i128 {
+                        return Err(Error::InvalidInput(
+                            "Input amount exceed u64::MAX".to_string(),
+                        ));
+                    }
+                    amounts.push(amount as u64)
+                }
+            }
+        }
+        let sender = sender.ok_or_else(|| Error::MissingInput("Sender address".to_string()))?;
+        let currency = currency.ok_or_else(|| Error::MissingInput("Currency".to_string()))?;
+        Ok(InternalOperation::PayCoin {
+            sender,
+            recipients,
+            amounts,
+            currency,
+        })
+    }
+
     fn stake_ops_to_internal(self) -> Result<InternalOperation, Error> {
         let mut ops = self
             .0
@@ -217,23 +268,29 @@ impl Operations {
         tx: SuiTransactionBlockKind,
         sender: SuiAddress,
         status: Option<OperationStatus>,
+        input_coins: &HashMap<ObjectID, Currency>,
     ) -> Result<Vec<Operation>, Error> {
         Ok(match tx {
             SuiTransactionBlockKind::ProgrammableTransaction(pt) => {
-                Self::parse_programmable_transaction(sender, status, pt)?
+                Self::parse_programmable_transaction(sender, status, pt, input_coins)?
             }
             _ => vec![Operation::generic_op(status, sender, tx)],
         })
     }
 
+    /// `input_coins` are the currencies of the input coins, which are needed to recognise
+    /// `PayCoin` transactions, as the coin types are not part of the transaction.
     fn parse_programmable_transaction(
         sender: SuiAddress,
         status: Option<OperationStatus>,
         pt: SuiProgrammableTransactionBlock,
+        input_coins: &HashMap<ObjectID, Currency>,
     ) -> Result<Vec<Operation>, Error> {
         #[derive(Debug)]
         enum KnownValue {
             GasCoin(u64),
+            // Split from the input coin of a `PayCoin` transaction
+            Coin(u64),
         }
         fn resolve_result(
             known_results: &[Vec<KnownValue>],
@@ -244,22 +301,62 @@ impl Operations {
                 .get(i as usize)
                 .and_then(|inner| inner.get(j as usize))
         }
+        fn input_coin(
+            inputs: &[SuiCallArg],
+            input_coins: &HashMap<ObjectID, Currency>,
+            pay_currency: &mut Option<Currency>,
+            i: u16,
+        ) -> Option<()> {
+            let currency = input_coins.get(inputs.get(i as usize)?.object()?)?;
+            // Only coins of one currency can be paid in a transaction
+            match pay_currency {
+                Some(paid) if paid != currency => return None,
+                _ => *pay_currency = Some(currency.clone()),
+            }
+            Some(())
+        }
+        fn merge_coins(
+            inputs: &[SuiCallArg],
+            input_coins: &HashMap<ObjectID, Currency>,
+            pay_currency: &mut Option<Currency>,
+            coin: SuiArgument,
+            coins: &[SuiArgument],
+        ) -> Option<Vec<KnownValue>> {
+            let SuiArgument::Input(i) = coin else {
+                return None;
+            };
+            input_coin(inputs, input_coins, pay_currency, i)?;
+            // The coins merged in have the coin's type, or the transaction fails.
+            for coin in coins {
+                let SuiArgument::Input(i) = *coin else {
+                    return None;
+                };
+                inputs.get(i as usize)?.object()?;
+            }
+            Some(vec![])
+        }
         fn split_coins(
             inputs: &[SuiCallArg],
+            input_coins: &HashMap<ObjectID, Currency>,
+            pay_currency: &mut Option<Currency>,
             known_results: &[Vec<KnownValue>],
             coin: SuiArgument,
             amounts: &[SuiArgument],
         ) -> Option<Vec<KnownValue>> {
-            match coin {
-                SuiArgument::Result(i) => {
-                    let KnownValue::GasCoin(_) = resolve_result(known_results, i, 0)?;
-                }
-                SuiArgument::NestedResult(i, j) => {
-                    let KnownValue::GasCoin(_) = resolve_result(known_results, i, j)?;
+            let known_value: fn(u64) -> KnownValue = match coin {
+                SuiArgument::Result(i) => match resolve_result(known_results, i, 0)? {
+                    KnownValue::GasCoin(_) => KnownValue::GasCoin,
+                    KnownValue::Coin(_) => KnownValue::Coin,
+                },
+                SuiArgument::NestedResult(i, j) => match resolve_result(known_results, i, j)? {
+                    KnownValue::GasCoin(_) => KnownValue::GasCoin,
+                    KnownValue::Coin(_) => KnownValue::Coin,
+                },
+                SuiArgument::GasCoin => KnownValue::GasCoin,
+                SuiArgument::Input(i) => {
+                    input_coin(inputs, input_coins, pay_currency, i)?;
+                    KnownValue::Coin
                 }
-                SuiArgument::GasCoin => (),
-                // Might not be a SUI coin
-                SuiArgument::Input(_) => return None,
             };
             let amounts = amounts
                 .iter()
@@ -273,13 +370,14 @@ impl Operations {
                         | SuiArgument::Result(_)
                         | SuiArgument::NestedResult(_, _) => return None,
                     };
-                    Some(KnownValue::GasCoin(value))
+                    Some(known_value(value))
                 })
                 .collect::<Option<_>>()?;
             Some(amounts)
         }
         fn transfer_object(
             aggregated_recipients: &mut HashMap<SuiAddress, u64>,
+            aggregated_coin_recipients: &mut HashMap<SuiAddress, u64>,
             inputs: &[SuiCallArg],
             known_results: &[Vec<KnownValue>],
             objs: &[SuiArgument],
@@ -292,18 +390,19 @@ impl Operations {
                 }
             };
             for obj in objs {
-                let value = match *obj {
-                    SuiArgument::Result(i) => {
-                        let KnownValue::GasCoin(value) = resolve_result(known_results, i, 0)?;
-                        value
+                let known_value = match *obj {
+                    SuiArgument::Result(i) => resolve_result(known_results, i, 0)?,
+                    SuiArgument::NestedResult(i, j) => resolve_result(known_results, i, j)?,
+                    SuiArgument::GasCoin | SuiArgument::Input(_) => return None,
+                };
+                let (aggregate, value) = match known_value {
+                    KnownValue::GasCoin(value) => {
+                        (aggregated_recipients.entry(addr).or_default(), value)
                     }
-                    SuiArgument::NestedResult(i, j) => {
-                        let KnownValue::GasCoin(value) = resolve_result(known_results, i, j)?;
-                        value
+                    KnownValue::Coin(value) => {
+                        (aggregated_coin_recipients.entry(addr).or_default(), value)
                     }
-                    SuiArgument::GasCoin | SuiArgument::Input(_) => return None,
                 };
-                let aggregate = aggregated_recipients.entry(addr).or_default();
                 *aggregate += value;
             }
             Some(vec![])
@@ -318,7 +417,9 @@ impl Operations {
                 [_, coin, validator] => {
                     let amount = match coin {
                         SuiArgument::Result(i) =>{
-                            let KnownValue::GasCoin(value) = resolve_result(known_results, *i, 0).ok_or_else(||anyhow!("Cannot resolve Gas coin value at Result({i})"))?;
+                            let KnownValue::GasCoin(value) = resolve_result(known_results, *i, 0).ok_or_else(||anyhow!("Cannot resolve Gas coin value at Result({i})"))? else {
+                                return Ok(None);
+                            };
                             value
                         },
                         _ => return Ok(None),
@@ -363,16 +464,27 @@ impl Operations {
         let SuiProgrammableTransactionBlock { inputs, commands } = &pt;
         let mut known_results: Vec<Vec<KnownValue>> = vec![];
         let mut aggregated_recipients: HashMap<SuiAddress, u64> = HashMap::new();
+        let mut aggregated_coin_recipients: HashMap<SuiAddress, u64> = HashMap::new();
+        let mut pay_currency = None;
         let mut needs_generic = false;
         let mut operations = vec![];
         let mut stake_ids = vec![];
         for command in commands {
             let result = match command {
-                SuiCommand::SplitCoins(coin, amounts) => {
-                    split_coins(inputs, &known_results, *coin, amounts)
+                SuiCommand::MergeCoins(coin, coins) => {
+                    merge_coins(inputs, input_coins, &mut pay_currency, *coin, coins)
                 }
+                SuiCommand::SplitCoins(coin, amounts) => split_coins(
+                    inputs,
+                    input_coins,
+                    &mut pay_currency,
+                    &known_results,
+                    *coin,
+                    amounts,
+                ),
                 SuiCommand::TransferObjects(objs, addr) => transfer_object(
                     &mut aggregated_recipients,
+                    &mut aggregated_coin_recipients,
                     inputs,
                     &known_results,
                     objs,
@@ -408,16 +520,35 @@ impl Operations {
             }
         }
 
-        if !needs_generic && !aggregated_recipients.is_empty() {
-            let total_paid: u64 = aggregated_recipients.values().copied().sum();
-            operations.extend(
-                aggregated_recipients
-                    .into_iter()
-                    .map(|(recipient, amount)| {
-                        Operation::pay_sui(status, recipient, amount.into())
-                    }),
-            );
-            operations.push(Operation::pay_sui(status, sender, -(total_paid as i128)));
+        if !needs_generic
+            && !(aggregated_recipients.is_empty() && aggregated_coin_recipients.is_empty())
+        {
+            if !aggregated_recipients.is_empty() {
+                let total_paid: u64 = aggregated_recipients.values().copied().sum();
+                operations.extend(
+                    aggregated_recipients
+                        .into_iter()
+                        .map(|(recipient, amount)| {
+                            Operation::pay_sui(status, recipient, amount.into())
+                        }),
+                );
+                operations.push(Operation::pay_sui(status, sender, -(total_paid as i128)));
+            }
+            if let Some(currency) = pay_currency.filter(|_| !aggregated_coin_recipients.is_empty())
+            {
+                let total_paid: u64 = aggregated_coin_recipients.values().copied().sum();
+                operations.extend(aggregated_coin_recipients.into_iter().map(
+                    |(recipient, amount)| {
+                        Operation::pay_coin(status, recipient, amount.into(), currency.clone())
+                    },
+                ));
+                operations.push(Operation::pay_coin(
+                    status,
+                    sender,
+                    -(total_paid as i128),
+                    currency,
+                ));
+            }
         } else if !stake_ids.is_empty() {
             let stake_ids = stake_ids.into_iter().flatten().collect::<Vec<_>>();
             let metadata = stake_ids
@@ -455,31 +586,40 @@ impl Operations {
             && tx.function == WITHDRAW_STAKE_FUN_NAME.as_str()
     }
 
+    /// Balance changes in coins other than SUI are only reported for the coin types in
+    /// `currencies`.
     fn process_balance_change(
         gas_owner: SuiAddress,
         gas_used: i128,
         balance_changes: &[BalanceChange],
         status: Option<OperationStatus>,
-        balances: HashMap<SuiAddress, i128>,
+        balances: HashMap<(SuiAddress, Currency), i128>,
+        currencies: &HashMap<TypeTag, Currency>,
     ) -> impl Iterator<Item = Operation> {
         let mut balances = balance_changes
             .iter()
             .fold(balances, |mut balances, balance_change| {
                 // Rosetta only care about address owner
                 if let Owner::AddressOwner(owner) = balance_change.owner {
-                    if balance_change.coin_type == GAS::type_tag() {
-                        *balances.entry(owner).or_default() += balance_change.amount;
+                    let currency = if balance_change.coin_type == GAS::type_tag() {
+                        Some(SUI.clone())
+                    } else {
+                        currencies.get(&balance_change.coin_type).cloned()
+                    };
+                    if let Some(currency) = currency {
+                        *balances.entry((owner, currency)).or_default() += balance_change.amount;
                     }
                 }
                 balances
             });
         // separate gas from balances
-        *balances.entry(gas_owner).or_default() -= gas_used;
+        *balances.entry((gas_owner, SUI.clone())).or_default() -= gas_used;
 
-        let balance_change = balances
-            .into_iter()
-            .filter(|(_, amount)| *amount != 0)
-            .map(move |(addr, amount)| Operation::balance_change(status, addr, amount));
+        let balance_change = balances.into_iter().filter(|(_, amount)| *amount != 0).map(
+            move |((addr, currency), amount)| {
+                Operation::balance_change(status, addr, amount, currency)
+            },
+        );
 
         let gas = if gas_used != 0 {
             vec![Operation::gas(gas_owner, gas_used)]
@@ -494,18 +634,37 @@ impl Operations {
 impl TryFrom<SuiTransactionBlockData> for Operations {
     type Error = Error;
     fn try_from(data: SuiTransactionBlockData) -> Result<Self, Self::Error> {
+        Self::from_transaction_data(data, &HashMap::new())
+    }
+}
+
+impl TryFrom<SuiTransactionBlockResponse> for Operations {
+    type Error = Error;
+    fn try_from(response: SuiTransactionBlockResponse) -> Result<Self, Self::Error> {
+        Self::try_from_response(response, &HashMap::new())
+    }
+}
+
+impl Operations {
+    fn from_transaction_data(
+        data: SuiTransactionBlockData,
+        input_coins: &HashMap<ObjectID, Currency>,
+    ) -> Result<Self, Error> {
         let sender = *data.sender();
         Ok(Self::new(Self::from_transaction(
             data.transaction().clone(),
             sender,
             None,
+            input_coins,
         )?))
     }
-}
 
-impl TryFrom<SuiTransactionBlockResponse> for Operations {
-    type Error = Error;
-    fn try_from(response: SuiTransactionBlockResponse) -> Result<Self, Self::Error> {
+    /// Creates the "confirmed" operations of an executed transaction, including the balance
+    /// changes of any coins with a type in `currencies`, as well as SUI.
+    pub fn try_from_response(
+        response: SuiTransactionBlockResponse,
+        currencies: &HashMap<TypeTag, Currency>,
+    ) -> Result<Self, Error> {
         let tx = response
             .transaction
             .ok_or_else(|| anyhow!("Response input should not be empty"))?;
@@ -519,8 +678,28 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             - gas_summary.storage_cost as i128
             - gas_summary.computation_cost as i128;
 
+        // The coin types of the coins paid with in a `PayCoin` transaction are only known from
+        // the objects it changed.
+        let input_coins = response
+            .object_changes
+            .iter()
+            .flatten()
+            .filter_map(|change| match change {
+                ObjectChange::Mutated {
+                    object_id,
+                    object_type,
+                    ..
+                } if Coin::is_coin(object_type) => {
+                    let currency = currencies.get(object_type.type_params.first()?)?;
+                    // SUI is paid from the gas coin, with `PaySui`
+                    (!currency.is_sui()).then(|| (*object_id, currency.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+
         let status = Some(effect.into_status().into());
-        let ops: Operations = tx.data.try_into()?;
+        let ops = Self::from_transaction_data(tx.data, &input_coins)?;
         let ops = ops.set_status(status).into_iter();
 
         // We will need to subtract the operation amounts from the actual balance
@@ -532,7 +711,9 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
                     if let (Some(acc), Some(amount), Some(OperationStatus::Success)) =
                         (&op.account, &op.amount, &op.status)
                     {
-                        *balances.entry(acc.address).or_default() -= amount.value;
+                        *balances
+                            .entry((acc.address, amount.currency.clone()))
+                            .or_default() -= amount.value;
                     }
                     balances
                 });
@@ -564,8 +745,8 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
             }
         }
         let staking_balance = if principal_amounts != 0 {
-            *accounted_balances.entry(sender).or_default() -= principal_amounts;
-            *accounted_balances.entry(sender).or_default() -= reward_amounts;
+            *accounted_balances.entry((sender, SUI.clone())).or_default() -= principal_amounts;
+            *accounted_balances.entry((sender, SUI.clone())).or_default() -= reward_amounts;
             vec![
                 Operation::stake_principle(status, sender, principal_amounts),
                 Operation::stake_reward(status, sender, reward_amounts),
@@ -583,6 +764,7 @@ impl TryFrom<SuiTransactionBlockResponse> for Operations {
                 .ok_or_else(|| anyhow!("Response balance changes should not be empty."))?,
             status,
             accounted_balances,
+            currencies,
         );
 
         Ok(ops
@@ -698,13 +880,40 @@ impl Operation {
         }
     }
 
-    fn balance_change(status: Option<OperationStatus>, addr: SuiAddress, amount: i128) -> Self {
+    fn pay_coin(
+        status: Option<OperationStatus>,
+        address: SuiAddress,
+        amount: i128,
+        currency: Currency,
+    ) -> Self {
+        Operation {
+            operation_identifier: Default::default(),
+            type_: OperationType::PayCoin,
+            status,
+            account: Some(address.into()),
+            amount: Some(Amount::new_with_currency(amount, currency)),
+            coin_change: None,
+            metadata: None,
+        }
+    }
+
+    fn balance_change(
+        status: Option<OperationStatus>,
+        addr: SuiAddress,
+        amount: i128,
+        currency: Currency,
+    ) -> Self {
+        let type_ = if currency.is_sui() {
+            OperationType::SuiBalanceChange
+        } else {
+            OperationType::CoinBalanceChange
+        };
         Self {
             operation_identifier: Default::default(),
-            type_: OperationType::SuiBalanceChange,
+            type_,
             status,
             account: Some(addr.into()),
-            amount: Some(Amount::new(amount)),
+            amount: Some(Amount::new_with_currency(amount, currency)),
             coin_change: None,
             metadata: None,
         }
@@ -3,14 +3,18 @@
 
 use crate::operations::Operations;
 use crate::types::{
-    Block, BlockHash, BlockIdentifier, BlockResponse, Transaction, TransactionIdentifier,
+    Block, BlockHash, BlockIdentifier, BlockResponse, Currency, CurrencyMetadata, Transaction,
+    TransactionIdentifier,
 };
-use crate::Error;
+use crate::{Error, SUI};
 use async_trait::async_trait;
-use std::sync::Arc;
+use move_core_types::language_storage::TypeTag;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
 use sui_sdk::rpc_types::Checkpoint;
 use sui_sdk::SuiClient;
+use sui_types::gas_coin::GAS;
 use sui_types::messages_checkpoint::CheckpointSequenceNumber;
 
 #[cfg(test)]
@@ -53,6 +57,7 @@ pub trait BlockProvider {
 #[derive(Clone)]
 pub struct CheckpointBlockProvider {
     client: SuiClient,
+    coin_metadata_cache: CoinMetadataCache,
 }
 
 #[async_trait]
@@ -104,7 +109,11 @@ impl BlockProvider for CheckpointBlockProvider {
 
 impl CheckpointBlockProvider {
     pub fn new(client: SuiClient) -> Self {
-        Self { client }
+        let coin_metadata_cache = CoinMetadataCache::new(client.clone());
+        Self {
+            client,
+            coin_metadata_cache,
+        }
     }
 
     async fn create_block_response(&self, checkpoint: Checkpoint) -> Result<BlockResponse, Error> {
@@ -121,13 +130,25 @@ impl CheckpointBlockProvider {
                         .with_input()
                         .with_effects()
                         .with_balance_changes()
+                        .with_object_changes()
                         .with_events(),
                 )
                 .await?;
             for tx in transaction_responses.into_iter() {
+                let mut currencies = HashMap::new();
+                for balance_change in tx.balance_changes.iter().flatten() {
+                    let coin_type = &balance_change.coin_type;
+                    if currencies.contains_key(coin_type) {
+                        continue;
+                    }
+                    if let Some(currency) = self.coin_metadata_cache.get_currency(coin_type).await?
+                    {
+                        currencies.insert(coin_type.clone(), currency);
+                    }
+                }
                 transactions.push(Transaction {
                     transaction_identifier: TransactionIdentifier { hash: tx.digest },
-                    operations: Operations::try_from(tx)?,
+                    operations: Operations::try_from_response(tx, &currencies)?,
                     related_transactions: vec![],
                     metadata: None,
                 })
@@ -176,3 +197,48 @@ impl CheckpointBlockProvider {
         })
     }
 }
+
+/// Caches the `Currency` of each coin type, as read from its `CoinMetadata`. Coin types without
+/// `CoinMetadata` have no currency, so their balance changes are left out of blocks.
+#[derive(Clone)]
+pub struct CoinMetadataCache {
+    client: SuiClient,
+    currencies: Arc<Mutex<HashMap<TypeTag, Option<Currency>>>>,
+}
+
+impl CoinMetadataCache {
+    pub fn new(client: SuiClient) -> Self {
+        Self {
+            client,
+            currencies: Default::default(),
+        }
+    }
+
+    pub async fn get_currency(&self, coin_type: &TypeTag) -> Result<Option<Currency>, Error> {
+        if coin_type == &GAS::type_tag() {
+            return Ok(Some(SUI.clone()));
+        }
+        let cached = self.currencies.lock().unwrap().get(coin_type).cloned();
+        if let Some(currency) = cached {
+            return Ok(currency);
+        }
+
+        let currency = self
+            .client
+            .coin_read_api()
+            .get_coin_metadata(coin_type.to_string())
+            .await?
+            .map(|metadata| Currency {
+                symbol: metadata.symbol,
+                decimals: metadata.decimals.into(),
+                metadata: CurrencyMetadata {
+                    coin_type: coin_type.to_string(),
+                },
+            });
+        self.currencies
+            .lock()
+            .unwrap()
+            .insert(coin_type.clone(), currency.clone());
+        Ok(currency)
+    }
+}
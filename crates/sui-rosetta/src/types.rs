@@ -14,16 +14,19 @@ use serde_json::Value;
 use strum_macros::EnumIter;
 use strum_macros::EnumString;
 
+use move_core_types::language_storage::TypeTag;
 use sui_sdk::rpc_types::{SuiExecutionStatus, SuiTransactionBlockKind};
+use sui_sdk::SUI_COIN_TYPE;
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber, SuiAddress, TransactionDigest};
 use sui_types::crypto::PublicKey as SuiPublicKey;
 use sui_types::crypto::SignatureScheme;
+use sui_types::gas_coin::GAS;
 use sui_types::governance::{ADD_STAKE_FUN_NAME, WITHDRAW_STAKE_FUN_NAME};
 use sui_types::messages_checkpoint::CheckpointDigest;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::sui_system_state::SUI_SYSTEM_MODULE_NAME;
 use sui_types::transaction::{Argument, CallArg, Command, ObjectArg, TransactionData};
-use sui_types::SUI_SYSTEM_PACKAGE_ID;
+use sui_types::{parse_sui_type_tag, SUI_SYSTEM_PACKAGE_ID};
 
 use crate::errors::{Error, ErrorType};
 use crate::operations::Operations;
@@ -95,11 +98,50 @@ impl From<SuiAddress> for AccountIdentifier {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Currency {
     pub symbol: String,
     pub decimals: u64,
+    #[serde(default, skip_serializing_if = "CurrencyMetadata::is_sui")]
+    pub metadata: CurrencyMetadata,
+}
+
+impl Currency {
+    pub fn coin_type(&self) -> Result<TypeTag, Error> {
+        parse_sui_type_tag(&self.metadata.coin_type).map_err(|e| {
+            Error::InvalidInput(format!(
+                "Invalid coin type [{}]: {e}",
+                self.metadata.coin_type
+            ))
+        })
+    }
+
+    pub fn is_sui(&self) -> bool {
+        self.metadata.is_sui()
+    }
+}
+
+/// Identifies the `Coin<T>` a currency is made of, by its coin type `T`. Currencies without
+/// metadata are SUI, so that requests and responses that only deal with SUI are unchanged.
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct CurrencyMetadata {
+    pub coin_type: String,
+}
+
+impl Default for CurrencyMetadata {
+    fn default() -> Self {
+        Self {
+            coin_type: SUI_COIN_TYPE.to_string(),
+        }
+    }
+}
+
+impl CurrencyMetadata {
+    fn is_sui(&self) -> bool {
+        parse_sui_type_tag(&self.coin_type).is_ok_and(|tag| tag == GAS::type_tag())
+    }
 }
+
 #[derive(Serialize, Deserialize)]
 pub struct AccountBalanceRequest {
     pub network_identifier: NetworkIdentifier,
@@ -153,9 +195,12 @@ pub struct SubBalance {
 
 impl Amount {
     pub fn new(value: i128) -> Self {
+        Self::new_with_currency(value, SUI.clone())
+    }
+    pub fn new_with_currency(value: i128, currency: Currency) -> Self {
         Self {
             value,
-            currency: SUI.clone(),
+            currency,
             metadata: None,
         }
     }
@@ -395,10 +440,12 @@ pub enum OperationType {
     // Balance changing operations from TransactionEffect
     Gas,
     SuiBalanceChange,
+    CoinBalanceChange,
     StakeReward,
     StakePrinciple,
     // sui-rosetta supported operation type
     PaySui,
+    PayCoin,
     Stake,
     WithdrawStake,
     // All other Sui transaction types, readonly
@@ -849,6 +896,12 @@ pub enum InternalOperation {
         recipients: Vec<SuiAddress>,
         amounts: Vec<u64>,
     },
+    PayCoin {
+        sender: SuiAddress,
+        recipients: Vec<SuiAddress>,
+        amounts: Vec<u64>,
+        currency: Currency,
+    },
     Stake {
         sender: SuiAddress,
         validator: SuiAddress,
@@ -865,6 +918,7 @@ impl InternalOperation {
     pub fn sender(&self) -> SuiAddress {
         match self {
             InternalOperation::PaySui { sender, .. }
+            | InternalOperation::PayCoin { sender, .. }
             | InternalOperation::Stake { sender, .. }
             | InternalOperation::WithdrawStake { sender, .. } => *sender,
         }
@@ -881,6 +935,17 @@ impl InternalOperation {
                 builder.pay_sui(recipients, amounts)?;
                 builder.finish()
             }
+            // The coins to pay with are in the metadata's objects, its coins only pay for gas, as in
+            // the transactions `TransactionBuilder::pay` builds.
+            Self::PayCoin {
+                recipients,
+                amounts,
+                ..
+            } => {
+                let mut builder = ProgrammableTransactionBuilder::new();
+                builder.pay(metadata.objects, recipients, amounts)?;
+                builder.finish()
+            }
             InternalOperation::Stake {
                 validator, amount, ..
             } => {
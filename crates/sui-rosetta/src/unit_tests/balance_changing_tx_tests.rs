@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::operations::Operations;
-use crate::types::{ConstructionMetadata, OperationStatus, OperationType};
+use crate::types::{
+    ConstructionMetadata, Currency, CurrencyMetadata, OperationStatus, OperationType,
+};
 use anyhow::anyhow;
 use move_core_types::identifier::Identifier;
 use rand::seq::{IteratorRandom, SliceRandom};
@@ -26,6 +28,7 @@ use sui_sdk::rpc_types::{
 use sui_sdk::SuiClient;
 use sui_types::base_types::{ObjectID, ObjectRef, SuiAddress};
 use sui_types::gas_coin::GasCoin;
+use sui_types::parse_sui_type_tag;
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::quorum_driver_types::ExecuteTransactionRequestType;
 use sui_types::transaction::{
@@ -217,6 +220,142 @@ async fn test_publish_and_move_call() {
     .await;
 }
 
+#[tokio::test]
+async fn test_pay_coin() {
+    let network = TestClusterBuilder::new().build().await;
+    let client = network.wallet.get_client().await.unwrap();
+    let keystore = &network.wallet.config.keystore;
+    let rgp = network.get_reference_gas_price().await;
+
+    let addresses = network.get_addresses();
+    let sender = get_random_address(&addresses, vec![]);
+    let recipient = get_random_address(&addresses, vec![sender]);
+
+    // Publish the managed coin and mint some to the sender
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.extend([
+        "..",
+        "..",
+        "sui_programmability",
+        "examples",
+        "fungible_tokens",
+    ]);
+    let compiled_package = BuildConfig::new_for_testing().build(path).unwrap();
+    let compiled_modules_bytes =
+        compiled_package.get_package_bytes(/* with_unpublished_deps */ false);
+    let dependencies = compiled_package.get_dependency_original_package_ids();
+
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder.publish_immutable(compiled_modules_bytes, dependencies);
+        builder.finish()
+    };
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        rgp * TEST_ONLY_GAS_UNIT_FOR_HEAVY_COMPUTATION_STORAGE,
+        rgp,
+        false,
+    )
+    .await;
+    let object_changes = response.object_changes.unwrap();
+    let package = object_changes
+        .iter()
+        .find_map(|change| {
+            if let ObjectChange::Published { package_id, .. } = change {
+                Some(*package_id)
+            } else {
+                None
+            }
+        })
+        .unwrap();
+    let treasury = find_module_object(&object_changes, "::TreasuryCap");
+    let treasury = treasury.reference.to_object_ref();
+
+    let coin_type = format!("{package}::managed::MANAGED");
+    let currency = Currency {
+        symbol: "MANAGED".to_string(),
+        decimals: 2,
+        metadata: CurrencyMetadata {
+            coin_type: coin_type.clone(),
+        },
+    };
+    let currencies = HashMap::from([(parse_sui_type_tag(&coin_type).unwrap(), currency.clone())]);
+
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .move_call(
+                package,
+                Identifier::from_str("managed").unwrap(),
+                Identifier::from_str("mint").unwrap(),
+                vec![],
+                vec![
+                    CallArg::Object(ObjectArg::ImmOrOwnedObject(treasury)),
+                    CallArg::Pure(bcs::to_bytes(&10000u64).unwrap()),
+                    CallArg::Pure(bcs::to_bytes(&sender).unwrap()),
+                ],
+            )
+            .unwrap();
+        builder.finish()
+    };
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+        rgp,
+        false,
+    )
+    .await;
+    let coin = find_module_object(response.object_changes.as_ref().unwrap(), "::coin::Coin");
+    let coin = coin.reference.to_object_ref();
+
+    // The minted coins are a balance change in the managed currency
+    let ops = Operations::try_from_response(response, &currencies).unwrap();
+    let coin_changes = extract_coin_balance_changes_from_ops(ops, &currency);
+    assert_eq!(HashMap::from([(sender, 10000)]), coin_changes);
+
+    // Paying with the minted coin is a PayCoin operation, which accounts for its balance changes
+    let pt = {
+        let mut builder = ProgrammableTransactionBuilder::new();
+        builder
+            .pay(vec![coin], vec![recipient], vec![3000])
+            .unwrap();
+        builder.finish()
+    };
+    let response = test_transaction(
+        &client,
+        keystore,
+        vec![],
+        sender,
+        pt,
+        vec![],
+        rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        rgp,
+        false,
+    )
+    .await;
+    let ops = Operations::try_from_response(response, &currencies).unwrap();
+    assert!(ops
+        .clone()
+        .into_iter()
+        .all(|op| op.type_ != OperationType::CoinBalanceChange));
+    assert_eq!(Some(OperationType::PayCoin), ops.type_());
+    let coin_changes = extract_coin_balance_changes_from_ops(ops, &currency);
+    assert_eq!(
+        HashMap::from([(sender, -3000), (recipient, 3000)]),
+        coin_changes
+    );
+}
+
 #[tokio::test]
 async fn test_split_coin() {
     let network = TestClusterBuilder::new().build().await;
@@ -780,6 +919,23 @@ fn extract_balance_changes_from_ops(ops: Operations) -> HashMap<SuiAddress, i128
         })
 }
 
+fn extract_coin_balance_changes_from_ops(
+    ops: Operations,
+    currency: &Currency,
+) -> HashMap<SuiAddress, i128> {
+    ops.into_iter()
+        .fold(HashMap::<SuiAddress, i128>::new(), |mut changes, op| {
+            if let (Some(OperationStatus::Success), Some(addr), Some(amount)) =
+                (op.status, op.account, op.amount)
+            {
+                if &amount.currency == currency {
+                    *changes.entry(addr.address).or_default() += amount.value
+                }
+            }
+            changes
+        })
+}
+
 async fn get_random_sui(
     client: &SuiClient,
     sender: SuiAddress,
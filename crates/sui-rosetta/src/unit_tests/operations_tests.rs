@@ -2,13 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use move_core_types::value::MoveTypeLayout;
+use serde_json::json;
 use sui_json_rpc_types::SuiCallArg;
 use sui_types::base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress};
 use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use sui_types::transaction::{CallArg, TransactionData, TEST_ONLY_GAS_UNIT_FOR_TRANSFER};
 
 use crate::operations::Operations;
-use crate::types::ConstructionMetadata;
+use crate::types::{ConstructionMetadata, Currency, InternalOperation};
+use crate::SUI;
 
 #[tokio::test]
 async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
@@ -50,6 +52,88 @@ async fn test_operation_data_parsing() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+#[tokio::test]
+async fn test_pay_coin_operation_parsing() -> Result<(), anyhow::Error> {
+    let random_ref = || {
+        (
+            ObjectID::random(),
+            SequenceNumber::new(),
+            ObjectDigest::random(),
+        )
+    };
+    let gas = random_ref();
+    let coins = vec![random_ref(), random_ref()];
+    let sender = SuiAddress::random_for_testing_only();
+    let recipient = SuiAddress::random_for_testing_only();
+    let currency = json!({
+        "symbol": "USDC",
+        "decimals": 6,
+        "metadata": { "coin_type": "0x1234::usdc::USDC" }
+    });
+
+    let ops: Operations = serde_json::from_value(json!([
+        {
+            "operation_identifier": { "index": 0 },
+            "type": "PayCoin",
+            "account": { "address": recipient.to_string() },
+            "amount": { "value": "500000", "currency": currency }
+        },
+        {
+            "operation_identifier": { "index": 1 },
+            "type": "PayCoin",
+            "account": { "address": sender.to_string() },
+            "amount": { "value": "-500000", "currency": currency }
+        }
+    ]))?;
+    let internal = ops.into_internal()?;
+    let InternalOperation::PayCoin {
+        currency: parsed_currency,
+        ..
+    } = &internal
+    else {
+        panic!("Expected a PayCoin operation, got {internal:?}");
+    };
+    assert_eq!(parsed_currency.symbol, "USDC");
+    assert!(!parsed_currency.is_sui());
+
+    let gas_price = 10;
+    let metadata = ConstructionMetadata {
+        sender,
+        coins: vec![gas],
+        objects: coins.clone(),
+        total_coin_value: 0,
+        gas_price,
+        budget: TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+    };
+    let data = internal.try_into_data(metadata)?;
+
+    let expected = TransactionData::new_pay(
+        sender,
+        coins,
+        vec![recipient],
+        vec![500000],
+        gas,
+        TEST_ONLY_GAS_UNIT_FOR_TRANSFER * gas_price,
+        gas_price,
+    )?;
+    assert_eq!(expected, data);
+
+    Ok(())
+}
+
+#[test]
+fn test_sui_currency_serde() {
+    // SUI is the currency when there is no coin type, and is written without one.
+    let currency: Currency =
+        serde_json::from_value(json!({"symbol": "SUI", "decimals": 9})).unwrap();
+    assert_eq!(*SUI, currency);
+    assert!(currency.is_sui());
+    assert_eq!(
+        json!({"symbol": "SUI", "decimals": 9}),
+        serde_json::to_value(&currency).unwrap()
+    );
+}
+
 #[tokio::test]
 async fn test_sui_json() {
     let arg1 = CallArg::Pure(bcs::to_bytes(&1000000u64).unwrap());
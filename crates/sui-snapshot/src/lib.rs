@@ -10,6 +10,7 @@ pub mod uploader;
 mod writer;
 
 use anyhow::Result;
+use fastcrypto::hash::MultisetHash;
 use num_enum::IntoPrimitive;
 use num_enum::TryFromPrimitive;
 use object_store::path::Path;
@@ -20,11 +21,12 @@ use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_core::authority::epoch_start_configuration::EpochStartConfiguration;
 use sui_core::checkpoints::CheckpointStore;
 use sui_core::epoch::committee_store::CommitteeStore;
+use sui_core::state_accumulator::WrappedObject;
 use sui_storage::object_store::util::path_to_filesystem;
 use sui_storage::{compute_sha3_checksum, FileCompression, SHA3_BYTES};
 use sui_types::accumulator::Accumulator;
 use sui_types::authenticator_state::get_authenticator_state_obj_initial_shared_version;
-use sui_types::base_types::ObjectID;
+use sui_types::base_types::{ObjectDigest, ObjectID, ObjectRef};
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
 use sui_types::sui_system_state::get_sui_system_state;
 use sui_types::sui_system_state::SuiSystemStateTrait;
@@ -60,6 +62,19 @@ use sui_types::sui_system_state::SuiSystemStateTrait;
 ///     - epoch_1/
 ///       - 1_1.obj
 ///       - ...
+///
+/// Delta Snapshots
+/// Instead of the whole live object set, a snapshot may hold just the changes to it since the
+/// snapshot of an earlier (base) epoch, which may itself be a delta. The *.obj and *.ref files of a
+/// delta snapshot hold the objects that were created or mutated since the base epoch, and their
+/// references. Removed files (*.rm) hold the references, as of the base epoch, of the objects that
+/// have since been mutated or deleted. Removed files use the REFERENCE file format, with their own
+/// magic (0x0DE1E7ED). The MANIFEST of a delta snapshot is a `ManifestV2` that names its base epoch.
+/// State for the epoch is rebuilt by restoring the full snapshot at the start of the chain, and then
+/// for each delta in turn, removing the objects in its removed files and inserting the objects in
+/// its object files. The accumulator of the live object set is rebuilt the same way from the
+/// references, so it can be verified before any objects are downloaded.
+///
 /// Object File Disk Format
 ///┌──────────────────────────────┐
 ///│  magic(0x00B7EC75) <4 byte>  │
@@ -105,6 +120,7 @@ use sui_types::sui_system_state::SuiSystemStateTrait;
 const OBJECT_FILE_MAGIC: u32 = 0x00B7EC75;
 const REFERENCE_FILE_MAGIC: u32 = 0xDEADBEEF;
 const MANIFEST_FILE_MAGIC: u32 = 0x00C0FFEE;
+const REMOVED_FILE_MAGIC: u32 = 0x0DE1E7ED;
 const MAGIC_BYTES: usize = 4;
 const SNAPSHOT_VERSION_BYTES: usize = 1;
 const ADDRESS_LENGTH_BYTES: usize = 8;
@@ -130,6 +146,7 @@ const FILE_METADATA_BYTES: usize =
 pub enum FileType {
     Object = 0,
     Reference,
+    Removed,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
@@ -150,6 +167,9 @@ impl FileMetadata {
             FileType::Reference => {
                 dir_path.child(&*format!("{}_{}.ref", self.bucket_num, self.part_num))
             }
+            FileType::Removed => {
+                dir_path.child(&*format!("{}_{}.rm", self.bucket_num, self.part_num))
+            }
         }
     }
     pub fn local_file_path(&self, root_path: &std::path::Path, dir_path: &Path) -> Result<PathBuf> {
@@ -165,30 +185,52 @@ pub struct ManifestV1 {
     pub epoch: u64,
 }
 
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ManifestV2 {
+    pub snapshot_version: u8,
+    pub address_length: u64,
+    pub file_metadata: Vec<FileMetadata>,
+    pub epoch: u64,
+    /// The epoch whose snapshot this one holds the changes since, or `None` if this snapshot holds
+    /// the whole live object set.
+    pub base_epoch: Option<u64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
 pub enum Manifest {
     V1(ManifestV1),
+    V2(ManifestV2),
 }
 
 impl Manifest {
     pub fn snapshot_version(&self) -> u8 {
         match self {
             Self::V1(manifest) => manifest.snapshot_version,
+            Self::V2(manifest) => manifest.snapshot_version,
         }
     }
     pub fn address_length(&self) -> u64 {
         match self {
             Self::V1(manifest) => manifest.address_length,
+            Self::V2(manifest) => manifest.address_length,
         }
     }
     pub fn file_metadata(&self) -> &Vec<FileMetadata> {
         match self {
             Self::V1(manifest) => &manifest.file_metadata,
+            Self::V2(manifest) => &manifest.file_metadata,
         }
     }
     pub fn epoch(&self) -> u64 {
         match self {
             Self::V1(manifest) => manifest.epoch,
+            Self::V2(manifest) => manifest.epoch,
+        }
+    }
+    pub fn base_epoch(&self) -> Option<u64> {
+        match self {
+            Self::V1(_) => None,
+            Self::V2(manifest) => manifest.base_epoch,
         }
     }
 }
@@ -212,6 +254,31 @@ pub fn create_file_metadata(
     Ok(file_metadata)
 }
 
+/// Adds `object_ref` to `acc` the same way the state accumulator adds a live object to the
+/// accumulator of the live object set.
+fn accumulate_object_ref(acc: &mut Accumulator, object_ref: &ObjectRef) {
+    if object_ref.2 == ObjectDigest::OBJECT_DIGEST_WRAPPED {
+        acc.insert(
+            bcs::to_bytes(&WrappedObject::new(object_ref.0, object_ref.1))
+                .expect("Failed to serialize WrappedObject"),
+        );
+    } else {
+        acc.insert(object_ref.2);
+    }
+}
+
+/// Undoes `accumulate_object_ref`.
+fn remove_object_ref(acc: &mut Accumulator, object_ref: &ObjectRef) {
+    if object_ref.2 == ObjectDigest::OBJECT_DIGEST_WRAPPED {
+        acc.remove(
+            bcs::to_bytes(&WrappedObject::new(object_ref.0, object_ref.1))
+                .expect("Failed to serialize WrappedObject"),
+        );
+    } else {
+        acc.remove(object_ref.2);
+    }
+}
+
 pub async fn setup_db_state(
     epoch: u64,
     accumulator: Accumulator,
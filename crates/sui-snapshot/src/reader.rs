@@ -2,22 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    FileMetadata, FileType, Manifest, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
-    OBJECT_ID_BYTES, OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES, SHA3_BYTES,
+    accumulate_object_ref, remove_object_ref, FileMetadata, FileType, Manifest, MAGIC_BYTES,
+    MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC, OBJECT_ID_BYTES, OBJECT_REF_BYTES,
+    REFERENCE_FILE_MAGIC, REMOVED_FILE_MAGIC, SEQUENCE_NUM_BYTES, SHA3_BYTES,
 };
 use anyhow::{anyhow, Context, Result};
 use byteorder::{BigEndian, ReadBytesExt};
 use bytes::{Buf, Bytes};
-use fastcrypto::hash::{HashFunction, Sha3_256};
+use fastcrypto::hash::{HashFunction, MultisetHash, Sha3_256};
 use futures::future::{AbortRegistration, Abortable};
 use futures::{StreamExt, TryStreamExt};
 use integer_encoding::VarIntReader;
 use object_store::path::Path;
 use object_store::DynObjectStore;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::fs::File;
 use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::iter::Peekable;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -26,17 +28,94 @@ use sui_core::authority::AuthorityStore;
 use sui_storage::blob::{Blob, BlobEncoding};
 use sui_storage::object_store::util::{copy_file, copy_files, path_to_filesystem};
 use sui_storage::object_store::ObjectStoreConfig;
+use sui_types::accumulator::Accumulator;
 use sui_types::base_types::{ObjectDigest, ObjectID, ObjectRef, SequenceNumber};
+use sui_types::messages_checkpoint::ECMHLiveObjectSetDigest;
 use tokio::sync::Mutex;
 
 pub type DigestByBucketAndPartition = BTreeMap<u32, BTreeMap<u32, [u8; 32]>>;
+type FilesByBucketAndPartition = BTreeMap<u32, BTreeMap<u32, FileMetadata>>;
+pub(crate) type LiveObjectRefIter = Box<dyn Iterator<Item = Result<ObjectRef>>>;
+
+/// The files of a single snapshot in the chain of snapshots that make up the state of an epoch.
+struct SnapshotLayer {
+    epoch: u64,
+    base_epoch: Option<u64>,
+    ref_files: FilesByBucketAndPartition,
+    object_files: FilesByBucketAndPartition,
+    removed_files: FilesByBucketAndPartition,
+}
+
+impl SnapshotLayer {
+    fn new(manifest: &Manifest) -> Self {
+        let mut object_files = BTreeMap::new();
+        let mut ref_files = BTreeMap::new();
+        let mut removed_files = BTreeMap::new();
+        for file_metadata in manifest.file_metadata() {
+            let files: &mut FilesByBucketAndPartition = match file_metadata.file_type {
+                FileType::Object => &mut object_files,
+                FileType::Reference => &mut ref_files,
+                FileType::Removed => &mut removed_files,
+            };
+            files
+                .entry(file_metadata.bucket_num)
+                .or_insert_with(BTreeMap::new)
+                .insert(file_metadata.part_num, file_metadata.clone());
+        }
+        SnapshotLayer {
+            epoch: manifest.epoch(),
+            base_epoch: manifest.base_epoch(),
+            ref_files,
+            object_files,
+            removed_files,
+        }
+    }
+
+    fn epoch_dir(&self) -> Path {
+        Path::from(format!("epoch_{}", self.epoch))
+    }
+
+    /// All the files of this snapshot that hold object references, which are downloaded upfront.
+    fn ref_file_paths(&self) -> Vec<Path> {
+        let epoch_dir = self.epoch_dir();
+        self.ref_files
+            .values()
+            .chain(self.removed_files.values())
+            .flat_map(|entry| entry.values())
+            .map(|file_metadata| file_metadata.file_path(&epoch_dir))
+            .collect()
+    }
+
+    /// Iterates over the object references in all partitions of a bucket, in partition order.
+    fn bucket_iter(
+        files: &FilesByBucketAndPartition,
+        bucket_num: u32,
+        local_staging_dir_root: &std::path::Path,
+        epoch_dir: &Path,
+    ) -> Result<Box<dyn Iterator<Item = ObjectRef>>> {
+        let mut iter: Box<dyn Iterator<Item = ObjectRef>> = Box::new(std::iter::empty());
+        for file_metadata in files.get(&bucket_num).into_iter().flat_map(|e| e.values()) {
+            let part_iter = ObjectRefIter::new(
+                file_metadata,
+                local_staging_dir_root.to_path_buf(),
+                epoch_dir.clone(),
+            )?;
+            iter = Box::new(iter.chain(part_iter));
+        }
+        Ok(iter)
+    }
+}
+
+/// StateSnapshotReaderV1 restores the live object set of an epoch from its state snapshot. If that
+/// is a delta snapshot, the chain of snapshots it is based on is restored first, starting from the
+/// full snapshot at the start of the chain.
 pub struct StateSnapshotReaderV1 {
     epoch: u64,
     local_staging_dir_root: PathBuf,
     remote_object_store: Arc<DynObjectStore>,
     local_object_store: Arc<DynObjectStore>,
-    ref_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
-    object_files: BTreeMap<u32, BTreeMap<u32, FileMetadata>>,
+    /// The full snapshot at the start of the chain, followed by each delta on top of it in order.
+    layers: Vec<SnapshotLayer>,
     indirect_objects_threshold: usize,
     concurrency: usize,
 }
@@ -49,22 +128,81 @@ impl StateSnapshotReaderV1 {
         indirect_objects_threshold: usize,
         download_concurrency: NonZeroUsize,
     ) -> Result<Self> {
-        let epoch_dir = format!("epoch_{}", epoch);
         let remote_object_store = remote_store_config.make()?;
-
         let local_object_store = local_store_config.make()?;
         let local_staging_dir_root = local_store_config
             .directory
             .as_ref()
-            .context("No directory specified")?
-            .clone();
+            .context("No directory specified")?;
+        Self::new_from_store(
+            epoch,
+            &remote_object_store,
+            &local_object_store,
+            local_staging_dir_root,
+            indirect_objects_threshold,
+            download_concurrency,
+        )
+        .await
+    }
+
+    pub async fn new_from_store(
+        epoch: u64,
+        remote_object_store: &Arc<DynObjectStore>,
+        local_object_store: &Arc<DynObjectStore>,
+        local_staging_dir_root: &std::path::Path,
+        indirect_objects_threshold: usize,
+        download_concurrency: NonZeroUsize,
+    ) -> Result<Self> {
+        let mut layers = vec![];
+        let mut next_epoch = Some(epoch);
+        while let Some(layer_epoch) = next_epoch {
+            let layer = Self::download_layer(
+                layer_epoch,
+                remote_object_store,
+                local_object_store,
+                local_staging_dir_root,
+            )
+            .await?;
+            if let Some(base_epoch) = layer.base_epoch {
+                if base_epoch >= layer_epoch {
+                    return Err(anyhow!(
+                        "Snapshot for epoch: {} has invalid base epoch: {}",
+                        layer_epoch,
+                        base_epoch
+                    ));
+                }
+            }
+            next_epoch = layer.base_epoch;
+            layers.push(layer);
+        }
+        layers.reverse();
+        Ok(StateSnapshotReaderV1 {
+            epoch,
+            local_staging_dir_root: local_staging_dir_root.to_path_buf(),
+            remote_object_store: remote_object_store.clone(),
+            local_object_store: local_object_store.clone(),
+            layers,
+            indirect_objects_threshold,
+            concurrency: download_concurrency.get(),
+        })
+    }
+
+    /// Downloads the MANIFEST of the snapshot for `epoch`, and the files that hold its object
+    /// references.
+    async fn download_layer(
+        epoch: u64,
+        remote_object_store: &Arc<DynObjectStore>,
+        local_object_store: &Arc<DynObjectStore>,
+        local_staging_dir_root: &std::path::Path,
+    ) -> Result<SnapshotLayer> {
+        let epoch_dir = format!("epoch_{}", epoch);
         let local_epoch_dir_path = local_staging_dir_root.join(&epoch_dir);
         if local_epoch_dir_path.exists() {
             fs::remove_dir_all(&local_epoch_dir_path)?;
         }
         fs::create_dir_all(&local_epoch_dir_path)?;
         // Download MANIFEST first
-        let manifest_file_path = Path::from(epoch_dir.clone()).child("MANIFEST");
+        let manifest_file_path = Path::from(epoch_dir).child("MANIFEST");
         copy_file(
             manifest_file_path.clone(),
             manifest_file_path.clone(),
@@ -73,11 +211,11 @@ impl StateSnapshotReaderV1 {
         )
         .await?;
         let manifest = Self::read_manifest(path_to_filesystem(
-            local_staging_dir_root.clone(),
+            local_staging_dir_root.to_path_buf(),
             &manifest_file_path,
         )?)?;
         let snapshot_version = manifest.snapshot_version();
-        if snapshot_version != 1u8 {
+        if snapshot_version != 1u8 && snapshot_version != 2u8 {
             return Err(anyhow!("Unexpected snapshot version: {}", snapshot_version));
         }
         if manifest.address_length() as usize > ObjectID::LENGTH {
@@ -89,35 +227,8 @@ impl StateSnapshotReaderV1 {
         if manifest.epoch() != epoch {
             return Err(anyhow!("Download manifest is not for epoch: {}", epoch,));
         }
-        let mut object_files = BTreeMap::new();
-        let mut ref_files = BTreeMap::new();
-        for file_metadata in manifest.file_metadata() {
-            match file_metadata.file_type {
-                FileType::Object => {
-                    let entry = object_files
-                        .entry(file_metadata.bucket_num)
-                        .or_insert_with(BTreeMap::new);
-                    entry.insert(file_metadata.part_num, file_metadata.clone());
-                }
-                FileType::Reference => {
-                    let entry = ref_files
-                        .entry(file_metadata.bucket_num)
-                        .or_insert_with(BTreeMap::new);
-                    entry.insert(file_metadata.part_num, file_metadata.clone());
-                }
-            }
-        }
-        let epoch_dir_path = Path::from(epoch_dir);
-        let files: Vec<Path> = ref_files
-            .values()
-            .flat_map(|entry| {
-                let files: Vec<_> = entry
-                    .values()
-                    .map(|file_metadata| file_metadata.file_path(&epoch_dir_path))
-                    .collect();
-                files
-            })
-            .collect();
+        let layer = SnapshotLayer::new(&manifest);
+        let files = layer.ref_file_paths();
         copy_files(
             &files,
             &files,
@@ -126,22 +237,147 @@ impl StateSnapshotReaderV1 {
             NonZeroUsize::new(1).unwrap(),
         )
         .await?;
-        Ok(StateSnapshotReaderV1 {
-            epoch,
-            local_staging_dir_root,
-            remote_object_store,
-            local_object_store,
-            ref_files,
-            object_files,
-            indirect_objects_threshold,
-            concurrency: download_concurrency.get(),
-        })
+        Ok(layer)
     }
 
+    /// Restores the live object set into `perpetual_db`, failing before any objects are downloaded
+    /// if the object references in the snapshot don't add up to `expected_root_state_digest`.
     pub async fn read(
         &mut self,
         perpetual_db: &AuthorityPerpetualTables,
         abort_registration: AbortRegistration,
+        expected_root_state_digest: &ECMHLiveObjectSetDigest,
+    ) -> Result<()> {
+        let root_state_digest: ECMHLiveObjectSetDigest = self.accumulate_refs()?.digest().into();
+        if root_state_digest != *expected_root_state_digest {
+            return Err(anyhow!(
+                "Root state digest of snapshot for epoch: {} doesn't match, expected: {:?}, actual: {:?}",
+                self.epoch,
+                expected_root_state_digest,
+                root_state_digest
+            ));
+        }
+        let indirect_objects_threshold = self.indirect_objects_threshold;
+        let download_concurrency = self.concurrency;
+        let remote_object_store = self.remote_object_store.clone();
+        let local_staging_dir_root = self.local_staging_dir_root.clone();
+        let layers = &self.layers;
+        Abortable::new(
+            async move {
+                for layer in layers {
+                    if layer.base_epoch.is_some() {
+                        Self::remove_objects(perpetual_db, layer, &local_staging_dir_root)?;
+                    }
+                    Self::insert_objects(
+                        perpetual_db,
+                        layer,
+                        &local_staging_dir_root,
+                        remote_object_store.clone(),
+                        indirect_objects_threshold,
+                        download_concurrency,
+                    )
+                    .await?;
+                }
+                Ok::<(), anyhow::Error>(())
+            },
+            abort_registration,
+        )
+        .await?
+    }
+
+    /// Computes the accumulator of the live object set at the epoch of the snapshot, from the
+    /// object references alone.
+    pub fn accumulate_refs(&self) -> Result<Accumulator> {
+        let mut acc = Accumulator::default();
+        for layer in &self.layers {
+            let epoch_dir = layer.epoch_dir();
+            for file_metadata in layer.removed_files.values().flat_map(|e| e.values()) {
+                let ref_iter = ObjectRefIter::new(
+                    file_metadata,
+                    self.local_staging_dir_root.clone(),
+                    epoch_dir.clone(),
+                )?;
+                for object_ref in ref_iter {
+                    remove_object_ref(&mut acc, &object_ref);
+                }
+            }
+            for file_metadata in layer.ref_files.values().flat_map(|e| e.values()) {
+                let ref_iter = ObjectRefIter::new(
+                    file_metadata,
+                    self.local_staging_dir_root.clone(),
+                    epoch_dir.clone(),
+                )?;
+                for object_ref in ref_iter {
+                    accumulate_object_ref(&mut acc, &object_ref);
+                }
+            }
+        }
+        Ok(acc)
+    }
+
+    /// Iterates over the references of the live objects in a bucket at the epoch of the snapshot,
+    /// in order of object id.
+    pub(crate) fn live_object_refs(&self, bucket_num: u32) -> Result<LiveObjectRefIter> {
+        let mut iter: LiveObjectRefIter = Box::new(std::iter::empty());
+        for layer in &self.layers {
+            let epoch_dir = layer.epoch_dir();
+            let removed = SnapshotLayer::bucket_iter(
+                &layer.removed_files,
+                bucket_num,
+                &self.local_staging_dir_root,
+                &epoch_dir,
+            )?;
+            let added = SnapshotLayer::bucket_iter(
+                &layer.ref_files,
+                bucket_num,
+                &self.local_staging_dir_root,
+                &epoch_dir,
+            )?;
+            iter = Box::new(ApplyDeltaIter {
+                base: iter.peekable(),
+                removed: removed.peekable(),
+                added: added.peekable(),
+            });
+        }
+        Ok(iter)
+    }
+
+    /// Removes the local copies of the files downloaded for the snapshot.
+    pub(crate) fn remove_local_files(&self) -> Result<()> {
+        for layer in &self.layers {
+            let local_epoch_dir_path =
+                path_to_filesystem(self.local_staging_dir_root.clone(), &layer.epoch_dir())?;
+            if local_epoch_dir_path.exists() {
+                fs::remove_dir_all(&local_epoch_dir_path)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn remove_objects(
+        perpetual_db: &AuthorityPerpetualTables,
+        layer: &SnapshotLayer,
+        local_staging_dir_root: &std::path::Path,
+    ) -> Result<()> {
+        let epoch_dir = layer.epoch_dir();
+        for file_metadata in layer.removed_files.values().flat_map(|e| e.values()) {
+            let ref_iter = ObjectRefIter::new(
+                file_metadata,
+                local_staging_dir_root.to_path_buf(),
+                epoch_dir.clone(),
+            )?;
+            AuthorityStore::bulk_remove_live_objects(perpetual_db, ref_iter)?;
+        }
+        Ok(())
+    }
+
+    async fn insert_objects(
+        perpetual_db: &AuthorityPerpetualTables,
+        layer: &SnapshotLayer,
+        local_staging_dir_root: &std::path::Path,
+        remote_object_store: Arc<DynObjectStore>,
+        indirect_objects_threshold: usize,
+        download_concurrency: usize,
     ) -> Result<()> {
         // This computes and stores the sha3 digest of object references in REFERENCE file for each
         // bucket partition. When downloading objects, we will match sha3 digest of object references
@@ -150,14 +386,20 @@ impl StateSnapshotReaderV1 {
         // doesn't match but we still need to ensure that objects match references exactly.
         let sha3_digests: Arc<Mutex<DigestByBucketAndPartition>> =
             Arc::new(Mutex::new(BTreeMap::new()));
+        let epoch_dir = layer.epoch_dir();
 
-        for (bucket, part_files) in self.ref_files.clone().iter() {
-            for (part, _part_file) in part_files.iter() {
+        for (bucket, part_files) in layer.ref_files.iter() {
+            for (part, part_file) in part_files.iter() {
                 let mut sha3_digests = sha3_digests.lock().await;
-                let ref_iter = self.ref_iter(*bucket, *part)?;
+                let ref_iter = ObjectRefIter::new(
+                    part_file,
+                    local_staging_dir_root.to_path_buf(),
+                    epoch_dir.clone(),
+                )?;
                 let mut hasher = Sha3_256::default();
                 let mut empty = true;
-                self.object_files
+                layer
+                    .object_files
                     .get(bucket)
                     .context(format!("No bucket exists for: {bucket}"))?
                     .get(part)
@@ -176,7 +418,7 @@ impl StateSnapshotReaderV1 {
             }
         }
 
-        let input_files: Vec<_> = self
+        let input_files: Vec<_> = layer
             .object_files
             .iter()
             .flat_map(|(bucket, parts)| {
@@ -184,75 +426,57 @@ impl StateSnapshotReaderV1 {
                 vec
             })
             .collect();
-        let epoch_dir = self.epoch_dir();
-        let remote_object_store = self.remote_object_store.clone();
-        let indirect_objects_threshold = self.indirect_objects_threshold;
-        let download_concurrency = self.concurrency;
-        Abortable::new(
-            async move {
-                futures::stream::iter(input_files.iter())
-                    .map(|(bucket, (part_num, file_metadata))| {
-                        let epoch_dir = epoch_dir.clone();
-                        let file_path = file_metadata.file_path(&epoch_dir);
-                        let remote_object_store = remote_object_store.clone();
-                        let sha3_digests_cloned = sha3_digests.clone();
-                        async move {
-                            let bytes = remote_object_store
-                                .get(&file_path)
-                                .await
-                                .map_err(|e| anyhow!("Failed to download file: {e}"))?
-                                .bytes()
-                                .await?;
-                            let sha3_digest = sha3_digests_cloned.lock().await;
-                            let bucket_map = sha3_digest.get(bucket).context("Missing bucket")?;
-                            let sha3_digest = bucket_map.get(part_num).context("Missing part")?;
-                            Ok::<(Bytes, FileMetadata, [u8; 32]), anyhow::Error>((
-                                bytes,
-                                (*file_metadata).clone(),
-                                *sha3_digest,
-                            ))
-                        }
-                    })
-                    .boxed()
-                    .buffer_unordered(download_concurrency)
-                    .try_for_each(|(bytes, file_metadata, sha3_digest)| {
-                        let result: Result<(), anyhow::Error> =
-                            LiveObjectIter::new(&file_metadata, bytes).and_then(|obj_iter| {
-                                AuthorityStore::bulk_insert_live_objects(
-                                    perpetual_db,
-                                    obj_iter,
-                                    indirect_objects_threshold,
-                                    &sha3_digest,
-                                )?;
-                                Ok::<(), anyhow::Error>(())
-                            });
-                        futures::future::ready(result)
-                    })
-                    .await
-            },
-            abort_registration,
-        )
-        .await?
+        futures::stream::iter(input_files.iter())
+            .map(|(bucket, (part_num, file_metadata))| {
+                let epoch_dir = epoch_dir.clone();
+                let file_path = file_metadata.file_path(&epoch_dir);
+                let remote_object_store = remote_object_store.clone();
+                let sha3_digests_cloned = sha3_digests.clone();
+                async move {
+                    let bytes = remote_object_store
+                        .get(&file_path)
+                        .await
+                        .map_err(|e| anyhow!("Failed to download file: {e}"))?
+                        .bytes()
+                        .await?;
+                    let sha3_digest = sha3_digests_cloned.lock().await;
+                    let bucket_map = sha3_digest.get(bucket).context("Missing bucket")?;
+                    let sha3_digest = bucket_map.get(part_num).context("Missing part")?;
+                    Ok::<(Bytes, FileMetadata, [u8; 32]), anyhow::Error>((
+                        bytes,
+                        (*file_metadata).clone(),
+                        *sha3_digest,
+                    ))
+                }
+            })
+            .boxed()
+            .buffer_unordered(download_concurrency)
+            .try_for_each(|(bytes, file_metadata, sha3_digest)| {
+                let result: Result<(), anyhow::Error> = LiveObjectIter::new(&file_metadata, bytes)
+                    .and_then(|obj_iter| {
+                        AuthorityStore::bulk_insert_live_objects(
+                            perpetual_db,
+                            obj_iter,
+                            indirect_objects_threshold,
+                            &sha3_digest,
+                        )?;
+                        Ok::<(), anyhow::Error>(())
+                    });
+                futures::future::ready(result)
+            })
+            .await
     }
 
-    pub fn ref_iter(&mut self, bucket_num: u32, part_num: u32) -> Result<ObjectRefIter> {
-        let file_metadata = self
-            .ref_files
-            .get(&bucket_num)
-            .context(format!("No ref files found for bucket: {bucket_num}"))?
-            .get(&part_num)
-            .context(format!(
-                "No ref files found for bucket: {bucket_num}, part: {part_num}"
-            ))?;
-        ObjectRefIter::new(
-            file_metadata,
-            self.local_staging_dir_root.clone(),
-            self.epoch_dir(),
-        )
+    pub(crate) fn epoch(&self) -> u64 {
+        self.epoch
     }
 
-    fn buckets(&self) -> Result<Vec<u32>> {
-        Ok(self.ref_files.keys().copied().collect())
+    pub(crate) fn buckets(&self) -> Result<Vec<u32>> {
+        let mut buckets = BTreeSet::new();
+        for layer in &self.layers {
+            buckets.extend(layer.ref_files.keys().chain(layer.removed_files.keys()));
+        }
+        Ok(buckets.into_iter().collect())
     }
 
     fn epoch_dir(&self) -> Path {
@@ -291,7 +515,50 @@ impl StateSnapshotReaderV1 {
     }
 }
 
-/// An iterator over all object refs in a .ref file.
+/// Applies the changes of a delta snapshot in one bucket to the references of the live objects in
+/// that bucket at its base epoch. All three iterators are in order of object id.
+struct ApplyDeltaIter {
+    base: Peekable<LiveObjectRefIter>,
+    removed: Peekable<Box<dyn Iterator<Item = ObjectRef>>>,
+    added: Peekable<Box<dyn Iterator<Item = ObjectRef>>>,
+}
+
+impl Iterator for ApplyDeltaIter {
+    type Item = Result<ObjectRef>;
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let base = match self.base.peek() {
+                Some(Ok(object_ref)) => Some(*object_ref),
+                Some(Err(_)) => return self.base.next(),
+                None => None,
+            };
+            if let Some(removed) = self.removed.peek().copied() {
+                match base {
+                    Some(base) if base == removed => {
+                        self.base.next();
+                        self.removed.next();
+                        continue;
+                    }
+                    Some(base) if base.0 < removed.0 => {}
+                    _ => {
+                        self.removed.next();
+                        return Some(Err(anyhow!(
+                            "Removed object is not live in base snapshot: {:?}",
+                            removed
+                        )));
+                    }
+                }
+            }
+            return match (base, self.added.peek()) {
+                (Some(base), Some(added)) if added.0 < base.0 => self.added.next().map(Ok),
+                (Some(_), _) => self.base.next(),
+                (None, _) => self.added.next().map(Ok),
+            };
+        }
+    }
+}
+
+/// An iterator over all object refs in a .ref or .rm file.
 pub struct ObjectRefIter {
     reader: Box<dyn Read>,
 }
@@ -301,7 +568,11 @@ impl ObjectRefIter {
         let file_path = file_metadata.local_file_path(&root_path, &dir_path)?;
         let mut reader = file_metadata.file_compression.decompress(&file_path)?;
         let magic = reader.read_u32::<BigEndian>()?;
-        if magic != REFERENCE_FILE_MAGIC {
+        let expected_magic = match file_metadata.file_type {
+            FileType::Removed => REMOVED_FILE_MAGIC,
+            _ => REFERENCE_FILE_MAGIC,
+        };
+        if magic != expected_magic {
             Err(anyhow!(
                 "Unexpected magic string in REFERENCE file: {:?}",
                 magic
@@ -3,7 +3,8 @@
 
 use crate::reader::StateSnapshotReaderV1;
 use crate::writer::StateSnapshotWriterV1;
-use crate::FileCompression;
+use crate::{accumulate_object_ref, FileCompression};
+use fastcrypto::hash::MultisetHash;
 use futures::future::AbortHandle;
 use std::collections::HashSet;
 use std::num::NonZeroUsize;
@@ -11,8 +12,11 @@ use std::sync::Arc;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
 use sui_protocol_config::ProtocolConfig;
 use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
-use sui_types::base_types::ObjectID;
+use sui_types::accumulator::Accumulator;
+use sui_types::base_types::{ObjectID, SequenceNumber, SuiAddress};
+use sui_types::messages_checkpoint::ECMHLiveObjectSetDigest;
 use sui_types::object::Object;
+use sui_types::storage::ObjectKey;
 use tempfile::tempdir;
 
 fn temp_dir() -> std::path::PathBuf {
@@ -50,6 +54,17 @@ fn compare_live_objects(
     Ok(())
 }
 
+fn root_state_digest(
+    db: &AuthorityPerpetualTables,
+    include_wrapped_tombstone: bool,
+) -> ECMHLiveObjectSetDigest {
+    let mut acc = Accumulator::default();
+    for live_object in db.iter_live_object_set(include_wrapped_tombstone) {
+        accumulate_object_ref(&mut acc, &live_object.object_reference());
+    }
+    acc.digest().into()
+}
+
 #[tokio::test]
 async fn test_snapshot_basic() -> Result<(), anyhow::Error> {
     let db_path = temp_dir();
@@ -78,7 +93,7 @@ async fn test_snapshot_basic() -> Result<(), anyhow::Error> {
     let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
     insert_keys(&perpetual_db, 1000)?;
     snapshot_writer
-        .write_internal(0, true, perpetual_db.clone())
+        .write_internal(0, None, true, perpetual_db.clone())
         .await?;
     let local_store_restore_config = ObjectStoreConfig {
         object_store: Some(ObjectStoreType::File),
@@ -96,7 +111,11 @@ async fn test_snapshot_basic() -> Result<(), anyhow::Error> {
     let restored_perpetual_db = AuthorityPerpetualTables::open(&restored_db_path, None);
     let (_abort_handle, abort_registration) = AbortHandle::new_pair();
     snapshot_reader
-        .read(&restored_perpetual_db, abort_registration)
+        .read(
+            &restored_perpetual_db,
+            abort_registration,
+            &root_state_digest(&perpetual_db, true),
+        )
         .await?;
     compare_live_objects(&perpetual_db, &restored_perpetual_db, true)?;
     Ok(())
@@ -130,7 +149,7 @@ async fn test_snapshot_empty_db() -> Result<(), anyhow::Error> {
     .await?;
     let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
     snapshot_writer
-        .write_internal(0, true, perpetual_db.clone())
+        .write_internal(0, None, true, perpetual_db.clone())
         .await?;
     let local_store_restore_config = ObjectStoreConfig {
         object_store: Some(ObjectStoreType::File),
@@ -148,7 +167,11 @@ async fn test_snapshot_empty_db() -> Result<(), anyhow::Error> {
     let restored_perpetual_db = AuthorityPerpetualTables::open(&restored_db_path, None);
     let (_abort_handle, abort_registration) = AbortHandle::new_pair();
     snapshot_reader
-        .read(&restored_perpetual_db, abort_registration)
+        .read(
+            &restored_perpetual_db,
+            abort_registration,
+            &root_state_digest(&perpetual_db, include_wrapped_tombstone),
+        )
         .await?;
     compare_live_objects(
         &perpetual_db,
@@ -157,3 +180,125 @@ async fn test_snapshot_empty_db() -> Result<(), anyhow::Error> {
     )?;
     Ok(())
 }
+
+#[tokio::test]
+async fn test_snapshot_delta() -> Result<(), anyhow::Error> {
+    let db_path = temp_dir();
+    let local = temp_dir().join("local_dir");
+    let remote = temp_dir().join("remote_dir");
+    let local_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(local),
+        ..Default::default()
+    };
+    let remote_store_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(remote),
+        ..Default::default()
+    };
+    let perpetual_db = Arc::new(AuthorityPerpetualTables::open(&db_path, None));
+    let ids = ObjectID::in_range(ObjectID::ZERO, 1100)?;
+    let owner = SuiAddress::random_for_testing_only();
+
+    // Epoch 0 is a full snapshot
+    insert_keys(&perpetual_db, 1000)?;
+    StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?
+    .write_internal(0, None, true, perpetual_db.clone())
+    .await?;
+
+    // Epoch 1 mutates, deletes and creates objects
+    for id in &ids[0..100] {
+        perpetual_db.insert_object_test_only(Object::with_id_owner_version_for_testing(
+            *id,
+            SequenceNumber::from_u64(10),
+            owner,
+        ))?;
+    }
+    for id in &ids[100..200] {
+        perpetual_db.insert_tombstone_test_only(ObjectKey(*id, SequenceNumber::from_u64(10)))?;
+    }
+    for id in &ids[1000..1050] {
+        perpetual_db.insert_object_test_only(Object::immutable_with_id_for_testing(*id))?;
+    }
+    StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?
+    .write_internal(1, Some(0), true, perpetual_db.clone())
+    .await?;
+
+    // Epoch 2 changes some of the objects that epoch 1 changed, on top of epoch 1
+    for id in &ids[0..50] {
+        perpetual_db.insert_tombstone_test_only(ObjectKey(*id, SequenceNumber::from_u64(11)))?;
+    }
+    for id in &ids[1000..1025] {
+        perpetual_db.insert_object_test_only(Object::with_id_owner_version_for_testing(
+            *id,
+            SequenceNumber::from_u64(11),
+            owner,
+        ))?;
+    }
+    for id in &ids[1050..1100] {
+        perpetual_db.insert_object_test_only(Object::immutable_with_id_for_testing(*id))?;
+    }
+    StateSnapshotWriterV1::new(
+        &local_store_config,
+        &remote_store_config,
+        FileCompression::Zstd,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?
+    .write_internal(2, Some(1), true, perpetual_db.clone())
+    .await?;
+
+    let local_store_restore_config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(temp_dir().join("local_dir_restore")),
+        ..Default::default()
+    };
+    let mut snapshot_reader = StateSnapshotReaderV1::new(
+        2,
+        &remote_store_config,
+        &local_store_restore_config,
+        usize::MAX,
+        NonZeroUsize::new(1).unwrap(),
+    )
+    .await?;
+    let expected_root_state_digest = root_state_digest(&perpetual_db, true);
+    assert_eq!(
+        ECMHLiveObjectSetDigest::from(snapshot_reader.accumulate_refs()?.digest()),
+        expected_root_state_digest
+    );
+
+    // A restore is refused up front if the snapshot doesn't match the epoch's root state
+    let restored_perpetual_db = AuthorityPerpetualTables::open(&temp_dir(), None);
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    assert!(snapshot_reader
+        .read(
+            &restored_perpetual_db,
+            abort_registration,
+            &ECMHLiveObjectSetDigest::default(),
+        )
+        .await
+        .is_err());
+
+    let (_abort_handle, abort_registration) = AbortHandle::new_pair();
+    snapshot_reader
+        .read(
+            &restored_perpetual_db,
+            abort_registration,
+            &expected_root_state_digest,
+        )
+        .await?;
+    compare_live_objects(&perpetual_db, &restored_perpetual_db, true)?;
+    Ok(())
+}
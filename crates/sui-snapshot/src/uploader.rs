@@ -55,6 +55,9 @@ pub struct StateSnapshotUploader {
     snapshot_store: Arc<DynObjectStore>,
     /// Time interval to check for presence of new db checkpoint
     interval: Duration,
+    /// Number of delta snapshots to write after each full snapshot, each holding only the changes
+    /// since the previous epoch. Zero means every snapshot is a full snapshot.
+    delta_snapshots_per_full_snapshot: u64,
     metrics: Arc<StateSnapshotUploaderMetrics>,
 }
 
@@ -64,6 +67,7 @@ impl StateSnapshotUploader {
         staging_path: &std::path::Path,
         snapshot_store_config: ObjectStoreConfig,
         interval_s: u64,
+        delta_snapshots_per_full_snapshot: u64,
         registry: &Registry,
     ) -> Result<Self> {
         let db_checkpoint_store_config = ObjectStoreConfig {
@@ -83,6 +87,7 @@ impl StateSnapshotUploader {
             staging_store: staging_store_config.make()?,
             snapshot_store: snapshot_store_config.make()?,
             interval: Duration::from_secs(interval_s),
+            delta_snapshots_per_full_snapshot,
             metrics: StateSnapshotUploaderMetrics::new(registry),
         })
     }
@@ -121,6 +126,7 @@ impl StateSnapshotUploader {
             find_all_dirs_with_epoch_prefix(&self.db_checkpoint_store, None).await?;
         let mut dirs: Vec<_> = local_checkpoints_by_epoch.iter().collect();
         dirs.sort_by_key(|(epoch_num, _path)| *epoch_num);
+        let mut uploaded_epochs = vec![];
         for (epoch, db_path) in dirs {
             if missing_epochs.contains(epoch) || *epoch >= last_missing_epoch {
                 let dir_path = path_to_filesystem(self.db_checkpoint_path.clone(), db_path)?;
@@ -129,7 +135,11 @@ impl StateSnapshotUploader {
                     info!("State snapshot creation for epoch: {} to wait until db checkpoint uploaded", *epoch);
                     continue;
                 }
-                info!("Starting state snapshot creation for epoch: {}", *epoch);
+                let base_epoch = self.base_epoch(*epoch, &missing_epochs, &uploaded_epochs);
+                info!(
+                    "Starting state snapshot creation for epoch: {}, base epoch: {:?}",
+                    *epoch, base_epoch
+                );
                 let state_snapshot_writer = StateSnapshotWriterV1::new_from_store(
                     &self.staging_path,
                     &self.staging_store,
@@ -142,7 +152,8 @@ impl StateSnapshotUploader {
                     &path_to_filesystem(self.db_checkpoint_path.clone(), &db_path.child("store"))?,
                     None,
                 ));
-                state_snapshot_writer.write(*epoch, db).await?;
+                state_snapshot_writer.write(*epoch, base_epoch, db).await?;
+                uploaded_epochs.push(*epoch);
                 info!("State snapshot creation successful for epoch: {}", *epoch);
                 // Drop marker in the output directory that upload completed successfully
                 let bytes = Bytes::from_static(b"success");
@@ -163,6 +174,29 @@ impl StateSnapshotUploader {
         Ok(())
     }
 
+    /// Picks the epoch that the snapshot for `epoch` should hold the changes since, or `None` if it
+    /// should be a full snapshot. Deltas are only ever taken against the previous epoch, and only if
+    /// its snapshot has been uploaded.
+    fn base_epoch(
+        &self,
+        epoch: u64,
+        missing_epochs: &[u64],
+        uploaded_epochs: &[u64],
+    ) -> Option<u64> {
+        if self.delta_snapshots_per_full_snapshot == 0
+            || epoch % (self.delta_snapshots_per_full_snapshot + 1) == 0
+        {
+            return None;
+        }
+        let prev_epoch = epoch - 1;
+        // The last missing epoch is the one after the latest epoch in the remote store
+        let last_missing_epoch = missing_epochs.last().cloned().unwrap_or(0);
+        let prev_epoch_uploaded = (prev_epoch < last_missing_epoch
+            && !missing_epochs.contains(&prev_epoch))
+            || uploaded_epochs.contains(&prev_epoch);
+        prev_epoch_uploaded.then_some(prev_epoch)
+    }
+
     async fn get_missing_epochs(&self) -> Result<Vec<u64>> {
         let missing_epochs = find_missing_epochs_dirs(&self.snapshot_store, SUCCESS_MARKER).await?;
         Ok(missing_epochs.to_vec())
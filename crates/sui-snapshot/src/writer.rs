@@ -2,10 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 #![allow(dead_code)]
 
+use crate::reader::{LiveObjectRefIter, StateSnapshotReaderV1};
 use crate::{
     compute_sha3_checksum, create_file_metadata, FileCompression, FileMetadata, FileType, Manifest,
-    ManifestV1, FILE_MAX_BYTES, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
-    OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, SEQUENCE_NUM_BYTES,
+    ManifestV1, ManifestV2, FILE_MAX_BYTES, MAGIC_BYTES, MANIFEST_FILE_MAGIC, OBJECT_FILE_MAGIC,
+    OBJECT_REF_BYTES, REFERENCE_FILE_MAGIC, REMOVED_FILE_MAGIC, SEQUENCE_NUM_BYTES,
 };
 use anyhow::{anyhow, Context, Result};
 use byteorder::{BigEndian, ByteOrder};
@@ -18,6 +19,7 @@ use std::collections::HashMap;
 use std::fs;
 use std::fs::{File, OpenOptions};
 use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::iter::Peekable;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -189,21 +191,121 @@ impl LiveObjectSetWriterV1 {
         Ok(())
     }
     fn write_object_ref(&mut self, object_ref: &ObjectRef) -> Result<()> {
-        let mut buf = [0u8; OBJECT_REF_BYTES];
-        buf[0..ObjectID::LENGTH].copy_from_slice(object_ref.0.as_ref());
-        BigEndian::write_u64(
-            &mut buf[ObjectID::LENGTH..OBJECT_REF_BYTES],
-            object_ref.1.value(),
-        );
-        buf[ObjectID::LENGTH + SEQUENCE_NUM_BYTES..OBJECT_REF_BYTES]
-            .copy_from_slice(object_ref.2.as_ref());
-        self.ref_wbuf.write_all(&buf)?;
+        self.ref_wbuf.write_all(&object_ref_bytes(object_ref))?;
+        Ok(())
+    }
+}
+
+/// RemovedObjectsWriterV1 writes the references of the objects a delta snapshot removes from its
+/// base to *.rm files
+struct RemovedObjectsWriterV1 {
+    dir_path: PathBuf,
+    bucket_num: u32,
+    current_part_num: u32,
+    wbuf: BufWriter<File>,
+    n: usize,
+    files: Vec<FileMetadata>,
+    sender: Option<Sender<FileMetadata>>,
+    file_compression: FileCompression,
+}
+
+impl RemovedObjectsWriterV1 {
+    fn new(
+        dir_path: PathBuf,
+        bucket_num: u32,
+        file_compression: FileCompression,
+        sender: Sender<FileMetadata>,
+    ) -> Result<Self> {
+        let part_num = 1;
+        let (n, f) = Self::removed_file(dir_path.clone(), bucket_num, part_num)?;
+        Ok(RemovedObjectsWriterV1 {
+            dir_path,
+            bucket_num,
+            current_part_num: part_num,
+            wbuf: BufWriter::new(f),
+            n,
+            files: vec![],
+            sender: Some(sender),
+            file_compression,
+        })
+    }
+    pub fn write(&mut self, object_ref: &ObjectRef) -> Result<()> {
+        if self.n + OBJECT_REF_BYTES > FILE_MAX_BYTES {
+            self.cut()?;
+        }
+        self.wbuf.write_all(&object_ref_bytes(object_ref))?;
+        self.n += OBJECT_REF_BYTES;
+        Ok(())
+    }
+    pub fn done(mut self) -> Result<Vec<FileMetadata>> {
+        self.finalize()?;
+        self.sender = None;
+        Ok(self.files.clone())
+    }
+    fn removed_file(dir_path: PathBuf, bucket_num: u32, part_num: u32) -> Result<(usize, File)> {
+        let removed_path = dir_path.join(format!("{bucket_num}_{part_num}.rm"));
+        let removed_tmp_path = dir_path.join(format!("{bucket_num}_{part_num}.rm.tmp"));
+        let mut f = File::create(removed_tmp_path.clone())?;
+        f.rewind()?;
+        let mut metab = [0u8; MAGIC_BYTES];
+        BigEndian::write_u32(&mut metab, REMOVED_FILE_MAGIC);
+        let n = f.write(&metab)?;
+        drop(f);
+        fs::rename(removed_tmp_path, removed_path.clone())?;
+        let mut f = OpenOptions::new().append(true).open(removed_path)?;
+        f.seek(SeekFrom::Start(n as u64))?;
+        Ok((n, f))
+    }
+    fn finalize(&mut self) -> Result<()> {
+        self.wbuf.flush()?;
+        self.wbuf.get_ref().sync_data()?;
+        let off = self.wbuf.get_ref().stream_position()?;
+        self.wbuf.get_ref().set_len(off)?;
+        let file_path = self
+            .dir_path
+            .join(format!("{}_{}.rm", self.bucket_num, self.current_part_num));
+        let file_metadata = create_file_metadata(
+            &file_path,
+            self.file_compression,
+            FileType::Removed,
+            self.bucket_num,
+            self.current_part_num,
+        )?;
+        self.files.push(file_metadata.clone());
+        if let Some(sender) = &self.sender {
+            sender.blocking_send(file_metadata)?;
+        }
+        Ok(())
+    }
+    fn cut(&mut self) -> Result<()> {
+        self.finalize()?;
+        self.current_part_num += 1;
+        let (n, f) = Self::removed_file(
+            self.dir_path.clone(),
+            self.bucket_num,
+            self.current_part_num,
+        )?;
+        self.n = n;
+        self.wbuf = BufWriter::new(f);
         Ok(())
     }
 }
 
+fn object_ref_bytes(object_ref: &ObjectRef) -> [u8; OBJECT_REF_BYTES] {
+    let mut buf = [0u8; OBJECT_REF_BYTES];
+    buf[0..ObjectID::LENGTH].copy_from_slice(object_ref.0.as_ref());
+    BigEndian::write_u64(
+        &mut buf[ObjectID::LENGTH..OBJECT_REF_BYTES],
+        object_ref.1.value(),
+    );
+    buf[ObjectID::LENGTH + SEQUENCE_NUM_BYTES..OBJECT_REF_BYTES]
+        .copy_from_slice(object_ref.2.as_ref());
+    buf
+}
+
 /// StateSnapshotWriterV1 writes snapshot files to a local staging dir and simultaneously uploads them
-/// to a remote object store
+/// to a remote object store. A snapshot either holds the whole live object set, or, given a base
+/// epoch that already has a snapshot in the remote object store, only the changes since then.
 pub struct StateSnapshotWriterV1 {
     local_staging_dir: PathBuf,
     file_compression: FileCompression,
@@ -254,6 +356,7 @@ impl StateSnapshotWriterV1 {
     pub async fn write(
         self,
         epoch: u64,
+        base_epoch: Option<u64>,
         perpetual_db: Arc<AuthorityPerpetualTables>,
     ) -> Result<()> {
         let system_state_object = get_sui_system_state(&perpetual_db)?;
@@ -267,16 +370,42 @@ impl StateSnapshotWriterV1 {
             chain_identifier.chain(),
         );
         let include_wrapped_tombstone = !protocol_config.simplified_unwrap_then_delete();
-        self.write_internal(epoch, include_wrapped_tombstone, perpetual_db)
+        self.write_internal(epoch, base_epoch, include_wrapped_tombstone, perpetual_db)
             .await
     }
 
     pub(crate) async fn write_internal(
         mut self,
         epoch: u64,
+        base_epoch: Option<u64>,
         include_wrapped_tombstone: bool,
         perpetual_db: Arc<AuthorityPerpetualTables>,
     ) -> Result<()> {
+        let base = match base_epoch {
+            Some(base_epoch) if base_epoch >= epoch => {
+                return Err(anyhow!(
+                    "Base epoch: {} of state snapshot is not before epoch: {}",
+                    base_epoch,
+                    epoch
+                ));
+            }
+            Some(base_epoch) => Some(
+                StateSnapshotReaderV1::new_from_store(
+                    base_epoch,
+                    &self.remote_object_store,
+                    &self.local_staging_store,
+                    &self.local_staging_dir,
+                    usize::MAX,
+                    NonZeroUsize::new(self.concurrency).unwrap(),
+                )
+                .await
+                .context(format!(
+                    "Failed to read base state snapshot for epoch: {}",
+                    &base_epoch
+                ))?,
+            ),
+            None => None,
+        };
         self.setup_epoch_dir(epoch).await?;
 
         let manifest_file_path = self.epoch_dir(epoch).child("MANIFEST");
@@ -287,13 +416,18 @@ impl StateSnapshotWriterV1 {
         let (sender, receiver) = mpsc::channel::<FileMetadata>(1000);
         let upload_handle = self.start_upload(epoch, receiver)?;
         let write_handler = tokio::task::spawn_blocking(move || {
-            self.write_live_object_set(
+            let result = self.write_live_object_set(
                 epoch,
+                base.as_ref(),
                 perpetual_db,
                 sender,
                 Self::bucket_func,
                 include_wrapped_tombstone,
-            )
+            );
+            if let Some(base) = base {
+                base.remove_local_files()?;
+            }
+            result
         });
         write_handler.await?.context(format!(
             "Failed to write state snapshot for epoch: {}",
@@ -357,19 +491,57 @@ impl StateSnapshotWriterV1 {
     fn write_live_object_set<F>(
         &mut self,
         epoch: u64,
+        base: Option<&StateSnapshotReaderV1>,
         perpetual_db: Arc<AuthorityPerpetualTables>,
         sender: Sender<FileMetadata>,
         bucket_func: F,
         include_wrapped_tombstone: bool,
     ) -> Result<()>
     where
-        F: Fn(&LiveObject) -> u32,
+        F: Fn(&ObjectID) -> u32,
     {
         let mut object_writers: HashMap<u32, LiveObjectSetWriterV1> = HashMap::new();
+        let mut removed_writers: HashMap<u32, RemovedObjectsWriterV1> = HashMap::new();
+        let mut base_refs: HashMap<u32, Peekable<LiveObjectRefIter>> = HashMap::new();
         let local_staging_dir_path =
             path_to_filesystem(self.local_staging_dir.clone(), &self.epoch_dir(epoch))?;
+        let file_compression = self.file_compression;
+        let mut remove = |bucket_num: u32, object_ref: &ObjectRef| -> Result<()> {
+            if let Vacant(entry) = removed_writers.entry(bucket_num) {
+                entry.insert(RemovedObjectsWriterV1::new(
+                    local_staging_dir_path.clone(),
+                    bucket_num,
+                    file_compression,
+                    sender.clone(),
+                )?);
+            }
+            removed_writers
+                .get_mut(&bucket_num)
+                .context("Unexpected missing bucket writer")?
+                .write(object_ref)
+        };
         for object in perpetual_db.iter_live_object_set(include_wrapped_tombstone) {
-            let bucket_num = bucket_func(&object);
+            let object_ref = object.object_reference();
+            let bucket_num = bucket_func(&object_ref.0);
+            if let Some(base) = base {
+                if let Vacant(entry) = base_refs.entry(bucket_num) {
+                    entry.insert(base.live_object_refs(bucket_num)?.peekable());
+                }
+                let base_refs = base_refs
+                    .get_mut(&bucket_num)
+                    .context("Unexpected missing base bucket")?;
+                // Objects in the base that sort before this one have been deleted since
+                while let Some(base_ref) = next_base_ref(base_refs, |id| id < object_ref.0) {
+                    remove(bucket_num, &base_ref?)?;
+                }
+                if let Some(base_ref) = next_base_ref(base_refs, |id| id == object_ref.0) {
+                    let base_ref = base_ref?;
+                    if base_ref == object_ref {
+                        continue;
+                    }
+                    remove(bucket_num, &base_ref)?;
+                }
+            }
             if let Vacant(entry) = object_writers.entry(bucket_num) {
                 entry.insert(LiveObjectSetWriterV1::new(
                     local_staging_dir_path.clone(),
@@ -383,23 +555,55 @@ impl StateSnapshotWriterV1 {
                 .context("Unexpected missing bucket writer")?;
             writer.write(&object)?;
         }
+        // Whatever is left in the base has been deleted since, including the whole of any bucket
+        // that no longer has any objects in it
+        if let Some(base) = base {
+            for bucket_num in base.buckets()? {
+                if let Vacant(entry) = base_refs.entry(bucket_num) {
+                    entry.insert(base.live_object_refs(bucket_num)?.peekable());
+                }
+            }
+        }
+        for (bucket_num, base_refs) in base_refs.into_iter() {
+            for base_ref in base_refs {
+                remove(bucket_num, &base_ref?)?;
+            }
+        }
         let mut files = vec![];
         for (_, writer) in object_writers.into_iter() {
             files.extend(writer.done()?);
         }
-        self.write_manifest(epoch, files)?;
+        for (_, writer) in removed_writers.into_iter() {
+            files.extend(writer.done()?);
+        }
+        self.write_manifest(epoch, base.map(|base| base.epoch()), files)?;
         Ok(())
     }
 
-    fn write_manifest(&mut self, epoch: u64, file_metadata: Vec<FileMetadata>) -> Result<()> {
+    fn write_manifest(
+        &mut self,
+        epoch: u64,
+        base_epoch: Option<u64>,
+        file_metadata: Vec<FileMetadata>,
+    ) -> Result<()> {
         let (f, manifest_file_path) = self.manifest_file(epoch)?;
         let mut wbuf = BufWriter::new(f);
-        let manifest: Manifest = Manifest::V1(ManifestV1 {
-            snapshot_version: 1,
-            address_length: ObjectID::LENGTH as u64,
-            file_metadata,
-            epoch,
-        });
+        // Full snapshots are still written with a V1 manifest, so older readers can restore them
+        let manifest: Manifest = match base_epoch {
+            Some(base_epoch) => Manifest::V2(ManifestV2 {
+                snapshot_version: 2,
+                address_length: ObjectID::LENGTH as u64,
+                file_metadata,
+                epoch,
+                base_epoch: Some(base_epoch),
+            }),
+            None => Manifest::V1(ManifestV1 {
+                snapshot_version: 1,
+                address_length: ObjectID::LENGTH as u64,
+                file_metadata,
+                epoch,
+            }),
+        };
         let serialized_manifest = bcs::to_bytes(&manifest)?;
         wbuf.write_all(&serialized_manifest)?;
         wbuf.flush()?;
@@ -436,7 +640,7 @@ impl StateSnapshotWriterV1 {
         Ok((f, manifest_file_path))
     }
 
-    fn bucket_func(_object: &LiveObject) -> u32 {
+    fn bucket_func(_object_id: &ObjectID) -> u32 {
         // TODO: Use the hash bucketing function used for accumulator tree if there is one
         1u32
     }
@@ -475,3 +679,15 @@ impl StateSnapshotWriterV1 {
         Ok(())
     }
 }
+
+/// Takes the next object reference from a bucket of the base snapshot if its object id satisfies
+/// `pred`, or if reading it failed.
+fn next_base_ref(
+    base_refs: &mut Peekable<LiveObjectRefIter>,
+    pred: impl Fn(ObjectID) -> bool,
+) -> Option<Result<ObjectRef>> {
+    base_refs.next_if(|base_ref| match base_ref {
+        Ok(base_ref) => pred(base_ref.0),
+        Err(_) => true,
+    })
+}
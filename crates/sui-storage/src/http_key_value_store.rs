@@ -95,7 +95,7 @@ enum Value {
     TxToCheckpoint(CheckpointSequenceNumber),
 }
 
-fn key_to_path_elements(key: &Key) -> SuiResult<(String, &'static str)> {
+pub fn key_to_path_elements(key: &Key) -> SuiResult<(String, &'static str)> {
     match key {
         Key::Tx(digest) => Ok((encode_digest(digest), "tx")),
         Key::Fx(digest) => Ok((encode_digest(digest), "fx")),
@@ -115,6 +115,20 @@ fn key_to_path_elements(key: &Key) -> SuiResult<(String, &'static str)> {
     }
 }
 
+/// A store of the raw, bcs serialized values behind each `Key`. Each backend of the key value
+/// store only needs to fetch these, `TransactionKeyValueStoreTrait` is implemented on top.
+#[async_trait]
+pub trait KeyValueFetcher: Send + Sync {
+    /// Returns `None` if there is no value for the key.
+    async fn fetch(&self, key: Key) -> SuiResult<Option<Bytes>>;
+
+    async fn multi_fetch(&self, keys: Vec<Key>) -> Vec<SuiResult<Option<Bytes>>> {
+        let num_keys = keys.len();
+        let fetches = stream::iter(keys.into_iter().map(|key| self.fetch(key)));
+        fetches.buffered(num_keys).collect::<Vec<_>>().await
+    }
+}
+
 impl HttpKVStore {
     pub fn new_kv(
         base_url: &str,
@@ -158,18 +172,10 @@ impl HttpKVStore {
             .into_sui_result()?;
         Uri::from_str(joined.as_str()).into_sui_result()
     }
+}
 
-    async fn multi_fetch(&self, uris: Vec<Key>) -> Vec<SuiResult<Option<Bytes>>> {
-        let uris_vec = uris.to_vec();
-        let fetches = stream::iter(
-            uris_vec
-                .into_iter()
-                .enumerate()
-                .map(|(_i, uri)| self.fetch(uri)),
-        );
-        fetches.buffered(uris.len()).collect::<Vec<_>>().await
-    }
-
+#[async_trait]
+impl KeyValueFetcher for HttpKVStore {
     async fn fetch(&self, key: Key) -> SuiResult<Option<Bytes>> {
         let uri = self.get_url(&key)?;
         trace!("fetching uri: {}", uri);
@@ -268,61 +274,7 @@ impl TransactionKeyValueStoreTrait for HttpKVStore {
         Vec<Option<TransactionEffects>>,
         Vec<Option<TransactionEvents>>,
     )> {
-        let num_txns = transactions.len();
-        let num_effects = effects.len();
-        let num_events = events.len();
-
-        let keys = transactions
-            .iter()
-            .map(|tx| Key::Tx(*tx))
-            .chain(effects.iter().map(|fx| Key::Fx(*fx)))
-            .chain(events.iter().map(|events| Key::Events(*events)))
-            .collect::<Vec<_>>();
-
-        let fetches = self.multi_fetch(keys).await;
-        let txn_slice = fetches[..num_txns].to_vec();
-        let fx_slice = fetches[num_txns..num_txns + num_effects].to_vec();
-        let events_slice = fetches[num_txns + num_effects..].to_vec();
-
-        let txn_results = txn_slice
-            .iter()
-            .take(num_txns)
-            .zip(transactions.iter())
-            .map(map_fetch)
-            .map(|maybe_bytes| {
-                maybe_bytes.and_then(|(bytes, digest)| {
-                    deser_check_digest(digest, bytes, |tx: &Transaction| *tx.digest())
-                })
-            })
-            .collect::<Vec<_>>();
-
-        let fx_results = fx_slice
-            .iter()
-            .take(num_effects)
-            .zip(effects.iter())
-            .map(map_fetch)
-            .map(|maybe_bytes| {
-                maybe_bytes.and_then(|(bytes, digest)| {
-                    deser_check_digest(digest, bytes, |fx: &TransactionEffects| {
-                        *fx.transaction_digest()
-                    })
-                })
-            })
-            .collect::<Vec<_>>();
-
-        let events_results = events_slice
-            .iter()
-            .take(num_events)
-            .zip(events.iter())
-            .map(map_fetch)
-            .map(|maybe_bytes| {
-                maybe_bytes.and_then(|(bytes, digest)| {
-                    deser_check_digest(digest, bytes, |events: &TransactionEvents| events.digest())
-                })
-            })
-            .collect::<Vec<_>>();
-
-        Ok((txn_results, fx_results, events_results))
+        multi_get(self, transactions, effects, events).await
     }
 
     #[instrument(level = "trace", skip_all)]
@@ -338,89 +290,14 @@ impl TransactionKeyValueStoreTrait for HttpKVStore {
         Vec<Option<CertifiedCheckpointSummary>>,
         Vec<Option<CheckpointContents>>,
     )> {
-        let keys = checkpoint_summaries
-            .iter()
-            .map(|cp| Key::CheckpointSummary(*cp))
-            .chain(
-                checkpoint_contents
-                    .iter()
-                    .map(|cp| Key::CheckpointContents(*cp)),
-            )
-            .chain(
-                checkpoint_summaries_by_digest
-                    .iter()
-                    .map(|cp| Key::CheckpointSummaryByDigest(*cp)),
-            )
-            .chain(
-                checkpoint_contents_by_digest
-                    .iter()
-                    .map(|cp| Key::CheckpointContentsByDigest(*cp)),
-            )
-            .collect::<Vec<_>>();
-
-        let summaries_len = checkpoint_summaries.len();
-        let contents_len = checkpoint_contents.len();
-        let summaries_by_digest_len = checkpoint_summaries_by_digest.len();
-        let contents_by_digest_len = checkpoint_contents_by_digest.len();
-
-        let fetches = self.multi_fetch(keys).await;
-
-        let input_slices = [
-            summaries_len,
-            contents_len,
-            summaries_by_digest_len,
-            contents_by_digest_len,
-        ];
-
-        let result_slices = multi_split_slice(&fetches, &input_slices);
-
-        let summaries_results = result_slices[0]
-            .iter()
-            .zip(checkpoint_summaries.iter())
-            .map(map_fetch)
-            .map(|maybe_bytes| {
-                maybe_bytes
-                    .and_then(|(bytes, seq)| deser::<_, CertifiedCheckpointSummary>(seq, bytes))
-            })
-            .collect::<Vec<_>>();
-
-        let contents_results = result_slices[1]
-            .iter()
-            .zip(checkpoint_contents.iter())
-            .map(map_fetch)
-            .map(|maybe_bytes| {
-                maybe_bytes.and_then(|(bytes, seq)| deser::<_, CheckpointContents>(seq, bytes))
-            })
-            .collect::<Vec<_>>();
-
-        let summaries_by_digest_results = result_slices[2]
-            .iter()
-            .zip(checkpoint_summaries_by_digest.iter())
-            .map(map_fetch)
-            .map(|maybe_bytes| {
-                maybe_bytes.and_then(|(bytes, digest)| {
-                    deser_check_digest(digest, bytes, |s: &CertifiedCheckpointSummary| *s.digest())
-                })
-            })
-            .collect::<Vec<_>>();
-
-        let contents_by_digest_results = result_slices[3]
-            .iter()
-            .zip(checkpoint_contents_by_digest.iter())
-            .map(map_fetch)
-            .map(|maybe_bytes| {
-                maybe_bytes.and_then(|(bytes, digest)| {
-                    deser_check_digest(digest, bytes, |c: &CheckpointContents| *c.digest())
-                })
-            })
-            .collect::<Vec<_>>();
-
-        Ok((
-            summaries_results,
-            contents_results,
-            summaries_by_digest_results,
-            contents_by_digest_results,
-        ))
+        multi_get_checkpoints(
+            self,
+            checkpoint_summaries,
+            checkpoint_contents,
+            checkpoint_summaries_by_digest,
+            checkpoint_contents_by_digest,
+        )
+        .await
     }
 
     #[instrument(level = "trace", skip_all)]
@@ -428,10 +305,7 @@ impl TransactionKeyValueStoreTrait for HttpKVStore {
         &self,
         digest: TransactionDigest,
     ) -> SuiResult<Option<CheckpointSequenceNumber>> {
-        let key = Key::TxToCheckpoint(digest);
-        self.fetch(key).await.map(|maybe| {
-            maybe.and_then(|bytes| deser::<_, CheckpointSequenceNumber>(&key, bytes.as_ref()))
-        })
+        deprecated_get_transaction_checkpoint(self, digest).await
     }
 
     #[instrument(level = "trace", skip_all)]
@@ -440,10 +314,12 @@ impl TransactionKeyValueStoreTrait for HttpKVStore {
         object_id: ObjectID,
         version: SequenceNumber,
     ) -> SuiResult<Option<Object>> {
-        let key = Key::ObjectKey(object_id, version);
-        self.fetch(key)
-            .await
-            .map(|maybe| maybe.and_then(|bytes| deser::<_, Object>(&key, bytes.as_ref())))
+        get_object(self, object_id, version).await
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn multi_get_objects(&self, keys: &[ObjectKey]) -> SuiResult<Vec<Option<Object>>> {
+        multi_get_objects(self, keys).await
     }
 
     #[instrument(level = "trace", skip_all)]
@@ -451,23 +327,239 @@ impl TransactionKeyValueStoreTrait for HttpKVStore {
         &self,
         digests: &[TransactionDigest],
     ) -> SuiResult<Vec<Option<CheckpointSequenceNumber>>> {
-        let keys = digests
-            .iter()
-            .map(|digest| Key::TxToCheckpoint(*digest))
-            .collect::<Vec<_>>();
-
-        let fetches = self.multi_fetch(keys).await;
-
-        let results = fetches
-            .iter()
-            .zip(digests.iter())
-            .map(map_fetch)
-            .map(|maybe_bytes| {
-                maybe_bytes
-                    .and_then(|(bytes, key)| deser::<_, CheckpointSequenceNumber>(&key, bytes))
+        multi_get_transaction_checkpoint(self, digests).await
+    }
+}
+
+// The typed lookups of `TransactionKeyValueStoreTrait`, built on the raw values from any
+// `KeyValueFetcher`, so that every backend decodes and checks the values in the same way.
+
+pub(crate) async fn multi_get<F: KeyValueFetcher + ?Sized>(
+    store: &F,
+    transactions: &[TransactionDigest],
+    effects: &[TransactionDigest],
+    events: &[TransactionEventsDigest],
+) -> SuiResult<(
+    Vec<Option<Transaction>>,
+    Vec<Option<TransactionEffects>>,
+    Vec<Option<TransactionEvents>>,
+)> {
+    let num_txns = transactions.len();
+    let num_effects = effects.len();
+    let num_events = events.len();
+
+    let keys = transactions
+        .iter()
+        .map(|tx| Key::Tx(*tx))
+        .chain(effects.iter().map(|fx| Key::Fx(*fx)))
+        .chain(events.iter().map(|events| Key::Events(*events)))
+        .collect::<Vec<_>>();
+
+    let fetches = store.multi_fetch(keys).await;
+    let txn_slice = fetches[..num_txns].to_vec();
+    let fx_slice = fetches[num_txns..num_txns + num_effects].to_vec();
+    let events_slice = fetches[num_txns + num_effects..].to_vec();
+
+    let txn_results = txn_slice
+        .iter()
+        .take(num_txns)
+        .zip(transactions.iter())
+        .map(map_fetch)
+        .map(|maybe_bytes| {
+            maybe_bytes.and_then(|(bytes, digest)| {
+                deser_check_digest(digest, bytes, |tx: &Transaction| *tx.digest())
             })
-            .collect::<Vec<_>>();
+        })
+        .collect::<Vec<_>>();
 
-        Ok(results)
-    }
+    let fx_results = fx_slice
+        .iter()
+        .take(num_effects)
+        .zip(effects.iter())
+        .map(map_fetch)
+        .map(|maybe_bytes| {
+            maybe_bytes.and_then(|(bytes, digest)| {
+                deser_check_digest(digest, bytes, |fx: &TransactionEffects| {
+                    *fx.transaction_digest()
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let events_results = events_slice
+        .iter()
+        .take(num_events)
+        .zip(events.iter())
+        .map(map_fetch)
+        .map(|maybe_bytes| {
+            maybe_bytes.and_then(|(bytes, digest)| {
+                deser_check_digest(digest, bytes, |events: &TransactionEvents| events.digest())
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok((txn_results, fx_results, events_results))
+}
+
+pub(crate) async fn multi_get_checkpoints<F: KeyValueFetcher + ?Sized>(
+    store: &F,
+    checkpoint_summaries: &[CheckpointSequenceNumber],
+    checkpoint_contents: &[CheckpointSequenceNumber],
+    checkpoint_summaries_by_digest: &[CheckpointDigest],
+    checkpoint_contents_by_digest: &[CheckpointContentsDigest],
+) -> SuiResult<(
+    Vec<Option<CertifiedCheckpointSummary>>,
+    Vec<Option<CheckpointContents>>,
+    Vec<Option<CertifiedCheckpointSummary>>,
+    Vec<Option<CheckpointContents>>,
+)> {
+    let keys = checkpoint_summaries
+        .iter()
+        .map(|cp| Key::CheckpointSummary(*cp))
+        .chain(
+            checkpoint_contents
+                .iter()
+                .map(|cp| Key::CheckpointContents(*cp)),
+        )
+        .chain(
+            checkpoint_summaries_by_digest
+                .iter()
+                .map(|cp| Key::CheckpointSummaryByDigest(*cp)),
+        )
+        .chain(
+            checkpoint_contents_by_digest
+                .iter()
+                .map(|cp| Key::CheckpointContentsByDigest(*cp)),
+        )
+        .collect::<Vec<_>>();
+
+    let summaries_len = checkpoint_summaries.len();
+    let contents_len = checkpoint_contents.len();
+    let summaries_by_digest_len = checkpoint_summaries_by_digest.len();
+    let contents_by_digest_len = checkpoint_contents_by_digest.len();
+
+    let fetches = store.multi_fetch(keys).await;
+
+    let input_slices = [
+        summaries_len,
+        contents_len,
+        summaries_by_digest_len,
+        contents_by_digest_len,
+    ];
+
+    let result_slices = multi_split_slice(&fetches, &input_slices);
+
+    let summaries_results = result_slices[0]
+        .iter()
+        .zip(checkpoint_summaries.iter())
+        .map(map_fetch)
+        .map(|maybe_bytes| {
+            maybe_bytes.and_then(|(bytes, seq)| deser::<_, CertifiedCheckpointSummary>(seq, bytes))
+        })
+        .collect::<Vec<_>>();
+
+    let contents_results = result_slices[1]
+        .iter()
+        .zip(checkpoint_contents.iter())
+        .map(map_fetch)
+        .map(|maybe_bytes| {
+            maybe_bytes.and_then(|(bytes, seq)| deser::<_, CheckpointContents>(seq, bytes))
+        })
+        .collect::<Vec<_>>();
+
+    let summaries_by_digest_results = result_slices[2]
+        .iter()
+        .zip(checkpoint_summaries_by_digest.iter())
+        .map(map_fetch)
+        .map(|maybe_bytes| {
+            maybe_bytes.and_then(|(bytes, digest)| {
+                deser_check_digest(digest, bytes, |s: &CertifiedCheckpointSummary| *s.digest())
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let contents_by_digest_results = result_slices[3]
+        .iter()
+        .zip(checkpoint_contents_by_digest.iter())
+        .map(map_fetch)
+        .map(|maybe_bytes| {
+            maybe_bytes.and_then(|(bytes, digest)| {
+                deser_check_digest(digest, bytes, |c: &CheckpointContents| *c.digest())
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok((
+        summaries_results,
+        contents_results,
+        summaries_by_digest_results,
+        contents_by_digest_results,
+    ))
+}
+
+pub(crate) async fn deprecated_get_transaction_checkpoint<F: KeyValueFetcher + ?Sized>(
+    store: &F,
+    digest: TransactionDigest,
+) -> SuiResult<Option<CheckpointSequenceNumber>> {
+    let key = Key::TxToCheckpoint(digest);
+    store.fetch(key).await.map(|maybe| {
+        maybe.and_then(|bytes| deser::<_, CheckpointSequenceNumber>(&key, bytes.as_ref()))
+    })
+}
+
+pub(crate) async fn get_object<F: KeyValueFetcher + ?Sized>(
+    store: &F,
+    object_id: ObjectID,
+    version: SequenceNumber,
+) -> SuiResult<Option<Object>> {
+    let key = Key::ObjectKey(object_id, version);
+    store
+        .fetch(key)
+        .await
+        .map(|maybe| maybe.and_then(|bytes| deser::<_, Object>(&key, bytes.as_ref())))
+}
+
+pub(crate) async fn multi_get_objects<F: KeyValueFetcher + ?Sized>(
+    store: &F,
+    keys: &[ObjectKey],
+) -> SuiResult<Vec<Option<Object>>> {
+    let fetches = store
+        .multi_fetch(
+            keys.iter()
+                .map(|ObjectKey(object_id, version)| Key::ObjectKey(*object_id, *version))
+                .collect(),
+        )
+        .await;
+
+    let results = fetches
+        .iter()
+        .zip(keys.iter())
+        .map(map_fetch)
+        .map(|maybe_bytes| maybe_bytes.and_then(|(bytes, key)| deser::<_, Object>(key, bytes)))
+        .collect::<Vec<_>>();
+
+    Ok(results)
+}
+
+pub(crate) async fn multi_get_transaction_checkpoint<F: KeyValueFetcher + ?Sized>(
+    store: &F,
+    digests: &[TransactionDigest],
+) -> SuiResult<Vec<Option<CheckpointSequenceNumber>>> {
+    let keys = digests
+        .iter()
+        .map(|digest| Key::TxToCheckpoint(*digest))
+        .collect::<Vec<_>>();
+
+    let fetches = store.multi_fetch(keys).await;
+
+    let results = fetches
+        .iter()
+        .zip(digests.iter())
+        .map(map_fetch)
+        .map(|maybe_bytes| {
+            maybe_bytes.and_then(|(bytes, key)| deser::<_, CheckpointSequenceNumber>(&key, bytes))
+        })
+        .collect::<Vec<_>>();
+
+    Ok(results)
 }
@@ -18,6 +18,7 @@ use sui_types::messages_checkpoint::{
     CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber,
 };
 use sui_types::object::Object;
+use sui_types::storage::ObjectKey;
 use sui_types::transaction::Transaction;
 use tracing::instrument;
 
@@ -406,6 +407,10 @@ impl TransactionKeyValueStore {
         self.inner.get_object(object_id, version).await
     }
 
+    pub async fn multi_get_objects(&self, keys: &[ObjectKey]) -> SuiResult<Vec<Option<Object>>> {
+        self.inner.multi_get_objects(keys).await
+    }
+
     pub async fn multi_get_transaction_checkpoint(
         &self,
         digests: &[TransactionDigest],
@@ -446,6 +451,16 @@ pub trait TransactionKeyValueStoreTrait {
         version: SequenceNumber,
     ) -> SuiResult<Option<Object>>;
 
+    /// Fetches the object at each of `keys`. Stores that can fetch several objects in one round
+    /// trip should override this, as it fetches them one at a time by default.
+    async fn multi_get_objects(&self, keys: &[ObjectKey]) -> SuiResult<Vec<Option<Object>>> {
+        let mut objects = Vec::with_capacity(keys.len());
+        for ObjectKey(object_id, version) in keys {
+            objects.push(self.get_object(*object_id, *version).await?);
+        }
+        Ok(objects)
+    }
+
     async fn multi_get_transaction_checkpoint(
         &self,
         digests: &[TransactionDigest],
@@ -601,6 +616,23 @@ impl TransactionKeyValueStoreTrait for FallbackTransactionKVStore {
         Ok(res)
     }
 
+    #[instrument(level = "trace", skip_all)]
+    async fn multi_get_objects(&self, keys: &[ObjectKey]) -> SuiResult<Vec<Option<Object>>> {
+        let mut res = self.primary.multi_get_objects(keys).await?;
+
+        let (fallback, indices) = find_fallback(&res, keys);
+
+        if fallback.is_empty() {
+            return Ok(res);
+        }
+
+        let secondary_res = self.fallback.multi_get_objects(&fallback).await?;
+
+        merge_res(&mut res, secondary_res, &indices);
+
+        Ok(res)
+    }
+
     #[instrument(level = "trace", skip_all)]
     async fn multi_get_transaction_checkpoint(
         &self,
@@ -27,6 +27,7 @@ pub mod key_value_store;
 pub mod key_value_store_metrics;
 pub mod mutex_table;
 pub mod object_store;
+pub mod object_store_key_value_store;
 pub mod package_object_cache;
 pub mod sharded_lru;
 pub mod write_path_pending_tx_log;
@@ -0,0 +1,156 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use object_store::path::Path;
+use object_store::DynObjectStore;
+use std::sync::Arc;
+use sui_types::base_types::{ObjectID, SequenceNumber};
+use sui_types::object::Object;
+use sui_types::storage::ObjectKey;
+use sui_types::{
+    digests::{
+        CheckpointContentsDigest, CheckpointDigest, TransactionDigest, TransactionEventsDigest,
+    },
+    effects::{TransactionEffects, TransactionEvents},
+    error::{SuiError, SuiResult},
+    messages_checkpoint::{
+        CertifiedCheckpointSummary, CheckpointContents, CheckpointSequenceNumber,
+    },
+    transaction::Transaction,
+};
+use tracing::{info, instrument, trace};
+
+use crate::http_key_value_store::{
+    deprecated_get_transaction_checkpoint, get_object, key_to_path_elements, multi_get,
+    multi_get_checkpoints, multi_get_objects, multi_get_transaction_checkpoint, Key,
+    KeyValueFetcher,
+};
+use crate::key_value_store::{TransactionKeyValueStore, TransactionKeyValueStoreTrait};
+use crate::key_value_store_metrics::KeyValueStoreMetrics;
+use crate::object_store::ObjectStoreConfig;
+
+/// The path a value is stored at in an object store backed key value store, given the item type
+/// and the encoded key that would make up its url in an `HttpKVStore`.
+pub fn object_store_kv_path(item_type: &str, encoded_key: &str) -> Path {
+    Path::from(item_type).child(encoded_key)
+}
+
+/// A key value store kept in an object store, such as a local or network file system, or a self
+/// hosted S3 compatible store, rather than behind an http endpoint. Values are laid out as
+/// `{item_type}/{encoded_key}`, with the same item types and key encodings as `HttpKVStore`.
+pub struct ObjectStoreKVStore {
+    store: Arc<DynObjectStore>,
+}
+
+impl ObjectStoreKVStore {
+    pub fn new_kv(
+        config: &ObjectStoreConfig,
+        metrics: Arc<KeyValueStoreMetrics>,
+    ) -> SuiResult<TransactionKeyValueStore> {
+        let inner = Arc::new(Self::new(config)?);
+        Ok(TransactionKeyValueStore::new(
+            "object_store",
+            metrics,
+            inner,
+        ))
+    }
+
+    pub fn new(config: &ObjectStoreConfig) -> SuiResult<Self> {
+        info!("creating ObjectStoreKVStore");
+        let store = config
+            .make()
+            .map_err(|e| SuiError::GenericStorageError(e.to_string()))?;
+        Ok(Self { store })
+    }
+}
+
+#[async_trait]
+impl KeyValueFetcher for ObjectStoreKVStore {
+    async fn fetch(&self, key: Key) -> SuiResult<Option<Bytes>> {
+        let (encoded_key, item_type) = key_to_path_elements(&key)?;
+        let path = object_store_kv_path(item_type, &encoded_key);
+        trace!("fetching path: {}", path);
+        let result = match self.store.get(&path).await {
+            Ok(result) => result,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(SuiError::GenericStorageError(e.to_string())),
+        };
+        result
+            .bytes()
+            .await
+            .map(Some)
+            .map_err(|e| SuiError::GenericStorageError(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl TransactionKeyValueStoreTrait for ObjectStoreKVStore {
+    #[instrument(level = "trace", skip_all)]
+    async fn multi_get(
+        &self,
+        transactions: &[TransactionDigest],
+        effects: &[TransactionDigest],
+        events: &[TransactionEventsDigest],
+    ) -> SuiResult<(
+        Vec<Option<Transaction>>,
+        Vec<Option<TransactionEffects>>,
+        Vec<Option<TransactionEvents>>,
+    )> {
+        multi_get(self, transactions, effects, events).await
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn multi_get_checkpoints(
+        &self,
+        checkpoint_summaries: &[CheckpointSequenceNumber],
+        checkpoint_contents: &[CheckpointSequenceNumber],
+        checkpoint_summaries_by_digest: &[CheckpointDigest],
+        checkpoint_contents_by_digest: &[CheckpointContentsDigest],
+    ) -> SuiResult<(
+        Vec<Option<CertifiedCheckpointSummary>>,
+        Vec<Option<CheckpointContents>>,
+        Vec<Option<CertifiedCheckpointSummary>>,
+        Vec<Option<CheckpointContents>>,
+    )> {
+        multi_get_checkpoints(
+            self,
+            checkpoint_summaries,
+            checkpoint_contents,
+            checkpoint_summaries_by_digest,
+            checkpoint_contents_by_digest,
+        )
+        .await
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn deprecated_get_transaction_checkpoint(
+        &self,
+        digest: TransactionDigest,
+    ) -> SuiResult<Option<CheckpointSequenceNumber>> {
+        deprecated_get_transaction_checkpoint(self, digest).await
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn get_object(
+        &self,
+        object_id: ObjectID,
+        version: SequenceNumber,
+    ) -> SuiResult<Option<Object>> {
+        get_object(self, object_id, version).await
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn multi_get_objects(&self, keys: &[ObjectKey]) -> SuiResult<Vec<Option<Object>>> {
+        multi_get_objects(self, keys).await
+    }
+
+    #[instrument(level = "trace", skip_all)]
+    async fn multi_get_transaction_checkpoint(
+        &self,
+        digests: &[TransactionDigest],
+    ) -> SuiResult<Vec<Option<CheckpointSequenceNumber>>> {
+        multi_get_transaction_checkpoint(self, digests).await
+    }
+}
@@ -22,8 +22,11 @@ use sui_types::messages_checkpoint::{
 };
 use sui_types::transaction::Transaction;
 
+use sui_storage::http_key_value_store::encode_digest;
 use sui_storage::key_value_store::*;
 use sui_storage::key_value_store_metrics::KeyValueStoreMetrics;
+use sui_storage::object_store::{ObjectStoreConfig, ObjectStoreType};
+use sui_storage::object_store_key_value_store::{object_store_kv_path, ObjectStoreKVStore};
 use sui_types::object::Object;
 use sui_types::storage::ObjectKey;
 
@@ -421,6 +424,51 @@ async fn test_get_tx_from_fallback() {
     );
 }
 
+#[tokio::test]
+async fn test_object_store_kv_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = ObjectStoreConfig {
+        object_store: Some(ObjectStoreType::File),
+        directory: Some(dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let object_store = config.make().unwrap();
+
+    let tx = random_tx();
+    let fx = random_fx();
+    let events = random_events();
+    for (path, bytes) in [
+        (
+            object_store_kv_path("tx", &encode_digest(tx.digest())),
+            bcs::to_bytes(&tx).unwrap(),
+        ),
+        (
+            object_store_kv_path("fx", &encode_digest(fx.transaction_digest())),
+            bcs::to_bytes(&fx).unwrap(),
+        ),
+        (
+            object_store_kv_path("ev", &encode_digest(&events.digest())),
+            bcs::to_bytes(&events).unwrap(),
+        ),
+    ] {
+        object_store.put(&path, bytes.into()).await.unwrap();
+    }
+
+    let store = ObjectStoreKVStore::new(&config).unwrap();
+    let result = store
+        .multi_get(
+            &[*tx.digest(), *random_tx().digest()],
+            &[*fx.transaction_digest()],
+            &[events.digest()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        result,
+        (vec![Some(tx), None], vec![Some(fx)], vec![Some(events)])
+    );
+}
+
 #[cfg(msim)]
 mod simtests {
 
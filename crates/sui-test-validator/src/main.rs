@@ -55,6 +55,11 @@ struct Args {
     #[clap(long, default_value = "localhost")]
     pg_host: String,
 
+    /// URL of the Indexer DB, overriding `--pg-host` and `--pg-port`. Use `sqlite://<path>` to run
+    /// the indexer against an embedded SQLite database, without a Postgres server.
+    #[clap(long)]
+    indexer_db_url: Option<String>,
+
     /// The duration for epochs (defaults to one minute)
     #[clap(long, default_value = "60000")]
     epoch_duration_ms: u64,
@@ -81,6 +86,7 @@ async fn main() -> Result<()> {
         indexer_rpc_port,
         pg_port,
         pg_host,
+        indexer_db_url,
         epoch_duration_ms,
         faucet_port,
         with_indexer,
@@ -98,9 +104,10 @@ async fn main() -> Result<()> {
         env: Env::NewLocal,
         fullnode_address: Some(format!("127.0.0.1:{}", fullnode_rpc_port)),
         indexer_address: with_indexer.then_some(format!("127.0.0.1:{}", indexer_rpc_port)),
-        pg_address: with_indexer.then_some(format!(
-            "postgres://postgres@{pg_host}:{pg_port}/sui_indexer"
-        )),
+        pg_address: with_indexer.then(|| {
+            indexer_db_url
+                .unwrap_or_else(|| format!("postgres://postgres@{pg_host}:{pg_port}/sui_indexer"))
+        }),
         faucet_address: None,
         epoch_duration_ms,
         use_indexer_experimental_methods,
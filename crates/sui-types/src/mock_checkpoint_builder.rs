@@ -31,9 +31,16 @@ pub struct MockCheckpointBuilder {
 
 impl MockCheckpointBuilder {
     pub fn new(previous_checkpoint: VerifiedCheckpoint) -> Self {
-        let epoch_rolling_gas_cost_summary =
-            previous_checkpoint.epoch_rolling_gas_cost_summary.clone();
-        let epoch = previous_checkpoint.epoch;
+        // If the previous checkpoint was the last in its epoch, the next one starts a new epoch.
+        let (epoch, epoch_rolling_gas_cost_summary) =
+            if previous_checkpoint.end_of_epoch_data.is_some() {
+                (previous_checkpoint.epoch + 1, GasCostSummary::default())
+            } else {
+                (
+                    previous_checkpoint.epoch,
+                    previous_checkpoint.epoch_rolling_gas_cost_summary.clone(),
+                )
+            };
 
         Self {
             previous_checkpoint,
@@ -152,7 +159,9 @@ impl MockCheckpointBuilder {
         (checkpoint, contents, full_contents)
     }
 
-    fn create_certified_checkpoint(
+    /// Certifies `checkpoint` with a signature from every member of the committee provided by
+    /// `validator_keys`.
+    pub fn create_certified_checkpoint(
         validator_keys: &impl ValidatorKeypairProvider,
         checkpoint: CheckpointSummary,
     ) -> VerifiedCheckpoint {
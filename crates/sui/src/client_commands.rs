@@ -3,6 +3,7 @@
 
 use core::fmt;
 use std::{
+    collections::BTreeMap,
     fmt::{Debug, Display, Formatter, Write},
     path::PathBuf,
     sync::Arc,
@@ -31,11 +32,12 @@ use shared_crypto::intent::Intent;
 use sui_execution::verifier::VerifierOverrides;
 use sui_json::SuiJsonValue;
 use sui_json_rpc_types::{
-    DynamicFieldPage, SuiData, SuiObjectData, SuiObjectResponse, SuiObjectResponseQuery,
-    SuiParsedData, SuiRawData, SuiTransactionBlockEffectsAPI, SuiTransactionBlockResponse,
-    SuiTransactionBlockResponseOptions,
+    DryRunTransactionBlockResponse, DynamicFieldPage, SuiData, SuiObjectData, SuiObjectResponse,
+    SuiObjectResponseQuery, SuiParsedData, SuiRawData, SuiTransactionBlockEffectsAPI,
+    SuiTransactionBlockResponse, SuiTransactionBlockResponseOptions,
 };
 use sui_json_rpc_types::{SuiExecutionStatus, SuiObjectDataOptions};
+use sui_keys::key_identity::KeyIdentity;
 use sui_keys::keystore::AccountKeystore;
 use sui_move_build::{
     build_from_resolution_graph, check_invalid_dependencies, check_unpublished_dependencies,
@@ -69,6 +71,9 @@ use tabled::{
 };
 use tracing::info;
 
+use crate::client_ptb::PtbSpec;
+use crate::multisig_commands::{MultiSigCommand, MultiSigCommandResult};
+
 macro_rules! serialize_or_execute {
     ($tx_data:expr, $serialize_unsigned:expr, $serialize_signed:expr, $context:expr, $result_variant:ident) => {{
         assert!(
@@ -200,9 +205,9 @@ pub enum SuiClientCommands {
     /// Obtain all gas objects owned by the address.
     #[clap(name = "gas")]
     Gas {
-        /// Address owning the objects
+        /// Address (or its alias) owning the objects
         #[clap(name = "owner_address")]
-        address: Option<SuiAddress>,
+        address: Option<KeyIdentity>,
     },
 
     /// Merge two coin objects into one coin
@@ -243,6 +248,14 @@ pub enum SuiClientCommands {
         derivation_path: Option<DerivationPath>,
     },
 
+    /// Sign and execute a transaction from a MultiSig address offline, by collecting its
+    /// signers' partial signatures in a proposal file.
+    #[clap(name = "multisig")]
+    MultiSig {
+        #[clap(subcommand)]
+        cmd: MultiSigCommand,
+    },
+
     /// Add new Sui environment.
     #[clap(name = "new-env")]
     NewEnv {
@@ -268,10 +281,10 @@ pub enum SuiClientCommands {
     /// Obtain all objects owned by the address
     #[clap(name = "objects")]
     Objects {
-        /// Address owning the object. If no address is provided, it will show all
+        /// Address (or its alias) owning the object. If no address is provided, it will show all
         /// objects owned by `sui client active-address`.
         #[clap(name = "owner_address")]
-        address: Option<SuiAddress>,
+        address: Option<KeyIdentity>,
     },
     /// Pay coins to recipients following specified amounts, with input coins.
     /// Length of recipients must be the same as that of amounts.
@@ -281,9 +294,9 @@ pub enum SuiClientCommands {
         #[clap(long, num_args(1..))]
         input_coins: Vec<ObjectID>,
 
-        /// The recipient addresses, must be of same length as amounts
+        /// The recipient addresses (or their aliases), must be of same length as amounts
         #[clap(long, num_args(1..))]
-        recipients: Vec<SuiAddress>,
+        recipients: Vec<KeyIdentity>,
 
         /// The amounts to be paid, following the order of recipients.
         #[clap(long, num_args(1..))]
@@ -316,9 +329,9 @@ pub enum SuiClientCommands {
         #[clap(long, num_args(1..))]
         input_coins: Vec<ObjectID>,
 
-        /// The recipient address.
+        /// The recipient address (or its alias).
         #[clap(long)]
-        recipient: SuiAddress,
+        recipient: KeyIdentity,
 
         /// Gas budget for this transaction
         #[clap(long)]
@@ -343,9 +356,9 @@ pub enum SuiClientCommands {
         #[clap(long, num_args(1..))]
         input_coins: Vec<ObjectID>,
 
-        /// The recipient addresses, must be of same length as amounts.
+        /// The recipient addresses (or their aliases), must be of same length as amounts.
         #[clap(long, num_args(1..))]
-        recipients: Vec<SuiAddress>,
+        recipients: Vec<KeyIdentity>,
 
         /// The amounts to be paid, following the order of recipients.
         #[clap(long, num_args(1..))]
@@ -366,6 +379,41 @@ pub enum SuiClientCommands {
         serialize_signed_transaction: bool,
     },
 
+    /// Build a programmable transaction block of several commands, such as splitting coins,
+    /// calling Move functions and transferring their results, from a JSON file of its commands.
+    #[clap(name = "ptb")]
+    Ptb {
+        /// Path to the JSON file of the transaction's commands
+        file: PathBuf,
+
+        /// Build options for the packages the transaction publishes or upgrades
+        #[clap(flatten)]
+        build_config: MoveBuildConfig,
+
+        /// ID of the gas object for gas payment, in 20 bytes Hex string
+        /// If not provided, a gas object with at least gas_budget value will be selected
+        #[clap(long)]
+        gas: Option<ObjectID>,
+
+        /// Gas budget for this transaction
+        #[clap(long)]
+        gas_budget: u64,
+
+        /// Instead of executing the transaction, dry run it and print out its effects.
+        #[clap(long, required = false)]
+        dry_run: bool,
+
+        /// Instead of executing the transaction, serialize the bcs bytes of the unsigned transaction data
+        /// (TransactionData) using base64 encoding, and print out the string.
+        #[clap(long, required = false)]
+        serialize_unsigned_transaction: bool,
+
+        /// Instead of executing the transaction, serialize the bcs bytes of the signed transaction data
+        /// (SenderSignedData) using base64 encoding, and print out the string.
+        #[clap(long, required = false)]
+        serialize_signed_transaction: bool,
+    },
+
     /// Publish Move modules
     #[clap(name = "publish")]
     Publish {
@@ -444,10 +492,10 @@ pub enum SuiClientCommands {
     /// Switch active address and network(e.g., devnet, local rpc server)
     #[clap(name = "switch")]
     Switch {
-        /// An Sui address to be used as the active address for subsequent
+        /// An Sui address (or its alias) to be used as the active address for subsequent
         /// commands.
         #[clap(long)]
-        address: Option<SuiAddress>,
+        address: Option<KeyIdentity>,
         /// The RPC server URL (e.g., local rpc server, devnet rpc server, etc) to be
         /// used for subsequent commands.
         #[clap(long)]
@@ -465,9 +513,9 @@ pub enum SuiClientCommands {
     /// Transfer object
     #[clap(name = "transfer")]
     Transfer {
-        /// Recipient address
+        /// Recipient address (or its alias)
         #[clap(long)]
-        to: SuiAddress,
+        to: KeyIdentity,
 
         /// Object to transfer, in 20 bytes Hex string
         #[clap(long)]
@@ -498,9 +546,9 @@ pub enum SuiClientCommands {
     /// is transferred.
     #[clap(name = "transfer-sui")]
     TransferSui {
-        /// Recipient address
+        /// Recipient address (or its alias)
         #[clap(long)]
-        to: SuiAddress,
+        to: KeyIdentity,
 
         /// Sui coin object to transfer, ID in 20 bytes Hex string. This is also the gas object.
         #[clap(long)]
@@ -620,6 +668,11 @@ pub enum SuiClientCommands {
         /// The digest of the transaction to replay
         #[arg(long, short)]
         tx_digest: String,
+
+        /// Profile the gas used by the transaction, and write a per-function flamegraph of it to
+        /// this file, which can be viewed with speedscope (https://www.speedscope.app)
+        #[arg(long)]
+        profile_output: Option<PathBuf>,
     },
 
     /// Replay transactions listed in a file.
@@ -665,13 +718,18 @@ impl SuiClientCommands {
         context: &mut WalletContext,
     ) -> Result<SuiClientCommandResult, anyhow::Error> {
         let ret = Ok(match self {
-            SuiClientCommands::ReplayTransaction { rpc_url, tx_digest } => {
+            SuiClientCommands::ReplayTransaction {
+                rpc_url,
+                tx_digest,
+                profile_output,
+            } => {
                 let cmd = ReplayToolCommand::ReplayTransaction {
                     tx_digest,
                     show_effects: true,
                     diag: false,
                     executor_version_override: None,
                     protocol_version_override: None,
+                    profile_output,
                 };
                 // todo: automatically get non pruning fullnode url
                 let _command_result =
@@ -716,9 +774,17 @@ impl SuiClientCommands {
             SuiClientCommands::Addresses => {
                 let active_address = context.active_address()?;
                 let addresses = context.config.keystore.addresses();
+                let aliases = addresses
+                    .iter()
+                    .filter_map(|address| {
+                        let alias = context.config.keystore.get_alias_by_address(address)?;
+                        Some((*address, alias))
+                    })
+                    .collect();
                 SuiClientCommandResult::Addresses(AddressesOutput {
                     addresses,
                     active_address,
+                    aliases,
                 })
             }
             SuiClientCommands::DynamicFieldQuery { id, cursor, limit } => {
@@ -820,6 +886,60 @@ impl SuiClientCommands {
                     Upgrade
                 )
             }
+            SuiClientCommands::Ptb {
+                file,
+                build_config,
+                gas,
+                gas_budget,
+                dry_run,
+                serialize_unsigned_transaction,
+                serialize_signed_transaction,
+            } => {
+                ensure!(
+                    !dry_run || (!serialize_unsigned_transaction && !serialize_signed_transaction),
+                    "Cannot specify --dry-run with --serialize-unsigned-transaction or \
+                     --serialize-signed-transaction"
+                );
+                ensure!(
+                    !build_config.test_mode,
+                    "The `ptb` subcommand should not be used with the `--test` flag, as code in \
+                     published packages must not depend on test code"
+                );
+                let sender = context.try_get_object_owner(&gas).await?;
+                let sender = sender.unwrap_or(context.active_address()?);
+
+                let (pt, input_objects) =
+                    PtbSpec::read(&file)?.build(context, build_config).await?;
+                let gas = match gas {
+                    Some(gas) => context.get_object_ref(gas).await?,
+                    None => context
+                        .gas_for_owner_budget(sender, gas_budget, input_objects)
+                        .await?
+                        .1
+                        .object_ref(),
+                };
+                let gas_price = context.get_reference_gas_price().await?;
+                let data =
+                    TransactionData::new_programmable(sender, vec![gas], pt, gas_budget, gas_price);
+                if dry_run {
+                    let response = context
+                        .get_client()
+                        .await?
+                        .read_api()
+                        .dry_run_transaction_block(data)
+                        .await?;
+                    SuiClientCommandResult::DryRun(response)
+                } else {
+                    serialize_or_execute!(
+                        data,
+                        serialize_unsigned_transaction,
+                        serialize_signed_transaction,
+                        context,
+                        Ptb
+                    )
+                }
+            }
+
             SuiClientCommands::Publish {
                 package_path,
                 gas,
@@ -980,6 +1100,7 @@ impl SuiClientCommands {
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
             } => {
+                let to = to.resolve(&context.config.keystore)?;
                 let from = context.get_object_owner(&object_id).await?;
                 let client = context.get_client().await?;
                 let data = client
@@ -1003,6 +1124,7 @@ impl SuiClientCommands {
                 serialize_unsigned_transaction,
                 serialize_signed_transaction,
             } => {
+                let to = to.resolve(&context.config.keystore)?;
                 let from = context.get_object_owner(&object_id).await?;
 
                 let client = context.get_client().await?;
@@ -1044,6 +1166,7 @@ impl SuiClientCommands {
                         amounts.len()
                     ),
                 );
+                let recipients = resolve_key_identities(&recipients, context)?;
                 let from = context.get_object_owner(&input_coins[0]).await?;
                 let client = context.get_client().await?;
                 let data = client
@@ -1083,6 +1206,7 @@ impl SuiClientCommands {
                         amounts.len()
                     ),
                 );
+                let recipients = resolve_key_identities(&recipients, context)?;
                 let signer = context.get_object_owner(&input_coins[0]).await?;
                 let client = context.get_client().await?;
                 let data = client
@@ -1109,6 +1233,7 @@ impl SuiClientCommands {
                     !input_coins.is_empty(),
                     "PayAllSui transaction requires a non-empty list of input coins"
                 );
+                let recipient = recipient.resolve(&context.config.keystore)?;
                 let signer = context.get_object_owner(&input_coins[0]).await?;
                 let client = context.get_client().await?;
                 let data = client
@@ -1126,7 +1251,7 @@ impl SuiClientCommands {
             }
 
             SuiClientCommands::Objects { address } => {
-                let address = address.unwrap_or(context.active_address()?);
+                let address = resolve_key_identity_or_active(address, context)?;
                 let client = context.get_client().await?;
                 let mut objects: Vec<SuiObjectResponse> = Vec::new();
                 let mut cursor = None;
@@ -1171,7 +1296,7 @@ impl SuiClientCommands {
                 })
             }
             SuiClientCommands::Gas { address } => {
-                let address = address.unwrap_or(context.active_address()?);
+                let address = resolve_key_identity_or_active(address, context)?;
                 let coins = context
                     .gas_objects(address)
                     .await?
@@ -1252,6 +1377,9 @@ impl SuiClientCommands {
                 )
             }
             SuiClientCommands::Switch { address, env } => {
+                let address = address
+                    .map(|address| address.resolve(&context.config.keystore))
+                    .transpose()?;
                 match (address, &env) {
                     (None, Some(env)) => {
                         Self::switch_env(&mut context.config, env)?;
@@ -1300,6 +1428,9 @@ impl SuiClientCommands {
                 let response = context.execute_transaction_may_fail(transaction).await?;
                 SuiClientCommandResult::ExecuteSignedTx(response)
             }
+            SuiClientCommands::MultiSig { cmd } => {
+                SuiClientCommandResult::MultiSig(cmd.execute(context).await?)
+            }
             SuiClientCommands::NewEnv { alias, rpc, ws } => {
                 if context.config.envs.iter().any(|env| env.alias == alias) {
                     return Err(anyhow!(
@@ -1393,7 +1524,7 @@ fn compile_package_simple(
     )?)
 }
 
-async fn compile_package(
+pub(crate) async fn compile_package(
     client: &SuiClient,
     build_config: MoveBuildConfig,
     package_path: PathBuf,
@@ -1655,6 +1786,12 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::PayAllSui(response) => {
                 write!(writer, "{}", write_transaction_response(response)?)?;
             }
+            SuiClientCommandResult::Ptb(response) => {
+                write!(writer, "{}", write_transaction_response(response)?)?;
+            }
+            SuiClientCommandResult::DryRun(response) => {
+                write!(writer, "{}", write_dry_run_response(response)?)?;
+            }
             SuiClientCommandResult::SyncClientState => {
                 writeln!(writer, "Client state sync complete.")?;
             }
@@ -1682,6 +1819,9 @@ impl Display for SuiClientCommandResult {
             SuiClientCommandResult::ActiveEnv(env) => {
                 write!(writer, "{}", env.as_deref().unwrap_or("None"))?;
             }
+            SuiClientCommandResult::MultiSig(result) => {
+                write!(writer, "{}", result)?;
+            }
             SuiClientCommandResult::NewEnv(env) => {
                 writeln!(writer, "Added new Sui env [{}] to config.", env.alias)?;
             }
@@ -1776,6 +1916,27 @@ async fn construct_move_call_transaction(
         .await
 }
 
+/// Resolves `address` against the wallet's keystore, or falls back to the active address.
+fn resolve_key_identity_or_active(
+    address: Option<KeyIdentity>,
+    context: &mut WalletContext,
+) -> Result<SuiAddress, anyhow::Error> {
+    match address {
+        Some(address) => address.resolve(&context.config.keystore),
+        None => context.active_address(),
+    }
+}
+
+fn resolve_key_identities(
+    identities: &[KeyIdentity],
+    context: &WalletContext,
+) -> Result<Vec<SuiAddress>, anyhow::Error> {
+    identities
+        .iter()
+        .map(|identity| identity.resolve(&context.config.keystore))
+        .collect()
+}
+
 fn convert_number_to_string(value: Value) -> Value {
     match value {
         Value::Number(n) => Value::String(n.to_string()),
@@ -1823,6 +1984,27 @@ pub fn write_transaction_response(
     Ok(writer)
 }
 
+pub fn write_dry_run_response(
+    response: &DryRunTransactionBlockResponse,
+) -> Result<String, fmt::Error> {
+    let mut writer = String::new();
+    writeln!(writer, "{}", "----- Transaction Data ----".bold())?;
+    writeln!(writer, "{}", response.input)?;
+
+    writeln!(writer, "{}", "----- Transaction Effects ----".bold())?;
+    writeln!(writer, "{}", response.effects)?;
+
+    writeln!(writer, "{}", "----- Events ----".bold())?;
+    writeln!(writer, "{:#?}", json!(response.events))?;
+
+    writeln!(writer, "{}", "----- Object changes ----".bold())?;
+    writeln!(writer, "{:#?}", json!(response.object_changes))?;
+
+    writeln!(writer, "{}", "----- Balance changes ----".bold())?;
+    writeln!(writer, "{:#?}", json!(response.balance_changes))?;
+    Ok(writer)
+}
+
 impl Debug for SuiClientCommandResult {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let s = unwrap_err_to_string(|| match self {
@@ -1881,9 +2063,20 @@ impl SuiClientCommandResult {
     pub fn tx_block_response(&self) -> Option<&SuiTransactionBlockResponse> {
         use SuiClientCommandResult::*;
         match self {
-            Upgrade(b) | Publish(b) | TransactionBlock(b) | Call(b) | Transfer(b)
-            | TransferSui(b) | Pay(b) | PaySui(b) | PayAllSui(b) | SplitCoin(b) | MergeCoin(b)
-            | ExecuteSignedTx(b) => Some(b),
+            Upgrade(b)
+            | Publish(b)
+            | TransactionBlock(b)
+            | Call(b)
+            | Transfer(b)
+            | TransferSui(b)
+            | Pay(b)
+            | PaySui(b)
+            | PayAllSui(b)
+            | SplitCoin(b)
+            | MergeCoin(b)
+            | ExecuteSignedTx(b)
+            | Ptb(b)
+            | MultiSig(MultiSigCommandResult::Execute(b)) => Some(b),
             _ => None,
         }
     }
@@ -1894,6 +2087,8 @@ impl SuiClientCommandResult {
 pub struct AddressesOutput {
     pub active_address: SuiAddress,
     pub addresses: Vec<SuiAddress>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<SuiAddress, String>,
 }
 
 #[derive(Serialize)]
@@ -2021,11 +2216,13 @@ pub enum SuiClientCommandResult {
     Addresses(AddressesOutput),
     Call(SuiTransactionBlockResponse),
     ChainIdentifier(String),
+    DryRun(DryRunTransactionBlockResponse),
     DynamicFieldQuery(DynamicFieldPage),
     Envs(Vec<SuiEnv>, Option<String>),
     ExecuteSignedTx(SuiTransactionBlockResponse),
     Gas(Vec<GasCoin>),
     MergeCoin(SuiTransactionBlockResponse),
+    MultiSig(MultiSigCommandResult),
     NewAddress(NewAddressOutput),
     NewEnv(SuiEnv),
     Object(SuiObjectResponse),
@@ -2033,6 +2230,7 @@ pub enum SuiClientCommandResult {
     Pay(SuiTransactionBlockResponse),
     PayAllSui(SuiTransactionBlockResponse),
     PaySui(SuiTransactionBlockResponse),
+    Ptb(SuiTransactionBlockResponse),
     Publish(SuiTransactionBlockResponse),
     RawObject(SuiObjectResponse),
     SerializedSignedTransaction(SenderSignedData),
@@ -0,0 +1,441 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail};
+use move_core_types::ident_str;
+use move_core_types::identifier::Identifier;
+use move_core_types::u256::U256;
+use move_package::BuildConfig as MoveBuildConfig;
+use serde::{Deserialize, Serialize};
+use sui_json_rpc_types::{SuiData, SuiObjectData, SuiObjectDataOptions};
+use sui_keys::key_identity::KeyIdentity;
+use sui_move_build::PublishedAtError;
+use sui_sdk::wallet_context::WalletContext;
+use sui_sdk::SuiClient;
+use sui_types::base_types::{ObjectID, SuiAddress};
+use sui_types::move_package::{UpgradeCap, PACKAGE_MODULE_NAME};
+use sui_types::object::Owner;
+use sui_types::parse_sui_type_tag;
+use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use sui_types::transaction::{Argument, Command, ObjectArg, ProgrammableTransaction};
+use sui_types::SUI_FRAMEWORK_PACKAGE_ID;
+
+use crate::client_commands::compile_package;
+
+/// The commands of a programmable transaction block, as read from a JSON file by
+/// `sui client ptb`. For example, to split two coins off the gas coin, and send them both to
+/// another address:
+///
+/// ```json
+/// {
+///   "commands": [
+///     { "SplitCoins": { "coin": "gas", "amounts": [{ "u64": 1000 }, { "u64": 2000 }] } },
+///     {
+///       "TransferObjects": {
+///         "objects": [{ "nested_result": [0, 0] }, { "nested_result": [0, 1] }],
+///         "address": { "address": "0x..." }
+///       }
+///     }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PtbSpec {
+    pub commands: Vec<PtbCommand>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum PtbCommand {
+    SplitCoins {
+        coin: PtbArgument,
+        amounts: Vec<PtbArgument>,
+    },
+    MergeCoins {
+        coin: PtbArgument,
+        coins: Vec<PtbArgument>,
+    },
+    TransferObjects {
+        objects: Vec<PtbArgument>,
+        address: PtbArgument,
+    },
+    MoveCall {
+        package: ObjectID,
+        module: String,
+        function: String,
+        /// Type arguments, such as `0x2::sui::SUI`.
+        #[serde(default)]
+        type_arguments: Vec<String>,
+        #[serde(default)]
+        arguments: Vec<PtbArgument>,
+    },
+    MakeMoveVec {
+        /// Type of the vector's elements, which must be given if they are pure values.
+        #[serde(default, rename = "type")]
+        type_: Option<String>,
+        elements: Vec<PtbArgument>,
+    },
+    /// Publishes the Move package at `package_path`. Its result is the package's `UpgradeCap`,
+    /// which a later command must use, for example by transferring it.
+    Publish {
+        package_path: PathBuf,
+        #[serde(default)]
+        with_unpublished_dependencies: bool,
+        #[serde(default)]
+        skip_dependency_verification: bool,
+    },
+    /// Upgrades the package published at the `published-at` address of the Move package at
+    /// `package_path`, keeping the upgrade capability's existing policy.
+    Upgrade {
+        package_path: PathBuf,
+        upgrade_capability: ObjectID,
+        #[serde(default)]
+        with_unpublished_dependencies: bool,
+        #[serde(default)]
+        skip_dependency_verification: bool,
+    },
+}
+
+/// An argument of a command. Results of earlier commands are referred to by their index among the
+/// commands in the file, and everything other than the gas coin, results and objects is passed as
+/// a pure value of the given type.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum PtbArgument {
+    /// The gas coin, as `"gas"`.
+    Gas,
+    /// `{"result": i}`, the result of the `i`-th command.
+    Result(u16),
+    /// `{"nested_result": [i, j]}`, the `j`-th result of the `i`-th command.
+    NestedResult(u16, u16),
+    /// An owned, shared or immutable object, by its ID.
+    Object(ObjectID),
+    /// An address, or the alias of one of the keystore's addresses.
+    Address(String),
+    Id(ObjectID),
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    /// A `u128`, as a decimal string.
+    U128(String),
+    /// A `u256`, as a decimal string.
+    U256(String),
+    String(String),
+    /// A vector of pure values, which must all be of the same type.
+    Vector(Vec<PtbArgument>),
+}
+
+/// A pure value, serialized by BCS as the Move value it stands for.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum PureValue {
+    Bool(bool),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    U256(U256),
+    Address(SuiAddress),
+    String(String),
+    Vector(Vec<PureValue>),
+}
+
+impl PtbSpec {
+    pub fn read(path: &Path) -> Result<Self, anyhow::Error> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Can't read PTB commands from {:?}: {e}", path))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("Can't deserialize PTB commands from {:?}: {e}", path))
+    }
+
+    /// Builds the programmable transaction, returning it along with the IDs of the objects it
+    /// takes as inputs, none of which can also pay for its gas. Packages are published and
+    /// upgraded as built with `build_config`.
+    pub async fn build(
+        self,
+        context: &WalletContext,
+        build_config: MoveBuildConfig,
+    ) -> Result<(ProgrammableTransaction, BTreeSet<ObjectID>), anyhow::Error> {
+        let mut builder = PtbBuilder {
+            context,
+            client: context.get_client().await?,
+            build_config,
+            builder: ProgrammableTransactionBuilder::new(),
+            results: vec![],
+            input_objects: BTreeSet::new(),
+        };
+        for (i, command) in self.commands.into_iter().enumerate() {
+            let result = builder
+                .command(command)
+                .await
+                .map_err(|e| anyhow!("Invalid PTB command {i}: {e}"))?;
+            builder.results.push(result);
+        }
+        Ok((builder.builder.finish(), builder.input_objects))
+    }
+}
+
+struct PtbBuilder<'a> {
+    context: &'a WalletContext,
+    client: SuiClient,
+    build_config: MoveBuildConfig,
+    builder: ProgrammableTransactionBuilder,
+    /// The result of each of the file's commands built so far. A file command can be built from
+    /// several PTB commands, so its index isn't necessarily that of its result.
+    results: Vec<Argument>,
+    input_objects: BTreeSet<ObjectID>,
+}
+
+impl PtbBuilder<'_> {
+    async fn command(&mut self, command: PtbCommand) -> Result<Argument, anyhow::Error> {
+        Ok(match command {
+            PtbCommand::SplitCoins { coin, amounts } => {
+                let coin = self.argument(coin).await?;
+                let amounts = self.arguments(amounts).await?;
+                self.builder.command(Command::SplitCoins(coin, amounts))
+            }
+            PtbCommand::MergeCoins { coin, coins } => {
+                let coin = self.argument(coin).await?;
+                let coins = self.arguments(coins).await?;
+                self.builder.command(Command::MergeCoins(coin, coins))
+            }
+            PtbCommand::TransferObjects { objects, address } => {
+                let objects = self.arguments(objects).await?;
+                let address = self.argument(address).await?;
+                self.builder
+                    .command(Command::TransferObjects(objects, address))
+            }
+            PtbCommand::MoveCall {
+                package,
+                module,
+                function,
+                type_arguments,
+                arguments,
+            } => {
+                let module = Identifier::new(module)?;
+                let function = Identifier::new(function)?;
+                let type_arguments = type_arguments
+                    .iter()
+                    .map(|type_argument| parse_sui_type_tag(type_argument))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let arguments = self.arguments(arguments).await?;
+                self.builder.programmable_move_call(
+                    package,
+                    module,
+                    function,
+                    type_arguments,
+                    arguments,
+                )
+            }
+            PtbCommand::MakeMoveVec { type_, elements } => {
+                let type_ = type_.as_deref().map(parse_sui_type_tag).transpose()?;
+                let elements = self.arguments(elements).await?;
+                self.builder.command(Command::MakeMoveVec(type_, elements))
+            }
+            PtbCommand::Publish {
+                package_path,
+                with_unpublished_dependencies,
+                skip_dependency_verification,
+            } => {
+                let (dependencies, compiled_modules, _, _) = compile_package(
+                    &self.client,
+                    self.build_config.clone(),
+                    package_path,
+                    with_unpublished_dependencies,
+                    skip_dependency_verification,
+                    false,
+                )
+                .await?;
+                self.builder.publish_upgradeable(
+                    compiled_modules,
+                    dependencies.published.into_values().collect(),
+                )
+            }
+            PtbCommand::Upgrade {
+                package_path,
+                upgrade_capability,
+                with_unpublished_dependencies,
+                skip_dependency_verification,
+            } => {
+                let (dependencies, compiled_modules, compiled_package, package_id) =
+                    compile_package(
+                        &self.client,
+                        self.build_config.clone(),
+                        package_path,
+                        with_unpublished_dependencies,
+                        skip_dependency_verification,
+                        false,
+                    )
+                    .await?;
+                let package_id = package_id.map_err(|e| match e {
+                    PublishedAtError::NotPresent => {
+                        anyhow!("No 'published-at' field in manifest for package to be upgraded.")
+                    }
+                    PublishedAtError::Invalid(v) => anyhow!(
+                        "Invalid 'published-at' field in manifest of package to be upgraded. \
+                         Expected an on-chain address, but found: {v:?}"
+                    ),
+                })?;
+
+                let data = self
+                    .object(
+                        upgrade_capability,
+                        SuiObjectDataOptions::default().with_bcs().with_owner(),
+                    )
+                    .await?;
+                let upgrade_cap: UpgradeCap = data
+                    .bcs
+                    .as_ref()
+                    .ok_or_else(|| {
+                        anyhow!("Fetch upgrade capability object but no data was returned")
+                    })?
+                    .try_as_move()
+                    .ok_or_else(|| anyhow!("Upgrade capability is not a Move Object"))?
+                    .deserialize()?;
+                let package_digest =
+                    compiled_package.get_package_digest(with_unpublished_dependencies);
+
+                let cap = self.input(&data)?;
+                let policy = self.builder.pure(upgrade_cap.policy)?;
+                let digest = self.builder.pure(package_digest.to_vec())?;
+                let ticket = self.builder.programmable_move_call(
+                    SUI_FRAMEWORK_PACKAGE_ID,
+                    PACKAGE_MODULE_NAME.to_owned(),
+                    ident_str!("authorize_upgrade").to_owned(),
+                    vec![],
+                    vec![cap, policy, digest],
+                );
+                let receipt = self.builder.upgrade(
+                    package_id,
+                    ticket,
+                    dependencies.published.into_values().collect(),
+                    compiled_modules,
+                );
+                self.builder.programmable_move_call(
+                    SUI_FRAMEWORK_PACKAGE_ID,
+                    PACKAGE_MODULE_NAME.to_owned(),
+                    ident_str!("commit_upgrade").to_owned(),
+                    vec![],
+                    vec![cap, receipt],
+                )
+            }
+        })
+    }
+
+    async fn arguments(
+        &mut self,
+        arguments: Vec<PtbArgument>,
+    ) -> Result<Vec<Argument>, anyhow::Error> {
+        let mut args = Vec::with_capacity(arguments.len());
+        for argument in arguments {
+            args.push(self.argument(argument).await?);
+        }
+        Ok(args)
+    }
+
+    async fn argument(&mut self, argument: PtbArgument) -> Result<Argument, anyhow::Error> {
+        Ok(match argument {
+            PtbArgument::Gas => Argument::GasCoin,
+            PtbArgument::Result(i) => self.result(i)?,
+            PtbArgument::NestedResult(i, j) => match self.result(i)? {
+                Argument::Result(command) => Argument::NestedResult(command, j),
+                _ => bail!("Command {i} has no nested results"),
+            },
+            PtbArgument::Object(id) => {
+                let data = self
+                    .object(id, SuiObjectDataOptions::new().with_owner())
+                    .await?;
+                self.input(&data)?
+            }
+            argument => {
+                let value = self.pure_value(argument)?;
+                self.builder.pure(value)?
+            }
+        })
+    }
+
+    fn result(&self, i: u16) -> Result<Argument, anyhow::Error> {
+        self.results
+            .get(i as usize)
+            .copied()
+            .ok_or_else(|| anyhow!("Result of command {i} is used before the command"))
+    }
+
+    async fn object(
+        &self,
+        id: ObjectID,
+        options: SuiObjectDataOptions,
+    ) -> Result<SuiObjectData, anyhow::Error> {
+        Ok(self
+            .client
+            .read_api()
+            .get_object_with_options(id, options)
+            .await?
+            .into_object()?)
+    }
+
+    /// Adds the object to the transaction's inputs, as a mutable input if it is shared.
+    fn input(&mut self, data: &SuiObjectData) -> Result<Argument, anyhow::Error> {
+        let id = data.object_id;
+        let owner = data
+            .owner
+            .ok_or_else(|| anyhow!("Unable to determine ownership of object {id}"))?;
+        let object_arg = match owner {
+            Owner::Shared {
+                initial_shared_version,
+            } => ObjectArg::SharedObject {
+                id,
+                initial_shared_version,
+                mutable: true,
+            },
+            Owner::AddressOwner(_) | Owner::ObjectOwner(_) | Owner::Immutable => {
+                ObjectArg::ImmOrOwnedObject(data.object_ref())
+            }
+        };
+        self.input_objects.insert(id);
+        self.builder.obj(object_arg)
+    }
+
+    fn pure_value(&self, argument: PtbArgument) -> Result<PureValue, anyhow::Error> {
+        Ok(match argument {
+            PtbArgument::Address(address) => PureValue::Address(
+                KeyIdentity::from_str(&address)?.resolve(&self.context.config.keystore)?,
+            ),
+            PtbArgument::Id(id) => PureValue::Address(id.into()),
+            PtbArgument::Bool(value) => PureValue::Bool(value),
+            PtbArgument::U8(value) => PureValue::U8(value),
+            PtbArgument::U16(value) => PureValue::U16(value),
+            PtbArgument::U32(value) => PureValue::U32(value),
+            PtbArgument::U64(value) => PureValue::U64(value),
+            PtbArgument::U128(value) => PureValue::U128(
+                u128::from_str(&value).map_err(|e| anyhow!("Invalid u128 {value}: {e}"))?,
+            ),
+            PtbArgument::U256(value) => PureValue::U256(
+                U256::from_str(&value).map_err(|e| anyhow!("Invalid u256 {value}: {e}"))?,
+            ),
+            PtbArgument::String(value) => PureValue::String(value),
+            PtbArgument::Vector(elements) => PureValue::Vector(
+                elements
+                    .into_iter()
+                    .map(|element| self.pure_value(element))
+                    .collect::<Result<_, _>>()?,
+            ),
+            PtbArgument::Gas
+            | PtbArgument::Result(_)
+            | PtbArgument::NestedResult(_, _)
+            | PtbArgument::Object(_) => bail!(
+                "Vectors can only hold pure values, use MakeMoveVec for vectors of the gas coin, \
+                 results or objects"
+            ),
+        })
+    }
+}
@@ -132,6 +132,7 @@ async fn handle_command(
                     .addresses
                     .iter()
                     .map(|addr| format!("{addr}"))
+                    .chain(addresses.aliases.values().cloned())
                     .collect::<Vec<_>>();
                 cache.insert(CacheKey::flag("--address"), addresses.clone());
                 cache.insert(CacheKey::flag("--to"), addresses);
@@ -28,12 +28,16 @@ use std::fmt::{Debug, Display, Formatter};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use sui_keys::encrypted_keystore::{
+    read_new_passphrase, read_passphrase, EncryptedFileBasedKeystore,
+};
 use sui_keys::key_derive::generate_new_key;
+use sui_keys::key_identity::KeyIdentity;
 use sui_keys::keypair_file::{
     read_authority_keypair_from_file, read_keypair_from_file, write_authority_keypair_to_file,
     write_keypair_to_file,
 };
-use sui_keys::keystore::{AccountKeystore, Keystore};
+use sui_keys::keystore::{AccountKeystore, InMemKeystore, Keystore};
 use sui_types::base_types::SuiAddress;
 use sui_types::committee::EpochId;
 use sui_types::crypto::{get_authority_key_pair, EncodeDecodeBase64, SignatureScheme, SuiKeyPair};
@@ -56,6 +60,10 @@ mod keytool_tests;
 #[derive(Subcommand)]
 #[clap(rename_all = "kebab-case")]
 pub enum KeyToolCommand {
+    /// Change the passphrase of an encrypted keystore. The current passphrase is read from the
+    /// SUI_KEYSTORE_PASSPHRASE environment variable and the new one from
+    /// SUI_KEYSTORE_NEW_PASSPHRASE, if they are set, or else they are prompted for.
+    ChangeKeystorePassphrase,
     /// Convert private key from wallet format (hex of 32 byte private key) to sui.keystore format
     /// (base64 of 33 byte flag || private key) or vice versa.
     Convert { value: String },
@@ -72,6 +80,11 @@ pub enum KeyToolCommand {
         #[clap(long)]
         tx_bytes: Option<String>,
     },
+    /// Encrypt a plaintext sui.keystore in place, with a key derived from a passphrase, which is
+    /// read from the SUI_KEYSTORE_NEW_PASSPHRASE environment variable if it is set, or else
+    /// prompted for. The keystore's addresses can still be listed without the passphrase, but it
+    /// is needed whenever a key is used to sign.
+    EncryptKeystore,
     /// Generate a new keypair with key scheme flag {ed25519 | secp256k1 | secp256r1}
     /// with optional derivation path, default to m/44'/784'/0'/0'/0' for ed25519 or
     /// m/54'/784'/0'/0/0 for secp256k1 or m/74'/784'/0'/0/0 for secp256r1. Word
@@ -96,7 +109,7 @@ pub enum KeyToolCommand {
         key_scheme: SignatureScheme,
         derivation_path: Option<DerivationPath>,
     },
-    /// List all keys by its Sui address, Base64 encoded public key, key scheme name in
+    /// List all keys by its Sui address, alias, Base64 encoded public key, key scheme name in
     /// sui.keystore.
     List,
     /// This reads the content at the provided file path. The accepted format can be
@@ -180,6 +193,14 @@ pub enum KeyToolCommand {
     /// outputs the keypair into a file at the current directory where the address is the filename,
     /// and prints out its Sui address, Base64 encoded public key, the key scheme, and the key scheme flag.
     Unpack { keypair: String },
+    /// Set the alias of an address in sui.keystore, given either the address or its current
+    /// alias. The alias can then be used in place of the address in `sui client` commands. If no
+    /// new alias is given, the address' alias is removed. An alias must start with a letter and
+    /// contain only letters, digits, `-` and `_`.
+    UpdateAlias {
+        key_identity: KeyIdentity,
+        new_alias: Option<String>,
+    },
 
     /// Given the max_epoch, generate an OAuth url, ask user to paste the redirect with id_token, call salt server, then call the prover server,
     /// create a test transaction, use the ephemeral key to sign and execute it by assembling to a serialized zkLogin signature.
@@ -242,10 +263,26 @@ pub struct DecodedMultiSigOutput {
     transaction_result: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AliasUpdate {
+    sui_address: SuiAddress,
+    alias: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedKeystoreOutput {
+    keystore_path: PathBuf,
+    addresses: Vec<SuiAddress>,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Key {
     sui_address: SuiAddress,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    alias: Option<String>,
     public_base64_key: String,
     key_scheme: String,
     flag: u8,
@@ -349,9 +386,11 @@ pub struct ZkLoginSigVerifyResponse {
 #[derive(Serialize)]
 #[serde(untagged)]
 pub enum CommandOutput {
+    ChangeKeystorePassphrase(EncryptedKeystoreOutput),
     Convert(ConvertOutput),
     DecodeMultiSig(DecodedMultiSigOutput),
     DecodeTxBytes(TransactionData),
+    EncryptKeystore(EncryptedKeystoreOutput),
     Error(String),
     Generate(Key),
     Import(Key),
@@ -364,6 +403,7 @@ pub enum CommandOutput {
     Show(Key),
     Sign(SignData),
     SignKMS(SerializedSig),
+    UpdateAlias(AliasUpdate),
     ZkLoginSignAndExecuteTx(ZkLoginSignAndExecuteTx),
     ZkLoginSigVerify(ZkLoginSigVerifyResponse),
 }
@@ -371,6 +411,20 @@ pub enum CommandOutput {
 impl KeyToolCommand {
     pub async fn execute(self, keystore: &mut Keystore) -> Result<CommandOutput, anyhow::Error> {
         let cmd_result = Ok(match self {
+            KeyToolCommand::ChangeKeystorePassphrase => {
+                let Keystore::Encrypted(encrypted) = keystore else {
+                    return Err(anyhow!(
+                        "The keystore is not encrypted, use `sui keytool encrypt-keystore` to encrypt it"
+                    ));
+                };
+                encrypted.unlock_with_passphrase(&read_passphrase(encrypted.path())?)?;
+                encrypted.change_passphrase(&read_new_passphrase()?)?;
+                CommandOutput::ChangeKeystorePassphrase(EncryptedKeystoreOutput {
+                    keystore_path: encrypted.path().to_path_buf(),
+                    addresses: encrypted.addresses(),
+                })
+            }
+
             KeyToolCommand::Convert { value } => {
                 let result = convert_private_key_to_base64(value)?;
                 CommandOutput::Convert(result)
@@ -435,6 +489,33 @@ impl KeyToolCommand {
                 CommandOutput::DecodeTxBytes(tx_data)
             }
 
+            KeyToolCommand::EncryptKeystore => {
+                let path = match keystore {
+                    Keystore::File(file_keystore) => file_keystore
+                        .path()
+                        .map(Path::to_path_buf)
+                        .ok_or_else(|| anyhow!("The keystore is not backed by a file"))?,
+                    _ => return Err(anyhow!("Only a plaintext file keystore can be encrypted")),
+                };
+                let passphrase = read_new_passphrase()?;
+                let Keystore::File(file_keystore) =
+                    std::mem::replace(keystore, Keystore::InMem(InMemKeystore::default()))
+                else {
+                    unreachable!("the keystore was checked to be a file keystore above")
+                };
+                let encrypted = EncryptedFileBasedKeystore::create(
+                    &path,
+                    file_keystore.into_key_pairs(),
+                    &passphrase,
+                )?;
+                let output = EncryptedKeystoreOutput {
+                    keystore_path: path,
+                    addresses: encrypted.addresses(),
+                };
+                *keystore = Keystore::Encrypted(encrypted);
+                CommandOutput::EncryptKeystore(output)
+            }
+
             KeyToolCommand::Generate {
                 key_scheme,
                 derivation_path,
@@ -446,6 +527,7 @@ impl KeyToolCommand {
                     write_authority_keypair_to_file(&kp, file_name)?;
                     CommandOutput::Generate(Key {
                         sui_address,
+                        alias: None,
                         public_base64_key: kp.public().encode_base64(),
                         key_scheme: key_scheme.to_string(),
                         flag: SignatureScheme::BLS12381.flag(),
@@ -502,7 +584,11 @@ impl KeyToolCommand {
                 let keys = keystore
                     .keys()
                     .into_iter()
-                    .map(Key::from)
+                    .map(|pk| {
+                        let mut key = Key::from(pk);
+                        key.alias = keystore.get_alias_by_address(&key.sui_address);
+                        key
+                    })
                     .collect::<Vec<_>>();
 
                 CommandOutput::List(keys)
@@ -619,6 +705,7 @@ impl KeyToolCommand {
                             let public_base64_key = keypair.public().encode_base64();
                             CommandOutput::Show(Key {
                                 sui_address: (keypair.public()).into(),
+                                alias: None,
                                 public_base64_key,
                                 key_scheme: SignatureScheme::BLS12381.to_string(),
                                 flag: SignatureScheme::BLS12381.flag(),
@@ -741,6 +828,18 @@ impl KeyToolCommand {
                 CommandOutput::Show(key)
             }
 
+            KeyToolCommand::UpdateAlias {
+                key_identity,
+                new_alias,
+            } => {
+                let sui_address = key_identity.resolve(keystore)?;
+                keystore.update_alias(&sui_address, new_alias.as_deref())?;
+                CommandOutput::UpdateAlias(AliasUpdate {
+                    sui_address,
+                    alias: new_alias,
+                })
+            }
+
             KeyToolCommand::ZkLoginSignAndExecuteTx {
                 max_epoch,
                 network,
@@ -906,6 +1005,7 @@ impl From<PublicKey> for Key {
     fn from(key: PublicKey) -> Self {
         Key {
             sui_address: Into::<SuiAddress>::into(&key),
+            alias: None,
             public_base64_key: key.encode_base64(),
             key_scheme: key.scheme().to_string(),
             mnemonic: None,
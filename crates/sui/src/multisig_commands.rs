@@ -0,0 +1,342 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, ensure};
+use clap::*;
+use fastcrypto::encoding::{Base64, Encoding};
+use json_to_table::json_to_table;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use shared_crypto::intent::{Intent, IntentMessage};
+use sui_json_rpc_types::SuiTransactionBlockResponse;
+use sui_keys::key_identity::KeyIdentity;
+use sui_keys::keystore::AccountKeystore;
+use sui_sdk::wallet_context::WalletContext;
+use sui_types::base_types::SuiAddress;
+use sui_types::crypto::{default_hash, EncodeDecodeBase64, PublicKey, Signature, SuiSignature};
+use sui_types::digests::TransactionDigest;
+use sui_types::multisig::{MultiSig, MultiSigPublicKey, ThresholdUnit, WeightUnit};
+use sui_types::transaction::{Transaction, TransactionData, TransactionDataAPI};
+use tabled::settings::Style as TableStyle;
+
+use crate::client_commands::write_transaction_response;
+
+/// Signing a transaction from a MultiSig address offline, by passing a proposal file between its
+/// signers, each of whom approves it with their own key, until enough have to execute it.
+#[derive(Subcommand)]
+#[clap(rename_all = "kebab-case")]
+pub enum MultiSigCommand {
+    /// Create a proposal file for a transaction from a MultiSig address. The transaction is given
+    /// as the Base64 encoded bytes output by `--serialize-unsigned-transaction`, and the MultiSig
+    /// by its public keys `flag || pk` in Base64, their weights and its threshold, as for
+    /// `sui keytool multi-sig-address`.
+    Propose {
+        /// Path to write the proposal file to
+        proposal: PathBuf,
+        /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
+        #[clap(long)]
+        tx_bytes: String,
+        #[clap(long, num_args(1..))]
+        pks: Vec<PublicKey>,
+        #[clap(long, num_args(1..))]
+        weights: Vec<WeightUnit>,
+        #[clap(long)]
+        threshold: ThresholdUnit,
+    },
+    /// Sign the transaction in a proposal file with one of the MultiSig's keys in the keystore,
+    /// and add the signature to the proposal file.
+    Approve {
+        /// Path to the proposal file
+        proposal: PathBuf,
+        /// Address (or its alias) of the key to sign with. Defaults to the active address.
+        #[clap(long)]
+        address: Option<KeyIdentity>,
+    },
+    /// Show who has signed the transaction in a proposal file, and how much of the MultiSig's
+    /// threshold their weights add up to.
+    Status {
+        /// Path to the proposal file
+        proposal: PathBuf,
+    },
+    /// Combine the signatures in a proposal file into a MultiSig, once their weights reach its
+    /// threshold, and execute the transaction with it.
+    Execute {
+        /// Path to the proposal file
+        proposal: PathBuf,
+    },
+}
+
+/// A transaction from a MultiSig address, and the partial signatures collected for it so far, as
+/// kept in a proposal file.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigProposal {
+    /// BCS serialized `TransactionData`, as a Base64 encoded string.
+    pub tx_bytes: String,
+    pub multisig_pk: MultiSigPublicKey,
+    /// Base64 encoded `flag || signature || pubkey` of each signature collected.
+    pub signatures: Vec<String>,
+}
+
+impl MultiSigProposal {
+    pub fn new(
+        tx_data: &TransactionData,
+        multisig_pk: MultiSigPublicKey,
+    ) -> Result<Self, anyhow::Error> {
+        let address = SuiAddress::from(&multisig_pk);
+        ensure!(
+            tx_data.sender() == address,
+            "The transaction is sent by {}, not by the MultiSig address {address}",
+            tx_data.sender()
+        );
+        Ok(Self {
+            tx_bytes: Base64::encode(bcs::to_bytes(tx_data)?),
+            multisig_pk,
+            signatures: vec![],
+        })
+    }
+
+    pub fn read(path: &Path) -> Result<Self, anyhow::Error> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Can't read MultiSig proposal from {:?}: {e}", path))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("Can't deserialize MultiSig proposal from {:?}: {e}", path))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), anyhow::Error> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .map_err(|e| anyhow!("Can't write MultiSig proposal to {:?}: {e}", path))
+    }
+
+    pub fn tx_data(&self) -> Result<TransactionData, anyhow::Error> {
+        let bytes = Base64::decode(&self.tx_bytes)
+            .map_err(|e| anyhow!("Invalid transaction bytes in MultiSig proposal: {e}"))?;
+        Ok(bcs::from_bytes(&bytes)?)
+    }
+
+    /// The index of `address`' key among the MultiSig's public keys.
+    fn signer_index(&self, address: &SuiAddress) -> Result<u8, anyhow::Error> {
+        self.multisig_pk
+            .pubkeys()
+            .iter()
+            .position(|(pk, _)| SuiAddress::from(pk) == *address)
+            .map(|index| index as u8)
+            .ok_or_else(|| anyhow!("Address {address} is not one of the MultiSig's signers"))
+    }
+
+    /// Verifies that `signature` is a valid signature of the transaction by one of the MultiSig's
+    /// signers, returning the index of their key.
+    fn verify_signature(
+        &self,
+        intent_msg: &IntentMessage<&TransactionData>,
+        signature: &Signature,
+    ) -> Result<u8, anyhow::Error> {
+        let signer = SuiAddress::from(&signature.to_public_key()?);
+        let index = self.signer_index(&signer)?;
+        signature
+            .verify_secure(intent_msg, signer, signature.scheme())
+            .map_err(|e| anyhow!("Invalid signature by {signer}: {e}"))?;
+        Ok(index)
+    }
+
+    /// Parses and verifies the signatures collected, returning each with the index of its
+    /// signer's key.
+    fn verified_signatures(&self) -> Result<Vec<(u8, Signature)>, anyhow::Error> {
+        let tx_data = self.tx_data()?;
+        let intent_msg = IntentMessage::new(Intent::sui_transaction(), &tx_data);
+        self.signatures
+            .iter()
+            .map(|signature| {
+                let signature = Signature::decode_base64(signature)
+                    .map_err(|e| anyhow!("Invalid signature {signature}: {e}"))?;
+                Ok((self.verify_signature(&intent_msg, &signature)?, signature))
+            })
+            .collect()
+    }
+
+    /// Adds `signature` to the proposal, if it is a valid signature of its transaction by one of
+    /// the MultiSig's signers who has not signed it yet.
+    pub fn add_signature(&mut self, signature: Signature) -> Result<(), anyhow::Error> {
+        let tx_data = self.tx_data()?;
+        let index = self.verify_signature(
+            &IntentMessage::new(Intent::sui_transaction(), &tx_data),
+            &signature,
+        )?;
+        ensure!(
+            self.verified_signatures()?.iter().all(|(i, _)| *i != index),
+            "Address {} has already signed the MultiSig proposal",
+            SuiAddress::from(&signature.to_public_key()?)
+        );
+        self.signatures.push(signature.encode_base64());
+        Ok(())
+    }
+
+    pub fn status(&self) -> Result<MultiSigProposalStatus, anyhow::Error> {
+        let signed = self
+            .verified_signatures()?
+            .into_iter()
+            .map(|(index, _)| index as usize)
+            .collect::<Vec<_>>();
+        let signers = self
+            .multisig_pk
+            .pubkeys()
+            .iter()
+            .enumerate()
+            .map(|(index, (pk, weight))| MultiSigSignerStatus {
+                address: SuiAddress::from(pk),
+                public_base64_key: pk.encode_base64(),
+                weight: *weight,
+                signed: signed.contains(&index),
+            })
+            .collect::<Vec<_>>();
+        let weight = signers
+            .iter()
+            .filter(|signer| signer.signed)
+            .map(|signer| signer.weight as ThresholdUnit)
+            .sum();
+        Ok(MultiSigProposalStatus {
+            multisig_address: SuiAddress::from(&self.multisig_pk),
+            tx_digest: TransactionDigest::new(default_hash(&self.tx_data()?)),
+            threshold: *self.multisig_pk.threshold(),
+            weight,
+            signers,
+        })
+    }
+
+    /// Combines the signatures collected into a MultiSig, and returns the transaction signed
+    /// with it.
+    pub fn combine(&self) -> Result<Transaction, anyhow::Error> {
+        let status = self.status()?;
+        ensure!(
+            status.weight >= status.threshold,
+            "The MultiSig proposal has signatures of weight {}, below the threshold of {}",
+            status.weight,
+            status.threshold
+        );
+        // `MultiSig::combine` expects the signatures in the order of the public keys.
+        let mut signatures = self.verified_signatures()?;
+        signatures.sort_by_key(|(index, _)| *index);
+        let multisig = MultiSig::combine(
+            signatures
+                .into_iter()
+                .map(|(_, signature)| signature)
+                .collect(),
+            self.multisig_pk.clone(),
+        )?;
+        Ok(Transaction::from_generic_sig_data(
+            self.tx_data()?,
+            Intent::sui_transaction(),
+            vec![multisig.into()],
+        ))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigProposalStatus {
+    pub multisig_address: SuiAddress,
+    pub tx_digest: TransactionDigest,
+    pub threshold: ThresholdUnit,
+    /// The total weight of the signers who have signed so far.
+    pub weight: ThresholdUnit,
+    pub signers: Vec<MultiSigSignerStatus>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigSignerStatus {
+    pub address: SuiAddress,
+    pub public_base64_key: String,
+    pub weight: WeightUnit,
+    pub signed: bool,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum MultiSigCommandResult {
+    Propose(MultiSigProposalStatus),
+    Approve(MultiSigProposalStatus),
+    Status(MultiSigProposalStatus),
+    Execute(SuiTransactionBlockResponse),
+}
+
+impl MultiSigCommand {
+    pub async fn execute(
+        self,
+        context: &mut WalletContext,
+    ) -> Result<MultiSigCommandResult, anyhow::Error> {
+        Ok(match self {
+            MultiSigCommand::Propose {
+                proposal: path,
+                tx_bytes,
+                pks,
+                weights,
+                threshold,
+            } => {
+                ensure!(
+                    !path.exists(),
+                    "A MultiSig proposal already exists at {:?}",
+                    path
+                );
+                let bytes = Base64::decode(&tx_bytes)
+                    .map_err(|e| anyhow!("Invalid transaction bytes: {e}"))?;
+                let tx_data: TransactionData = bcs::from_bytes(&bytes)?;
+                let multisig_pk = MultiSigPublicKey::new(pks, weights, threshold)?;
+                let proposal = MultiSigProposal::new(&tx_data, multisig_pk)?;
+                proposal.write(&path)?;
+                MultiSigCommandResult::Propose(proposal.status()?)
+            }
+
+            MultiSigCommand::Approve {
+                proposal: path,
+                address,
+            } => {
+                let mut proposal = MultiSigProposal::read(&path)?;
+                let address = match address {
+                    Some(address) => address.resolve(&context.config.keystore)?,
+                    None => context.active_address()?,
+                };
+                proposal.signer_index(&address)?;
+                let signature = context.config.keystore.sign_secure(
+                    &address,
+                    &proposal.tx_data()?,
+                    Intent::sui_transaction(),
+                )?;
+                proposal.add_signature(signature)?;
+                proposal.write(&path)?;
+                MultiSigCommandResult::Approve(proposal.status()?)
+            }
+
+            MultiSigCommand::Status { proposal: path } => {
+                MultiSigCommandResult::Status(MultiSigProposal::read(&path)?.status()?)
+            }
+
+            MultiSigCommand::Execute { proposal: path } => {
+                let transaction = MultiSigProposal::read(&path)?.combine()?;
+                let response = context.execute_transaction_may_fail(transaction).await?;
+                MultiSigCommandResult::Execute(response)
+            }
+        })
+    }
+}
+
+impl Display for MultiSigCommandResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MultiSigCommandResult::Propose(status)
+            | MultiSigCommandResult::Approve(status)
+            | MultiSigCommandResult::Status(status) => {
+                let mut table = json_to_table(&json!(status));
+                table.with(TableStyle::rounded().horizontals([]));
+                write!(f, "{}", table)
+            }
+            MultiSigCommandResult::Execute(response) => {
+                write!(f, "{}", write_transaction_response(response)?)
+            }
+        }
+    }
+}
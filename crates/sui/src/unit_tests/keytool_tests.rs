@@ -15,6 +15,7 @@ use rand::rngs::StdRng;
 use rand::SeedableRng;
 use shared_crypto::intent::Intent;
 use shared_crypto::intent::IntentScope;
+use sui_keys::key_identity::KeyIdentity;
 use sui_keys::keystore::{AccountKeystore, FileBasedKeystore, InMemKeystore, Keystore};
 use sui_types::base_types::ObjectDigest;
 use sui_types::base_types::ObjectID;
@@ -494,3 +495,39 @@ async fn test_sign_command() -> Result<(), anyhow::Error> {
     .await?;
     Ok(())
 }
+
+#[test]
+async fn test_update_alias() -> Result<(), anyhow::Error> {
+    let mut keystore = Keystore::from(InMemKeystore::new_insecure_for_tests(1));
+    let address = keystore.addresses()[0];
+
+    KeyToolCommand::UpdateAlias {
+        key_identity: KeyIdentity::Address(address),
+        new_alias: Some("alice".to_string()),
+    }
+    .execute(&mut keystore)
+    .await?;
+    assert_eq!(keystore.get_address_by_alias("alice")?, address);
+
+    // The address can be given by its current alias.
+    KeyToolCommand::UpdateAlias {
+        key_identity: KeyIdentity::from_str("alice")?,
+        new_alias: Some("bob".to_string()),
+    }
+    .execute(&mut keystore)
+    .await?;
+    assert_eq!(
+        keystore.get_alias_by_address(&address).as_deref(),
+        Some("bob")
+    );
+    assert!(keystore.get_address_by_alias("alice").is_err());
+
+    assert!(KeyToolCommand::UpdateAlias {
+        key_identity: KeyIdentity::Address(address),
+        new_alias: Some("not an alias".to_string()),
+    }
+    .execute(&mut keystore)
+    .await
+    .is_err());
+    Ok(())
+}
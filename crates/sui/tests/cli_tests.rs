@@ -13,13 +13,16 @@ use serde_json::json;
 use sui_test_transaction_builder::batch_make_transfer_transactions;
 use sui_types::object::Owner;
 use sui_types::transaction::{
-    TEST_ONLY_GAS_UNIT_FOR_GENERIC, TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS,
-    TEST_ONLY_GAS_UNIT_FOR_PUBLISH, TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN,
-    TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+    TransactionDataAPI, TransactionKind, TEST_ONLY_GAS_UNIT_FOR_GENERIC,
+    TEST_ONLY_GAS_UNIT_FOR_OBJECT_BASICS, TEST_ONLY_GAS_UNIT_FOR_PUBLISH,
+    TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN, TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
 };
+use sui_types::SUI_FRAMEWORK_PACKAGE_ID;
 use tokio::time::sleep;
 
+use fastcrypto::encoding::{Base64, Encoding};
 use sui::client_commands::SwitchResponse;
+use sui::multisig_commands::{MultiSigCommand, MultiSigCommandResult};
 use sui::{
     client_commands::{SuiClientCommandResult, SuiClientCommands},
     sui_commands::SuiCommand,
@@ -45,6 +48,7 @@ use sui_types::crypto::{
     Ed25519SuiSignature, Secp256k1SuiSignature, SignatureScheme, SuiKeyPair, SuiSignatureInner,
 };
 use sui_types::error::SuiObjectResponseError;
+use sui_types::multisig::MultiSigPublicKey;
 use sui_types::{base_types::ObjectID, crypto::get_key_pair, gas_coin::GasCoin};
 use test_cluster::TestClusterBuilder;
 
@@ -152,7 +156,7 @@ async fn test_objects_command() -> Result<(), anyhow::Error> {
 
     // Print objects owned by `address`
     SuiClientCommands::Objects {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(context)
     .await?
@@ -184,7 +188,7 @@ async fn test_regression_6546() -> Result<(), anyhow::Error> {
     let context = &mut test_cluster.wallet;
 
     let SuiClientCommandResult::Objects(coins) = SuiClientCommands::Objects {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(context)
     .await?
@@ -235,7 +239,7 @@ async fn test_custom_genesis() -> Result<(), anyhow::Error> {
 
     // Print objects owned by `address`
     SuiClientCommands::Objects {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(context)
     .await?
@@ -317,7 +321,7 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
     let object_to_send = object_refs.data.get(1).unwrap().object().unwrap().object_id;
 
     SuiClientCommands::Gas {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(context)
     .await?
@@ -327,7 +331,7 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
 
     // Send an object
     SuiClientCommands::Transfer {
-        to: SuiAddress::random_for_testing_only(),
+        to: SuiAddress::random_for_testing_only().into(),
         object_id: object_to_send,
         gas: Some(object_id),
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
@@ -339,7 +343,7 @@ async fn test_gas_command() -> Result<(), anyhow::Error> {
 
     // Fetch gas again
     SuiClientCommands::Gas {
-        address: Some(address),
+        address: Some(address.into()),
     }
     .execute(context)
     .await?
@@ -415,7 +419,7 @@ async fn test_move_call_args_linter_command() -> Result<(), anyhow::Error> {
 
     // Print objects owned by `address1`
     SuiClientCommands::Objects {
-        address: Some(address1),
+        address: Some(address1.into()),
     }
     .execute(context)
     .await?
@@ -1503,7 +1507,7 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
 
     let resp = SuiClientCommands::Transfer {
         gas: Some(gas_obj_id),
-        to: recipient,
+        to: recipient.into(),
         object_id: obj_id,
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
         serialize_unsigned_transaction: false,
@@ -1608,7 +1612,7 @@ async fn test_native_transfer() -> Result<(), anyhow::Error> {
 
     let resp = SuiClientCommands::Transfer {
         gas: None,
-        to: recipient,
+        to: recipient.into(),
         object_id: obj_id,
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
         serialize_unsigned_transaction: false,
@@ -1704,7 +1708,7 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
 
     // Switch the address
     let resp = SuiClientCommands::Switch {
-        address: Some(addr2),
+        address: Some(addr2.into()),
         env: None,
     }
     .execute(context)
@@ -1742,7 +1746,7 @@ async fn test_switch_command() -> Result<(), anyhow::Error> {
     // Check that we can switch to this address
     // Switch the address
     let resp = SuiClientCommands::Switch {
-        address: Some(new_addr),
+        address: Some(new_addr.into()),
         env: None,
     }
     .execute(context)
@@ -1821,7 +1825,7 @@ async fn test_active_address_command() -> Result<(), anyhow::Error> {
 
     let addr2 = context.config.keystore.addresses().get(1).cloned().unwrap();
     let resp = SuiClientCommands::Switch {
-        address: Some(addr2),
+        address: Some(addr2.into()),
         env: None,
     }
     .execute(context)
@@ -2234,6 +2238,369 @@ async fn test_execute_signed_tx() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[sim_test]
+async fn test_multisig_proposal() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let temp_dir = tempfile::tempdir()?;
+    let proposal = temp_dir.path().join("proposal.json");
+
+    // A 2-of-3 MultiSig of the wallet's first three keys.
+    let pks = context.config.keystore.keys()[..3].to_vec();
+    let multisig_pk = MultiSigPublicKey::new(pks.clone(), vec![1, 1, 1], 2)?;
+    let multisig_address = SuiAddress::from(&multisig_pk);
+
+    // Fund the MultiSig address with a whole gas coin.
+    let coin = context
+        .get_one_gas_object_owned_by_address(address)
+        .await?
+        .unwrap()
+        .0;
+    SuiClientCommands::TransferSui {
+        to: multisig_address.into(),
+        sui_coin_object_id: coin,
+        gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+        amount: None,
+        serialize_unsigned_transaction: false,
+        serialize_signed_transaction: false,
+    }
+    .execute(context)
+    .await?;
+
+    let SuiClientCommandResult::SerializedUnsignedTransaction(tx_data) =
+        SuiClientCommands::TransferSui {
+            to: address1.into(),
+            sui_coin_object_id: coin,
+            gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
+            amount: Some(1),
+            serialize_unsigned_transaction: true,
+            serialize_signed_transaction: false,
+        }
+        .execute(context)
+        .await?
+    else {
+        panic!("expected an unsigned transaction");
+    };
+
+    SuiClientCommands::MultiSig {
+        cmd: MultiSigCommand::Propose {
+            proposal: proposal.clone(),
+            tx_bytes: Base64::encode(bcs::to_bytes(&tx_data)?),
+            pks,
+            weights: vec![1, 1, 1],
+            threshold: 2,
+        },
+    }
+    .execute(context)
+    .await?;
+
+    let approve = |signer: SuiAddress| SuiClientCommands::MultiSig {
+        cmd: MultiSigCommand::Approve {
+            proposal: proposal.clone(),
+            address: Some(signer.into()),
+        },
+    };
+    approve(address).execute(context).await?;
+    // The same signer can't approve twice, and one signature is below the threshold.
+    assert!(approve(address).execute(context).await.is_err());
+    let execute_proposal = || SuiClientCommands::MultiSig {
+        cmd: MultiSigCommand::Execute {
+            proposal: proposal.clone(),
+        },
+    };
+    assert!(execute_proposal().execute(context).await.is_err());
+
+    approve(address1).execute(context).await?;
+    let SuiClientCommandResult::MultiSig(MultiSigCommandResult::Status(status)) =
+        SuiClientCommands::MultiSig {
+            cmd: MultiSigCommand::Status {
+                proposal: proposal.clone(),
+            },
+        }
+        .execute(context)
+        .await?
+    else {
+        panic!("expected the status of the MultiSig proposal");
+    };
+    assert_eq!(status.multisig_address, multisig_address);
+    assert_eq!(status.weight, 2);
+
+    let response = execute_proposal().execute(context).await?;
+    let effects = response
+        .tx_block_response()
+        .unwrap()
+        .effects
+        .as_ref()
+        .unwrap();
+    assert!(effects.status().is_ok());
+    Ok(())
+}
+
+#[sim_test]
+async fn test_ptb() -> Result<(), anyhow::Error> {
+    let mut test_cluster = TestClusterBuilder::new().build().await;
+    let rgp = test_cluster.get_reference_gas_price().await;
+    let address = test_cluster.get_address_0();
+    let address1 = test_cluster.get_address_1();
+    let context = &mut test_cluster.wallet;
+    let temp_dir = tempfile::tempdir()?;
+    let file = temp_dir.path().join("ptb.json");
+
+    // Split two coins off the gas coin, merge them back together, and send the result.
+    std::fs::write(
+        &file,
+        json!({
+            "commands": [
+                { "SplitCoins": { "coin": "gas", "amounts": [{ "u64": 1000 }, { "u64": 2000 }] } },
+                {
+                    "MergeCoins": {
+                        "coin": { "nested_result": [0, 0] },
+                        "coins": [{ "nested_result": [0, 1] }]
+                    }
+                },
+                {
+                    "TransferObjects": {
+                        "objects": [{ "nested_result": [0, 0] }],
+                        "address": { "address": address1.to_string() }
+                    }
+                }
+            ]
+        })
+        .to_string(),
+    )?;
+    let ptb = |gas_budget: u64, dry_run: bool, serialize_unsigned_transaction: bool| {
+        SuiClientCommands::Ptb {
+            file: file.clone(),
+            build_config: BuildConfig::new_for_testing().config,
+            gas: None,
+            gas_budget,
+            dry_run,
+            serialize_unsigned_transaction,
+            serialize_signed_transaction: false,
+        }
+    };
+    let split_budget = rgp * TEST_ONLY_GAS_UNIT_FOR_SPLIT_COIN;
+
+    let SuiClientCommandResult::DryRun(response) =
+        ptb(split_budget, true, false).execute(context).await?
+    else {
+        panic!("expected a dry run response");
+    };
+    assert!(response.effects.status().is_ok());
+
+    // The transaction can be serialized instead of executed.
+    let SuiClientCommandResult::SerializedUnsignedTransaction(data) =
+        ptb(split_budget, false, true).execute(context).await?
+    else {
+        panic!("expected a serialized unsigned transaction");
+    };
+    let TransactionKind::ProgrammableTransaction(pt) = data.kind() else {
+        panic!("expected a programmable transaction");
+    };
+    assert_eq!(pt.commands.len(), 3);
+    assert_eq!(data.sender(), address);
+
+    let response = ptb(split_budget, false, false).execute(context).await?;
+    let effects = response
+        .tx_block_response()
+        .unwrap()
+        .effects
+        .as_ref()
+        .unwrap();
+    assert!(effects.status().is_ok());
+    let coins = context
+        .get_client()
+        .await?
+        .coin_read_api()
+        .get_coins(address1, None, None, None)
+        .await?
+        .data;
+    assert!(coins.iter().any(|coin| coin.balance == 3000));
+
+    // Results can only be used by the commands after them.
+    std::fs::write(
+        &file,
+        json!({
+            "commands": [
+                {
+                    "TransferObjects": {
+                        "objects": [{ "result": 1 }],
+                        "address": { "address": address1.to_string() }
+                    }
+                },
+                { "SplitCoins": { "coin": "gas", "amounts": [{ "u64": 1000 }] } }
+            ]
+        })
+        .to_string(),
+    )?;
+    assert!(ptb(split_budget, false, false)
+        .execute(context)
+        .await
+        .is_err());
+
+    // Split coins into a vector, join them into another coin with a Move call, and split that
+    // coin by a vector of amounts.
+    let framework = SUI_FRAMEWORK_PACKAGE_ID.to_string();
+    std::fs::write(
+        &file,
+        json!({
+            "commands": [
+                { "SplitCoins": { "coin": "gas", "amounts": [{ "u64": 1000 }, { "u64": 2000 }] } },
+                {
+                    "MakeMoveVec": {
+                        "elements": [{ "nested_result": [0, 0] }, { "nested_result": [0, 1] }]
+                    }
+                },
+                { "SplitCoins": { "coin": "gas", "amounts": [{ "u64": 4000 }] } },
+                {
+                    "MoveCall": {
+                        "package": framework,
+                        "module": "pay",
+                        "function": "join_vec",
+                        "type_arguments": ["0x2::sui::SUI"],
+                        "arguments": [{ "nested_result": [2, 0] }, { "result": 1 }]
+                    }
+                },
+                { "MakeMoveVec": { "type": "u64", "elements": [{ "u64": 100 }, { "u64": 200 }] } },
+                {
+                    "MoveCall": {
+                        "package": framework,
+                        "module": "pay",
+                        "function": "split_vec",
+                        "type_arguments": ["0x2::sui::SUI"],
+                        "arguments": [{ "nested_result": [2, 0] }, { "result": 4 }]
+                    }
+                },
+                {
+                    "TransferObjects": {
+                        "objects": [{ "nested_result": [2, 0] }],
+                        "address": { "address": address1.to_string() }
+                    }
+                }
+            ]
+        })
+        .to_string(),
+    )?;
+    let response = ptb(rgp * TEST_ONLY_GAS_UNIT_FOR_GENERIC, false, false)
+        .execute(context)
+        .await?;
+    let effects = response
+        .tx_block_response()
+        .unwrap()
+        .effects
+        .as_ref()
+        .unwrap();
+    assert!(effects.status().is_ok());
+    let coins = context
+        .get_client()
+        .await?
+        .coin_read_api()
+        .get_coins(address1, None, None, None)
+        .await?
+        .data;
+    assert!(coins.iter().any(|coin| coin.balance == 6700));
+
+    // A published package's upgrade capability must be used by the transaction.
+    let mut package_path = PathBuf::from(TEST_DATA_DIR);
+    package_path.push("dummy_modules_upgrade");
+    let publish = json!({ "Publish": { "package_path": package_path } });
+    std::fs::write(&file, json!({ "commands": [publish] }).to_string())?;
+    let publish_budget = rgp * TEST_ONLY_GAS_UNIT_FOR_PUBLISH;
+    let SuiClientCommandResult::DryRun(response) =
+        ptb(publish_budget, true, false).execute(context).await?
+    else {
+        panic!("expected a dry run response");
+    };
+    assert!(response.effects.status().is_err());
+
+    std::fs::write(
+        &file,
+        json!({
+            "commands": [
+                publish,
+                {
+                    "TransferObjects": {
+                        "objects": [{ "result": 0 }],
+                        "address": { "address": address.to_string() }
+                    }
+                }
+            ]
+        })
+        .to_string(),
+    )?;
+    let SuiClientCommandResult::Ptb(response) =
+        ptb(publish_budget, false, false).execute(context).await?
+    else {
+        panic!("expected a PTB response");
+    };
+    let SuiTransactionBlockEffects::V1(effects) = response.effects.unwrap();
+    assert!(effects.status.is_ok());
+    let package = effects
+        .created()
+        .iter()
+        .find(|refe| matches!(refe.owner, Owner::Immutable))
+        .unwrap();
+    let cap = effects
+        .created()
+        .iter()
+        .find(|refe| matches!(refe.owner, Owner::AddressOwner(owner) if owner == address))
+        .unwrap();
+
+    // Upgrade the package, from a copy of it that is published at its address.
+    let tmp_dir = tempfile::tempdir().unwrap();
+    fs_extra::dir::copy(
+        &package_path,
+        tmp_dir.path(),
+        &fs_extra::dir::CopyOptions::default(),
+    )
+    .unwrap();
+    let mut upgrade_pkg_path = tmp_dir.path().to_path_buf();
+    upgrade_pkg_path.extend(["dummy_modules_upgrade", "Move.toml"]);
+    let manifest = std::fs::read_to_string(&upgrade_pkg_path)?.replacen(
+        "[package]",
+        &format!(
+            "[package]\npublished-at = \"{}\"",
+            package.reference.object_id.to_hex_uncompressed()
+        ),
+        1,
+    );
+    std::fs::write(&upgrade_pkg_path, manifest)?;
+    upgrade_pkg_path.pop();
+
+    std::fs::write(
+        &file,
+        json!({
+            "commands": [{
+                "Upgrade": {
+                    "package_path": upgrade_pkg_path,
+                    "upgrade_capability": cap.reference.object_id
+                }
+            }]
+        })
+        .to_string(),
+    )?;
+    let SuiClientCommandResult::Ptb(response) =
+        ptb(publish_budget, false, false).execute(context).await?
+    else {
+        panic!("expected a PTB response");
+    };
+    let SuiTransactionBlockEffects::V1(effects) = response.effects.unwrap();
+    assert!(effects.status.is_ok());
+    assert!(effects
+        .created()
+        .iter()
+        .any(|refe| matches!(refe.owner, Owner::Immutable)));
+    // The upgrade capability is used, and kept, by the upgrade.
+    assert!(effects
+        .mutated()
+        .iter()
+        .any(|refe| refe.reference.object_id == cap.reference.object_id));
+    Ok(())
+}
+
 #[sim_test]
 async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     let mut test_cluster = TestClusterBuilder::new().build().await;
@@ -2260,7 +2627,7 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     let coin = object_refs.get(1).unwrap().object().unwrap().object_id;
 
     SuiClientCommands::TransferSui {
-        to: address1,
+        to: address1.into(),
         sui_coin_object_id: coin,
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
         amount: Some(1),
@@ -2271,7 +2638,7 @@ async fn test_serialize_tx() -> Result<(), anyhow::Error> {
     .await?;
 
     SuiClientCommands::TransferSui {
-        to: address1,
+        to: address1.into(),
         sui_coin_object_id: coin,
         gas_budget: rgp * TEST_ONLY_GAS_UNIT_FOR_TRANSFER,
         amount: Some(1),
@@ -20,7 +20,7 @@ use move_core_types::{
     vm_status::{StatusCode, StatusType},
 };
 use move_vm_config::runtime::VMRuntimeLimitsConfig;
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 use move_vm_profiler::GasProfiler;
 use move_vm_profiler::{
     profile_close_frame, profile_close_instr, profile_open_frame, profile_open_instr,
@@ -240,10 +240,7 @@ impl Interpreter {
                 }
                 ExitCode::Call(fh_idx) => {
                     let func = resolver.function_from_handle(fh_idx);
-                    // Compiled out in release mode
-                    #[cfg(debug_assertions)]
-                    let func_name = func.pretty_string();
-                    profile_open_frame!(gas_meter, func_name.clone());
+                    profile_open_frame!(gas_meter, func.pretty_string());
 
                     if self.paranoid_type_checks {
                         self.check_friend_or_private_call(&current_frame.function, &func)?;
@@ -269,6 +266,11 @@ impl Interpreter {
                         .map_err(|e| set_err_info!(current_frame, e))?;
 
                     if func.is_native() {
+                        // Named before `func` is moved into the call, only if there is a
+                        // profiler to close its frame. Compiled out in release mode, unless the
+                        // gas profiler is enabled
+                        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
+                        let func_name = gas_meter.get_profiler_mut().map(|_| func.pretty_string());
                         self.call_native(
                             &resolver,
                             data_store,
@@ -278,7 +280,7 @@ impl Interpreter {
                             vec![],
                         )?;
                         current_frame.pc += 1; // advance past the Call instruction in the caller
-                        profile_close_frame!(gas_meter, func_name);
+                        profile_close_frame!(gas_meter, func_name.unwrap_or_default());
 
                         continue;
                     }
@@ -300,10 +302,7 @@ impl Interpreter {
                         .instantiate_generic_function(idx, current_frame.ty_args())
                         .map_err(|e| set_err_info!(current_frame, e))?;
                     let func = resolver.function_from_instantiation(idx);
-                    // Compiled out in release mode
-                    #[cfg(debug_assertions)]
-                    let func_name = func.pretty_string();
-                    profile_open_frame!(gas_meter, func_name.clone());
+                    profile_open_frame!(gas_meter, func.pretty_string());
                     if self.paranoid_type_checks {
                         self.check_friend_or_private_call(&current_frame.function, &func)?;
                     }
@@ -329,11 +328,16 @@ impl Interpreter {
                         .map_err(|e| set_err_info!(current_frame, e))?;
 
                     if func.is_native() {
+                        // Named before `func` is moved into the call, only if there is a
+                        // profiler to close its frame. Compiled out in release mode, unless the
+                        // gas profiler is enabled
+                        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
+                        let func_name = gas_meter.get_profiler_mut().map(|_| func.pretty_string());
                         self.call_native(
                             &resolver, data_store, gas_meter, extensions, func, ty_args,
                         )?;
                         current_frame.pc += 1; // advance past the Call instruction in the caller
-                        profile_close_frame!(gas_meter, func_name);
+                        profile_close_frame!(gas_meter, func_name.unwrap_or_default());
                         continue;
                     }
                     let frame = self
@@ -21,7 +21,7 @@ use move_core_types::{
     vm_status::{StatusCode, StatusType},
 };
 use move_vm_config::runtime::VMRuntimeLimitsConfig;
-#[cfg(debug_assertions)]
+#[cfg(any(debug_assertions, feature = "gas-profiler"))]
 use move_vm_profiler::GasProfiler;
 use move_vm_profiler::{
     profile_close_frame, profile_close_instr, profile_open_frame, profile_open_instr,
@@ -176,7 +176,7 @@ impl Interpreter {
         function: &Function,
         ty_args: Option<&[Type]>,
     ) -> VMResult<()> {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         profile_open_frame!(gas_meter, function.pretty_string());
 
         for plugin in plugins.iter_mut() {
@@ -19,9 +19,9 @@ pub struct VMConfig {
     // When this flag is set to true, MoveVM will check that there are no trailing bytes after
     // deserializing and check for no metadata bytes
     pub check_no_extraneous_bytes_during_deserialization: bool,
-    // Configs for profiling VM
-    #[cfg(debug_assertions)]
-    pub profiler_config: VMProfilerConfig,
+    // Configs for profiling VM. When set, every transaction executed by the VM is profiled, and
+    // in debug builds, a default profiler is used otherwise.
+    pub profiler_config: Option<VMProfilerConfig>,
     // When this flag is set to true, errors from the VM will be augmented with execution state
     // (stacktrace etc.)
     pub error_execution_state: bool,
@@ -36,8 +36,7 @@ impl Default for VMConfig {
             runtime_limits_config: VMRuntimeLimitsConfig::default(),
             enable_invariant_violation_check_in_swap_loc: true,
             check_no_extraneous_bytes_during_deserialization: false,
-            #[cfg(debug_assertions)]
-            profiler_config: VMProfilerConfig::default(),
+            profiler_config: None,
             error_execution_state: true,
         }
     }
@@ -60,22 +59,23 @@ impl Default for VMRuntimeLimitsConfig {
     }
 }
 
-#[cfg(debug_assertions)]
 #[derive(Clone, Debug)]
 pub struct VMProfilerConfig {
     /// Base path for files
     pub base_path: std::path::PathBuf,
+    /// Path to write the profile to, instead of a file named after the profile in `base_path`
+    pub full_path: Option<std::path::PathBuf>,
     /// Whether or not to track bytecode instructions
     pub track_bytecode_instructions: bool,
     /// Whether or not to use the long name for functions
     pub use_long_function_name: bool,
 }
 
-#[cfg(debug_assertions)]
 impl std::default::Default for VMProfilerConfig {
     fn default() -> Self {
         Self {
             base_path: std::path::PathBuf::from("."),
+            full_path: None,
             track_bytecode_instructions: false,
             use_long_function_name: false,
         }
@@ -622,8 +622,7 @@ fn run_with_module(
         .collect::<VMResult<Vec<_>>>();
 
     #[cfg(debug_assertions)]
-    gas.set_profiler(GasProfiler::init(
-        &session.vm_config().profiler_config,
+    gas.set_profiler(GasProfiler::init_default_cfg(
         entry_name.to_string(),
         gas.remaining_gas().into(),
     ));
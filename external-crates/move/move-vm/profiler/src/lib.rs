@@ -1,30 +1,24 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-#[cfg(debug_assertions)]
 use move_vm_config::runtime::VMProfilerConfig;
-#[cfg(debug_assertions)]
 use once_cell::sync::Lazy;
-#[cfg(debug_assertions)]
 use serde::Serialize;
-#[cfg(debug_assertions)]
 use std::collections::BTreeMap;
 
-#[cfg(debug_assertions)]
+// In debug builds, the profilers the VM sets up by default only record anything when this
+// environment variable is set. Profilers set up from an explicit config always record.
 const MOVE_VM_PROFILER_ENV_VAR_NAME: &str = "MOVE_VM_PROFILE";
 
-#[cfg(debug_assertions)]
 static PROFILER_ENABLED: Lazy<bool> =
     Lazy::new(|| std::env::var(MOVE_VM_PROFILER_ENV_VAR_NAME).is_ok());
 
-#[cfg(debug_assertions)]
 #[derive(Debug, Clone, Serialize)]
 pub struct FrameName {
     name: String,
     file: String,
 }
 
-#[cfg(debug_assertions)]
 #[derive(Debug, Clone, Serialize)]
 pub struct Shared {
     frames: Vec<FrameName>,
@@ -33,7 +27,6 @@ pub struct Shared {
     frame_table: BTreeMap<String, usize>,
 }
 
-#[cfg(debug_assertions)]
 #[derive(Debug, Clone, Serialize)]
 pub struct Event {
     #[serde(rename(serialize = "type"))]
@@ -42,7 +35,6 @@ pub struct Event {
     at: u64,
 }
 
-#[cfg(debug_assertions)]
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Profile {
@@ -55,7 +47,6 @@ pub struct Profile {
     events: Vec<Event>,
 }
 
-#[cfg(debug_assertions)]
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GasProfiler {
@@ -72,10 +63,11 @@ pub struct GasProfiler {
     #[serde(skip)]
     pub config: VMProfilerConfig,
     #[serde(skip)]
+    enabled: bool,
+    #[serde(skip)]
     finished: bool,
 }
 
-#[cfg(debug_assertions)]
 impl GasProfiler {
     // Used by profiler viz tool
     const OPEN_FRAME_IDENT: &str = "O";
@@ -84,6 +76,19 @@ impl GasProfiler {
     const TOP_LEVEL_FRAME_NAME: &str = "root";
 
     pub fn init(config: &VMProfilerConfig, name: String, start_gas: u64) -> Self {
+        Self::new(config, name, start_gas, true)
+    }
+
+    pub fn init_default_cfg(name: String, start_gas: u64) -> Self {
+        Self::new(
+            &VMProfilerConfig::default(),
+            name,
+            start_gas,
+            *PROFILER_ENABLED,
+        )
+    }
+
+    fn new(config: &VMProfilerConfig, name: String, start_gas: u64, enabled: bool) -> Self {
         let mut prof = GasProfiler {
             exporter: "speedscope@1.15.2".to_string(),
             name: name.clone(),
@@ -103,20 +108,17 @@ impl GasProfiler {
             }],
             start_gas,
             config: config.clone(),
+            enabled,
             finished: false,
         };
-        profile_open_frame_impl!(
-            Some(&mut prof),
+        prof.open_frame(
+            Self::TOP_LEVEL_FRAME_NAME.to_string(),
             Self::TOP_LEVEL_FRAME_NAME.to_string(),
-            start_gas
+            start_gas,
         );
         prof
     }
 
-    pub fn init_default_cfg(name: String, start_gas: u64) -> Self {
-        Self::init(&VMProfilerConfig::default(), name, start_gas)
-    }
-
     fn profile_name(&self) -> String {
         self.name.clone()
     }
@@ -154,7 +156,7 @@ impl GasProfiler {
     }
 
     pub fn open_frame(&mut self, frame_name: String, metadata: String, gas_start: u64) {
-        if !*PROFILER_ENABLED || self.start_gas == 0 {
+        if !self.enabled || self.start_gas == 0 {
             return;
         }
 
@@ -169,7 +171,7 @@ impl GasProfiler {
     }
 
     pub fn close_frame(&mut self, frame_name: String, metadata: String, gas_end: u64) {
-        if !*PROFILER_ENABLED || self.start_gas == 0 {
+        if !self.enabled || self.start_gas == 0 {
             return;
         }
         let frame_idx = self.add_frame(metadata.clone(), frame_name, metadata);
@@ -183,49 +185,63 @@ impl GasProfiler {
         self.profiles[0].end_value = start - gas_end;
     }
 
-    pub fn to_file(&self) {
-        if !*PROFILER_ENABLED || !self.is_metered() {
-            return;
+    pub fn to_file(&self) -> std::io::Result<()> {
+        if !self.enabled || !self.is_metered() {
+            return Ok(());
         }
-        // Get the unix timestamp
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .expect("Clock may have gone backwards")
-            .as_nanos();
-
-        let mut p = self.config.base_path.clone();
-        p.push(format!("gas_profile_{}_{}.json", self.profile_name(), now));
+
+        let p = match &self.config.full_path {
+            Some(full_path) => full_path.clone(),
+            None => {
+                // Get the unix timestamp
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .expect("Clock may have gone backwards")
+                    .as_nanos();
+                self.config.base_path.join(format!(
+                    "gas_profile_{}_{}.json",
+                    self.profile_name(),
+                    now
+                ))
+            }
+        };
         let path_str = p.as_os_str().to_string_lossy().to_string();
-        let mut file = std::fs::File::create(p).expect("Unable to create file");
+        let mut file = std::fs::File::create(p)?;
 
-        let json = serde_json::to_string_pretty(&self).expect("Unable to serialize profile");
-        std::io::Write::write_all(&mut file, json.as_bytes()).expect("Unable to write to file");
+        let json = serde_json::to_string_pretty(&self)?;
+        std::io::Write::write_all(&mut file, json.as_bytes())?;
         println!("Gas profile written to file: {}", path_str);
+        Ok(())
     }
 
-    pub fn finish(&mut self) {
+    pub fn finish(&mut self) -> std::io::Result<()> {
         if self.finished {
-            return;
+            return Ok(());
         }
         self.finished = true;
         let end_gas = self.start_gas() - self.profiles[0].end_value;
-        let mut q = Some(self);
-        profile_close_frame_impl!(&mut q, Self::TOP_LEVEL_FRAME_NAME.to_string(), end_gas);
-        profile_dump_file!(q.unwrap());
+        self.close_frame(
+            Self::TOP_LEVEL_FRAME_NAME.to_string(),
+            Self::TOP_LEVEL_FRAME_NAME.to_string(),
+            end_gas,
+        );
+        self.to_file()
     }
 }
 
-#[cfg(debug_assertions)]
 impl Drop for GasProfiler {
     fn drop(&mut self) {
-        self.finish();
+        // Profiles are written when execution is over, which should not fail because of them
+        if let Err(e) = self.finish() {
+            eprintln!("Unable to write gas profile: {}", e);
+        }
     }
 }
 
 #[macro_export]
 macro_rules! profile_open_frame {
     ($gas_meter:expr, $frame_name:expr) => {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         {
             let gas_rem = $gas_meter.remaining_gas().into();
             move_vm_profiler::profile_open_frame_impl!(
@@ -240,15 +256,17 @@ macro_rules! profile_open_frame {
 #[macro_export]
 macro_rules! profile_open_frame_impl {
     ($profiler:expr, $frame_name:expr, $gas_rem:expr) => {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         {
             if let Some(profiler) = $profiler {
+                // Only named once there is a profiler, as naming the frame is expensive
+                let frame_name = $frame_name;
                 let name = if !profiler.config.use_long_function_name {
-                    GasProfiler::short_name(&$frame_name)
+                    GasProfiler::short_name(&frame_name)
                 } else {
-                    $frame_name
+                    frame_name.clone()
                 };
-                profiler.open_frame(name, $frame_name, $gas_rem)
+                profiler.open_frame(name, frame_name, $gas_rem)
             }
         }
     };
@@ -257,7 +275,7 @@ macro_rules! profile_open_frame_impl {
 #[macro_export]
 macro_rules! profile_close_frame {
     ($gas_meter:expr, $frame_name:expr) => {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         {
             let gas_rem = $gas_meter.remaining_gas().into();
             move_vm_profiler::profile_close_frame_impl!(
@@ -272,15 +290,17 @@ macro_rules! profile_close_frame {
 #[macro_export]
 macro_rules! profile_close_frame_impl {
     ($profiler:expr, $frame_name:expr, $gas_rem:expr) => {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         {
             if let Some(profiler) = $profiler {
+                // Only named once there is a profiler, as naming the frame is expensive
+                let frame_name = $frame_name;
                 let name = if !profiler.config.use_long_function_name {
-                    GasProfiler::short_name(&$frame_name)
+                    GasProfiler::short_name(&frame_name)
                 } else {
-                    $frame_name.clone()
+                    frame_name.clone()
                 };
-                profiler.close_frame(name, $frame_name, $gas_rem)
+                profiler.close_frame(name, frame_name, $gas_rem)
             }
         }
     };
@@ -289,7 +309,7 @@ macro_rules! profile_close_frame_impl {
 #[macro_export]
 macro_rules! profile_open_instr {
     ($gas_meter:expr, $frame_name:expr) => {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         {
             let gas_rem = $gas_meter.remaining_gas().into();
             if let Some(profiler) = $gas_meter.get_profiler_mut() {
@@ -304,7 +324,7 @@ macro_rules! profile_open_instr {
 #[macro_export]
 macro_rules! profile_close_instr {
     ($gas_meter:expr, $frame_name:expr) => {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         {
             let gas_rem = $gas_meter.remaining_gas().into();
             if let Some(profiler) = $gas_meter.get_profiler_mut() {
@@ -319,7 +339,7 @@ macro_rules! profile_close_instr {
 #[macro_export]
 macro_rules! profile_dump_file {
     ($profiler:expr) => {
-        #[cfg(debug_assertions)]
+        #[cfg(any(debug_assertions, feature = "gas-profiler"))]
         $profiler.to_file()
     };
 }
@@ -25,7 +25,6 @@ use move_core_types::{
     u256,
     vm_status::StatusCode,
 };
-#[cfg(debug_assertions)]
 use move_vm_profiler::GasProfiler;
 use move_vm_types::{
     gas::{GasMeter, SimpleInstruction},
@@ -114,7 +113,6 @@ pub struct GasStatus<'a> {
     cost_table: &'a CostTable,
     gas_left: InternalGas,
     charge: bool,
-    #[cfg(debug_assertions)]
     profiler: Option<GasProfiler>,
 }
 
@@ -128,7 +126,6 @@ impl<'a> GasStatus<'a> {
             gas_left: gas_left.to_unit(),
             cost_table,
             charge: true,
-            #[cfg(debug_assertions)]
             profiler: None,
         }
     }
@@ -142,7 +139,6 @@ impl<'a> GasStatus<'a> {
             gas_left: InternalGas::new(0),
             cost_table: &ZERO_COST_SCHEDULE,
             charge: false,
-            #[cfg(debug_assertions)]
             profiler: None,
         }
     }
@@ -562,12 +558,10 @@ impl<'b> GasMeter for GasStatus<'b> {
         self.gas_left
     }
 
-    #[cfg(debug_assertions)]
     fn get_profiler_mut(&mut self) -> Option<&mut GasProfiler> {
         self.profiler.as_mut()
     }
 
-    #[cfg(debug_assertions)]
     fn set_profiler(&mut self, profiler: GasProfiler) {
         self.profiler = Some(profiler);
     }
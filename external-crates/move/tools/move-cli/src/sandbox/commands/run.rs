@@ -108,11 +108,8 @@ move run` must be applied to a module inside `storage/`",
             #[cfg(debug_assertions)]
             {
                 let gas_rem: u64 = gas_status.remaining_gas().into();
-                gas_status.set_profiler(GasProfiler::init(
-                    &session.vm_config().profiler_config,
-                    script_name.clone(),
-                    gas_rem,
-                ));
+                gas_status
+                    .set_profiler(GasProfiler::init_default_cfg(script_name.clone(), gas_rem));
             }
 
             session.execute_entry_function(
@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
 
 use move_binary_format::CompiledModule;
 use move_vm_config::verifier::VerifierConfig;
@@ -51,11 +51,13 @@ impl Executor {
         protocol_config: &ProtocolConfig,
         paranoid_type_checks: bool,
         silent: bool,
+        enable_profiler: Option<PathBuf>,
     ) -> Result<Self, SuiError> {
         Ok(Executor(Arc::new(new_move_vm(
             all_natives(silent),
             protocol_config,
             paranoid_type_checks,
+            enable_profiler,
         )?)))
     }
 }
@@ -6,7 +6,7 @@ pub use checked::*;
 #[sui_macros::with_checked_arithmetic]
 mod checked {
 
-    use std::{collections::BTreeMap, sync::Arc};
+    use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
 
     use anyhow::Result;
     use move_binary_format::{access::ModuleAccess, file_format::CompiledModule};
@@ -14,7 +14,7 @@ mod checked {
     use move_bytecode_verifier::verify_module_with_config_metered;
     use move_core_types::account_address::AccountAddress;
     use move_vm_config::{
-        runtime::{VMConfig, VMRuntimeLimitsConfig},
+        runtime::{VMConfig, VMProfilerConfig, VMRuntimeLimitsConfig},
         verifier::VerifierConfig,
     };
     use move_vm_runtime::{
@@ -85,6 +85,7 @@ mod checked {
         natives: NativeFunctionTable,
         protocol_config: &ProtocolConfig,
         paranoid_type_checks: bool,
+        enable_profiler: Option<PathBuf>,
     ) -> Result<MoveVM, SuiError> {
         MoveVM::new_with_config(
             natives,
@@ -105,8 +106,10 @@ mod checked {
                     .no_extraneous_module_bytes(),
                 // Don't augment errors with execution state on-chain
                 error_execution_state: false,
-                #[cfg(debug_assertions)]
-                profiler_config: Default::default(),
+                profiler_config: enable_profiler.map(|path| VMProfilerConfig {
+                    full_path: Some(path),
+                    ..Default::default()
+                }),
             },
         )
         .map_err(|_| SuiError::ExecutionInvariantViolation)
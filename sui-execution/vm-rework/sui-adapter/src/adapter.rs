@@ -5,7 +5,7 @@ pub use checked::*;
 
 #[sui_macros::with_checked_arithmetic]
 mod checked {
-    use std::{collections::BTreeMap, sync::Arc};
+    use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
 
     use anyhow::Result;
     use move_binary_format::{access::ModuleAccess, file_format::CompiledModule};
@@ -13,7 +13,7 @@ mod checked {
     use move_bytecode_verifier::verify_module_with_config_metered;
     use move_core_types::account_address::AccountAddress;
     use move_vm_config::{
-        runtime::{VMConfig, VMRuntimeLimitsConfig},
+        runtime::{VMConfig, VMProfilerConfig, VMRuntimeLimitsConfig},
         verifier::VerifierConfig,
     };
     use move_vm_runtime::{
@@ -84,6 +84,7 @@ mod checked {
         natives: NativeFunctionTable,
         protocol_config: &ProtocolConfig,
         paranoid_type_checks: bool,
+        enable_profiler: Option<PathBuf>,
     ) -> Result<MoveVM, SuiError> {
         MoveVM::new_with_config(
             natives,
@@ -102,8 +103,10 @@ mod checked {
                     .disable_invariant_violation_check_in_swap_loc(),
                 check_no_extraneous_bytes_during_deserialization: protocol_config
                     .no_extraneous_module_bytes(),
-                #[cfg(debug_assertions)]
-                profiler_config: std::default::Default::default(),
+                profiler_config: enable_profiler.map(|path| VMProfilerConfig {
+                    full_path: Some(path),
+                    ..Default::default()
+                }),
                 // Don't augment errors with execution state on-chain
                 error_execution_state: false,
             },
@@ -29,7 +29,6 @@ mod checked {
         identifier::IdentStr,
         language_storage::{ModuleId, StructTag, TypeTag},
     };
-    #[cfg(debug_assertions)]
     use move_vm_profiler::GasProfiler;
     use move_vm_runtime::native_extensions::NativeContextExtensions;
     use move_vm_runtime::{
@@ -37,7 +36,6 @@ mod checked {
         session::{LoadedFunctionInstantiation, SerializedReturnValues},
     };
     use move_vm_types::data_store::DataStore;
-    #[cfg(debug_assertions)]
     use move_vm_types::gas::GasMeter;
     use move_vm_types::loaded_data::runtime_types::Type;
     use move_vm_types::values::{GlobalValue, Value as VMValue};
@@ -192,20 +190,20 @@ mod checked {
                 tx_context.epoch(),
             );
 
-            // Set the profiler if in debug mode
-            #[cfg(debug_assertions)]
-            {
+            // Set the profiler if the VM was configured with one, or if in debug mode
+            let profiler_config = vm.config().profiler_config.as_ref();
+            if profiler_config.is_some() || cfg!(debug_assertions) {
                 let tx_digest = tx_context.digest();
                 let remaining_gas: u64 =
                     move_vm_types::gas::GasMeter::remaining_gas(gas_charger.move_gas_status())
                         .into();
-                gas_charger
-                    .move_gas_status_mut()
-                    .set_profiler(GasProfiler::init(
-                        &vm.config().profiler_config,
-                        format!("{}", tx_digest),
-                        remaining_gas,
-                    ));
+                let profiler = match profiler_config {
+                    Some(config) => {
+                        GasProfiler::init(config, format!("{}", tx_digest), remaining_gas)
+                    }
+                    None => GasProfiler::init_default_cfg(format!("{}", tx_digest), remaining_gas),
+                };
+                gas_charger.move_gas_status_mut().set_profiler(profiler);
             }
 
             Ok(Self {